@@ -1,14 +1,26 @@
 use eframe::egui::{self, Vec2, Pos2};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-#[cfg(debug_assertions)]
 use crate::privacy;
-use crate::canvas::{BrowserAction, CanvasState};
-use crate::preview::{PreviewManager, PreviewLayout, PreviewId, FpsPreset, WindowHandle};
-use crate::window_picker::{WindowPicker, WindowInfo, enumerate_windows, spawn_preview};
+use crate::canvas::{BrowserAction, CanvasState, TileRenderer};
+use crate::util::format_age;
+use crate::preview::{apply_layout_fields, PreviewManager, PreviewLayout, PreviewId, FpsPreset, CaptureOptions, WindowHandle};
+use crate::platform::{Native, WindowFocus, WindowInfo, WindowSource};
+use crate::window_picker::{WindowPicker, spawn_preview, best_match};
 use crate::capture::CaptureCoordinator;
-use crate::persistence::{Storage, SavedLayout, CanvasLayout};
+use crate::focus::FocusTracker;
+use crate::diagnostics::{self, Check, DiagnosticsAction};
+use crate::file_dialog::FileDialog;
+use crate::health;
+use crate::persistence::{AppSettings, Storage, SavedLayout, SessionManifest, SettingsBundle, CanvasLayout, ScheduleEntry, Theme, Trash, WindowBlocklist, WorkspaceSlot, ZoomSettings};
 use crate::tray::TrayManager;
 use crate::overlay::RegionSelector;
+use crate::panic_button::{self, PANIC_HOTKEY_LABEL};
+use crate::power::{PowerSaver, POWER_SAVER_FPS};
+use crate::schedule::{self, Scheduler};
+use crate::toast::Toasts;
+use crate::welcome::{WelcomeAction, WelcomeScreen};
 #[cfg(windows)]
 use crate::browser::{self, normalize_url, BrowserManager};
 #[cfg(windows)]
@@ -18,7 +30,7 @@ use windows::Win32::Foundation::HWND;
 #[cfg(windows)]
 use windows::Win32::UI::Shell::ShellExecuteW;
 #[cfg(windows)]
-use windows::Win32::UI::WindowsAndMessaging::{SetForegroundWindow, SW_SHOWNORMAL};
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
 use wry::raw_window_handle::{HasWindowHandle as _, RawWindowHandle};
 
 /// How long after activating a browser we skip the focus-loss check, so the
@@ -28,16 +40,136 @@ const BROWSER_FOCUS_GRACE: Duration = Duration::from_millis(300);
 /// How many recent browser URLs to keep for the Add Browser dialog.
 const MAX_RECENT_URLS: usize = 8;
 
+/// Consecutive starts that never became healthy before we assume the
+/// autosave (or a source in it) is crashing us and fall back to safe mode.
+const MAX_FAILED_STARTS: u32 = 3;
+
+/// How long the app must run before a start counts as healthy.
+const STARTUP_OK_AFTER: Duration = Duration::from_secs(10);
+
+/// How often the canvas is compared against the last saved layout for the
+/// title bar's unsaved-changes marker.
+const DIRTY_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How often captured windows' titles are re-read for their previews.
+const TITLE_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often offline tiles look for their window to come back.
+const OFFLINE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A window (or display) matched to a saved tile: its handle, title, and
+/// executable and class (None for displays)
+type TileSource = (isize, String, Option<(String, String)>);
+
+/// Name the autosave is stored under while the canvas is untitled.
+const UNTITLED_AUTOSAVE: &str = "autosave";
+
+/// Something that replaces the canvas, held back while the user decides
+/// what to do with unsaved changes.
+#[derive(Clone, Debug)]
+enum PendingChange {
+    Exit,
+    /// File > Reload Layout
+    Reload,
+    /// Welcome screen "Reopen Last Session"
+    ReopenLastSession,
+    NewCanvas,
+    OpenLayout(String),
+    /// "Restart as Admin" on a preview of an elevated window
+    RestartElevated,
+}
+
+/// A workspace whose tab isn't showing. Its previews keep their capture
+/// sessions, paused, so switching back is instant.
+struct ParkedWorkspace {
+    canvas: CanvasState,
+    preview_manager: PreviewManager,
+    layout_name: Option<String>,
+    saved_fingerprint: u64,
+}
+
 /// Canvas right-click "Add Window..." popup: a small searchable list shown
 /// at the click position so windows can be added without the sidebar.
 struct QuickAddPopup {
-    /// Where to place the new preview (canvas coordinates).
+    /// Where to center the new preview (canvas coordinates).
     canvas_pos: Pos2,
     /// Where to anchor the popup (screen coordinates).
     screen_pos: Pos2,
     /// Snapshot of open windows, taken when the popup was opened.
     windows: Vec<WindowInfo>,
     search: String,
+    /// Opened this frame: the click that opened it isn't a click outside.
+    just_opened: bool,
+}
+
+/// What a file transfer dialog writes or reads
+enum TransferKind {
+    /// A settings bundle: `include_layouts` exports every saved layout too
+    /// / restores the bundle's layouts
+    Settings { include_layouts: bool },
+    /// The canvas as a layout file to share or keep under version control
+    Layout,
+}
+
+/// File > Export/Import Layout and File > Settings > Export/Import: the
+/// file to write or read, picked with the native dialog (which opens right
+/// away) or typed in.
+struct FileTransferDialog {
+    kind: TransferKind,
+    import: bool,
+    path: String,
+    /// Native open/save dialog, while it's showing
+    browse: Option<FileDialog>,
+}
+
+impl FileTransferDialog {
+    fn new(kind: TransferKind, import: bool, path: String, owner: Option<isize>) -> Self {
+        let mut dialog = Self { kind, import, path, browse: None };
+        dialog.browse(owner);
+        dialog
+    }
+
+    fn title(&self) -> &'static str {
+        match (&self.kind, self.import) {
+            (TransferKind::Settings { .. }, true) => "Import Settings",
+            (TransferKind::Settings { .. }, false) => "Export Settings",
+            (TransferKind::Layout, true) => "Import Layout",
+            (TransferKind::Layout, false) => "Export Layout",
+        }
+    }
+
+    fn browse(&mut self, owner: Option<isize>) {
+        let initial = std::path::PathBuf::from(self.path.trim());
+        self.browse = Some(FileDialog::show(self.title(), &initial, !self.import, owner));
+    }
+}
+
+/// File > Hidden Windows: the blocklist being edited, one entry per line
+struct BlocklistDialog {
+    exe_names: String,
+    title_patterns: String,
+    class_names: String,
+}
+
+impl BlocklistDialog {
+    fn new(blocklist: &WindowBlocklist) -> Self {
+        Self {
+            exe_names: blocklist.exe_names.join("\n"),
+            title_patterns: blocklist.title_patterns.join("\n"),
+            class_names: blocklist.class_names.join("\n"),
+        }
+    }
+
+    fn blocklist(&self) -> WindowBlocklist {
+        let lines = |text: &str| -> Vec<String> {
+            text.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_owned).collect()
+        };
+        WindowBlocklist {
+            exe_names: lines(&self.exe_names),
+            title_patterns: lines(&self.title_patterns),
+            class_names: lines(&self.class_names),
+        }
+    }
 }
 
 struct AddBrowserDialog {
@@ -70,6 +202,40 @@ pub struct PluriviewApp {
     /// Storage for persistence
     storage: Option<Storage>,
 
+    /// App-wide preferences (saved to settings.json)
+    settings: AppSettings,
+
+    /// Open workspaces and their autosave slots (saved to session.json)
+    session: SessionManifest,
+
+    /// Workspaces other than the active one that were opened this run, by
+    /// slot. The rest are loaded from their autosave when switched to.
+    parked: HashMap<String, ParkedWorkspace>,
+
+    /// Focus/battery tracking for the power-saver option
+    power_saver: PowerSaver,
+
+    /// Is power saver currently throttling captures?
+    power_throttled: bool,
+
+    /// Fires the layout schedule's switches
+    scheduler: Scheduler,
+
+    /// Which desktop window has keyboard focus, for the preview marker
+    focus_tracker: FocusTracker,
+
+    /// Panic button pressed: every preview is blanked and captures are
+    /// paused until it's pressed again
+    panic_active: bool,
+
+    /// Started with --safe-mode (or after repeated failed starts): autosave
+    /// wasn't loaded, integrations are off, and nothing is autosaved until
+    /// the user loads or saves a layout themselves
+    safe_mode: bool,
+
+    /// When this run started; cleared once the start is marked healthy
+    startup_pending_since: Option<Instant>,
+
     /// System tray manager
     tray_manager: Option<TrayManager>,
 
@@ -82,6 +248,85 @@ pub struct PluriviewApp {
     /// Show Keyboard Shortcuts dialog
     show_shortcuts: bool,
 
+    /// Show the Capture Health window
+    show_health: bool,
+
+    /// Welcome screen (recent layouts), if open
+    welcome: Option<WelcomeScreen>,
+
+    /// Name being typed in the "Save Layout As" dialog, if open
+    save_layout_name: Option<String>,
+
+    /// Settings or layout export/import dialog, if open
+    file_transfer: Option<FileTransferDialog>,
+
+    /// Layout Schedule dialog, if open, with the saved layouts to pick from
+    schedule_layouts: Option<Vec<String>>,
+
+    /// Hidden Windows dialog, if open
+    blocklist_dialog: Option<BlocklistDialog>,
+
+    /// Previews removed from any workspace, newest first
+    trash: Trash,
+
+    /// Show the Trash window
+    show_trash: bool,
+
+    /// Canvas colors (`theme.json`)
+    theme: Theme,
+
+    /// Show the Theme window
+    show_theme: bool,
+
+    /// Presentation mode (F11): fullscreen canvas with previews only
+    presenting: bool,
+
+    /// Show the Bookmarks window
+    show_bookmarks: bool,
+
+    /// OpenGL renderer string, for the diagnostics report
+    gpu_adapter: Option<String>,
+
+    /// Results of the last self-test while the Diagnostics window is open
+    diagnostics: Option<Vec<Check>>,
+
+    /// Named layout the canvas was opened from or last saved as; None for
+    /// an untitled canvas (kept in the autosave only)
+    layout_name: Option<String>,
+
+    /// `SavedLayout::content_fingerprint` of the canvas as last saved or loaded
+    saved_fingerprint: u64,
+
+    /// Canvas differs from `saved_fingerprint` (refreshed every
+    /// `DIRTY_CHECK_INTERVAL`)
+    dirty: bool,
+    dirty_checked_at: Instant,
+
+    /// When preview titles were last re-read (every `TITLE_REFRESH_INTERVAL`)
+    titles_refreshed_at: Instant,
+
+    /// When offline tiles last looked for their window (every
+    /// `OFFLINE_CHECK_INTERVAL`)
+    offline_checked_at: Instant,
+
+    /// Waiting on the "Save changes?" prompt
+    pending_change: Option<PendingChange>,
+
+    /// Run once the "Save Layout As" dialog (opened from the prompt) saves
+    after_save: Option<PendingChange>,
+
+    /// The user answered the prompt for exiting; let the close through
+    exit_confirmed: bool,
+
+    /// Exiting with "Don't Save": the autosave gets the layout as saved
+    discard_on_exit: bool,
+
+    /// Window title last sent to the OS
+    window_title: String,
+
+    /// Bottom-right notifications (errors, saves, closed windows)
+    toasts: Toasts,
+
     /// Active region selector overlay (if any)
     region_selector: Option<RegionSelector>,
 
@@ -107,15 +352,37 @@ pub struct PluriviewApp {
 }
 
 impl PluriviewApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(_cc: &eframe::CreationContext<'_>, safe_mode: bool) -> Self {
         // Register phosphor icon glyphs alongside the default font so we can
         // use crisp vector icons instead of emoji/text glyphs in the UI.
         let mut fonts = egui::FontDefinitions::default();
         egui_phosphor::add_to_fonts(&mut fonts, egui_phosphor::Variant::Regular);
         _cc.egui_ctx.set_fonts(fonts);
+        // Ctrl+Plus/Minus/0 zoom the canvas, not the whole UI
+        _cc.egui_ctx.options_mut(|options| options.zoom_with_keyboard = false);
 
         let storage = Storage::new();
-        let tray_manager = TrayManager::new();
+        let failed_starts = storage.as_ref().map_or(0, |s| s.begin_startup());
+        let safe_mode = safe_mode || failed_starts >= MAX_FAILED_STARTS;
+        if safe_mode {
+            log::warn!("Starting in safe mode ({} failed starts)", failed_starts);
+        }
+
+        let settings = storage
+            .as_ref()
+            .and_then(|s| s.load_settings().ok())
+            .unwrap_or_default();
+        let session = storage.as_ref().map(|s| s.load_session()).unwrap_or_default();
+        let theme = storage.as_ref().map(|s| s.load_theme()).unwrap_or_default();
+        let trash = match &storage {
+            Some(storage) if settings.keep_trash => storage.load_trash(),
+            _ => Trash::default(),
+        };
+        // The tray is an integration; safe mode runs without it.
+        let tray_manager = if safe_mode { None } else { TrayManager::new() };
+        if !safe_mode {
+            panic_button::register_hotkey(_cc.egui_ctx.clone());
+        }
 
         #[cfg(debug_assertions)]
         if tray_manager.is_some() {
@@ -131,10 +398,46 @@ impl PluriviewApp {
             capture_coordinator: CaptureCoordinator::new(),
             picker_open: true,
             storage,
+            settings,
+            session,
+            parked: HashMap::new(),
+            power_saver: PowerSaver::default(),
+            power_throttled: false,
+            scheduler: Scheduler::default(),
+            focus_tracker: FocusTracker::default(),
+            panic_active: false,
+            safe_mode,
+            startup_pending_since: Some(Instant::now()),
             tray_manager,
             hwnd_set: false,
             show_about: false,
             show_shortcuts: false,
+            show_health: false,
+            welcome: None,
+            save_layout_name: None,
+            file_transfer: None,
+            schedule_layouts: None,
+            blocklist_dialog: None,
+            trash,
+            show_trash: false,
+            theme,
+            show_theme: false,
+            presenting: false,
+            show_bookmarks: false,
+            gpu_adapter: None,
+            diagnostics: None,
+            layout_name: None,
+            saved_fingerprint: SavedLayout::new(String::new()).content_fingerprint(),
+            dirty: false,
+            dirty_checked_at: Instant::now(),
+            titles_refreshed_at: Instant::now(),
+            offline_checked_at: Instant::now(),
+            pending_change: None,
+            after_save: None,
+            exit_confirmed: false,
+            discard_on_exit: false,
+            window_title: String::new(),
+            toasts: Toasts::default(),
             region_selector: None,
             region_select_preview_id: None,
             quick_add: None,
@@ -148,8 +451,40 @@ impl PluriviewApp {
             browser_activated_at: None,
         };
 
-        // Try to load autosave
-        app.load_autosave();
+        app.preview_manager.set_linear_scaling(app.settings.linear_scaling);
+        app.capture_coordinator.set_linear_scaling(app.settings.linear_scaling);
+        app.window_picker.set_recent(app.settings.recent_windows.clone());
+        app.window_picker.sort = app.settings.picker_sort;
+        app.window_picker.blocklist = app.settings.blocklist.clone();
+
+        app.gpu_adapter = _cc.gl.as_ref().map(|gl| {
+            use eframe::glow::HasContext as _;
+            unsafe { gl.get_parameter_string(eframe::glow::RENDERER) }
+        });
+
+        // Draw previews through our own shader where the GL context allows
+        // (not in safe mode, in case the driver chokes on it)
+        let gl = if safe_mode { None } else { _cc.gl.as_ref() };
+        app.canvas.tile_renderer = gl.and_then(|gl| match TileRenderer::new(gl) {
+            Ok(renderer) => Some(Arc::new(renderer)),
+            Err(e) => {
+                log::warn!("Tile shader unavailable, using egui images: {}", e);
+                None
+            }
+        });
+
+        // Try to load autosave (skipped in safe mode: it may be what crashed)
+        if !safe_mode {
+            app.load_autosave();
+        }
+
+        // Nothing to restore: greet with recent layouts instead of a bare canvas
+        if app.preview_manager.count() == 0 {
+            app.welcome = app
+                .storage
+                .as_ref()
+                .map(|storage| WelcomeScreen::new(&_cc.egui_ctx, storage, false));
+        }
 
         app
     }
@@ -182,8 +517,13 @@ impl PluriviewApp {
                     preview.browser_url = Some(url.clone());
                     preview.set_fps_preset(fps);
                 }
-                self.capture_coordinator
-                    .start_capture(id, hwnd, url.clone(), fps.as_u32());
+                self.capture_coordinator.start_capture(
+                    id,
+                    hwnd,
+                    url.clone(),
+                    fps.as_u32(),
+                    CaptureOptions::default(),
+                );
                 self.remember_recent_url(&url);
                 Ok(id)
             }
@@ -441,9 +781,7 @@ impl PluriviewApp {
                 // hand it back to the main window so keyboard input works.
                 if escape && !minimized {
                     if let Some(hwnd) = self.main_hwnd {
-                        unsafe {
-                            let _ = SetForegroundWindow(HWND(hwnd as *mut _));
-                        }
+                        Native::focus_window(hwnd, false);
                     }
                 }
             } else if let (Some(hwnd), Some(rect)) = (self.main_hwnd, tile_rect) {
@@ -459,6 +797,42 @@ impl PluriviewApp {
         self.canvas.interactive_browser = self.browser.active_id();
     }
 
+    /// Is the main window minimized or hidden (e.g. to the tray)?
+    fn is_window_hidden(&self, ctx: &egui::Context) -> bool {
+        #[cfg(windows)]
+        if let Some(hwnd) = self.main_hwnd {
+            return Native::is_hidden(hwnd);
+        }
+        ctx.input(|i| i.viewport().minimized.unwrap_or(false))
+    }
+
+    /// Panic button: blank every preview and pause all captures, or bring
+    /// them back
+    fn toggle_panic(&mut self) {
+        self.panic_active = !self.panic_active;
+        if self.panic_active {
+            // A browser tile being interacted with is a live window
+            #[cfg(windows)]
+            self.browser.park_all();
+            self.toasts.info(format!("Previews hidden. Press {} to show them again", PANIC_HOTKEY_LABEL));
+        } else {
+            self.toasts.info("Previews restored");
+        }
+    }
+
+    /// Presentation mode: go fullscreen with nothing but the previews on
+    /// the canvas, for a wall display
+    fn set_presenting(&mut self, ctx: &egui::Context, on: bool) {
+        if self.presenting == on {
+            return;
+        }
+        self.presenting = on;
+        ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(on));
+        if on {
+            self.toasts.info("Presentation mode. Press F11 or Esc to exit");
+        }
+    }
+
     /// Set the window HWND for the tray manager (call once after window is created)
     fn setup_tray_hwnd(&mut self) {
         if self.hwnd_set || self.tray_manager.is_none() {
@@ -476,7 +850,7 @@ impl PluriviewApp {
     /// Custom title bar (we run with `with_decorations(false)` so the OS
     /// doesn't draw its own white title bar over our dark theme).
     fn title_bar_ui(&mut self, ctx: &egui::Context) {
-        let bg = egui::Color32::from_rgb(13, 13, 13);
+        let bg = self.theme.background();
         let is_maximized = ctx.input(|i| i.viewport().maximized.unwrap_or(false));
 
         egui::TopBottomPanel::top("custom_title_bar")
@@ -513,6 +887,29 @@ impl PluriviewApp {
                                 .size(13.0)
                                 .color(egui::Color32::from_rgb(170, 170, 175)),
                         );
+                        ui.add_space(6.0);
+                        ui.label(
+                            egui::RichText::new(self.layout_display_name())
+                                .size(12.0)
+                                .color(egui::Color32::from_rgb(110, 110, 115)),
+                        );
+                        if self.dirty {
+                            ui.label(
+                                egui::RichText::new("●")
+                                    .size(9.0)
+                                    .color(egui::Color32::from_rgb(255, 150, 100)),
+                            )
+                            .on_hover_text("Unsaved changes");
+                        }
+                        if self.safe_mode {
+                            ui.add_space(8.0);
+                            ui.label(
+                                egui::RichText::new("SAFE MODE")
+                                    .size(10.0)
+                                    .color(egui::Color32::from_rgb(255, 150, 100)),
+                            )
+                            .on_hover_text("Autosave and integrations are off. File > Reload Layout restores your last session.");
+                        }
                         ui.add_space(16.0);
                         // File / View / Help, inline next to the app name.
                         self.menu_bar(ui, ctx);
@@ -583,13 +980,66 @@ impl PluriviewApp {
         egui::menu::bar(ui, |ui| {
             ui.menu_button("File", |ui| {
                 if ui.button("Save Layout Now").clicked() {
-                    self.save_autosave();
+                    self.safe_mode = false;
+                    if self.save_current() {
+                        self.toasts.success("Layout saved");
+                    }
                     ui.close_menu();
                 }
                 if ui.button("Reload Layout").clicked() {
-                    self.load_autosave();
+                    self.request_change(PendingChange::Reload, ctx);
+                    ui.close_menu();
+                }
+                if ui.button("Save Layout As...").clicked() {
+                    self.save_layout_name = Some(String::new());
+                    ui.close_menu();
+                }
+                for (label, import) in [("Export Layout...", false), ("Import Layout...", true)] {
+                    if ui.button(label).clicked() {
+                        let path = self
+                            .storage
+                            .as_ref()
+                            .map(|storage| {
+                                storage.default_layout_export_path(self.layout_display_name()).display().to_string()
+                            })
+                            .unwrap_or_default();
+                        self.file_transfer =
+                            Some(FileTransferDialog::new(TransferKind::Layout, import, path, self.main_hwnd));
+                        ui.close_menu();
+                    }
+                }
+                if ui.button("Welcome Screen").clicked() {
+                    self.open_welcome(ctx);
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button("New Workspace (Ctrl+T)").clicked() {
+                    self.new_workspace();
                     ui.close_menu();
                 }
+                if ui.button("Layout Schedule...").clicked() {
+                    let layouts = self.storage.as_ref().map(|storage| storage.list_layouts()).unwrap_or_default();
+                    self.schedule_layouts = Some(layouts);
+                    ui.close_menu();
+                }
+                if ui.button("Hidden Windows...").clicked() {
+                    self.blocklist_dialog = Some(BlocklistDialog::new(&self.settings.blocklist));
+                    ui.close_menu();
+                }
+                ui.menu_button("Settings", |ui| {
+                    for (label, import) in [("Export...", false), ("Import...", true)] {
+                        if ui.button(label).clicked() {
+                            let path = self
+                                .storage
+                                .as_ref()
+                                .map(|storage| storage.default_bundle_path().display().to_string())
+                                .unwrap_or_default();
+                            let kind = TransferKind::Settings { include_layouts: true };
+                            self.file_transfer = Some(FileTransferDialog::new(kind, import, path, self.main_hwnd));
+                            ui.close_menu();
+                        }
+                    }
+                });
                 ui.separator();
                 if self.tray_manager.is_some() {
                     if ui.button("Minimize to Tray").clicked() {
@@ -603,6 +1053,22 @@ impl PluriviewApp {
                 }
             });
 
+            ui.menu_button("Edit", |ui| {
+                if ui.add_enabled(self.canvas.can_undo(), egui::Button::new("Undo (Ctrl+Z)")).clicked() {
+                    self.canvas.undo(&mut self.preview_manager, &mut self.capture_coordinator);
+                    ui.close_menu();
+                }
+                if ui.add_enabled(self.canvas.can_redo(), egui::Button::new("Redo (Ctrl+Y)")).clicked() {
+                    self.canvas.redo(&mut self.preview_manager, &mut self.capture_coordinator);
+                    ui.close_menu();
+                }
+                ui.separator();
+                if ui.button(format!("Trash ({})...", self.trash.len())).clicked() {
+                    self.show_trash = true;
+                    ui.close_menu();
+                }
+            });
+
             ui.menu_button("View", |ui| {
                 if ui.checkbox(&mut self.picker_open, "Window Picker").clicked() {
                     ui.close_menu();
@@ -610,6 +1076,141 @@ impl PluriviewApp {
                 if ui.checkbox(&mut self.canvas.show_grid, "Show Grid (G)").clicked() {
                     ui.close_menu();
                 }
+                if ui.checkbox(&mut self.canvas.show_heat_map, "Activity Heat Map (H)").clicked() {
+                    ui.close_menu();
+                }
+                if ui.checkbox(&mut self.canvas.show_minimap, "Minimap (M)").clicked() {
+                    ui.close_menu();
+                }
+                if ui.checkbox(&mut self.canvas.show_layers, "Layers (L)").clicked() {
+                    ui.close_menu();
+                }
+                if ui.checkbox(&mut self.canvas.spotlight, "Spotlight Selection (S)").clicked() {
+                    ui.close_menu();
+                }
+                let mut loupe = self.canvas.loupe.is_some();
+                if ui.checkbox(&mut loupe, "Loupe (Q)").clicked() {
+                    self.canvas.loupe = loupe.then_some(2.0);
+                    ui.close_menu();
+                }
+                if ui.checkbox(&mut self.canvas.eyedropper, "Eyedropper (I)").clicked() {
+                    ui.close_menu();
+                }
+                if ui.checkbox(&mut self.canvas.measuring, "Measure (U)").clicked() {
+                    ui.close_menu();
+                }
+                if ui.button("Arrange Grid").clicked() {
+                    self.canvas.arrange_grid(&self.preview_manager);
+                    ui.close_menu();
+                }
+                if ui.button("Theme...").clicked() {
+                    self.show_theme = true;
+                    ui.close_menu();
+                }
+                if ui.button("Bookmarks...").clicked() {
+                    self.show_bookmarks = true;
+                    ui.close_menu();
+                }
+                if ui.button("Presentation Mode (F11)").clicked() {
+                    self.set_presenting(ui.ctx(), true);
+                    ui.close_menu();
+                }
+                if ui.checkbox(&mut self.show_health, "Capture Health").clicked() {
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.settings.exclude_from_capture, "Hide from Screen Sharing")
+                    .on_hover_text("Teams, OBS and other capture tools see an empty window instead of your previews")
+                    .clicked()
+                {
+                    if let Some(hwnd) = self.main_hwnd {
+                        privacy::set_excluded_from_capture(hwnd, self.settings.exclude_from_capture);
+                    }
+                    self.save_settings();
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.settings.capture_while_minimized, "Capture While Minimized")
+                    .on_hover_text("Keep captures running while minimized or in the tray (e.g. when recording)")
+                    .clicked()
+                {
+                    self.save_settings();
+                    ui.close_menu();
+                }
+                let mut blanked = self.panic_active;
+                if ui
+                    .checkbox(&mut blanked, format!("Blank All Previews ({})", PANIC_HOTKEY_LABEL))
+                    .on_hover_text("Black out every preview and pause captures at once; works from anywhere, and again to restore")
+                    .clicked()
+                {
+                    self.toggle_panic();
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.settings.linear_scaling, "Linear-Light Scaling")
+                    .on_hover_text("Scale previews in linear light so small tiles match the source's brightness and color")
+                    .clicked()
+                {
+                    self.preview_manager.set_linear_scaling(self.settings.linear_scaling);
+                    self.capture_coordinator.set_linear_scaling(self.settings.linear_scaling);
+                    self.save_settings();
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.settings.typing_indicator, "Show Typing Activity")
+                    .on_hover_text("Pulse a keyboard badge on the preview of the window you're typing in")
+                    .clicked()
+                {
+                    self.save_settings();
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.settings.power_saver, "Power Saver")
+                    .on_hover_text("Drop captures to a low frame rate while Pluriview is unfocused or on battery")
+                    .clicked()
+                {
+                    self.save_settings();
+                    ui.close_menu();
+                }
+                if ui
+                    .checkbox(&mut self.settings.bounded_canvas, "Bounded Canvas")
+                    .on_hover_text("Keep previews and panning inside an 8000 × 8000 area so nothing gets flung out of reach")
+                    .clicked()
+                {
+                    self.save_settings();
+                    ui.close_menu();
+                }
+                ui.menu_button("Zoom", |ui| {
+                    let zoom = &mut self.settings.zoom;
+                    let mut edits = Vec::new();
+                    ui.label("Furthest out");
+                    edits.push(ui.add(
+                        egui::Slider::new(&mut zoom.min, 0.02..=1.0)
+                            .logarithmic(true)
+                            .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)),
+                    ));
+                    ui.label("Furthest in");
+                    edits.push(ui.add(
+                        egui::Slider::new(&mut zoom.max, 1.0..=20.0)
+                            .logarithmic(true)
+                            .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)),
+                    ));
+                    ui.label("Scroll step");
+                    edits.push(ui.add(
+                        egui::Slider::new(&mut zoom.step, 0.02..=0.5)
+                            .custom_formatter(|value, _| format!("±{:.0}%", value * 100.0)),
+                    ));
+                    edits.push(
+                        ui.checkbox(&mut zoom.smooth, "Smooth Zoom")
+                            .on_hover_text("Glide to each new zoom level instead of jumping there"),
+                    );
+                    if ui.button("Defaults").clicked() {
+                        *zoom = ZoomSettings::default();
+                        self.save_settings();
+                    } else if edits.iter().any(|edit| edit.drag_stopped() || (edit.changed() && !edit.dragged())) {
+                        self.save_settings();
+                    }
+                });
                 ui.separator();
                 if ui.button("Reset View").clicked() {
                     self.canvas.reset();
@@ -622,6 +1223,10 @@ impl PluriviewApp {
                     self.show_shortcuts = true;
                     ui.close_menu();
                 }
+                if ui.button("Diagnostics...").clicked() {
+                    self.diagnostics = Some(self.run_diagnostics(ui.ctx()));
+                    ui.close_menu();
+                }
                 ui.separator();
                 if ui.button("About").clicked() {
                     self.show_about = true;
@@ -687,6 +1292,7 @@ impl PluriviewApp {
     /// Render the canvas right-click "Add Window..." popup, if open.
     fn quick_add_ui(&mut self, ctx: &egui::Context) {
         let Some(popup) = &mut self.quick_add else { return; };
+        let just_opened = std::mem::take(&mut popup.just_opened);
 
         // Read this before drawing the popup: the focused search box's
         // TextEdit consumes the Escape key itself (to drop focus), so
@@ -769,7 +1375,7 @@ impl PluriviewApp {
                     });
             });
 
-        if ctx.input(|i| i.pointer.any_click()) {
+        if !just_opened && ctx.input(|i| i.pointer.any_click()) {
             if let Some(click_pos) = ctx.input(|i| i.pointer.interact_pos()) {
                 if !area_response.response.rect.contains(click_pos) {
                     close = true;
@@ -780,13 +1386,15 @@ impl PluriviewApp {
         if let Some(idx) = clicked_index {
             if let Some(popup) = &self.quick_add {
                 if let Some(window) = popup.windows.get(idx) {
+                    let size = Vec2::new(320.0, 240.0);
                     spawn_preview(
                         window,
                         &mut self.preview_manager,
                         &mut self.capture_coordinator,
-                        popup.canvas_pos,
-                        Vec2::new(320.0, 240.0),
+                        popup.canvas_pos - size / 2.0,
+                        size,
                     );
+                    self.window_picker.remember(window);
                 }
             }
             close = true;
@@ -797,140 +1405,1411 @@ impl PluriviewApp {
         }
     }
 
-    /// Load the autosave layout if it exists
-    fn load_autosave(&mut self) {
-        if let Some(storage) = &self.storage {
-            if let Ok(layout) = storage.load_autosave() {
-                self.apply_layout(&layout);
-                #[cfg(debug_assertions)]
-                println!("Loaded autosave with {} previews", layout.previews.len());
+    /// Load the autosave layout if it exists. Returns true if it did.
+    fn load_autosave(&mut self) -> bool {
+        let slot = self.session.active_slot();
+        let Some(Ok(layout)) = self.storage.as_ref().map(|storage| storage.load_autosave(slot)) else {
+            return false;
+        };
+        self.apply_layout(&layout);
+        #[cfg(debug_assertions)]
+        println!("Loaded autosave with {} previews", layout.previews.len());
+
+        // The autosave remembers which named layout it was; edits made to
+        // it since its last save stay unsaved.
+        self.layout_name = (layout.name != UNTITLED_AUTOSAVE).then(|| layout.name.clone());
+        self.mark_saved();
+        let saved = self
+            .layout_name
+            .as_ref()
+            .zip(self.storage.as_ref())
+            .and_then(|(name, storage)| storage.load_layout(name).ok());
+        if let Some(saved) = saved {
+            if saved.content_fingerprint() != layout.content_fingerprint() {
+                self.saved_fingerprint = saved.content_fingerprint();
+                self.dirty = true;
             }
         }
+        true
     }
 
-    /// Save the current layout to autosave
-    fn save_autosave(&self) {
-        if let Some(storage) = &self.storage {
-            let layout = self.create_layout("autosave".to_string());
-            if let Err(e) = storage.save_autosave(&layout) {
-                eprintln!("Failed to save autosave: {}", e);
-            } else {
-                #[cfg(debug_assertions)]
-                println!("Saved autosave with {} previews", layout.previews.len());
-            }
+    /// Show another workspace. The outgoing one is autosaved and parked with
+    /// its captures paused; viewport culling resumes the incoming one's
+    /// visible previews on the next frame.
+    fn switch_workspace(&mut self, index: usize) {
+        if index == self.session.active || index >= self.session.workspaces.len() {
+            return;
+        }
+        if !self.safe_mode {
+            self.save_autosave();
         }
-    }
 
-    /// Create a SavedLayout from current state
-    fn create_layout(&self, name: String) -> SavedLayout {
-        let mut layout = SavedLayout::new(name);
+        for id in self.preview_manager.all_ids() {
+            if let Some(preview) = self.preview_manager.get_mut(id) {
+                if !preview.capture_paused {
+                    self.capture_coordinator.pause_capture(id);
+                    preview.capture_paused = true;
+                }
+            }
+        }
+        #[cfg(windows)]
+        self.browser.park_all();
 
-        // Save canvas state
-        layout.canvas = CanvasLayout {
-            pan: (self.canvas.pan.x, self.canvas.pan.y),
-            zoom: self.canvas.zoom,
-            show_grid: self.canvas.show_grid,
+        let outgoing = ParkedWorkspace {
+            canvas: std::mem::take(&mut self.canvas),
+            preview_manager: std::mem::replace(&mut self.preview_manager, PreviewManager::new()),
+            layout_name: self.layout_name.take(),
+            saved_fingerprint: self.saved_fingerprint,
         };
+        let tile_renderer = outgoing.canvas.tile_renderer.clone();
+        let last_screen_rect = outgoing.canvas.last_screen_rect;
+        self.parked.insert(self.session.active_slot().to_string(), outgoing);
+        self.session.active = index;
+
+        match self.parked.remove(self.session.active_slot()) {
+            Some(incoming) => {
+                self.canvas = incoming.canvas;
+                self.preview_manager = incoming.preview_manager;
+                self.layout_name = incoming.layout_name;
+                self.saved_fingerprint = incoming.saved_fingerprint;
+                self.dirty = self.has_unsaved_changes();
+            }
+            None => {
+                if !self.load_autosave() {
+                    self.layout_name = None;
+                    self.mark_saved();
+                }
+            }
+        }
+        self.canvas.tile_renderer = tile_renderer;
+        self.canvas.last_screen_rect = last_screen_rect;
+        self.preview_manager.set_linear_scaling(self.settings.linear_scaling);
+    }
 
-        // Save all previews
-        layout.previews = self.preview_manager.all()
-            .map(|p| PreviewLayout::from(p))
-            .collect();
-
-        layout.recent_browser_urls = self.recent_urls.clone();
-
-        layout
+    /// Open an empty workspace in a new tab
+    fn new_workspace(&mut self) {
+        let slot = self.session.unused_slot();
+        let name = format!("Workspace {}", self.session.workspaces.len() + 1);
+        self.session.workspaces.push(WorkspaceSlot { slot, name });
+        self.switch_workspace(self.session.workspaces.len() - 1);
     }
 
-    /// Apply a SavedLayout to restore state
-    fn apply_layout(&mut self, layout: &SavedLayout) {
-        // Clear existing state
-        self.preview_manager.clear();
-        self.capture_coordinator.stop_all();
-        #[cfg(windows)]
-        self.browser.clear();
+    /// Close a workspace tab: its captures stop and its autosave is deleted.
+    /// Named layouts it had open are kept. The last tab can't be closed.
+    fn close_workspace(&mut self, index: usize) {
+        if self.session.workspaces.len() < 2 || index >= self.session.workspaces.len() {
+            return;
+        }
+        if index == self.session.active {
+            self.switch_workspace(if index == 0 { 1 } else { index - 1 });
+        }
 
-        // Restore canvas state
-        self.canvas.pan = Vec2::new(layout.canvas.pan.0, layout.canvas.pan.1);
-        self.canvas.zoom = layout.canvas.zoom;
-        self.canvas.show_grid = layout.canvas.show_grid;
+        let closed = self.session.workspaces.remove(index);
+        if index < self.session.active {
+            self.session.active -= 1;
+        }
+        if let Some(workspace) = self.parked.remove(&closed.slot) {
+            // Its browser hosts go with the next frame's cleanup
+            for id in workspace.preview_manager.all_ids() {
+                self.capture_coordinator.stop_capture(id);
+            }
+        }
+        if !self.safe_mode {
+            if let Some(Err(e)) = self.storage.as_ref().map(|storage| storage.delete_autosave(&closed.slot)) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::error!("Failed to delete autosave: {}", e);
+                }
+            }
+        }
+        self.toasts.info(format!("Closed workspace \"{}\"", closed.name));
+    }
 
-        self.recent_urls = layout.recent_browser_urls.clone();
+    /// Tab strip under the title bar, shown once there's more than one
+    /// workspace. Right-click a tab to rename or close it.
+    fn workspace_tabs_ui(&mut self, ctx: &egui::Context) {
+        if self.session.workspaces.len() < 2 {
+            return;
+        }
 
-        // Enumerate current windows to find matching ones
-        let current_windows = enumerate_windows();
+        let active = self.session.active;
+        let mut switch_to = None;
+        let mut close = None;
+        let mut add = false;
 
-        // Restore previews
-        for preview_layout in &layout.previews {
-            // Browser tiles restore by recreating their WebView at the saved
-            // URL; a failed host creation skips just this tile.
-            #[cfg(windows)]
-            if let Some(url) = &preview_layout.browser_url {
-                match self.create_browser_tile(
-                    url,
-                    Pos2::new(preview_layout.position.0, preview_layout.position.1),
-                    Vec2::new(preview_layout.size.0, preview_layout.size.1),
-                    preview_layout.fps_preset,
-                ) {
-                    Ok(id) => {
-                        self.preview_manager.set_z_order(id, preview_layout.z_order);
-                        if let Some(preview) = self.preview_manager.get_mut(id) {
-                            // Restored tiles appear instantly, no spawn animation.
-                            preview.created_at = Instant::now() - Duration::from_secs(1);
+        egui::TopBottomPanel::top("workspace_tabs")
+            .frame(egui::Frame::none()
+                .fill(self.theme.background())
+                .inner_margin(egui::Margin::symmetric(8.0, 4.0)))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    for (i, workspace) in self.session.workspaces.iter_mut().enumerate() {
+                        let label = if workspace.name.trim().is_empty() {
+                            "Untitled"
+                        } else {
+                            workspace.name.as_str()
+                        };
+                        let mut tab = ui.selectable_label(i == active, label);
+                        if i < 9 {
+                            tab = tab.on_hover_text(format!("Ctrl+{}", i + 1));
+                        }
+                        if tab.clicked() {
+                            switch_to = Some(i);
                         }
-                        self.apply_browser_mute(id, preview_layout.browser_muted);
+                        tab.context_menu(|ui| {
+                            ui.label("Name");
+                            ui.text_edit_singleline(&mut workspace.name);
+                            ui.separator();
+                            if ui.button("Close Workspace").clicked() {
+                                close = Some(i);
+                                ui.close_menu();
+                            }
+                        });
                     }
-                    Err(error) => {
-                        log::error!("Failed to restore browser tile: {error}");
+                    if ui
+                        .add(egui::Button::new(egui_phosphor::regular::PLUS).frame(false))
+                        .on_hover_text("New workspace (Ctrl+T)")
+                        .clicked()
+                    {
+                        add = true;
                     }
-                }
-                continue;
-            }
+                });
+            });
 
-            // Try to find a matching window by title
-            let matching_window = current_windows.iter()
-                .find(|w| w.title == preview_layout.window_title);
+        if let Some(index) = switch_to {
+            self.switch_workspace(index);
+        }
+        if let Some(index) = close {
+            self.close_workspace(index);
+        }
+        if add {
+            self.new_workspace();
+        }
+    }
 
-            if let Some(window_info) = matching_window {
-                // Create preview with saved position/size
-                let id = self.preview_manager.add_with_window(
-                    window_info.title.clone(),
-                    Pos2::new(preview_layout.position.0, preview_layout.position.1),
-                    Vec2::new(preview_layout.size.0, preview_layout.size.1),
-                    window_info.hwnd,
-                    preview_layout.fps_preset,
-                    preview_layout.z_order,
-                );
+    /// "Untitled" or the open layout's name, for the title bar
+    fn layout_display_name(&self) -> &str {
+        self.layout_name.as_deref().unwrap_or("Untitled")
+    }
 
-                // Start capture
-                self.capture_coordinator.start_capture(
-                    id,
-                    window_info.hwnd,
-                    window_info.title.clone(),
-                    preview_layout.fps_preset.as_u32(),
-                );
+    /// Remember the canvas as it is now as saved
+    fn mark_saved(&mut self) {
+        self.saved_fingerprint = self.create_layout(String::new()).content_fingerprint();
+        self.dirty = false;
+        self.dirty_checked_at = Instant::now();
+    }
 
-                // Restore crop region if it was saved
-                if let Some(crop) = preview_layout.crop_uv {
-                    if let Some(preview) = self.preview_manager.get_mut(id) {
-                        preview.crop_uv = Some(crop);
-                    }
+    /// Does the canvas differ from the last saved or loaded layout?
+    fn has_unsaved_changes(&self) -> bool {
+        self.create_layout(String::new()).content_fingerprint() != self.saved_fingerprint
+    }
+
+    /// Follow title changes of captured windows (a browser switching tabs,
+    /// an editor opening another file). Layouts keep matching on the title
+    /// each preview was added with.
+    fn refresh_preview_titles(&mut self) {
+        if self.titles_refreshed_at.elapsed() < TITLE_REFRESH_INTERVAL {
+            return;
+        }
+        self.titles_refreshed_at = Instant::now();
+        for preview in self.preview_manager.all_mut() {
+            if preview.is_browser() || preview.capture.display {
+                continue;
+            }
+            let Some(handle) = &preview.window_handle else { continue; };
+            if let Some(title) = Native::window_title(handle.hwnd) {
+                preview.title = title;
+            }
+        }
+    }
+
+    /// Refresh the unsaved-changes marker and the OS window title
+    fn update_dirty_state(&mut self, ctx: &egui::Context) {
+        if self.dirty_checked_at.elapsed() >= DIRTY_CHECK_INTERVAL {
+            self.dirty = self.has_unsaved_changes();
+            self.dirty_checked_at = Instant::now();
+        }
+
+        let title = format!(
+            "Pluriview - {}{}",
+            self.layout_display_name(),
+            if self.dirty { "*" } else { "" }
+        );
+        if title != self.window_title {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(title.clone()));
+            self.window_title = title;
+        }
+    }
+
+    /// Replace the canvas, asking first if that would lose unsaved changes
+    fn request_change(&mut self, change: PendingChange, ctx: &egui::Context) {
+        if self.has_unsaved_changes() {
+            self.pending_change = Some(change);
+        } else {
+            self.perform_change(change, ctx);
+        }
+    }
+
+    fn perform_change(&mut self, change: PendingChange, ctx: &egui::Context) {
+        match change {
+            PendingChange::Exit => {
+                self.exit_confirmed = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+            PendingChange::Reload => {
+                self.safe_mode = false;
+                if self.load_autosave() {
+                    self.toasts.info("Layout reloaded");
+                }
+            }
+            PendingChange::ReopenLastSession => {
+                self.safe_mode = false;
+                self.load_autosave();
+            }
+            PendingChange::NewCanvas => {
+                let mut empty = SavedLayout::new(UNTITLED_AUTOSAVE.to_string());
+                empty.recent_browser_urls = self.recent_urls.clone();
+                self.apply_layout(&empty);
+                self.layout_name = None;
+                self.mark_saved();
+            }
+            PendingChange::OpenLayout(name) => {
+                self.safe_mode = false;
+                let layout = self.storage.as_ref().map(|storage| storage.load_layout(&name));
+                match layout {
+                    Some(Ok(layout)) => {
+                        self.apply_layout(&layout);
+                        self.layout_name = Some(name.clone());
+                        self.mark_saved();
+                        self.toasts.info(format!("Opened layout \"{}\"", name));
+                    }
+                    Some(Err(e)) => {
+                        log::error!("Failed to load layout: {}", e);
+                        self.toasts.error(format!("Could not open layout \"{}\"", name));
+                    }
+                    None => {}
+                }
+            }
+            PendingChange::RestartElevated => {
+                // The new instance picks the session up from the autosave,
+                // so write it before launching, and don't keep capturing
+                // alongside it. A declined prompt lifts the suspension on
+                // the next frame.
+                self.save_autosave();
+                self.capture_coordinator.set_suspended(true);
+                if crate::elevation::restart_elevated() {
+                    self.capture_coordinator.stop_all();
+                    self.exit_confirmed = true;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                } else {
+                    self.toasts.warning("Pluriview was not restarted as administrator");
+                }
+            }
+        }
+    }
+
+    /// "Save changes to layout X?" prompt for a held-back `PendingChange`
+    fn unsaved_changes_ui(&mut self, ctx: &egui::Context) {
+        if self.pending_change.is_none() {
+            return;
+        }
+        let question = match &self.layout_name {
+            Some(name) => format!("Save changes to layout \"{}\"?", name),
+            None => "Save changes to this untitled layout?".to_string(),
+        };
+        let mut choice = None;
+
+        egui::Window::new("Unsaved Changes")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style()).fill(egui::Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                ui.label(question);
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button("Don't Save").clicked() {
+                        choice = Some(false);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.pending_change = None;
+                    }
+                });
+            });
+
+        let Some(save) = choice else { return; };
+        let Some(change) = self.pending_change.take() else { return; };
+        if !save {
+            self.discard_on_exit = matches!(change, PendingChange::Exit);
+            self.perform_change(change, ctx);
+        } else if self.layout_name.is_none() {
+            // Untitled: name it first, then carry on
+            self.save_layout_name = Some(String::new());
+            self.after_save = Some(change);
+        } else if self.save_current() {
+            self.perform_change(change, ctx);
+        }
+    }
+
+    /// File > Layout Schedule: which saved layout to open at which time of day
+    fn schedule_ui(&mut self, ctx: &egui::Context) {
+        let Some(layouts) = &self.schedule_layouts else { return; };
+        let mut done = false;
+        let mut remove = None;
+
+        egui::Window::new("Layout Schedule")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style())
+                .fill(egui::Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                ui.checkbox(&mut self.settings.schedule_enabled, "Switch layouts on schedule");
+                ui.label(
+                    egui::RichText::new("A countdown appears a minute before each switch, with the option to skip it.")
+                        .size(11.0)
+                        .weak(),
+                );
+                ui.add_space(6.0);
+
+                egui::Grid::new("schedule_grid")
+                    .num_columns(3)
+                    .spacing([8.0, 6.0])
+                    .show(ui, |ui| {
+                        for (i, entry) in self.settings.schedule.iter_mut().enumerate() {
+                            let (mut hour, mut minute) = (entry.minute / 60, entry.minute % 60);
+                            ui.horizontal(|ui| {
+                                ui.spacing_mut().item_spacing.x = 2.0;
+                                let two_digits = |n: f64, _| format!("{:02}", n as u16);
+                                ui.add(egui::DragValue::new(&mut hour).range(0..=23).custom_formatter(two_digits));
+                                ui.label(":");
+                                ui.add(egui::DragValue::new(&mut minute).range(0..=59).custom_formatter(two_digits));
+                            });
+                            entry.minute = hour * 60 + minute;
+
+                            egui::ComboBox::from_id_salt(("schedule_layout", i))
+                                .width(180.0)
+                                .selected_text(entry.layout.as_str())
+                                .show_ui(ui, |ui| {
+                                    for name in layouts {
+                                        ui.selectable_value(&mut entry.layout, name.clone(), name);
+                                    }
+                                });
+
+                            if ui
+                                .add(egui::Button::new(egui_phosphor::regular::TRASH).frame(false))
+                                .on_hover_text("Remove")
+                                .clicked()
+                            {
+                                remove = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!layouts.is_empty(), egui::Button::new("Add Switch"))
+                        .on_disabled_hover_text("Save a layout first")
+                        .clicked()
+                    {
+                        self.settings.schedule.push(ScheduleEntry {
+                            minute: 9 * 60,
+                            layout: layouts[0].clone(),
+                        });
+                    }
+                    if ui.button("Done").clicked() {
+                        done = true;
+                    }
+                });
+            });
+
+        if let Some(i) = remove {
+            self.settings.schedule.remove(i);
+        }
+        if done {
+            self.schedule_layouts = None;
+            self.settings.schedule.sort_by_key(|entry| entry.minute);
+            self.save_settings();
+        }
+    }
+
+    /// File > Hidden Windows: exe names, title patterns and window classes
+    /// left out of the window picker and quick-add popup
+    fn blocklist_ui(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = self.blocklist_dialog.as_mut() else { return; };
+        let mut save = false;
+        let mut cancel = false;
+
+        egui::Window::new("Hidden Windows")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style())
+                .fill(egui::Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new("One per line. Right-click a window in the picker to hide its app.")
+                        .size(11.0)
+                        .weak(),
+                );
+                ui.add_space(6.0);
+                for (label, hint, text) in [
+                    ("Executables", "NVIDIA Share.exe", &mut dialog.exe_names),
+                    ("Titles containing (* matches anything)", "GeForce*Overlay", &mut dialog.title_patterns),
+                    ("Window classes", "CEF-OSC-WIDGET", &mut dialog.class_names),
+                ] {
+                    ui.label(label);
+                    ui.add(egui::TextEdit::multiline(text).desired_rows(3).desired_width(320.0).hint_text(hint));
+                    ui.add_space(4.0);
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        save = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if save {
+            let blocklist = dialog.blocklist();
+            self.settings.blocklist = blocklist.clone();
+            self.window_picker.blocklist = blocklist;
+            self.window_picker.refresh();
+            self.save_settings();
+        }
+        if save || cancel {
+            self.blocklist_dialog = None;
+        }
+    }
+
+    /// Countdown before a scheduled switch, with "Switch Now" and "Skip"
+    fn schedule_countdown_ui(&mut self, ctx: &egui::Context, now: u32) {
+        let Some((entry, secs)) = self.scheduler.upcoming(&self.settings.schedule, now) else { return; };
+        if self.layout_name.as_deref() == Some(entry.layout.as_str()) {
+            return;
+        }
+        let entry = entry.clone();
+        let mut switch_now = false;
+        let mut skip = false;
+
+        egui::Area::new(egui::Id::new("schedule_countdown"))
+            .anchor(egui::Align2::RIGHT_TOP, [-16.0, 48.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(egui::Color32::from_rgba_unmultiplied(24, 24, 28, 235))
+                    .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(40, 40, 46)))
+                    .rounding(8.0)
+                    .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(
+                                egui::RichText::new(egui_phosphor::regular::CLOCK)
+                                    .color(self.theme.accent()),
+                            );
+                            ui.label(
+                                egui::RichText::new(format!("Switching to \"{}\" in {} s", entry.layout, secs))
+                                    .size(12.0)
+                                    .color(egui::Color32::from_rgb(210, 210, 215)),
+                            );
+                            switch_now = ui.small_button("Switch Now").clicked();
+                            skip = ui.small_button("Skip").clicked();
+                        });
+                    });
+            });
+
+        if switch_now || skip {
+            self.scheduler.skip(&entry);
+        }
+        if switch_now {
+            self.request_change(PendingChange::OpenLayout(entry.layout), ctx);
+        } else if skip {
+            self.toasts.info(format!("Staying on this layout instead of \"{}\"", entry.layout));
+        }
+    }
+
+    /// Open the welcome screen, offering the autosave as "last session"
+    fn open_welcome(&mut self, ctx: &egui::Context) {
+        if let Some(storage) = &self.storage {
+            let has_last_session = storage
+                .load_autosave(self.session.active_slot())
+                .is_ok_and(|layout| !layout.previews.is_empty());
+            self.welcome = Some(WelcomeScreen::new(ctx, storage, has_last_session));
+        }
+    }
+
+    /// Show the welcome screen and act on the user's choice
+    fn welcome_ui(&mut self, ctx: &egui::Context) {
+        let Some(action) = self.welcome.as_ref().and_then(|welcome| welcome.ui(ctx)) else {
+            return;
+        };
+        self.welcome = None;
+
+        let change = match action {
+            WelcomeAction::NewCanvas => PendingChange::NewCanvas,
+            WelcomeAction::ReopenLastSession => PendingChange::ReopenLastSession,
+            WelcomeAction::OpenLayout(name) => PendingChange::OpenLayout(name),
+            WelcomeAction::Dismiss => return,
+        };
+        self.request_change(change, ctx);
+    }
+
+    /// "Save Layout As" dialog: saves the current canvas as a named layout
+    fn save_layout_as_ui(&mut self, ctx: &egui::Context) {
+        let Some(name) = self.save_layout_name.as_mut() else { return; };
+        let mut submit = false;
+        let mut cancel = false;
+
+        egui::Window::new("Save Layout As")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let response = ui.add_sized(
+                    [320.0, 24.0],
+                    egui::TextEdit::singleline(name).hint_text("Layout name"),
+                );
+                response.request_focus();
+                let entered = response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+                ui.horizontal(|ui| {
+                    let valid = !name.trim().is_empty();
+                    if ui.add_enabled(valid, egui::Button::new("Save")).clicked() || (entered && valid) {
+                        submit = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if submit {
+            if let Some(name) = self.save_layout_name.take() {
+                let layout = self.create_layout(name.trim().to_string());
+                let saved = self.storage.as_ref().map(|storage| storage.save_layout(&layout));
+                match saved {
+                    Some(Ok(())) => {
+                        self.toasts.success(format!("Saved layout \"{}\"", layout.name));
+                        self.layout_name = Some(layout.name);
+                        self.mark_saved();
+                        if let Some(change) = self.after_save.take() {
+                            self.perform_change(change, ctx);
+                        }
+                    }
+                    Some(Err(e)) => {
+                        log::error!("Failed to save layout: {}", e);
+                        self.toasts.error(format!("Could not save layout: {}", e));
+                        self.after_save = None;
+                    }
+                    None => self.after_save = None,
+                }
+            }
+        } else if cancel {
+            self.save_layout_name = None;
+            self.after_save = None;
+        }
+    }
+
+    /// Settings or layout export/import dialog
+    fn file_transfer_ui(&mut self, ctx: &egui::Context) {
+        let owner = self.main_hwnd;
+        let Some(dialog) = self.file_transfer.as_mut() else { return; };
+        if let Some(picked) = dialog.browse.as_ref().and_then(|browse| browse.poll()) {
+            dialog.browse = None;
+            if let Some(path) = picked {
+                dialog.path = path.display().to_string();
+            }
+        }
+        let browsing = dialog.browse.is_some();
+        if browsing {
+            // Nothing else wakes the UI when the native dialog closes
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+        let mut submit = false;
+        let mut cancel = false;
+
+        egui::Window::new(dialog.title())
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style()).fill(egui::Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                let description = match (&dialog.kind, dialog.import) {
+                    (TransferKind::Settings { .. }, true) => {
+                        "Replace this machine's settings with an exported bundle."
+                    }
+                    (TransferKind::Settings { .. }, false) => {
+                        "Bundle your settings into one file for another machine or your team."
+                    }
+                    (TransferKind::Layout, true) => {
+                        "Open a layout file from another machine or a repository; it's added to your saved layouts."
+                    }
+                    (TransferKind::Layout, false) => {
+                        "Write this canvas to a layout file to share or keep under version control."
+                    }
+                };
+                ui.label(egui::RichText::new(description).weak());
+                ui.add_space(6.0);
+                ui.add_enabled_ui(!browsing, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add_sized(
+                            [340.0, 24.0],
+                            egui::TextEdit::singleline(&mut dialog.path).hint_text("File path"),
+                        );
+                        if ui.button("Browse...").clicked() {
+                            dialog.browse(owner);
+                        }
+                    });
+                    if let TransferKind::Settings { include_layouts } = &mut dialog.kind {
+                        let layouts_label = if dialog.import {
+                            "Also import the bundle's layouts (replacing same-named ones)"
+                        } else {
+                            "Include saved layouts"
+                        };
+                        ui.checkbox(include_layouts, layouts_label);
+                    }
+                    ui.add_space(6.0);
+                    ui.horizontal(|ui| {
+                        let valid = !dialog.path.trim().is_empty();
+                        let label = if dialog.import { "Import" } else { "Export" };
+                        if ui.add_enabled(valid, egui::Button::new(label)).clicked() {
+                            submit = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancel = true;
+                        }
+                    });
+                });
+            });
+
+        if cancel {
+            self.file_transfer = None;
+        } else if submit {
+            let Some(dialog) = self.file_transfer.take() else { return; };
+            let path = std::path::PathBuf::from(dialog.path.trim());
+            match (dialog.kind, dialog.import) {
+                (TransferKind::Settings { include_layouts }, true) => self.import_settings(&path, include_layouts),
+                (TransferKind::Settings { include_layouts }, false) => self.export_settings(&path, include_layouts),
+                (TransferKind::Layout, true) => self.import_layout(&path, ctx),
+                (TransferKind::Layout, false) => self.export_layout(&path),
+            }
+        }
+    }
+
+    /// Write the canvas to a layout file outside Pluriview's data folder
+    fn export_layout(&mut self, path: &std::path::Path) {
+        let Some(storage) = &self.storage else { return; };
+        let name = self.layout_name.clone().unwrap_or_else(|| {
+            path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default()
+        });
+        let layout = self.create_layout(name);
+        match storage.export_layout(path, &layout) {
+            Ok(()) => self.toasts.success(format!("Exported layout to {}", path.display())),
+            Err(e) => {
+                log::error!("Failed to export layout: {}", e);
+                self.toasts.error(format!("Could not export layout: {}", e));
+            }
+        }
+    }
+
+    /// Add a layout file to the saved layouts (under a free name) and open it
+    fn import_layout(&mut self, path: &std::path::Path, ctx: &egui::Context) {
+        let Some(storage) = &self.storage else { return; };
+        let mut layout = match storage.import_layout(path) {
+            Ok(layout) => layout,
+            Err(e) => {
+                log::error!("Failed to import layout: {}", e);
+                self.toasts.error(format!("Could not import layout: {}", e));
+                return;
+            }
+        };
+
+        let wanted = match layout.name.trim() {
+            "" => path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default(),
+            name => name.to_string(),
+        };
+        layout.name = storage.unused_layout_name(&wanted);
+        if let Err(e) = storage.save_layout(&layout) {
+            log::error!("Failed to import layout: {}", e);
+            self.toasts.error(format!("Could not import layout: {}", e));
+            return;
+        }
+        if layout.name != wanted {
+            self.toasts.info(format!("A layout named \"{}\" exists; imported as \"{}\"", wanted, layout.name));
+        }
+        self.request_change(PendingChange::OpenLayout(layout.name), ctx);
+    }
+
+    /// Write the settings (and saved layouts) to a bundle file
+    fn export_settings(&mut self, path: &std::path::Path, include_layouts: bool) {
+        let Some(storage) = &self.storage else { return; };
+        let layouts = if include_layouts {
+            storage
+                .list_layouts()
+                .iter()
+                .filter_map(|name| storage.load_layout(name).ok())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let bundle = SettingsBundle {
+            settings: self.settings.clone(),
+            theme: self.theme,
+            layouts,
+            ..Default::default()
+        };
+        match storage.write_bundle(path, &bundle) {
+            Ok(()) => self.toasts.success(format!("Exported settings to {}", path.display())),
+            Err(e) => {
+                log::error!("Failed to export settings: {}", e);
+                self.toasts.error(format!("Could not export settings: {}", e));
+            }
+        }
+    }
+
+    /// Adopt the settings (and optionally layouts) from a bundle file
+    fn import_settings(&mut self, path: &std::path::Path, include_layouts: bool) {
+        let Some(storage) = &self.storage else { return; };
+        let bundle = match storage.read_bundle(path) {
+            Ok(bundle) => bundle,
+            Err(e) => {
+                log::error!("Failed to import settings: {}", e);
+                self.toasts.error(format!("Could not import settings: {}", e));
+                return;
+            }
+        };
+
+        let mut imported_layouts = 0;
+        if include_layouts {
+            for layout in &bundle.layouts {
+                match storage.save_layout(layout) {
+                    Ok(()) => imported_layouts += 1,
+                    Err(e) => log::error!("Failed to import layout {:?}: {}", layout.name, e),
+                }
+            }
+        }
+
+        self.theme = bundle.theme;
+        if let Err(e) = storage.save_theme(&self.theme) {
+            log::error!("Failed to save theme: {}", e);
+        }
+        self.settings = bundle.settings;
+        self.apply_settings();
+        self.save_settings();
+        self.toasts.success(if imported_layouts > 0 {
+            format!("Imported settings and {} layouts", imported_layouts)
+        } else {
+            "Imported settings".to_string()
+        });
+    }
+
+    /// Push the current settings to everything that caches them
+    fn apply_settings(&mut self) {
+        if let Some(hwnd) = self.main_hwnd {
+            privacy::set_excluded_from_capture(hwnd, self.settings.exclude_from_capture);
+        }
+        self.preview_manager.set_linear_scaling(self.settings.linear_scaling);
+        self.capture_coordinator.set_linear_scaling(self.settings.linear_scaling);
+        self.window_picker.set_recent(self.settings.recent_windows.clone());
+        self.window_picker.sort = self.settings.picker_sort;
+        self.window_picker.blocklist = self.settings.blocklist.clone();
+    }
+
+    /// Persist app-wide settings
+    fn save_settings(&mut self) {
+        if let Some(storage) = &self.storage {
+            if let Err(e) = storage.save_settings(&self.settings) {
+                log::error!("Failed to save settings: {}", e);
+                self.toasts.error(format!("Could not save settings: {}", e));
+            }
+        }
+    }
+
+    /// Jump to a bookmarked view, leaving focus mode first
+    fn go_to_bookmark(&mut self, index: usize) {
+        if index >= self.canvas.bookmarks.len() {
+            return;
+        }
+        self.canvas.exit_focus(&self.preview_manager, &mut self.capture_coordinator);
+        self.canvas.go_to_bookmark(index);
+    }
+
+    /// "Bookmarks" window: save the current view, rename, jump, delete
+    fn bookmarks_ui(&mut self, ctx: &egui::Context) {
+        if !self.show_bookmarks {
+            return;
+        }
+        let mut open = true;
+        let mut go = None;
+        let mut delete = None;
+        let mut add = false;
+
+        egui::Window::new("Bookmarks")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(320.0)
+            .frame(egui::Frame::window(&ctx.style())
+                .fill(egui::Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                if self.canvas.bookmarks.is_empty() {
+                    ui.label(egui::RichText::new("Save views you come back to, then press 1 … 9 to jump to them.").weak());
+                } else {
+                    egui::Grid::new("bookmarks_grid")
+                        .num_columns(4)
+                        .spacing([8.0, 6.0])
+                        .show(ui, |ui| {
+                            for (i, bookmark) in self.canvas.bookmarks.iter_mut().enumerate() {
+                                let key = if i < 9 { format!("{}", i + 1) } else { String::new() };
+                                ui.label(egui::RichText::new(key).weak());
+                                ui.add(egui::TextEdit::singleline(&mut bookmark.name).desired_width(160.0));
+                                if ui.button("Go").clicked() {
+                                    go = Some(i);
+                                }
+                                if ui
+                                    .add(egui::Button::new(egui_phosphor::regular::TRASH).frame(false))
+                                    .on_hover_text("Delete bookmark")
+                                    .clicked()
+                                {
+                                    delete = Some(i);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                }
+                ui.add_space(6.0);
+                if ui.button(format!("{} Bookmark This View", egui_phosphor::regular::BOOKMARK_SIMPLE)).clicked() {
+                    add = true;
+                }
+            });
+
+        if add {
+            self.canvas.add_bookmark();
+        }
+        if let Some(i) = go {
+            self.go_to_bookmark(i);
+        }
+        if let Some(i) = delete {
+            self.canvas.bookmarks.remove(i);
+        }
+        self.show_bookmarks = open;
+    }
+
+    /// "Theme" window: canvas background, grid and accent; changes show
+    /// live and are saved when the window closes
+    fn theme_ui(&mut self, ctx: &egui::Context) {
+        if !self.show_theme {
+            return;
+        }
+        let mut open = true;
+        let mut done = false;
+
+        egui::Window::new("Theme")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .frame(egui::Frame::window(&ctx.style())
+                .fill(egui::Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                egui::Grid::new("theme_grid")
+                    .num_columns(2)
+                    .spacing([12.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label("Background");
+                        ui.color_edit_button_srgb(&mut self.theme.background);
+                        ui.end_row();
+
+                        ui.label("Grid opacity");
+                        ui.add(egui::Slider::new(&mut self.theme.grid_opacity, 0..=80));
+                        ui.end_row();
+
+                        ui.label("Accent");
+                        ui.color_edit_button_srgb(&mut self.theme.accent);
+                        ui.end_row();
+                    });
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(self.theme != Theme::default(), egui::Button::new("Reset to Minimal Void"))
+                        .clicked()
+                    {
+                        self.theme = Theme::default();
+                    }
+                    if ui.button("Done").clicked() {
+                        done = true;
+                    }
+                });
+            });
+
+        if done || !open {
+            self.show_theme = false;
+            if let Some(storage) = &self.storage {
+                if let Err(e) = storage.save_theme(&self.theme) {
+                    log::error!("Failed to save theme: {}", e);
+                    self.toasts.error(format!("Could not save theme: {}", e));
+                }
+            }
+        }
+    }
+
+    /// Run the Help > Diagnostics self-tests
+    fn run_diagnostics(&self, ctx: &egui::Context) -> Vec<Check> {
+        diagnostics::run_checks(&diagnostics::Environment {
+            gpu_adapter: self.gpu_adapter.as_deref(),
+            safe_mode: self.safe_mode,
+            tray_available: self.tray_manager.is_some(),
+            storage: self.storage.as_ref(),
+            pixels_per_point: ctx.pixels_per_point(),
+        })
+    }
+
+    /// Persist the trash, if the user keeps it between sessions
+    fn save_trash(&mut self) {
+        if let (Some(storage), true) = (&self.storage, self.settings.keep_trash) {
+            if let Err(e) = storage.save_trash(&self.trash) {
+                log::error!("Failed to save trash: {}", e);
+            }
+        }
+    }
+
+    /// "Trash" window: previews removed this session (or, when kept,
+    /// earlier ones too), restorable with all their settings
+    fn trash_ui(&mut self, ctx: &egui::Context) {
+        if !self.show_trash {
+            return;
+        }
+        let mut open = true;
+        let mut restore = None;
+        let mut delete = None;
+        let mut clear = false;
+        let mut keep_toggled = false;
+        let now = unix_now();
+
+        egui::Window::new("Trash")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(420.0)
+            .frame(egui::Frame::window(&ctx.style())
+                .fill(egui::Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                if ui
+                    .checkbox(&mut self.settings.keep_trash, "Keep between sessions")
+                    .on_hover_text("Save removed previews so they can be restored after a restart")
+                    .changed()
+                {
+                    keep_toggled = true;
+                }
+                ui.add_space(6.0);
+
+                if self.trash.is_empty() {
+                    ui.label(egui::RichText::new("Removed previews appear here.").weak());
+                    return;
                 }
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    egui::Grid::new("trash_grid")
+                        .num_columns(3)
+                        .spacing([12.0, 6.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (i, entry) in self.trash.entries.iter().enumerate() {
+                                let tile = &entry.tile;
+                                ui.vertical(|ui| {
+                                    let name = tile.browser_url.as_deref().unwrap_or(&tile.window_title);
+                                    ui.label(name);
+                                    let mut details = format_age(Duration::from_secs(now.saturating_sub(entry.removed_at)));
+                                    if tile.crop_uv.is_some() {
+                                        details.push_str(" · cropped");
+                                    }
+                                    if let Some(note) = &tile.note {
+                                        details.push_str(&format!(" · \"{}\"", note));
+                                    }
+                                    ui.label(egui::RichText::new(details).size(11.0).weak());
+                                });
+                                if ui.button("Restore").clicked() {
+                                    restore = Some(i);
+                                }
+                                if ui
+                                    .add(egui::Button::new(egui_phosphor::regular::TRASH).frame(false))
+                                    .on_hover_text("Delete forever")
+                                    .clicked()
+                                {
+                                    delete = Some(i);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+                ui.add_space(6.0);
+                if ui.button("Empty Trash").clicked() {
+                    clear = true;
+                }
+            });
+
+        if let Some(i) = restore {
+            self.restore_from_trash(i);
+        }
+        if let Some(i) = delete {
+            self.trash.take(i);
+            self.save_trash();
+        }
+        if clear {
+            self.trash = Trash::default();
+            self.save_trash();
+        }
+        if keep_toggled {
+            self.save_settings();
+            if self.settings.keep_trash {
+                self.save_trash();
+            } else if let Some(storage) = &self.storage {
+                storage.delete_trash();
+            }
+        }
+        self.show_trash = open;
+    }
+
+    /// Bring a trashed preview back onto the canvas, in front
+    fn restore_from_trash(&mut self, index: usize) {
+        let Some(entry) = self.trash.entries.get(index).cloned() else { return; };
+        let Some(id) = self.restore_tile(&entry.tile, &Native::windows()) else { return; };
+        self.trash.take(index);
+        self.save_trash();
+        self.preview_manager.bring_to_front(id);
+        self.canvas.selection = vec![id];
+        if self.preview_manager.get(id).is_some_and(|p| p.is_offline()) {
+            self.toasts.warning(format!(
+                "\"{}\" isn't open; its tile waits for it",
+                entry.tile.window_title
+            ));
+        }
+    }
 
+    /// Save the canvas: to its named layout if it has one, and always to
+    /// the autosave. Returns true on success.
+    fn save_current(&mut self) -> bool {
+        if let (Some(storage), Some(name)) = (&self.storage, &self.layout_name) {
+            if let Err(e) = storage.save_layout(&self.create_layout(name.clone())) {
+                log::error!("Failed to save layout: {}", e);
+                self.toasts.error(format!("Could not save layout: {}", e));
+                return false;
+            }
+        }
+        if !self.save_autosave() {
+            return false;
+        }
+        self.mark_saved();
+        true
+    }
+
+    /// Save the current layout to autosave (under the open layout's name,
+    /// so the next start knows what it was). Returns true on success.
+    fn save_autosave(&mut self) -> bool {
+        let Some(storage) = &self.storage else { return false; };
+        let name = self.layout_name.clone().unwrap_or_else(|| UNTITLED_AUTOSAVE.to_string());
+        let layout = self.create_layout(name);
+        match storage.save_autosave(self.session.active_slot(), &layout) {
+            Ok(()) => {
                 #[cfg(debug_assertions)]
-                println!("Restored preview: {}", privacy::redact_title(&window_info.title));
-            } else {
+                println!("Saved autosave with {} previews", layout.previews.len());
+                true
+            }
+            Err(e) => {
+                log::error!("Failed to save autosave: {}", e);
+                self.toasts.error(format!("Could not save layout: {}", e));
+                false
+            }
+        }
+    }
+
+    /// Create a SavedLayout from current state
+    fn create_layout(&self, name: String) -> SavedLayout {
+        snapshot_layout(name, &self.canvas, &self.preview_manager, &self.recent_urls)
+    }
+
+    /// Apply a SavedLayout to restore state
+    fn apply_layout(&mut self, layout: &SavedLayout) {
+        // Clear existing state (parked workspaces keep their captures)
+        for id in self.preview_manager.all_ids() {
+            self.capture_coordinator.stop_capture(id);
+        }
+        #[cfg(windows)]
+        {
+            let previews = &self.preview_manager;
+            self.browser.retain(|id| previews.get(id).is_none());
+        }
+        self.preview_manager.clear();
+
+        // Restore canvas state
+        self.canvas.pan = Vec2::new(layout.canvas.pan.0, layout.canvas.pan.1);
+        self.canvas.zoom = layout.canvas.zoom;
+        self.canvas.show_grid = layout.canvas.show_grid;
+        self.canvas.fps_groups = layout.fps_groups.clone();
+        self.canvas.labels = layout.labels.clone();
+        self.canvas.ink = layout.ink.clone();
+        self.canvas.bookmarks = layout.bookmarks.clone();
+        self.canvas.widgets = layout.widgets.clone();
+        self.canvas.containers = layout.containers.clone();
+        self.canvas.close_label_editor();
+        self.canvas.clear_template();
+
+        self.recent_urls = layout.recent_browser_urls.clone();
+
+        // Enumerate current windows to find matching ones
+        let current_windows = Native::windows();
+
+        // Restore previews
+        let mut missing = 0;
+        for preview_layout in &layout.previews {
+            let restored = self.restore_tile(preview_layout, &current_windows);
+            if restored.and_then(|id| self.preview_manager.get(id)).is_some_and(|p| p.is_offline()) {
+                missing += 1;
+            }
+        }
+
+        if missing == 1 {
+            self.toasts.warning("1 window from the layout isn't open; its tile waits for it");
+        } else if missing > 1 {
+            self.toasts.warning(format!(
+                "{} windows from the layout aren't open; their tiles wait for them",
+                missing
+            ));
+        }
+
+        // Loading isn't a step to undo
+        self.canvas.reset_history(&self.preview_manager);
+    }
+
+    /// Recreate a saved preview: browser tiles get a new WebView at their
+    /// URL, window tiles are matched to an open window by executable, class
+    /// and title (display tiles to a connected display by name) and their
+    /// capture queued. A window that isn't open leaves an offline tile that
+    /// keeps its place and waits for it. None if the WebView failed.
+    fn restore_tile(&mut self, preview_layout: &PreviewLayout, current_windows: &[WindowInfo]) -> Option<PreviewId> {
+        // Browser tiles restore by recreating their WebView at the saved
+        // URL; a failed host creation skips just this tile.
+        #[cfg(windows)]
+        if let Some(url) = &preview_layout.browser_url {
+            return match self.create_browser_tile(
+                url,
+                Pos2::new(preview_layout.position.0, preview_layout.position.1),
+                Vec2::new(preview_layout.size.0, preview_layout.size.1),
+                preview_layout.fps_preset,
+            ) {
+                Ok(id) => {
+                    self.preview_manager.set_z_order(id, preview_layout.z_order);
+                    if let Some(preview) = self.preview_manager.get_mut(id) {
+                        // Restored tiles appear instantly, no spawn animation.
+                        preview.created_at = Instant::now() - Duration::from_secs(1);
+                        apply_layout_fields(preview, preview_layout);
+                    }
+                    self.apply_browser_mute(id, preview_layout.browser_muted);
+                    Some(id)
+                }
+                Err(error) => {
+                    log::error!("Failed to restore browser tile: {error}");
+                    self.toasts.error(format!("Could not restore browser tile: {error}"));
+                    None
+                }
+            };
+        }
+
+        let source = self.find_source(preview_layout, current_windows);
+        let title = source.as_ref().map_or_else(|| preview_layout.window_title.clone(), |(_, title, _)| title.clone());
+        let id = self.preview_manager.add_with_window(
+            title,
+            Pos2::new(preview_layout.position.0, preview_layout.position.1),
+            Vec2::new(preview_layout.size.0, preview_layout.size.1),
+            source.as_ref().map(|(hwnd, _, _)| *hwnd),
+            preview_layout.fps_preset,
+            preview_layout.z_order,
+        );
+        if let Some(preview) = self.preview_manager.get_mut(id) {
+            preview.capture = preview_layout.capture;
+            preview.lock_aspect_ratio = preview_layout.lock_aspect_ratio;
+            preview.window_exe = preview_layout.window_exe.clone();
+            preview.window_class = preview_layout.window_class.clone();
+            preview_layout.restore_history(preview);
+            // Restore crop region if it was saved
+            if let Some(crop) = preview_layout.crop_uv {
+                preview.crop_uv = Some(crop);
+            }
+            apply_layout_fields(preview, preview_layout);
+        }
+
+        match source {
+            Some(source) => self.attach_source(id, source),
+            None => {
                 #[cfg(debug_assertions)]
                 println!("Window not found: {}", privacy::redact_title(&preview_layout.window_title));
             }
         }
+        Some(id)
     }
+
+    /// The open window (or connected display) a saved tile showed.
+    /// Displays match by name ("Display 2"). Windows match by executable
+    /// and class first, then by title (see `best_match`), preferring
+    /// windows the canvas doesn't show yet.
+    fn find_source(&self, preview_layout: &PreviewLayout, current_windows: &[WindowInfo]) -> Option<TileSource> {
+        if preview_layout.capture.display {
+            Native::displays()
+                .into_iter()
+                .find(|d| d.name == preview_layout.window_title)
+                .map(|d| (d.handle, d.name, None))
+        } else {
+            let taken: HashSet<isize> = self
+                .preview_manager
+                .all()
+                .filter_map(|p| p.window_handle.as_ref().map(|h| h.hwnd))
+                .collect();
+            best_match(preview_layout, current_windows, &taken)
+                .map(|w| (w.hwnd, w.title.clone(), Some((w.exe_name.clone(), w.class_name.clone()))))
+        }
+    }
+
+    /// Point a restored or offline preview at the window it was matched
+    /// to and queue its capture; sessions are started a few at a time so
+    /// large layouts don't initialize dozens at once, and only once
+    /// viewport culling finds the preview on screen.
+    fn attach_source(&mut self, id: PreviewId, (hwnd, title, identity): TileSource) {
+        let Some(preview) = self.preview_manager.get_mut(id) else { return; };
+        preview.window_handle = Some(WindowHandle { hwnd, process_id: 0 });
+        // Queued paused; viewport culling resumes it once it's on screen
+        preview.capture_paused = true;
+        preview.title = title.clone();
+        preview.original_title = title.clone();
+        if let Some((exe_name, class_name)) = identity {
+            preview.window_exe = Some(exe_name);
+            preview.window_class = Some(class_name);
+        }
+        let (fps, options) = (preview.fps_preset.as_u32(), preview.capture);
+        self.capture_coordinator.queue_capture(id, hwnd, title.clone(), fps, options);
+
+        #[cfg(debug_assertions)]
+        println!("Restored preview: {}", privacy::redact_title(&title));
+    }
+
+    /// Attach offline tiles whose window (or display) has appeared since
+    fn reattach_offline_previews(&mut self) {
+        if self.offline_checked_at.elapsed() < OFFLINE_CHECK_INTERVAL {
+            return;
+        }
+        self.offline_checked_at = Instant::now();
+        let offline: Vec<(PreviewId, PreviewLayout)> = self
+            .preview_manager
+            .all()
+            .filter(|p| p.is_offline() && p.removing.is_none())
+            .map(|p| (p.id, PreviewLayout::from(p)))
+            .collect();
+        if offline.is_empty() {
+            return;
+        }
+        let current_windows = Native::windows();
+        for (id, preview_layout) in offline {
+            if let Some(source) = self.find_source(&preview_layout, &current_windows) {
+                self.attach_source(id, source);
+            }
+        }
+    }
+}
+
+/// Seconds since the Unix epoch, when a preview went to the trash
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |age| age.as_secs())
+}
+
+/// Create a SavedLayout from a workspace's canvas and previews
+fn snapshot_layout(
+    name: String,
+    canvas: &CanvasState,
+    preview_manager: &PreviewManager,
+    recent_urls: &[String],
+) -> SavedLayout {
+    let mut layout = SavedLayout::new(name);
+
+    // Save canvas state
+    layout.canvas = CanvasLayout {
+        pan: (canvas.pan.x, canvas.pan.y),
+        zoom: canvas.zoom,
+        show_grid: canvas.show_grid,
+    };
+
+    // Save all previews
+    layout.previews = preview_manager.all()
+        .map(|p| PreviewLayout::from(p))
+        .collect();
+
+    layout.recent_browser_urls = recent_urls.to_vec();
+    layout.fps_groups = canvas.fps_groups.clone();
+    layout.labels = canvas.labels.clone();
+    layout.ink = canvas.ink.clone();
+    layout.bookmarks = canvas.bookmarks.clone();
+    layout.widgets = canvas.widgets.clone();
+    layout.containers = canvas.containers.clone();
+
+    layout
 }
 
 impl eframe::App for PluriviewApp {
-    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
-        // Auto-save on exit
-        self.save_autosave();
+    fn on_exit(&mut self, gl: Option<&eframe::glow::Context>) {
+        if let (Some(renderer), Some(gl)) = (&self.canvas.tile_renderer, gl) {
+            renderer.destroy(gl);
+        }
+
+        // Auto-save on exit; in safe mode the empty canvas must not
+        // overwrite the session the user may want back. "Don't Save"
+        // restores the named layout as it was last saved instead, and
+        // leaves an untitled canvas's autosave as it was last written.
+        if !self.safe_mode {
+            let saved = self
+                .layout_name
+                .as_ref()
+                .zip(self.storage.as_ref())
+                .filter(|_| self.discard_on_exit)
+                .and_then(|(name, storage)| storage.load_layout(name).ok());
+            match saved {
+                Some(saved) => {
+                    let slot = self.session.active_slot();
+                    if let Some(Err(e)) = self.storage.as_ref().map(|storage| storage.save_autosave(slot, &saved)) {
+                        log::error!("Failed to save autosave: {}", e);
+                    }
+                }
+                None if self.discard_on_exit && self.layout_name.is_none() => {}
+                None => {
+                    self.save_autosave();
+                }
+            }
+            if let Some(storage) = &self.storage {
+                for (slot, workspace) in &self.parked {
+                    let name = workspace
+                        .layout_name
+                        .clone()
+                        .unwrap_or_else(|| UNTITLED_AUTOSAVE.to_string());
+                    let layout = snapshot_layout(
+                        name,
+                        &workspace.canvas,
+                        &workspace.preview_manager,
+                        &self.recent_urls,
+                    );
+                    if let Err(e) = storage.save_autosave(slot, &layout) {
+                        log::error!("Failed to save autosave: {}", e);
+                    }
+                }
+            }
+        }
+        if let Some(storage) = &self.storage {
+            if !self.safe_mode {
+                if let Err(e) = storage.save_session(&self.session) {
+                    log::error!("Failed to save session: {}", e);
+                }
+            }
+            storage.mark_startup_ok();
+        }
     }
 
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
@@ -940,6 +2819,9 @@ impl eframe::App for PluriviewApp {
             if let Ok(handle) = frame.window_handle() {
                 if let RawWindowHandle::Win32(win32) = handle.as_raw() {
                     self.main_hwnd = Some(win32.hwnd.get());
+                    if self.settings.exclude_from_capture {
+                        privacy::set_excluded_from_capture(win32.hwnd.get(), true);
+                    }
                 }
             }
         }
@@ -947,12 +2829,83 @@ impl eframe::App for PluriviewApp {
         // Set up tray HWND on first frame (window now exists)
         self.setup_tray_hwnd();
 
-        // Custom title bar + manual resize border (decorations are off)
-        self.handle_frameless_resize(ctx);
-        self.title_bar_ui(ctx);
+        // Survived long enough: this start doesn't count as a crash
+        if self.startup_pending_since.is_some_and(|t| t.elapsed() >= STARTUP_OK_AFTER) {
+            self.startup_pending_since = None;
+            if let Some(storage) = &self.storage {
+                storage.mark_startup_ok();
+            }
+        }
+
+        // Closing with unsaved changes asks first, untitled canvases too
+        if ctx.input(|i| i.viewport().close_requested())
+            && !self.exit_confirmed
+            && !self.safe_mode
+            && self.has_unsaved_changes()
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.pending_change = Some(PendingChange::Exit);
+        }
+        self.update_dirty_state(ctx);
+        self.refresh_preview_titles();
+        self.reattach_offline_previews();
+
+        // Custom title bar + manual resize border (decorations are off);
+        // presentation mode shows the canvas alone
+        if !self.presenting {
+            self.handle_frameless_resize(ctx);
+            self.title_bar_ui(ctx);
+            self.workspace_tabs_ui(ctx);
+        }
+
+        // Process any pending captured frames; failures become toasts
+        for error in self.capture_coordinator.process_frames(&mut self.preview_manager, ctx) {
+            let title = self
+                .preview_manager
+                .get(error.preview_id)
+                .map(|p| p.title.clone())
+                .unwrap_or_default();
+            self.toasts.warning(format!("{}: {}", title, error.message));
+        }
+
+        // Start the next few sessions queued by a layout load
+        self.capture_coordinator.start_queued();
+
+        // Power saver: throttle every capture while unfocused or on battery;
+        // the coordinator restores each preview's own rate when lifted.
+        let focused = ctx.input(|i| i.focused);
+        self.power_throttled = self.power_saver.should_throttle(self.settings.power_saver, focused);
+        self.capture_coordinator
+            .set_fps_cap(self.power_throttled.then_some(POWER_SAVER_FPS));
 
-        // Process any pending captured frames
-        self.capture_coordinator.process_frames(&mut self.preview_manager, ctx);
+        // Panic button (global hotkey or tray)
+        if panic_button::take_press() {
+            self.toggle_panic();
+        }
+        self.canvas.blanked = self.panic_active;
+        self.window_picker.set_blanked(self.panic_active);
+
+        // Nobody sees frames while minimized or hidden to the tray (or
+        // while the panic button has everything blanked), except in
+        // pop-outs, which stay on screen
+        let hidden = self.is_window_hidden(ctx);
+        self.capture_coordinator
+            .set_suspended((hidden && !self.settings.capture_while_minimized) || self.panic_active);
+        let awake = if self.panic_active { &[][..] } else { self.canvas.popped_out() };
+        self.capture_coordinator.set_awake(awake);
+
+        // Mark the preview of whatever window has focus on the desktop
+        self.canvas.desktop_focus = self.focus_tracker.poll(self.settings.typing_indicator);
+        if let Some(focus) = self.canvas.desktop_focus {
+            self.window_picker.note_foreground(focus.owner);
+        }
+        self.canvas.set_zoom_settings(&self.settings.zoom);
+        self.canvas.bounded = self.settings.bounded_canvas;
+
+        // Toasts the canvas asked for
+        if let Some(message) = self.canvas.pending_toast.take() {
+            self.toasts.info(message);
+        }
 
         // Handle pending region selection request (from context menu in canvas)
         if let Some(preview_id) = self.canvas.pending_region_select.take() {
@@ -967,6 +2920,10 @@ impl eframe::App for PluriviewApp {
             }
         }
 
+        if std::mem::take(&mut self.canvas.pending_restart_elevated) {
+            self.request_change(PendingChange::RestartElevated, ctx);
+        }
+
         // Poll for region selection result
         if let Some(ref mut selector) = self.region_selector {
             if let Some(result) = selector.poll_result() {
@@ -998,7 +2955,7 @@ impl eframe::App for PluriviewApp {
         // title bar; see `title_bar_ui` / `menu_bar`.
 
         // Minimal Void: Dark sidebar
-        if self.picker_open {
+        if self.picker_open && !self.presenting {
             egui::SidePanel::left("window_picker_panel")
                 .default_width(250.0)
                 .min_width(200.0)
@@ -1015,24 +2972,54 @@ impl eframe::App for PluriviewApp {
                     );
                 });
         }
+        // Windows added from the picker or quick-add are remembered across runs
+        if let Some(recent) = self.window_picker.take_recent_changes() {
+            self.settings.recent_windows = recent;
+            self.save_settings();
+        }
+        if self.window_picker.sort != self.settings.picker_sort {
+            self.settings.picker_sort = self.window_picker.sort;
+            self.save_settings();
+        }
+        if self.window_picker.blocklist != self.settings.blocklist {
+            self.settings.blocklist = self.window_picker.blocklist.clone();
+            self.save_settings();
+        }
+
+        if self.canvas.show_layers && !self.presenting {
+            egui::SidePanel::right("layers_panel")
+                .default_width(240.0)
+                .min_width(180.0)
+                .max_width(400.0)
+                .frame(egui::Frame::none()
+                    .fill(egui::Color32::from_rgb(18, 18, 18))
+                    .inner_margin(egui::Margin::same(8.0)))
+                .show(ctx, |ui| {
+                    self.canvas.layers_ui(ui, &mut self.preview_manager);
+                });
+        }
 
         // Minimal Void: No status bar - floating indicator is drawn in the canvas
 
         // Minimal Void: Main canvas area with dark background
         egui::CentralPanel::default()
-            .frame(egui::Frame::none().fill(egui::Color32::from_rgb(13, 13, 13)))
+            .frame(egui::Frame::none().fill(self.theme.background()))
             .show(ctx, |ui| {
+                self.canvas.theme = self.theme;
+                self.canvas.presenting = self.presenting;
                 self.canvas.ui(ui, &mut self.preview_manager, &mut self.capture_coordinator, ctx);
             });
 
         #[cfg(windows)]
         {
+            // Blanked previews can't be brought to life as a live page
             let browser_double_clicked = self
                 .canvas
                 .last_double_clicked
-                .filter(|id| self.browser.contains(*id));
+                .filter(|id| self.browser.contains(*id) && !self.panic_active);
 
             let browser_shortcut = (!ctx.wants_keyboard_input()
+                && !self.panic_active
                 && ctx.input(|input| input.modifiers.ctrl && input.key_pressed(egui::Key::B)))
             .then(|| {
                 self.canvas
@@ -1080,14 +3067,19 @@ impl eframe::App for PluriviewApp {
             self.browser_frame(ctx);
         }
 
-        // Canvas right-click "Add Window..." was selected: open the
-        // quick-add popup at that spot with a fresh window snapshot.
+        // Canvas right-click "Add Window..." was selected (or empty canvas
+        // double-clicked): open the quick-add popup at that spot with a
+        // fresh window snapshot.
         if let Some((canvas_pos, screen_pos)) = self.canvas.pending_quick_add.take() {
             self.quick_add = Some(QuickAddPopup {
                 canvas_pos,
                 screen_pos,
-                windows: enumerate_windows(),
+                windows: Native::windows()
+                    .into_iter()
+                    .filter(|w| !self.settings.blocklist.blocks(&w.exe_name, &w.title, &w.class_name))
+                    .collect(),
                 search: String::new(),
+                just_opened: true,
             });
         }
 
@@ -1110,17 +3102,40 @@ impl eframe::App for PluriviewApp {
 
             // "Undo" on a removed browser tile: recreate the WebView from
             // its saved URL (the original host window is already destroyed).
-            if let Some(info) = self.canvas.pending_browser_restore.take() {
+            for info in std::mem::take(&mut self.canvas.pending_browser_restores) {
                 if let Some(url) = info.browser_url.clone() {
                     match self.create_browser_tile(&url, info.position, info.size, info.fps_preset)
                     {
-                        Ok(id) => self.apply_browser_mute(id, info.browser_muted),
+                        Ok(id) => {
+                            self.apply_browser_mute(id, info.browser_muted);
+                            self.preview_manager.set_z_order(id, info.z_order);
+                            if let Some(preview) = self.preview_manager.get_mut(id) {
+                                apply_layout_fields(preview, &PreviewLayout::from(&info));
+                            }
+                            self.canvas.remap_preview(info.id, id);
+                            self.canvas.pending_untrash.push(info.id);
+                        }
                         Err(error) => log::error!("Failed to restore browser tile: {error}"),
                     }
                 }
             }
         }
 
+        // Removed previews go to the trash, and come out again when the
+        // removal is undone
+        let trashed = std::mem::take(&mut self.canvas.pending_trash);
+        let mut trash_changed = !trashed.is_empty();
+        let now = unix_now();
+        for (id, tile) in trashed {
+            self.trash.push(id, tile, now);
+        }
+        for id in std::mem::take(&mut self.canvas.pending_untrash) {
+            trash_changed |= self.trash.forget(id);
+        }
+        if trash_changed {
+            self.save_trash();
+        }
+
         self.quick_add_ui(ctx);
         #[cfg(windows)]
         self.add_browser_ui(ctx);
@@ -1128,22 +3143,164 @@ impl eframe::App for PluriviewApp {
         #[cfg(windows)]
         {
             let previews = &self.preview_manager;
-            self.browser.retain(|id| previews.get(id).is_some());
+            let parked = &self.parked;
+            self.browser.retain(|id| {
+                previews.get(id).is_some()
+                    || parked.values().any(|workspace| workspace.preview_manager.get(id).is_some())
+            });
         }
 
         // Handle global keyboard shortcuts (skip while typing in a text field)
         if !ctx.wants_keyboard_input() {
+            let mut present = None;
             ctx.input(|i| {
                 // G - Toggle grid
                 if i.key_pressed(egui::Key::G) && !i.modifiers.ctrl && !i.modifiers.alt {
                     self.canvas.show_grid = !self.canvas.show_grid;
                 }
+                // H - Toggle the activity heat map
+                if i.key_pressed(egui::Key::H) && !i.modifiers.ctrl && !i.modifiers.alt {
+                    self.canvas.show_heat_map = !self.canvas.show_heat_map;
+                }
+                // M - Toggle the minimap
+                if i.key_pressed(egui::Key::M) && !i.modifiers.ctrl && !i.modifiers.alt {
+                    self.canvas.show_minimap = !self.canvas.show_minimap;
+                }
+                // P - Pen tool; Esc - put it down, leave focus mode
+                if i.key_pressed(egui::Key::P) && !i.modifiers.ctrl && !i.modifiers.alt {
+                    self.canvas.toggle_pen();
+                }
+                // Esc backs out of one thing at a time: a drag in progress,
+                // then the pen, focus mode and presenting, then the selection
+                if i.key_pressed(egui::Key::Escape) && !self.canvas.cancel_drag(&mut self.preview_manager) {
+                    if self.canvas.ink_tool_active() || self.canvas.in_focus() || self.presenting {
+                        self.canvas.put_down_ink_tool();
+                        self.canvas.exit_focus(&self.preview_manager, &mut self.capture_coordinator);
+                        if self.presenting {
+                            present = Some(false);
+                        }
+                    } else {
+                        self.canvas.selection.clear();
+                    }
+                }
+                // Tab / Shift+Tab - Select the next / previous preview
+                if i.key_pressed(egui::Key::Tab) && !i.modifiers.ctrl && !i.modifiers.alt {
+                    self.canvas.cycle_selection(&self.preview_manager, !i.modifiers.shift);
+                }
+                // L - Layers panel
+                if i.key_pressed(egui::Key::L) && !i.modifiers.ctrl && !i.modifiers.alt {
+                    self.canvas.show_layers = !self.canvas.show_layers;
+                }
+                // S - Spotlight the selection
+                if i.key_pressed(egui::Key::S) && !i.modifiers.ctrl && !i.modifiers.alt {
+                    self.canvas.spotlight = !self.canvas.spotlight;
+                }
+                // Q - Loupe: 2×, 3×, 4×, then put away
+                if i.key_pressed(egui::Key::Q) && !i.modifiers.ctrl && !i.modifiers.alt {
+                    self.canvas.loupe = match self.canvas.loupe {
+                        None => Some(2.0),
+                        Some(m) if m < 4.0 => Some(m + 1.0),
+                        Some(_) => None,
+                    };
+                }
+                // I - Eyedropper
+                if i.key_pressed(egui::Key::I) && !i.modifiers.ctrl && !i.modifiers.alt {
+                    self.canvas.eyedropper = !self.canvas.eyedropper;
+                }
+                // U - Measure tool
+                if i.key_pressed(egui::Key::U) && !i.modifiers.ctrl && !i.modifiers.alt {
+                    self.canvas.measuring = !self.canvas.measuring;
+                }
+                // F11 - Presentation mode
+                if i.key_pressed(egui::Key::F11) {
+                    present = Some(!self.presenting);
+                }
                 // F1 - Show keyboard shortcuts
                 if i.key_pressed(egui::Key::F1) {
                     self.show_shortcuts = true;
                 }
             });
+            if let Some(on) = present {
+                self.set_presenting(ctx, on);
+            }
+
+            // Ctrl+Z - Undo, Ctrl+Y / Ctrl+Shift+Z - Redo
+            let (undo, redo) = ctx.input(|i| {
+                let command = i.modifiers.command && !i.modifiers.alt;
+                let z = command && i.key_pressed(egui::Key::Z);
+                (
+                    z && !i.modifiers.shift,
+                    (z && i.modifiers.shift) || (command && i.key_pressed(egui::Key::Y)),
+                )
+            });
+            if undo {
+                self.canvas.undo(&mut self.preview_manager, &mut self.capture_coordinator);
+            }
+            if redo {
+                self.canvas.redo(&mut self.preview_manager, &mut self.capture_coordinator);
+            }
+
+            // Ctrl+T - New workspace, Ctrl+1..9 - Switch workspace,
+            // 1..9 - Jump to bookmark
+            let (new_workspace, workspace_key, bookmark_key) = ctx.input(|i| {
+                const DIGITS: [egui::Key; 9] = [
+                    egui::Key::Num1, egui::Key::Num2, egui::Key::Num3,
+                    egui::Key::Num4, egui::Key::Num5, egui::Key::Num6,
+                    egui::Key::Num7, egui::Key::Num8, egui::Key::Num9,
+                ];
+                let command = i.modifiers.command && !i.modifiers.alt;
+                let plain = i.modifiers.is_none();
+                (
+                    command && i.key_pressed(egui::Key::T),
+                    DIGITS.iter().position(|&key| command && i.key_pressed(key)),
+                    DIGITS.iter().position(|&key| plain && i.key_pressed(key)),
+                )
+            });
+            if new_workspace {
+                self.new_workspace();
+            }
+            if let Some(index) = workspace_key {
+                self.switch_workspace(index);
+            }
+            if let Some(index) = bookmark_key {
+                self.go_to_bookmark(index);
+            }
+        }
+
+        // Layout schedule: count down, then open the scheduled layout (the
+        // scheduler keeps polling while off so re-enabling doesn't fire
+        // switches that passed in between)
+        let now = schedule::local_seconds_of_day();
+        let due = self.scheduler.poll(&self.settings.schedule, now).cloned();
+        if self.settings.schedule_enabled && !self.safe_mode {
+            if let Some(entry) = due.filter(|entry| self.layout_name.as_deref() != Some(entry.layout.as_str())) {
+                self.toasts.info(format!("Scheduled switch to \"{}\"", entry.layout));
+                self.request_change(PendingChange::OpenLayout(entry.layout), ctx);
+            }
+            self.schedule_countdown_ui(ctx, now);
+        }
+
+        self.welcome_ui(ctx);
+        self.save_layout_as_ui(ctx);
+        self.schedule_ui(ctx);
+        self.blocklist_ui(ctx);
+        self.unsaved_changes_ui(ctx);
+        self.file_transfer_ui(ctx);
+        self.trash_ui(ctx);
+        self.bookmarks_ui(ctx);
+        self.theme_ui(ctx);
+        match diagnostics::diagnostics_ui(ctx, &mut self.diagnostics) {
+            Some(DiagnosticsAction::RunAgain) => self.diagnostics = Some(self.run_diagnostics(ctx)),
+            Some(DiagnosticsAction::Copied) => self.toasts.success("Diagnostics report copied"),
+            None => {}
         }
+        health::health_ui(
+            ctx,
+            &mut self.show_health,
+            &mut self.capture_coordinator,
+            &mut self.preview_manager,
+        );
+        self.toasts.show(ctx);
 
         // About dialog
         if self.show_about {
@@ -1192,17 +3349,81 @@ impl eframe::App for PluriviewApp {
                             ui.end_row();
 
                             ui.label("Pan canvas");
-                            ui.label(egui::RichText::new("Middle Mouse / Alt+Drag").weak());
+                            ui.label(egui::RichText::new("Middle Mouse / Alt+Drag / Space+Drag").weak());
                             ui.end_row();
 
                             ui.label("Zoom");
                             ui.label(egui::RichText::new("Scroll Wheel").weak());
                             ui.end_row();
 
+                            ui.label("Zoom in / out / 100%");
+                            ui.label(egui::RichText::new("Ctrl+Plus / Ctrl+Minus / Ctrl+0").weak());
+                            ui.end_row();
+
+                            ui.label("Zoom to fit all previews");
+                            ui.label(egui::RichText::new("Home").weak());
+                            ui.end_row();
+
                             ui.label("Toggle grid");
                             ui.label(egui::RichText::new("G").weak());
                             ui.end_row();
 
+                            ui.label("Activity heat map");
+                            ui.label(egui::RichText::new("H").weak());
+                            ui.end_row();
+
+                            ui.label("Minimap");
+                            ui.label(egui::RichText::new("M").weak());
+                            ui.end_row();
+
+                            ui.label("Focus selected preview");
+                            ui.label(egui::RichText::new("F / Shift+double-click (Esc to return)").weak());
+                            ui.end_row();
+
+                            ui.label("Layers panel");
+                            ui.label(egui::RichText::new("L").weak());
+                            ui.end_row();
+
+                            ui.label("Spotlight selection");
+                            ui.label(egui::RichText::new("S").weak());
+                            ui.end_row();
+
+                            ui.label("Loupe (press again for 3×, 4×, off)");
+                            ui.label(egui::RichText::new("Q").weak());
+                            ui.end_row();
+
+                            ui.label("Eyedropper (click copies the color)");
+                            ui.label(egui::RichText::new("I").weak());
+                            ui.end_row();
+
+                            ui.label("Measure in source pixels (Shift: straight)");
+                            ui.label(egui::RichText::new("U").weak());
+                            ui.end_row();
+
+                            ui.label("Pen tool");
+                            ui.label(egui::RichText::new("P (Esc to stop)").weak());
+                            ui.end_row();
+
+                            ui.label("Jump to bookmark");
+                            ui.label(egui::RichText::new("1 … 9").weak());
+                            ui.end_row();
+
+                            ui.label("Presentation mode");
+                            ui.label(egui::RichText::new("F11 (Esc to exit)").weak());
+                            ui.end_row();
+
+                            ui.label("Blank all previews");
+                            ui.label(egui::RichText::new(format!("{} (anywhere)", PANIC_HOTKEY_LABEL)).weak());
+                            ui.end_row();
+
+                            ui.label("New workspace");
+                            ui.label(egui::RichText::new("Ctrl+T").weak());
+                            ui.end_row();
+
+                            ui.label("Switch workspace");
+                            ui.label(egui::RichText::new("Ctrl+1 … Ctrl+9").weak());
+                            ui.end_row();
+
                             ui.add_space(10.0);
                             ui.end_row();
 
@@ -1210,6 +3431,14 @@ impl eframe::App for PluriviewApp {
                             ui.label("");
                             ui.end_row();
 
+                            ui.label("Add a window here");
+                            ui.label(egui::RichText::new("Double-click empty canvas").weak());
+                            ui.end_row();
+
+                            ui.label("Pick several windows to add at once");
+                            ui.label(egui::RichText::new("Ctrl+Click in the window list").weak());
+                            ui.end_row();
+
                             ui.label("Select all");
                             ui.label(egui::RichText::new("Ctrl+A").weak());
                             ui.end_row();
@@ -1218,6 +3447,14 @@ impl eframe::App for PluriviewApp {
                             ui.label(egui::RichText::new("Ctrl+Click").weak());
                             ui.end_row();
 
+                            ui.label("Select next / previous preview");
+                            ui.label(egui::RichText::new("Tab / Shift+Tab").weak());
+                            ui.end_row();
+
+                            ui.label("Clear selection / cancel a drag");
+                            ui.label(egui::RichText::new("Esc").weak());
+                            ui.end_row();
+
                             ui.label("Delete selected");
                             ui.label(egui::RichText::new("Delete").weak());
                             ui.end_row();
@@ -1229,6 +3466,18 @@ impl eframe::App for PluriviewApp {
                             ui.label("");
                             ui.end_row();
 
+                            ui.label("Undo / redo");
+                            ui.label(egui::RichText::new("Ctrl+Z / Ctrl+Y").weak());
+                            ui.end_row();
+
+                            ui.label("Duplicate selected");
+                            ui.label(egui::RichText::new("Ctrl+D").weak());
+                            ui.end_row();
+
+                            ui.label("Copy / paste selected");
+                            ui.label(egui::RichText::new("Ctrl+C / Ctrl+V").weak());
+                            ui.end_row();
+
                             ui.label("Resize preview");
                             ui.label(egui::RichText::new("Drag corners/edges").weak());
                             ui.end_row();
@@ -1237,6 +3486,30 @@ impl eframe::App for PluriviewApp {
                             ui.label(egui::RichText::new("Alt+Drag corners").weak());
                             ui.end_row();
 
+                            ui.label("Swap two previews");
+                            ui.label(egui::RichText::new("Shift+Drag onto the other").weak());
+                            ui.end_row();
+
+                            ui.label("Nudge by 1 / by the grid size");
+                            ui.label(egui::RichText::new("Arrows / Shift+Arrows").weak());
+                            ui.end_row();
+
+                            ui.label("Grid step (onto the next grid line)");
+                            ui.label(egui::RichText::new("Ctrl+Arrows").weak());
+                            ui.end_row();
+
+                            ui.label("Rotate 90°");
+                            ui.label(egui::RichText::new("R / Shift+R").weak());
+                            ui.end_row();
+
+                            ui.label("Raise / lower");
+                            ui.label(egui::RichText::new("Page Up / Page Down").weak());
+                            ui.end_row();
+
+                            ui.label("Bring to front / send to back");
+                            ui.label(egui::RichText::new("Ctrl+Page Up / Ctrl+Page Down").weak());
+                            ui.end_row();
+
                             ui.label("Context menu");
                             ui.label(egui::RichText::new("Right-click").weak());
                             ui.end_row();
@@ -1287,7 +3560,10 @@ impl eframe::App for PluriviewApp {
         // otherwise we tick slowly, which is still frequent enough to process
         // tray events while keeping the app near-idle on the CPU.
         // (egui repaints immediately on input regardless of this hint.)
-        let repaint_after = if self.capture_coordinator.has_live_capture() {
+        // Power saver also drops the UI to a slow tick.
+        let repaint_after = if self.power_throttled {
+            std::time::Duration::from_millis(1000)
+        } else if self.capture_coordinator.has_live_capture() {
             std::time::Duration::from_millis(16)
         } else {
             std::time::Duration::from_millis(250)
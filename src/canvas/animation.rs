@@ -1,4 +1,4 @@
-use eframe::egui::{Pos2, Vec2};
+use eframe::egui::{Pos2, Rect, Vec2};
 use std::collections::HashMap;
 use crate::preview::PreviewId;
 
@@ -15,6 +15,9 @@ pub struct SpringValue {
     pub stiffness: f32,
     /// Damping factor (0.0-1.0, higher = less bouncy)
     pub damping: f32,
+    /// Within this of the target (and nearly still) the spring snaps to
+    /// it. Half a point suits positions; zoom needs finer.
+    pub tolerance: f32,
 }
 
 impl SpringValue {
@@ -25,9 +28,16 @@ impl SpringValue {
             velocity: 0.0,
             stiffness: 0.08,  // Very smooth, subtle movement
             damping: 0.65,    // Heavy damping, almost no bounce
+            tolerance: 0.5,
         }
     }
 
+    /// Snap to the target from closer in, for values on a small scale
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
     #[allow(dead_code)]
     pub fn with_params(initial: f32, stiffness: f32, damping: f32) -> Self {
         Self {
@@ -36,6 +46,7 @@ impl SpringValue {
             velocity: 0.0,
             stiffness,
             damping,
+            tolerance: 0.5,
         }
     }
 
@@ -56,7 +67,7 @@ impl SpringValue {
         self.current += self.velocity;
 
         // Snap to target when close enough (prevents infinite tiny oscillations)
-        if displacement.abs() < 0.5 && self.velocity.abs() < 0.1 {
+        if displacement.abs() < self.tolerance && self.velocity.abs() < self.tolerance / 5.0 {
             self.current = self.target;
             self.velocity = 0.0;
         }
@@ -76,7 +87,7 @@ impl SpringValue {
 
     /// Check if currently animating
     pub fn is_animating(&self) -> bool {
-        (self.target - self.current).abs() > 0.5 || self.velocity.abs() > 0.1
+        (self.target - self.current).abs() > self.tolerance || self.velocity.abs() > self.tolerance / 5.0
     }
 
     /// Add velocity (for momentum)
@@ -113,7 +124,6 @@ impl SpringVec2 {
         self.y.update(dt);
     }
 
-    #[allow(dead_code)]
     pub fn current(&self) -> Vec2 {
         Vec2::new(self.x.current, self.y.current)
     }
@@ -122,7 +132,6 @@ impl SpringVec2 {
         Pos2::new(self.x.current, self.y.current)
     }
 
-    #[allow(dead_code)]
     pub fn set_target(&mut self, target: Vec2) {
         self.x.set_target(target.x);
         self.y.set_target(target.y);
@@ -133,7 +142,6 @@ impl SpringVec2 {
         self.y.set_target(target.y);
     }
 
-    #[allow(dead_code)]
     pub fn set_immediate(&mut self, value: Vec2) {
         self.x.set_immediate(value.x);
         self.y.set_immediate(value.y);
@@ -230,6 +238,20 @@ pub struct SnapConfig {
     pub grid_size: f32,
     /// Distance threshold for snapping (in canvas units)
     pub snap_threshold: f32,
+    /// Align edges and centers with other previews while dragging/resizing
+    pub guides_enabled: bool,
+    /// Distance threshold for aligning to other previews (in screen points,
+    /// so it feels the same at any zoom)
+    pub guide_threshold: f32,
+}
+
+/// A line where a dragged preview lines up with a neighbour
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Guide {
+    /// At canvas x, spanning y from..to
+    Vertical { x: f32, from: f32, to: f32 },
+    /// At canvas y, spanning x from..to
+    Horizontal { y: f32, from: f32, to: f32 },
 }
 
 impl Default for SnapConfig {
@@ -238,6 +260,8 @@ impl Default for SnapConfig {
             enabled: true,
             grid_size: 50.0,
             snap_threshold: 15.0,  // Weaker snap - only very close to grid
+            guides_enabled: true,
+            guide_threshold: 6.0,
         }
     }
 }
@@ -262,6 +286,52 @@ impl SnapConfig {
         }
     }
 
+    /// Smallest shift that puts one of `lines` (x positions when
+    /// `vertical`, else y) on an edge or center of another rect, if one is
+    /// within the threshold.
+    pub fn alignment_shift(&self, lines: &[f32], others: &[Rect], vertical: bool, zoom: f32) -> Option<f32> {
+        if !self.guides_enabled {
+            return None;
+        }
+        let threshold = self.guide_threshold / zoom;
+        let mut best: Option<f32> = None;
+        for other in others {
+            for target in alignment_lines(*other, vertical) {
+                for line in lines {
+                    let shift = target - line;
+                    if shift.abs() <= threshold && best.is_none_or(|b| shift.abs() < b.abs()) {
+                        best = Some(shift);
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Guides for every edge or center of `rect` that lines up with one of
+    /// `others`, each spanning both rects.
+    pub fn guides(&self, rect: Rect, others: &[Rect]) -> Vec<Guide> {
+        let mut guides = Vec::new();
+        if !self.guides_enabled {
+            return guides;
+        }
+        for other in others {
+            for x in alignment_lines(rect, true) {
+                if alignment_lines(*other, true).iter().any(|t| (t - x).abs() < 0.5) {
+                    let (from, to) = (rect.min.y.min(other.min.y), rect.max.y.max(other.max.y));
+                    guides.push(Guide::Vertical { x, from, to });
+                }
+            }
+            for y in alignment_lines(rect, false) {
+                if alignment_lines(*other, false).iter().any(|t| (t - y).abs() < 0.5) {
+                    let (from, to) = (rect.min.x.min(other.min.x), rect.max.x.max(other.max.x));
+                    guides.push(Guide::Horizontal { y, from, to });
+                }
+            }
+        }
+        guides
+    }
+
     /// Always snap to nearest grid position
     #[allow(dead_code)]
     pub fn force_snap(&self, pos: Pos2) -> Pos2 {
@@ -271,12 +341,24 @@ impl SnapConfig {
     }
 }
 
+/// Edges and center of a rect on one axis (x when `vertical`)
+fn alignment_lines(rect: Rect, vertical: bool) -> [f32; 3] {
+    if vertical {
+        [rect.min.x, rect.center().x, rect.max.x]
+    } else {
+        [rect.min.y, rect.center().y, rect.max.y]
+    }
+}
+
 /// Animation state for the canvas
 #[derive(Clone, Debug, Default)]
 pub struct AnimationState {
     /// Spring animations for each preview's position
     pub preview_springs: HashMap<PreviewId, SpringVec2>,
 
+    /// Spring animations for preview sizes (only while arranging)
+    pub size_springs: HashMap<PreviewId, SpringVec2>,
+
     /// Spring animation for canvas pan
     pub pan_spring: Option<SpringVec2>,
 
@@ -303,6 +385,7 @@ impl AnimationState {
     pub fn new() -> Self {
         Self {
             preview_springs: HashMap::new(),
+            size_springs: HashMap::new(),
             pan_spring: None,
             zoom_spring: None,
             drag_tracker: DragTracker::new(),
@@ -320,16 +403,21 @@ impl AnimationState {
         })
     }
 
+    /// Get or create the size spring for a preview
+    pub fn get_or_create_size_spring(&mut self, id: PreviewId, initial_size: Vec2) -> &mut SpringVec2 {
+        self.size_springs.entry(id).or_insert_with(|| SpringVec2::new(initial_size))
+    }
+
     /// Remove spring for a preview (when preview is deleted)
-    #[allow(dead_code)]
     pub fn remove_spring(&mut self, id: PreviewId) {
         self.preview_springs.remove(&id);
+        self.size_springs.remove(&id);
     }
 
     /// Update all animations (call each frame)
     pub fn update(&mut self, dt: f32) {
         // Update preview springs
-        for spring in self.preview_springs.values_mut() {
+        for spring in self.preview_springs.values_mut().chain(self.size_springs.values_mut()) {
             spring.update(dt);
         }
 
@@ -360,6 +448,7 @@ impl AnimationState {
     pub fn is_animating(&self) -> bool {
         self.momentum_active
             || self.preview_springs.values().any(|s| s.is_animating())
+            || self.size_springs.values().any(|s| s.is_animating())
             || self.pan_spring.as_ref().map(|s| s.is_animating()).unwrap_or(false)
             || self.zoom_spring.as_ref().map(|s| s.is_animating()).unwrap_or(false)
     }
@@ -376,3 +465,30 @@ impl AnimationState {
         self.momentum_velocity
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Guide, SnapConfig};
+    use eframe::egui::{Pos2, Rect};
+
+    #[test]
+    fn aligns_to_the_nearest_neighbour_edge() {
+        let snap = SnapConfig::default();
+        let other = Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(100.0, 100.0));
+        let moving = Rect::from_min_max(Pos2::new(103.0, 240.0), Pos2::new(203.0, 300.0));
+
+        // Left edge 3 units right of the neighbour's right edge
+        let xs = [moving.min.x, moving.center().x, moving.max.x];
+        assert_eq!(snap.alignment_shift(&xs, &[other], true, 1.0), Some(-3.0));
+        // Threshold is in screen points: zoomed out, it reaches further
+        let far = [110.0];
+        assert_eq!(snap.alignment_shift(&far, &[other], true, 1.0), None);
+        assert_eq!(snap.alignment_shift(&far, &[other], true, 0.5), Some(-10.0));
+
+        let snapped = moving.translate(eframe::egui::Vec2::new(-3.0, 0.0));
+        assert_eq!(
+            snap.guides(snapped, &[other]),
+            vec![Guide::Vertical { x: 100.0, from: 0.0, to: 300.0 }]
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use eframe::egui::{Pos2, Rect, Vec2};
+use crate::persistence::Arrangement;
+
+/// Space between arranged tiles, in canvas units
+pub const ARRANGE_GAP: f32 = 16.0;
+
+/// A short last row is widened to the grid's width, but never scaled past
+/// this, so one leftover tile doesn't come out huge.
+const MAX_ROW_STRETCH: f32 = 1.5;
+
+/// Pack tiles with the given aspect ratios (width / height) into a roughly
+/// square grid, in order, starting at `origin`. Rows are justified to a
+/// common width by scaling their height, so every tile keeps its aspect.
+pub fn grid_layout(aspects: &[f32], origin: Pos2, row_height: f32, gap: f32) -> Vec<Rect> {
+    if aspects.is_empty() {
+        return Vec::new();
+    }
+
+    let rows = ((aspects.len() as f32).sqrt().round() as usize).max(1);
+    let per_row = aspects.len().div_ceil(rows);
+    let aspect_sum = |row: &[f32]| row.iter().map(|a| a.max(0.1)).sum::<f32>();
+    let gaps = |row: &[f32]| gap * (row.len() - 1) as f32;
+    let width = aspects
+        .chunks(per_row)
+        .map(|row| aspect_sum(row) * row_height + gaps(row))
+        .fold(0.0, f32::max);
+
+    let mut rects = Vec::with_capacity(aspects.len());
+    let mut y = origin.y;
+    for row in aspects.chunks(per_row) {
+        let height = ((width - gaps(row)) / aspect_sum(row)).min(row_height * MAX_ROW_STRETCH);
+        let mut x = origin.x;
+        for aspect in row {
+            let size = Vec2::new(aspect.max(0.1) * height, height);
+            rects.push(Rect::from_min_size(Pos2::new(x, y), size));
+            x += size.x + gap;
+        }
+        y += height + gap;
+    }
+    rects
+}
+
+/// Cells for `count` tiles filling `area`. A grid takes whichever number
+/// of columns shows 16:9 tiles largest.
+pub fn container_cells(area: Rect, count: usize, arrangement: Arrangement, gap: f32) -> Vec<Rect> {
+    if count == 0 {
+        return Vec::new();
+    }
+    let cell_size = |columns: usize| {
+        let rows = count.div_ceil(columns);
+        Vec2::new(
+            (area.width() - gap * (columns - 1) as f32) / columns as f32,
+            (area.height() - gap * (rows - 1) as f32) / rows as f32,
+        )
+        .max(Vec2::splat(1.0))
+    };
+    let columns = match arrangement {
+        Arrangement::Rows => 1,
+        Arrangement::Columns => count,
+        Arrangement::Grid => (1..=count)
+            .max_by(|&a, &b| {
+                let shown = |size: Vec2| size.x.min(size.y * 16.0 / 9.0);
+                shown(cell_size(a)).total_cmp(&shown(cell_size(b)))
+            })
+            .unwrap_or(1),
+    };
+    let size = cell_size(columns);
+    (0..count)
+        .map(|i| {
+            let (row, column) = (i / columns, i % columns);
+            let min = area.min + Vec2::new(column as f32 * (size.x + gap), row as f32 * (size.y + gap));
+            Rect::from_min_size(min, size)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{container_cells, grid_layout};
+    use crate::persistence::Arrangement;
+    use eframe::egui::{Pos2, Rect, Vec2};
+
+    #[test]
+    fn full_rows_share_a_width_and_keep_aspects() {
+        let aspects = [16.0 / 9.0, 4.0 / 3.0, 1.0, 16.0 / 9.0];
+        let rects = grid_layout(&aspects, Pos2::new(10.0, 20.0), 100.0, 8.0);
+        assert_eq!(rects.len(), 4);
+        assert_eq!(rects[0].min, Pos2::new(10.0, 20.0));
+
+        for (rect, aspect) in rects.iter().zip(aspects) {
+            assert!((rect.aspect_ratio() - aspect).abs() < 1e-3);
+        }
+        // Two rows of two, right edges lined up
+        assert!((rects[1].max.x - rects[3].max.x).abs() < 1e-3);
+        assert!(rects[2].min.y >= rects[0].max.y + 8.0 - 1e-3);
+        for (i, a) in rects.iter().enumerate() {
+            for b in &rects[i + 1..] {
+                assert!(!a.intersects(*b));
+            }
+        }
+    }
+
+    #[test]
+    fn lone_last_tile_is_not_blown_up() {
+        let rects = grid_layout(&[1.0, 1.0, 1.0], Pos2::ZERO, 100.0, 0.0);
+        assert_eq!(rects[0].height(), 100.0);
+        assert_eq!(rects[2].height(), 150.0);
+    }
+
+    #[test]
+    fn container_cells_fill_the_area() {
+        let area = Rect::from_min_size(Pos2::new(0.0, 0.0), Vec2::new(1650.0, 460.0));
+        let rows = container_cells(area, 2, Arrangement::Rows, 10.0);
+        assert_eq!(rows[1], Rect::from_min_size(Pos2::new(0.0, 235.0), Vec2::new(1650.0, 225.0)));
+        let columns = container_cells(area, 2, Arrangement::Columns, 10.0);
+        assert_eq!(columns[1].min, Pos2::new(830.0, 0.0));
+        // A wide area puts four tiles side by side rather than two by two
+        let grid = container_cells(area, 4, Arrangement::Grid, 10.0);
+        assert_eq!(grid[3].min.y, 0.0);
+        assert_eq!(grid[3].max.x, 1650.0);
+    }
+}
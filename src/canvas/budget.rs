@@ -0,0 +1,36 @@
+/// Split an FPS group's budget among its previews, which asked for `wants`
+/// each. Nobody gets more than they asked for, what they leave over is
+/// shared by the rest, and every preview keeps at least 1 FPS even when
+/// the budget is smaller than the group.
+pub fn divide_budget(budget: u32, wants: &[u32]) -> Vec<u32> {
+    let mut shares = vec![0; wants.len()];
+    let mut order: Vec<usize> = (0..wants.len()).collect();
+    order.sort_by_key(|&i| wants[i]);
+
+    let mut left = budget;
+    for (served, &i) in order.iter().enumerate() {
+        let waiting = (wants.len() - served) as u32;
+        let share = (left / waiting).min(wants[i]).max(1);
+        shares[i] = share;
+        left = left.saturating_sub(share);
+    }
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::divide_budget;
+
+    #[test]
+    fn modest_previews_leave_more_for_the_rest() {
+        assert_eq!(divide_budget(10, &[60, 60]), vec![5, 5]);
+        assert_eq!(divide_budget(10, &[60, 2, 60]), vec![4, 2, 4]);
+        assert_eq!(divide_budget(100, &[15, 30]), vec![15, 30]);
+    }
+
+    #[test]
+    fn every_preview_keeps_a_frame_per_second() {
+        assert_eq!(divide_budget(2, &[30, 30, 30, 30]), vec![1, 1, 1, 1]);
+        assert!(divide_budget(10, &[]).is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+use eframe::egui::{self, Color32, CursorIcon, Pos2, Rect, Sense, Stroke, Vec2};
+use crate::persistence::{Arrangement, Container};
+use crate::preview::{Preview, PreviewId, PreviewManager};
+use super::arrange::{container_cells, ARRANGE_GAP};
+use super::layouts::fit_in_slot;
+use super::state::{median_height, CanvasState, TILE_ROUNDING};
+
+/// Height of a container frame's title bar, in canvas units
+pub(super) const CONTAINER_HEADER: f32 = 32.0;
+
+impl CanvasState {
+    /// Container frames: drag the title bar to move one with its previews,
+    /// the corner grip to resize it; right-click for the arrangement
+    pub(super) fn draw_and_interact_containers(&mut self, ui: &mut egui::Ui, canvas_rect: Rect, preview_manager: &mut PreviewManager) {
+        if self.presenting {
+            return;
+        }
+        let painter = ui.painter_at(canvas_rect);
+        let mut removed = None;
+
+        for index in 0..self.containers.len() {
+            let container = &self.containers[index];
+            let rect = Rect::from_min_max(
+                self.canvas_to_screen(container_rect(container).min, canvas_rect),
+                self.canvas_to_screen(container_rect(container).max, canvas_rect),
+            );
+            if !canvas_rect.intersects(rect) {
+                continue;
+            }
+            let header = Rect::from_min_size(rect.min, Vec2::new(rect.width(), CONTAINER_HEADER * self.zoom));
+
+            let response = ui.interact(header, ui.id().with(("container", index)), Sense::click_and_drag());
+            let grip = Rect::from_min_max(rect.max - Vec2::splat(14.0), rect.max);
+            let grip_response = ui.interact(grip, ui.id().with(("container_grip", index)), Sense::drag());
+            let active = response.hovered() || response.dragged() || grip_response.hovered() || grip_response.dragged();
+
+            painter.rect_filled(rect, TILE_ROUNDING, self.theme.accent_alpha(10));
+            painter.rect_stroke(
+                rect,
+                TILE_ROUNDING,
+                Stroke::new(1.0, self.theme.accent_alpha(if active { 160 } else { 70 })),
+            );
+            let font_size = CONTAINER_HEADER * self.zoom * 0.45;
+            if font_size >= 6.0 {
+                painter.text(
+                    header.left_center() + Vec2::new(CONTAINER_HEADER * self.zoom * 0.35, 0.0),
+                    egui::Align2::LEFT_CENTER,
+                    &container.name,
+                    egui::FontId::proportional(font_size),
+                    Color32::from_gray(180),
+                );
+            }
+            if grip_response.hovered() || grip_response.dragged() {
+                ui.ctx().set_cursor_icon(CursorIcon::ResizeNwSe);
+            } else if response.hovered() || response.dragged() {
+                ui.ctx().set_cursor_icon(if response.dragged() { CursorIcon::Grabbing } else { CursorIcon::Grab });
+            }
+
+            let name = container.name.clone();
+            if grip_response.dragged() {
+                let delta = grip_response.drag_delta() / self.zoom;
+                let container = &mut self.containers[index];
+                container.size = (
+                    (container.size.0 + delta.x).max(CONTAINER_HEADER * 4.0),
+                    (container.size.1 + delta.y).max(CONTAINER_HEADER * 3.0),
+                );
+                self.reflow_containers = true;
+            } else if response.dragged() {
+                let delta = response.drag_delta() / self.zoom;
+                let container = &mut self.containers[index];
+                container.position = (container.position.0 + delta.x, container.position.1 + delta.y);
+                for preview in preview_manager.all_mut().filter(|p| p.container.as_deref() == Some(name.as_str())) {
+                    preview.position += delta;
+                    if let Some(spring) = self.animation.preview_springs.get_mut(&preview.id) {
+                        spring.set_immediate_pos(preview.position);
+                    }
+                }
+            }
+
+            let menu = response.context_menu(|ui| {
+                // Renamed on Enter (or leaving the field); a name that's
+                // empty or another frame's goes back to the old one
+                if !self.container_rename.as_ref().is_some_and(|(of, _)| *of == name) {
+                    self.container_rename = Some((name.clone(), name.clone()));
+                }
+                let Some((_, draft)) = &mut self.container_rename else { return; };
+                if ui.text_edit_singleline(draft).lost_focus() {
+                    let renamed = draft.trim().to_string();
+                    self.container_rename = None;
+                    let taken = self.containers.iter().any(|c| c.name == renamed)
+                        || preview_manager.all().any(|p| p.container.as_deref() == Some(renamed.as_str()));
+                    if !renamed.is_empty() && !taken {
+                        for preview in preview_manager.all_mut().filter(|p| p.container.as_deref() == Some(name.as_str())) {
+                            preview.container = Some(renamed.clone());
+                        }
+                        self.containers[index].name = renamed;
+                    }
+                }
+                ui.separator();
+                for arrangement in Arrangement::ALL {
+                    let current = self.containers[index].arrangement == arrangement;
+                    if ui.selectable_label(current, arrangement.label()).clicked() {
+                        self.containers[index].arrangement = arrangement;
+                        self.reflow_containers = true;
+                        ui.close_menu();
+                    }
+                }
+                ui.separator();
+                if ui.button("Remove Frame").on_hover_text("The previews stay where they are").clicked() {
+                    removed = Some(index);
+                    ui.close_menu();
+                }
+            });
+            if menu.is_none() && self.container_rename.as_ref().is_some_and(|(of, _)| *of == name) {
+                self.container_rename = None;
+            }
+        }
+
+        if let Some(index) = removed {
+            let container = self.containers.remove(index);
+            for preview in preview_manager.all_mut().filter(|p| p.container.as_ref() == Some(&container.name)) {
+                preview.container = None;
+            }
+        }
+    }
+
+    /// Tile each container's previews inside it, in reading order, when
+    /// one joins or leaves or the container changes. Waits for drags to end.
+    pub(super) fn layout_containers(&mut self, preview_manager: &PreviewManager) {
+        if self.preview_dragging || self.drag_state.is_some() {
+            return;
+        }
+        let force = std::mem::take(&mut self.reflow_containers);
+        for container in self.containers.clone() {
+            let mut members: Vec<&Preview> = preview_manager
+                .all()
+                .filter(|p| p.container.as_ref() == Some(&container.name) && p.removing.is_none() && !p.hidden)
+                .collect();
+            let mut ids: Vec<PreviewId> = members.iter().map(|p| p.id).collect();
+            ids.sort_by_key(|id| id.0);
+            if !force && self.container_members.get(&container.name) == Some(&ids) {
+                continue;
+            }
+
+            let row_height = median_height(&members);
+            members.sort_by(|a, b| {
+                let (a, b) = (a.rect().center(), b.rect().center());
+                match container.arrangement {
+                    Arrangement::Rows => a.y.total_cmp(&b.y),
+                    Arrangement::Columns => a.x.total_cmp(&b.x),
+                    Arrangement::Grid => (a.y / row_height)
+                        .round()
+                        .total_cmp(&(b.y / row_height).round())
+                        .then(a.x.total_cmp(&b.x)),
+                }
+            });
+            let area = Rect::from_min_max(
+                container_rect(&container).min + Vec2::new(ARRANGE_GAP, CONTAINER_HEADER),
+                container_rect(&container).max - Vec2::splat(ARRANGE_GAP),
+            );
+            let cells = container_cells(area, members.len(), container.arrangement, ARRANGE_GAP);
+            for (preview, cell) in members.into_iter().zip(cells) {
+                self.animate_to(preview, fit_in_slot(preview.size.x / preview.size.y.max(1.0), cell));
+            }
+            self.container_members.insert(container.name, ids);
+        }
+    }
+}
+
+/// Where a container frame is, in canvas coordinates
+pub(super) fn container_rect(container: &Container) -> Rect {
+    Rect::from_min_size(
+        Pos2::new(container.position.0, container.position.1),
+        Vec2::new(container.size.0, container.size.1),
+    )
+}
@@ -0,0 +1,103 @@
+use eframe::egui::{Pos2, Vec2};
+use crate::preview::PreviewId;
+use super::state::ResizeHandle;
+use super::tile_renderer::rotated_corner;
+
+/// A crop region in frame UVs: (min_u, min_v, max_u, max_v)
+pub type CropUv = (f32, f32, f32, f32);
+
+/// The whole frame
+pub const FULL_CROP: CropUv = (0.0, 0.0, 1.0, 1.0);
+
+/// A crop being edited over the whole, uncropped frame
+#[derive(Clone, Copy, Debug)]
+pub struct CropEdit {
+    pub id: PreviewId,
+    pub crop: CropUv,
+    /// The crop when the current drag began
+    pub drag_start: Option<CropUv>,
+}
+
+/// Move the edges a handle grabs by `delta` (in UVs), keeping the crop
+/// inside the frame and at least `min_size` wide and tall.
+pub fn adjust_crop(start: CropUv, handle: ResizeHandle, delta: Vec2, min_size: Vec2) -> CropUv {
+    use ResizeHandle::*;
+    let (min_u, min_v, max_u, max_v) = start;
+    let mut crop = start;
+    if matches!(handle, TopLeft | Left | BottomLeft) {
+        crop.0 = (min_u + delta.x).clamp(0.0, max_u - min_size.x);
+    }
+    if matches!(handle, TopRight | Right | BottomRight) {
+        crop.2 = (max_u + delta.x).clamp(min_u + min_size.x, 1.0);
+    }
+    if matches!(handle, TopLeft | Top | TopRight) {
+        crop.1 = (min_v + delta.y).clamp(0.0, max_v - min_size.y);
+    }
+    if matches!(handle, BottomLeft | Bottom | BottomRight) {
+        crop.3 = (max_v + delta.y).clamp(min_v + min_size.y, 1.0);
+    }
+    crop
+}
+
+/// Slide the crop by `delta` (in UVs) without changing its size, stopping
+/// at the frame's edges.
+pub fn slide_crop(start: CropUv, delta: Vec2) -> CropUv {
+    let (min_u, min_v, max_u, max_v) = start;
+    let du = delta.x.clamp(-min_u, 1.0 - max_u);
+    let dv = delta.y.clamp(-min_v, 1.0 - max_v);
+    (min_u + du, min_v + dv, max_u + du, max_v + dv)
+}
+
+/// How far to slide a crop so its content follows a pointer moved by
+/// `screen_delta` over a frame drawn `drawn_size` large from `sampled_uv`
+/// (signed: flipped axes run backwards) at `quarter_turns`.
+pub fn pan_delta(screen_delta: Vec2, drawn_size: Vec2, sampled_uv: Vec2, quarter_turns: u8) -> Vec2 {
+    let on_tile = screen_delta / drawn_size.max(Vec2::splat(1.0));
+    let in_frame = rotated_corner(Pos2::ZERO + on_tile, quarter_turns) - rotated_corner(Pos2::ZERO, quarter_turns);
+    -(in_frame * sampled_uv)
+}
+
+/// A crop in whole frame pixels: (x, y, width, height)
+pub fn crop_pixels(crop: CropUv, frame_w: u32, frame_h: u32) -> (u32, u32, u32, u32) {
+    let x0 = (crop.0 * frame_w as f32).round() as u32;
+    let y0 = (crop.1 * frame_h as f32).round() as u32;
+    let x1 = (crop.2 * frame_w as f32).round() as u32;
+    let y1 = (crop.3 * frame_h as f32).round() as u32;
+    (x0, y0, x1.saturating_sub(x0), y1.saturating_sub(y0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adjust_crop, crop_pixels, pan_delta, slide_crop, FULL_CROP};
+    use crate::canvas::state::ResizeHandle;
+    use eframe::egui::Vec2;
+
+    #[test]
+    fn handles_move_only_their_edges() {
+        let crop = adjust_crop(FULL_CROP, ResizeHandle::TopLeft, Vec2::new(0.25, 0.5), Vec2::splat(0.1));
+        assert_eq!(crop, (0.25, 0.5, 1.0, 1.0));
+        // The far edge stops short of the opposite one
+        let crop = adjust_crop(crop, ResizeHandle::Right, Vec2::new(-2.0, 0.3), Vec2::splat(0.125));
+        assert_eq!(crop, (0.25, 0.5, 0.375, 1.0));
+    }
+
+    #[test]
+    fn sliding_keeps_the_size_inside_the_frame() {
+        let crop = slide_crop((0.25, 0.25, 0.625, 0.5), Vec2::new(0.8, -0.125));
+        assert_eq!(crop, (0.625, 0.125, 1.0, 0.375));
+        assert_eq!(crop_pixels(crop, 1000, 400), (625, 50, 375, 100));
+    }
+
+    #[test]
+    fn panning_drags_the_content_along() {
+        let drawn = Vec2::new(200.0, 100.0);
+        let pan = pan_delta(Vec2::new(50.0, 0.0), drawn, Vec2::new(0.5, 0.5), 0);
+        assert_eq!(pan, Vec2::new(-0.125, 0.0));
+        // Mirrored: the frame's u runs right to left
+        let pan = pan_delta(Vec2::new(50.0, 0.0), drawn, Vec2::new(-0.5, 0.5), 0);
+        assert_eq!(pan, Vec2::new(0.125, 0.0));
+        // A quarter turn clockwise: moving down walks along the frame's u
+        let pan = pan_delta(Vec2::new(0.0, 50.0), drawn, Vec2::new(0.5, 0.5), 1);
+        assert_eq!(pan, Vec2::new(-0.25, 0.0));
+    }
+}
@@ -0,0 +1,215 @@
+use crate::persistence::Container;
+use crate::preview::{Preview, PreviewId, PreviewLayout, PreviewManager, RemovedPreviewInfo};
+
+/// Undo steps kept; the oldest are dropped past this.
+const MAX_UNDO_STEPS: usize = 100;
+
+/// The canvas at one point: every preview, sorted by id, and the
+/// container frames
+#[derive(Clone, Default)]
+pub struct Snapshot {
+    pub tiles: Vec<Tile>,
+    pub containers: Vec<Container>,
+}
+
+/// One preview in a snapshot
+#[derive(Clone)]
+pub struct Tile {
+    /// What undo recreates or restores it from
+    pub info: RemovedPreviewInfo,
+    /// Its layout with `PreviewLayout::edits_only`, to compare against
+    layout: PreviewLayout,
+}
+
+impl Tile {
+    fn of(preview: &Preview) -> Self {
+        Self { info: RemovedPreviewInfo::of(preview), layout: PreviewLayout::from(preview).edits_only() }
+    }
+}
+
+/// The canvas as the undo history sees it. Tiles on their way out count
+/// as gone already.
+pub fn snapshot(preview_manager: &PreviewManager, containers: &[Container]) -> Snapshot {
+    let mut tiles: Vec<Tile> = preview_manager
+        .all()
+        .filter(|preview| preview.removing.is_none())
+        .map(Tile::of)
+        .collect();
+    tiles.sort_by_key(|tile| tile.info.id.0);
+    Snapshot { tiles, containers: containers.to_vec() }
+}
+
+/// Does the canvas still match a snapshot in everything the user edits?
+/// Titles and the like change by themselves and aren't steps. Walks the
+/// previews in place rather than taking a snapshot, as this runs on every
+/// settled frame.
+fn same_canvas(snapshot: &Snapshot, preview_manager: &PreviewManager, containers: &[Container]) -> bool {
+    let mut count = 0;
+    snapshot.containers == containers
+        && preview_manager
+            .all()
+            .filter(|preview| preview.removing.is_none())
+            .all(|preview| {
+                count += 1;
+                snapshot
+                    .tiles
+                    .binary_search_by_key(&preview.id.0, |tile| tile.info.id.0)
+                    .is_ok_and(|index| snapshot.tiles[index].layout == PreviewLayout::from(preview).edits_only())
+            })
+        && count == snapshot.tiles.len()
+}
+
+/// Undo/redo history of one canvas. Edits don't record themselves: once
+/// the canvas settles it's compared with the last checkpoint, tile by tile
+/// as the layout would save it (without capture history or the window's
+/// executable and class) plus the container frames. Anything saved with a
+/// layout is a step without knowing about undo.
+#[derive(Default)]
+pub struct History {
+    checkpoint: Snapshot,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+}
+
+impl History {
+    /// Forget all steps, starting over from this canvas
+    pub fn reset(&mut self, snapshot: Snapshot) {
+        self.checkpoint = snapshot;
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Record the canvas as a step if it changed since the last
+    /// checkpoint; it's only snapshotted when it did
+    pub fn record(&mut self, preview_manager: &PreviewManager, containers: &[Container]) {
+        if same_canvas(&self.checkpoint, preview_manager, containers) {
+            return;
+        }
+        let snapshot = snapshot(preview_manager, containers);
+        self.undo.push(std::mem::replace(&mut self.checkpoint, snapshot));
+        if self.undo.len() > MAX_UNDO_STEPS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Step back, returning the canvas to restore
+    pub fn undo(&mut self) -> Option<Snapshot> {
+        let previous = self.undo.pop()?;
+        self.redo.push(std::mem::replace(&mut self.checkpoint, previous.clone()));
+        Some(previous)
+    }
+
+    /// Step forward again, returning the canvas to restore
+    pub fn redo(&mut self) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.undo.push(std::mem::replace(&mut self.checkpoint, next.clone()));
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// A removed preview came back under a new id
+    pub fn remap(&mut self, old: PreviewId, new: PreviewId) {
+        let snapshots = std::iter::once(&mut self.checkpoint)
+            .chain(&mut self.undo)
+            .chain(&mut self.redo);
+        for snapshot in snapshots {
+            for tile in snapshot.tiles.iter_mut().filter(|tile| tile.info.id == old) {
+                tile.info.id = new;
+            }
+            snapshot.tiles.sort_by_key(|tile| tile.info.id.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{snapshot, History};
+    use crate::persistence::{Arrangement, Container};
+    use crate::preview::PreviewManager;
+    use eframe::egui::{Pos2, Vec2};
+
+    #[test]
+    fn undo_and_redo_walk_the_steps() {
+        let mut previews = PreviewManager::new();
+        let mut history = History::default();
+        history.reset(snapshot(&previews, &[]));
+
+        let id = previews.add("a".to_string(), Pos2::ZERO, Vec2::splat(100.0));
+        history.record(&previews, &[]);
+        previews.translate(id, Vec2::new(10.0, 0.0));
+        history.record(&previews, &[]);
+        // Nothing changed: no step
+        history.record(&previews, &[]);
+
+        let moved_back = history.undo().unwrap();
+        assert_eq!(moved_back.tiles[0].info.position, Pos2::ZERO);
+        assert!(history.undo().unwrap().tiles.is_empty());
+        assert!(!history.can_undo());
+
+        assert_eq!(history.redo().unwrap().tiles.len(), 1);
+        // A new edit drops what's left to redo
+        previews.translate(id, Vec2::new(0.0, 10.0));
+        history.record(&previews, &[]);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn capture_history_is_not_a_step() {
+        let mut previews = PreviewManager::new();
+        let id = previews.add("a".to_string(), Pos2::ZERO, Vec2::splat(100.0));
+        let mut history = History::default();
+        history.reset(snapshot(&previews, &[]));
+
+        let preview = previews.get_mut(id).unwrap();
+        preview.frame_size = Some((1920, 1080));
+        preview.window_exe = Some("notepad.exe".to_string());
+        history.record(&previews, &[]);
+        assert!(!history.can_undo());
+
+        previews.get_mut(id).unwrap().lock_aspect_ratio = false;
+        history.record(&previews, &[]);
+        assert!(history.can_undo());
+    }
+
+    #[test]
+    fn remapped_ids_follow_a_recreated_preview() {
+        let mut previews = PreviewManager::new();
+        let old = previews.add("a".to_string(), Pos2::ZERO, Vec2::splat(100.0));
+        let mut history = History::default();
+        history.reset(snapshot(&previews, &[]));
+        previews.remove(old);
+        history.record(&previews, &[]);
+
+        let new = previews.add("a".to_string(), Pos2::ZERO, Vec2::splat(100.0));
+        history.remap(old, new);
+        assert_eq!(history.undo().unwrap().tiles[0].info.id, new);
+    }
+
+    #[test]
+    fn container_frames_are_steps() {
+        let previews = PreviewManager::new();
+        let mut history = History::default();
+        history.reset(snapshot(&previews, &[]));
+
+        let mut frame = Container {
+            name: "Frame 1".to_string(),
+            position: (0.0, 0.0),
+            size: (400.0, 300.0),
+            arrangement: Arrangement::Grid,
+        };
+        history.record(&previews, std::slice::from_ref(&frame));
+        frame.size = (500.0, 300.0);
+        history.record(&previews, std::slice::from_ref(&frame));
+
+        assert_eq!(history.undo().unwrap().containers[0].size, (400.0, 300.0));
+        assert!(history.undo().unwrap().containers.is_empty());
+    }
+}
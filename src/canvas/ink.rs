@@ -0,0 +1,49 @@
+use eframe::egui::Pos2;
+use crate::persistence::InkStroke;
+
+/// Points closer together than this on screen aren't added to a stroke
+pub const MIN_POINT_SPACING: f32 = 2.0;
+
+/// Radius of the eraser on screen, in points
+pub const ERASER_RADIUS: f32 = 10.0;
+
+/// Distance from `point` to the segment from `a` to `b`
+fn distance_to_segment(point: Pos2, a: Pos2, b: Pos2) -> f32 {
+    let ab = b - a;
+    let t = if ab.length_sq() > 0.0 {
+        ((point - a).dot(ab) / ab.length_sq()).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    point.distance(a + ab * t)
+}
+
+/// Does an eraser of `radius` at `point` touch the stroke? Both in canvas
+/// units; the stroke's own width counts.
+pub fn touches(stroke: &InkStroke, point: Pos2, radius: f32) -> bool {
+    let points: Vec<Pos2> = stroke.points.iter().map(|&(x, y)| Pos2::new(x, y)).collect();
+    let reach = radius + stroke.width / 2.0;
+    match points.as_slice() {
+        [] => false,
+        [only] => only.distance(point) <= reach,
+        _ => points.windows(2).any(|pair| distance_to_segment(point, pair[0], pair[1]) <= reach),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::touches;
+    use crate::persistence::InkStroke;
+    use eframe::egui::Pos2;
+
+    #[test]
+    fn eraser_reaches_the_line_between_points() {
+        let stroke = InkStroke { points: vec![(0.0, 0.0), (100.0, 0.0)], width: 4.0, color: [255, 255, 255] };
+        assert!(touches(&stroke, Pos2::new(50.0, 9.0), 8.0));
+        assert!(!touches(&stroke, Pos2::new(50.0, 12.0), 8.0));
+        assert!(!touches(&stroke, Pos2::new(112.0, 0.0), 8.0));
+
+        let dot = InkStroke { points: vec![(10.0, 10.0)], width: 2.0, color: [255, 255, 255] };
+        assert!(touches(&dot, Pos2::new(14.0, 10.0), 4.0));
+    }
+}
@@ -0,0 +1,187 @@
+use eframe::egui::{Pos2, Rect, Vec2};
+use crate::preview::PreviewId;
+
+/// A predefined arrangement of slots previews can be mapped into
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayoutTemplate {
+    Grid2x2,
+    Grid3x3,
+    PictureInPicture,
+}
+
+impl LayoutTemplate {
+    pub const ALL: [Self; 3] = [Self::Grid2x2, Self::Grid3x3, Self::PictureInPicture];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Grid2x2 => "2×2",
+            Self::Grid3x3 => "3×3",
+            Self::PictureInPicture => "Picture-in-Picture",
+        }
+    }
+
+    /// Slots as fractions of the template area. Previews fill them in
+    /// order, and later slots stack above earlier ones.
+    fn unit_slots(self) -> Vec<Rect> {
+        let grid = |n: usize| {
+            let cell = 1.0 / n as f32;
+            (0..n * n)
+                .map(|i| {
+                    let min = Pos2::new((i % n) as f32 * cell, (i / n) as f32 * cell);
+                    Rect::from_min_size(min, Vec2::splat(cell))
+                })
+                .collect()
+        };
+        match self {
+            Self::Grid2x2 => grid(2),
+            Self::Grid3x3 => grid(3),
+            Self::PictureInPicture => vec![
+                Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                Rect::from_min_max(Pos2::new(0.68, 0.68), Pos2::new(0.97, 0.97)),
+            ],
+        }
+    }
+
+    /// Slot rectangles for a template filling `area`, `gap` apart
+    pub fn slots(self, area: Rect, gap: f32) -> Vec<Rect> {
+        self.unit_slots()
+            .into_iter()
+            .map(|unit| {
+                let min = area.min + unit.min.to_vec2() * area.size();
+                Rect::from_min_size(min, unit.size() * area.size()).shrink(gap / 2.0)
+            })
+            .collect()
+    }
+}
+
+/// The largest rectangle with the given aspect ratio (width / height)
+/// that fits in `slot`, centered in it.
+pub fn fit_in_slot(aspect: f32, slot: Rect) -> Rect {
+    let aspect = aspect.max(0.01);
+    let size = if slot.width() / slot.height().max(1.0) > aspect {
+        Vec2::new(slot.height() * aspect, slot.height())
+    } else {
+        Vec2::new(slot.width(), slot.width() / aspect)
+    };
+    Rect::from_center_size(slot.center(), size)
+}
+
+/// A template applied to the canvas. Its slots stay around after applying
+/// so empty ones can be drawn as drop targets.
+#[derive(Clone, Debug)]
+pub struct PlacedTemplate {
+    pub template: LayoutTemplate,
+    pub slots: Vec<Rect>,
+    /// Preview in each slot, parallel to `slots`
+    occupants: Vec<Option<PreviewId>>,
+}
+
+impl PlacedTemplate {
+    pub fn new(template: LayoutTemplate, area: Rect, gap: f32) -> Self {
+        let slots = template.slots(area, gap);
+        let occupants = vec![None; slots.len()];
+        Self { template, slots, occupants }
+    }
+
+    /// Fill the slots with `ids` in order. Returns each placed preview with
+    /// its slot; previews beyond the last slot are left out.
+    pub fn assign(&mut self, ids: &[PreviewId]) -> Vec<(PreviewId, Rect)> {
+        self.occupants = vec![None; self.slots.len()];
+        ids.iter()
+            .zip(0..self.slots.len())
+            .map(|(&id, slot)| {
+                self.occupants[slot] = Some(id);
+                (id, self.slots[slot])
+            })
+            .collect()
+    }
+
+    /// Slots nobody occupies
+    pub fn empty_slots(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.slots
+            .iter()
+            .zip(&self.occupants)
+            .filter(|(_, occupant)| occupant.is_none())
+            .map(|(slot, _)| *slot)
+    }
+
+    /// Drop a preview at a canvas point: it takes the topmost empty slot
+    /// (or its own) under the point, leaving any other slot it held. A drop
+    /// outside the slots just vacates. Returns the slot it now fills.
+    pub fn drop_at(&mut self, id: PreviewId, point: Pos2) -> Option<Rect> {
+        let target = (0..self.slots.len())
+            .rev()
+            .find(|&i| self.slots[i].contains(point)
+                && (self.occupants[i].is_none() || self.occupants[i] == Some(id)));
+        self.vacate(id);
+        let slot = target?;
+        self.occupants[slot] = Some(id);
+        Some(self.slots[slot])
+    }
+
+    /// Free the slots of previews that are gone
+    pub fn retain(&mut self, keep: impl Fn(PreviewId) -> bool) {
+        for occupant in &mut self.occupants {
+            if occupant.is_some_and(|id| !keep(id)) {
+                *occupant = None;
+            }
+        }
+    }
+
+    /// Free the slot held by a preview
+    pub fn vacate(&mut self, id: PreviewId) {
+        for occupant in &mut self.occupants {
+            if *occupant == Some(id) {
+                *occupant = None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fit_in_slot, LayoutTemplate, PlacedTemplate};
+    use crate::preview::PreviewId;
+    use eframe::egui::{Pos2, Rect};
+
+    fn area() -> Rect {
+        Rect::from_min_max(Pos2::new(100.0, 50.0), Pos2::new(1300.0, 950.0))
+    }
+
+    #[test]
+    fn slots_stay_inside_the_area() {
+        for template in LayoutTemplate::ALL {
+            let slots = template.slots(area(), 16.0);
+            assert!(!slots.is_empty());
+            assert!(slots.iter().all(|s| area().contains_rect(*s)));
+        }
+        assert_eq!(LayoutTemplate::Grid3x3.slots(area(), 0.0).len(), 9);
+        assert_eq!(LayoutTemplate::Grid2x2.slots(area(), 0.0)[3].max, area().max);
+    }
+
+    #[test]
+    fn fitted_tiles_keep_their_aspect() {
+        let slot = Rect::from_min_max(Pos2::ZERO, Pos2::new(400.0, 400.0));
+        let wide = fit_in_slot(2.0, slot);
+        assert_eq!(wide.size(), eframe::egui::Vec2::new(400.0, 200.0));
+        assert_eq!(wide.center(), slot.center());
+        assert_eq!(fit_in_slot(0.5, slot).height(), 400.0);
+    }
+
+    #[test]
+    fn drops_fill_empty_slots_only() {
+        let mut placed = PlacedTemplate::new(LayoutTemplate::Grid2x2, area(), 0.0);
+        let placed_ids = placed.assign(&[PreviewId(1), PreviewId(2), PreviewId(3)]);
+        assert_eq!(placed_ids.len(), 3);
+        assert_eq!(placed.empty_slots().count(), 1);
+
+        // Onto an occupied slot: leaves its own slot, takes nothing
+        let taken = placed.slots[1].center();
+        assert!(placed.drop_at(PreviewId(3), taken).is_none());
+        assert_eq!(placed.empty_slots().count(), 2);
+
+        let empty = placed.slots[3].center();
+        assert_eq!(placed.drop_at(PreviewId(4), empty), Some(placed.slots[3]));
+        assert_eq!(placed.empty_slots().count(), 1);
+    }
+}
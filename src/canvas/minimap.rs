@@ -0,0 +1,55 @@
+use eframe::egui::{Pos2, Rect, Vec2};
+
+/// Size of the minimap, in points
+pub const MINIMAP_SIZE: Vec2 = Vec2::new(180.0, 120.0);
+
+/// Space around the content inside the minimap, as a fraction of its size
+const CONTENT_PADDING: f32 = 0.08;
+
+/// Maps canvas coordinates into the minimap and back. The content (every
+/// preview plus the current view) is scaled uniformly to fit and centered.
+#[derive(Clone, Copy, Debug)]
+pub struct MinimapTransform {
+    content_center: Pos2,
+    area_center: Pos2,
+    scale: f32,
+}
+
+impl MinimapTransform {
+    pub fn new(content: Rect, area: Rect) -> Self {
+        let content = content.expand2(content.size() * CONTENT_PADDING);
+        let scale = (area.width() / content.width().max(1.0))
+            .min(area.height() / content.height().max(1.0));
+        Self { content_center: content.center(), area_center: area.center(), scale }
+    }
+
+    pub fn to_minimap(&self, canvas_pos: Pos2) -> Pos2 {
+        self.area_center + (canvas_pos - self.content_center) * self.scale
+    }
+
+    pub fn rect_to_minimap(&self, canvas_rect: Rect) -> Rect {
+        Rect::from_min_max(self.to_minimap(canvas_rect.min), self.to_minimap(canvas_rect.max))
+    }
+
+    pub fn to_canvas(&self, minimap_pos: Pos2) -> Pos2 {
+        self.content_center + (minimap_pos - self.area_center) / self.scale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MinimapTransform;
+    use eframe::egui::{Pos2, Rect};
+
+    #[test]
+    fn content_fits_and_maps_back() {
+        let content = Rect::from_min_max(Pos2::new(-2000.0, 300.0), Pos2::new(6000.0, 1500.0));
+        let area = Rect::from_min_max(Pos2::new(900.0, 500.0), Pos2::new(1080.0, 620.0));
+        let transform = MinimapTransform::new(content, area);
+
+        assert!(area.contains_rect(transform.rect_to_minimap(content)));
+        let point = Pos2::new(1234.0, 987.0);
+        let back = transform.to_canvas(transform.to_minimap(point));
+        assert!((back - point).length() < 1e-2);
+    }
+}
@@ -1,5 +1,17 @@
 mod state;
 mod input;
 mod animation;
+mod arrange;
+mod budget;
+mod containers;
+mod crop;
+mod history;
+mod ink;
+mod layouts;
+mod minimap;
+mod tile_renderer;
+mod widgets;
 
+pub use arrange::{grid_layout, ARRANGE_GAP};
 pub use state::{BrowserAction, CanvasState};
+pub use tile_renderer::TileRenderer;
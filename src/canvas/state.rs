@@ -1,18 +1,83 @@
 use eframe::egui::{self, Pos2, Vec2, Rect, Color32, Stroke, Sense, CursorIcon};
-use std::time::Instant;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 #[cfg(debug_assertions)]
 use crate::privacy;
-use crate::preview::{PreviewManager, PreviewId, FpsPreset, RemovedPreviewInfo};
-use crate::capture::CaptureCoordinator;
-use super::animation::{AnimationState, DragTracker};
+use crate::preview::{
+    apply_layout_fields, Preview, PreviewManager, PreviewId, FpsPreset, CaptureBackend, AlphaMode, CaptureOptions,
+    ChromaKey, Corner, CropPreset, DisplayMode, EffectKind, FrameOverlays, Watermark, PreviewLayout, RemovedPreviewInfo,
+    SourceStats,
+};
+use crate::capture::{popups_supported, CaptureCoordinator, CaptureStats};
+use crate::persistence::{Arrangement, Bookmark, CanvasWidget, Container, FpsGroup, InkStroke, TextLabel, Theme, ZoomSettings};
+use crate::focus::DesktopFocus;
+use crate::platform::{Native, WindowFocus, WindowSource};
+use crate::util::format_age;
+use super::animation::{AnimationState, DragTracker, Guide, SpringValue, SpringVec2};
+use super::arrange::{grid_layout, ARRANGE_GAP};
+use super::budget::divide_budget;
+use super::containers::{container_rect, CONTAINER_HEADER};
+use super::crop::{adjust_crop, crop_pixels, pan_delta, slide_crop, CropEdit, FULL_CROP};
+use super::history::{self, History, Snapshot};
+use super::ink::{self, ERASER_RADIUS, MIN_POINT_SPACING};
+use super::layouts::{fit_in_slot, LayoutTemplate, PlacedTemplate};
+use super::minimap::{MinimapTransform, MINIMAP_SIZE};
+use super::tile_renderer::{rotated_corner, sub_uv_of, FrameStyle, TileRenderer};
+use super::widgets;
 
 /// How long the "Removed '...' · Undo" toast stays on screen.
 const UNDO_TOAST_SECS: f32 = 4.0;
 
-#[cfg(windows)]
-use windows::Win32::UI::WindowsAndMessaging::{ShowWindow, SetForegroundWindow, SW_RESTORE};
-#[cfg(windows)]
-use windows::Win32::Foundation::HWND;
+/// Space between an applied template and the edge of the view, in points.
+const TEMPLATE_MARGIN: f32 = 40.0;
+
+/// Content changes per minute at which the heat map shows a tile fully hot.
+const HEAT_MAP_HOT: f32 = 60.0;
+
+/// How long the "Layer N of M" badge shows after a keyboard z-order change.
+const DEPTH_BADGE_SECS: f32 = 1.2;
+
+/// How far a duplicated or pasted preview lands from its original, in
+/// canvas units (repeated pastes step further).
+const DUPLICATE_OFFSET: f32 = 24.0;
+
+/// Sticky notes are for a few words, not documents.
+const NOTE_MAX_CHARS: usize = 200;
+
+/// Width an expanded note wraps at, in points.
+const NOTE_CARD_WIDTH: f32 = 180.0;
+
+/// Budget a new FPS group starts with.
+const DEFAULT_GROUP_BUDGET: u32 = 10;
+
+/// Font size of a new text label, in canvas units.
+const DEFAULT_LABEL_SIZE: f32 = 28.0;
+
+/// Space left around a focused preview, in screen points
+const FOCUS_MARGIN: f32 = 24.0;
+
+/// Space left around the previews by Zoom to Fit, in screen points
+const FIT_MARGIN: f32 = 40.0;
+
+/// Side of the square, centered on the origin, that a bounded canvas keeps
+/// previews and the view inside, in canvas units
+const BOUNDED_CANVAS_SIZE: f32 = 8000.0;
+
+/// A focused preview captures at least this fast
+const FOCUS_FPS: u32 = 60;
+
+/// Initial width of a pop-out window
+const PIP_WIDTH: f32 = 360.0;
+
+/// Corner radius of tiles; their content is clipped to it
+pub(super) const TILE_ROUNDING: f32 = 8.0;
+
+/// Side of the loupe's magnified view
+const LOUPE_SIZE: f32 = 200.0;
+
+/// Pen colors offered on the ink toolbar
+const PEN_SWATCHES: [[u8; 3]; 5] = [[255, 80, 80], [255, 150, 100], [107, 170, 75], [74, 158, 255], [240, 240, 240]];
 
 /// Represents the current drag operation
 #[derive(Clone, Debug)]
@@ -34,11 +99,20 @@ pub enum DragState {
         /// Starting crop UV coordinates (min_u, min_v, max_u, max_v)
         start_crop_uv: (f32, f32, f32, f32),
     },
+    /// Dragging out a measurement over a preview
+    Measuring { id: PreviewId },
+    /// Sliding a crop across its frame (Ctrl+drag inside a cropped preview)
+    PanningCrop {
+        id: PreviewId,
+        start_mouse: Pos2,
+        start_crop_uv: (f32, f32, f32, f32),
+    },
 }
 
 #[cfg(test)]
 mod tests {
-    use super::CanvasState;
+    use super::{clamp_into, color_hex, grid_step, measure_label, offline_details, safe_areas, CanvasState};
+    use crate::preview::SourceStats;
 
     #[test]
     fn canvas_screen_rect_starts_empty() {
@@ -54,6 +128,72 @@ mod tests {
     fn browser_add_request_starts_empty() {
         assert!(CanvasState::default().pending_browser_add.is_none());
     }
+
+    #[test]
+    fn safe_areas_sit_in_the_centered_wide_area() {
+        use eframe::egui::{Pos2, Rect, Vec2};
+        // 4:3 frame: the 16:9 area is letterboxed inside it
+        let frame = Rect::from_min_size(Pos2::ZERO, Vec2::new(1600.0, 1200.0));
+        let [action, title] = safe_areas(frame);
+        assert_eq!(action.center(), frame.center());
+        assert!((action.size() - Vec2::new(1488.0, 837.0)).length() < 0.01);
+        assert!((title.size() - Vec2::new(1440.0, 810.0)).length() < 0.01);
+    }
+
+    #[test]
+    fn tab_cycles_through_the_stack() {
+        use crate::preview::PreviewManager;
+        use eframe::egui::{Pos2, Vec2};
+        let mut previews = PreviewManager::new();
+        let ids: Vec<_> = (0..3)
+            .map(|n| previews.add(format!("Window {}", n), Pos2::new(n as f32 * 100.0, 0.0), Vec2::splat(80.0)))
+            .collect();
+        let mut canvas = CanvasState::default();
+        canvas.cycle_selection(&previews, true);
+        assert_eq!(canvas.selection, vec![ids[0]]);
+        canvas.cycle_selection(&previews, false);
+        assert_eq!(canvas.selection, vec![ids[2]]);
+        canvas.cycle_selection(&previews, true);
+        assert_eq!(canvas.selection, vec![ids[0]]);
+    }
+
+    #[test]
+    fn grid_steps_land_on_the_grid() {
+        use eframe::egui::{Pos2, Vec2};
+        assert_eq!(grid_step(Pos2::new(40.0, 60.0), Vec2::new(1.0, 0.0), 20.0), Pos2::new(60.0, 60.0));
+        assert_eq!(grid_step(Pos2::new(47.0, 52.0), Vec2::new(-1.0, 0.0), 20.0), Pos2::new(40.0, 60.0));
+        assert_eq!(grid_step(Pos2::new(47.0, 52.0), Vec2::new(0.0, 1.0), 20.0), Pos2::new(40.0, 60.0));
+    }
+
+    #[test]
+    fn clamped_rects_move_the_least_to_fit() {
+        use eframe::egui::{Pos2, Rect, Vec2};
+        let bounds = Rect::from_min_max(Pos2::new(-100.0, -100.0), Pos2::new(100.0, 100.0));
+        let inside = Rect::from_min_size(Pos2::new(-50.0, 0.0), Vec2::splat(40.0));
+        assert_eq!(clamp_into(inside, bounds), inside.min);
+        let flung = Rect::from_min_size(Pos2::new(500.0, -300.0), Vec2::splat(40.0));
+        assert_eq!(clamp_into(flung, bounds), Pos2::new(60.0, -100.0));
+        let huge = Rect::from_min_size(Pos2::new(50.0, 50.0), Vec2::splat(400.0));
+        assert_eq!(clamp_into(huge, bounds), Pos2::new(-100.0, -100.0));
+    }
+
+    #[test]
+    fn measurements_read_in_whole_pixels() {
+        assert_eq!(measure_label(eframe::egui::Vec2::new(-300.0, 400.2)), "500 px · 300 × 400");
+    }
+
+    #[test]
+    fn offline_tiles_list_what_they_remember() {
+        let stats = SourceStats { fps: 29.7, latency_ms: 14 };
+        assert_eq!(offline_details(Some(stats), Some((1920, 1080))), "30 FPS · 14 ms · 1920×1080");
+        assert_eq!(offline_details(None, Some((800, 600))), "800×600");
+        assert_eq!(offline_details(None, None), "");
+    }
+
+    #[test]
+    fn colors_copy_as_hex() {
+        assert_eq!(color_hex(eframe::egui::Color32::from_rgb(255, 128, 0)), "#FF8000");
+    }
 }
 
 /// Resize handle positions
@@ -106,11 +246,55 @@ struct FrameInput {
     scroll_y: f32,
     alt: bool,
     ctrl: bool,
+    /// Space is held: the left button pans, like in design tools
+    space: bool,
     middle_down: bool,
     primary_down: bool,
     time: f64,
     delete_pressed: bool,
     select_all: bool,
+    page_up: bool,
+    page_down: bool,
+    /// Quarter turns requested with R / Shift+R
+    rotate: i32,
+    /// Arrow keys pressed this frame (without Ctrl), as a direction
+    nudge: Vec2,
+    /// Ctrl+arrow keys pressed this frame: a step to the next grid line
+    grid_step: Vec2,
+    /// An arrow key is held, so a run of nudges becomes one undo step
+    nudge_held: bool,
+    shift: bool,
+    /// F: focus the selected preview, or leave focus mode
+    focus: bool,
+    /// Home: frame every preview
+    home: bool,
+    /// Ctrl+Plus / Ctrl+Minus: a zoom step in (1) or out (-1)
+    zoom_keys: i32,
+    /// Ctrl+0: back to 100%
+    zoom_reset: bool,
+    duplicate: bool,
+    copy: bool,
+    paste: bool,
+    /// Pinch zoom factor this frame (touch screen or touchpad). Ctrl+wheel
+    /// is left out: the scroll zoom already handles it.
+    pinch: f32,
+    /// Two-finger drag on a touch screen: movement and the fingers' center
+    touch_pan: Option<(Vec2, Pos2)>,
+}
+
+/// A preview expanded to fill the canvas, with the view to go back to
+#[derive(Clone, Copy, Debug)]
+struct FocusMode {
+    id: PreviewId,
+    pan: Vec2,
+    zoom: f32,
+}
+
+/// What the left button does on the ink layer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum InkTool {
+    Pen,
+    Eraser,
 }
 
 /// Per-tile data collected up front so the manager isn't borrowed during
@@ -119,6 +303,8 @@ struct TileInfo {
     id: PreviewId,
     rect: Rect,
     title: String,
+    /// Nickname, or the title if there is none
+    name: String,
     target_fps: u32,
     fps_preset: FpsPreset,
     has_crop: bool,
@@ -127,6 +313,24 @@ struct TileInfo {
     remove_t: f32,
     is_browser: bool,
     muted: bool,
+    show_stats: bool,
+    /// Waiting for its window to appear
+    offline: bool,
+    last_seen: Option<SystemTime>,
+    last_stats: Option<SourceStats>,
+    frame_size: Option<(u32, u32)>,
+    /// Fraction of the frame the tile shows (crop applied)
+    uv_size: Vec2,
+    /// Quarter turns clockwise
+    rotation: u8,
+    style: FrameStyle,
+    display_mode: DisplayMode,
+    /// Width / height of the frame as shown (crop and rotation applied)
+    frame_aspect: f32,
+    overlays: FrameOverlays,
+    watermark: Option<Watermark>,
+    hwnd: Option<isize>,
+    note: Option<String>,
 }
 
 /// Canvas state managing pan, zoom, and interactions
@@ -142,6 +346,19 @@ pub struct CanvasState {
     pub zoom_min: f32,
     pub zoom_max: f32,
 
+    /// Zoom change per scroll wheel notch
+    zoom_step: f32,
+
+    /// Scroll zoom glides via the zoom spring
+    smooth_zoom: bool,
+
+    /// Keep previews and panning inside a finite region
+    pub bounded: bool,
+
+    /// Where a smooth scroll zoom is centered, relative to the canvas's
+    /// top-left corner
+    zoom_anchor: Option<Vec2>,
+
     /// Currently selected preview IDs
     pub selection: Vec<PreviewId>,
 
@@ -154,6 +371,45 @@ pub struct CanvasState {
     /// Grid size in canvas units
     pub grid_size: f32,
 
+    /// Overview mode tinting each tile by how busy its content is
+    pub show_heat_map: bool,
+
+    /// Minimap of all previews and the view in the bottom-right corner
+    pub show_minimap: bool,
+
+    /// Layers panel listing previews by z-order (drawn by the app)
+    pub show_layers: bool,
+
+    /// Dim every preview but the selected ones
+    pub spotlight: bool,
+
+    /// Loupe magnification, while the loupe is out
+    pub loupe: Option<f32>,
+
+    /// Eyedropper out: hovering a preview reads its pixel, clicking copies
+    /// it
+    pub eyedropper: bool,
+
+    /// Message for the app to show as a toast
+    pub pending_toast: Option<String>,
+
+    /// Preview double-clicked once while its window is on another virtual
+    /// desktop; the next double-click switches there
+    desktop_switch_armed: Option<PreviewId>,
+
+    /// Measure tool out: dragging over a preview measures in source pixels
+    pub measuring: bool,
+
+    /// The last measurement: preview and the two ends, in canvas
+    /// coordinates
+    measurement: Option<(PreviewId, Pos2, Pos2)>,
+
+    /// Watermark images by path, loaded on first use (None: unreadable)
+    watermark_textures: HashMap<String, Option<egui::TextureHandle>>,
+
+    /// Preview picked in the layers panel, to bring into view
+    layers_jump: Option<PreviewId>,
+
     /// Pending FPS changes to apply
     pending_fps_changes: Vec<PendingFpsChange>,
 
@@ -161,7 +417,25 @@ pub struct CanvasState {
     pub animation: AnimationState,
 
     /// Is a preview currently being dragged?
-    preview_dragging: bool,
+    pub(super) preview_dragging: bool,
+
+    /// Unsnapped position of the preview being dragged, so aligning to a
+    /// guide doesn't stop the drag from moving on past it
+    drag_free_pos: Option<Pos2>,
+
+    /// Where each dragged preview started, to put them back if Escape
+    /// cancels the drag
+    drag_origins: Vec<(PreviewId, Pos2)>,
+
+    /// Where the dragged preview started, for swapping places with the
+    /// preview it's dropped on
+    swap_origin: Option<Rect>,
+
+    /// The preview a Shift-drag would swap places with if dropped now
+    swap_target: Option<PreviewId>,
+
+    /// Alignment guides to draw for the current drag or resize
+    guides: Vec<Guide>,
 
     /// Is the canvas currently being panned?
     canvas_panning: bool,
@@ -172,6 +446,21 @@ pub struct CanvasState {
     /// Preview ID pending region selection (set from context menu, consumed by app)
     pub pending_region_select: Option<PreviewId>,
 
+    /// "Restart as Admin" clicked on a preview of an elevated window
+    /// (consumed by app)
+    pub pending_restart_elevated: bool,
+
+    /// Last applied layout template; its empty slots are drop targets.
+    template: Option<PlacedTemplate>,
+
+    /// Preview whose layer changed by keyboard, and when, for the brief
+    /// depth badge.
+    depth_badge: Option<(PreviewId, Instant)>,
+
+    /// Process ID and exe name last shown in a tile tooltip, so hovering
+    /// doesn't open the process every frame.
+    tooltip_exe: Option<(u32, String)>,
+
     /// Most recently removed preview, kept briefly to power the "Undo" toast.
     last_removed: Option<(Instant, RemovedPreviewInfo)>,
 
@@ -179,9 +468,10 @@ pub struct CanvasState {
     /// used to anchor the "Add Window..." quick-add popup.
     last_secondary_click: Option<Pos2>,
 
-    /// Set by the "Add Window..." context menu item: (canvas position to
-    /// place the new preview, screen position to anchor the popup). The app
-    /// consumes this to open the quick-add popup.
+    /// Set by the "Add Window..." context menu item or a double-click on
+    /// empty canvas: (canvas position to center the new preview on, screen
+    /// position to anchor the popup). The app consumes this to open the
+    /// quick-add popup.
     pub pending_quick_add: Option<(Pos2, Pos2)>,
 
     /// Canvas position requested by the "Add Browser..." context action.
@@ -191,19 +481,130 @@ pub struct CanvasState {
     /// consumed by the app.
     pub pending_browser_actions: Vec<(PreviewId, BrowserAction)>,
 
-    /// A removed browser tile whose "Undo" was clicked; the app recreates
-    /// the WebView from its saved URL (the original host is already gone).
-    pub pending_browser_restore: Option<RemovedPreviewInfo>,
+    /// Removed browser tiles brought back by "Undo"; the app recreates
+    /// each WebView from its saved URL (the original host is already gone)
+    /// and reports the new id through `remap_preview`.
+    pub pending_browser_restores: Vec<RemovedPreviewInfo>,
+
+    /// Removed previews for the app to put in the trash
+    pub pending_trash: Vec<(PreviewId, PreviewLayout)>,
+
+    /// Removed previews brought back by undo, for the app to take out of
+    /// the trash again
+    pub pending_untrash: Vec<PreviewId>,
+
+    /// Undo/redo steps of this canvas
+    history: History,
+
+    /// Previews copied with Ctrl+C, and how many times they've been pasted
+    clipboard: Vec<RemovedPreviewInfo>,
+    paste_count: u32,
+
+    /// Previews whose note was clicked open
+    open_notes: Vec<PreviewId>,
+
+    /// The note being written, and for which preview
+    note_editor: Option<(PreviewId, String)>,
+
+    /// Preview being renamed, with the name typed so far
+    rename_editor: Option<(PreviewId, String)>,
+
+    /// Preview whose crop is being typed in, as x, y, width, height in
+    /// frame pixels
+    crop_editor: Option<(PreviewId, [u32; 4])>,
+
+    /// "Edit Crop" window: the crop dragged over the uncropped frame
+    crop_edit: Option<CropEdit>,
+
+    /// FPS budgets shared by groups of previews (saved with the layout)
+    pub fps_groups: Vec<FpsGroup>,
+
+    /// Is the "FPS Groups" window open?
+    show_fps_groups: bool,
+
+    /// FPS group being renamed in the groups window, with the name typed
+    /// so far
+    fps_group_rename: Option<(String, String)>,
+
+    /// Text placed on the canvas (saved with the layout)
+    pub labels: Vec<TextLabel>,
+
+    /// Index of the label open in the editor
+    label_editor: Option<usize>,
+
+    /// Frames that tile the previews inside them (saved with the layout)
+    pub containers: Vec<Container>,
+
+    /// The previews each container was last laid out with, so it reflows
+    /// when one joins or leaves
+    pub(super) container_members: HashMap<String, Vec<PreviewId>>,
+
+    /// Lay every container out again (moved, resized or rearranged)
+    pub(super) reflow_containers: bool,
+
+    /// Container being renamed from its menu, with the name typed so far
+    pub(super) container_rename: Option<(String, String)>,
+
+    /// Clocks and countdowns on the canvas (saved with the layout)
+    pub widgets: Vec<CanvasWidget>,
+
+    /// Index of the widget open in the editor
+    pub(super) widget_editor: Option<usize>,
+
+    /// Saved viewpoints, jumped to with the number keys (saved with the layout)
+    pub bookmarks: Vec<Bookmark>,
+
+    /// The preview shown alone, filling the canvas
+    focus: Option<FocusMode>,
+
+    /// Previews popped out into their own always-on-top windows
+    popped_out: Vec<PreviewId>,
+
+    /// Preview picked for focus mode from its menu or Shift+double-click
+    focus_request: Option<PreviewId>,
+
+    /// Freehand strokes drawn over the previews (saved with the layout)
+    pub ink: Vec<InkStroke>,
+
+    /// Pen or eraser picked; None leaves the canvas to its usual controls
+    ink_tool: Option<InkTool>,
+
+    pen_color: [u8; 3],
+
+    /// Pen width in canvas units
+    pen_width: f32,
+
+    /// Stroke being drawn, moved into `ink` on release
+    current_stroke: Option<InkStroke>,
 
     /// The browser tile currently in interaction mode, set by the app each
     /// frame so the canvas can outline it in the accent color.
     pub interactive_browser: Option<PreviewId>,
 
+    /// The desktop window with keyboard focus, set by the app each frame so
+    /// the canvas can mark its preview.
+    pub desktop_focus: Option<DesktopFocus>,
+
+    /// Panic button pressed: tiles are drawn black, set by the app each
+    /// frame
+    pub blanked: bool,
+
+    /// Background, grid and accent colors, set by the app
+    pub theme: Theme,
+
+    /// Presentation mode: previews only, without grid, handles, badges or
+    /// hover controls. Set by the app.
+    pub presenting: bool,
+
     /// Last canvas rectangle in egui screen coordinates.
     pub last_screen_rect: Option<Rect>,
 
     /// Preview most recently double-clicked, consumed by the app.
     pub last_double_clicked: Option<PreviewId>,
+
+    /// GPU path for preview frames, set by the app when the GL context
+    /// supports it; None falls back to `Painter::image`.
+    pub tile_renderer: Option<Arc<TileRenderer>>,
 }
 
 impl Default for CanvasState {
@@ -213,25 +614,85 @@ impl Default for CanvasState {
             zoom: 1.0,
             zoom_min: 0.1,
             zoom_max: 5.0,
+            zoom_step: 0.1,
+            smooth_zoom: false,
+            bounded: false,
+            zoom_anchor: None,
             selection: Vec::new(),
             drag_state: None,
             show_grid: true,
             grid_size: 50.0,
+            show_heat_map: false,
+            show_minimap: true,
+            show_layers: false,
+            spotlight: false,
+            loupe: None,
+            eyedropper: false,
+            pending_toast: None,
+            desktop_switch_armed: None,
+            measuring: false,
+            measurement: None,
+            watermark_textures: HashMap::new(),
+            layers_jump: None,
             pending_fps_changes: Vec::new(),
             animation: AnimationState::new(),
             preview_dragging: false,
+            drag_free_pos: None,
+            drag_origins: Vec::new(),
+            swap_origin: None,
+            swap_target: None,
+            guides: Vec::new(),
             canvas_panning: false,
             pan_drag_tracker: DragTracker::new(),
             pending_region_select: None,
+            pending_restart_elevated: false,
+            template: None,
+            depth_badge: None,
+            tooltip_exe: None,
             last_removed: None,
             last_secondary_click: None,
             pending_quick_add: None,
             pending_browser_add: None,
             pending_browser_actions: Vec::new(),
-            pending_browser_restore: None,
+            pending_browser_restores: Vec::new(),
+            pending_trash: Vec::new(),
+            pending_untrash: Vec::new(),
+            history: History::default(),
+            clipboard: Vec::new(),
+            paste_count: 0,
+            open_notes: Vec::new(),
+            note_editor: None,
+            rename_editor: None,
+            crop_editor: None,
+            crop_edit: None,
+            fps_groups: Vec::new(),
+            show_fps_groups: false,
+            fps_group_rename: None,
+            labels: Vec::new(),
+            label_editor: None,
+            containers: Vec::new(),
+            container_members: HashMap::new(),
+            reflow_containers: false,
+            container_rename: None,
+            widgets: Vec::new(),
+            widget_editor: None,
+            bookmarks: Vec::new(),
+            focus: None,
+            popped_out: Vec::new(),
+            focus_request: None,
+            ink: Vec::new(),
+            ink_tool: None,
+            pen_color: PEN_SWATCHES[0],
+            pen_width: 4.0,
+            current_stroke: None,
             interactive_browser: None,
+            desktop_focus: None,
+            blanked: false,
+            theme: Theme::default(),
+            presenting: false,
             last_screen_rect: None,
             last_double_clicked: None,
+            tile_renderer: None,
         }
     }
 }
@@ -269,6 +730,14 @@ impl CanvasState {
         Rect::from_min_max(min, max)
     }
 
+    /// Canvas position at the middle of the view
+    pub fn view_center(&self) -> Pos2 {
+        match self.last_screen_rect {
+            Some(screen_rect) => self.screen_to_canvas(screen_rect.center(), screen_rect),
+            None => Pos2::new(400.0, 300.0) - self.pan,
+        }
+    }
+
     /// Get the visible canvas area
     pub fn get_viewport(&self, screen_rect: Rect) -> Rect {
         let min = self.screen_to_canvas(screen_rect.min, screen_rect);
@@ -318,19 +787,67 @@ impl CanvasState {
         self.last_screen_rect = Some(canvas_rect);
 
         // Snapshot the input fields we need once, instead of cloning the
-        // whole InputState in every interaction pass.
-        let input = ui.input(|i| FrameInput {
-            hover_pos: i.pointer.hover_pos(),
-            interact_pos: i.pointer.interact_pos(),
-            pointer_delta: i.pointer.delta(),
-            scroll_y: i.raw_scroll_delta.y,
-            alt: i.modifiers.alt,
-            ctrl: i.modifiers.ctrl,
-            middle_down: i.pointer.middle_down(),
-            primary_down: i.pointer.primary_down(),
-            time: i.time,
-            delete_pressed: i.key_pressed(egui::Key::Delete),
-            select_all: i.modifiers.ctrl && i.key_pressed(egui::Key::A),
+        // whole InputState in every interaction pass. Keys meant for a
+        // text field (a note, label or group name) never reach the tiles.
+        let typing = ctx.wants_keyboard_input();
+        let input = ui.input(|i| {
+            let arrows = [
+                (egui::Key::ArrowLeft, Vec2::new(-1.0, 0.0)),
+                (egui::Key::ArrowRight, Vec2::new(1.0, 0.0)),
+                (egui::Key::ArrowUp, Vec2::new(0.0, -1.0)),
+                (egui::Key::ArrowDown, Vec2::new(0.0, 1.0)),
+            ]
+            .into_iter()
+            .filter(|(key, _)| !typing && i.key_pressed(*key))
+            .fold(Vec2::ZERO, |sum, (_, direction)| sum + direction);
+            FrameInput {
+                hover_pos: i.pointer.hover_pos(),
+                interact_pos: i.pointer.interact_pos(),
+                pointer_delta: i.pointer.delta(),
+                scroll_y: i.raw_scroll_delta.y,
+                alt: i.modifiers.alt,
+                space: !typing && i.key_down(egui::Key::Space),
+                ctrl: i.modifiers.ctrl,
+                middle_down: i.pointer.middle_down(),
+                primary_down: i.pointer.primary_down(),
+                time: i.time,
+                delete_pressed: !typing && i.key_pressed(egui::Key::Delete),
+                select_all: !typing && i.modifiers.ctrl && i.key_pressed(egui::Key::A),
+                page_up: !typing && i.key_pressed(egui::Key::PageUp),
+                page_down: !typing && i.key_pressed(egui::Key::PageDown),
+                rotate: match (!typing && i.key_pressed(egui::Key::R), i.modifiers.shift) {
+                    (true, false) => 1,
+                    (true, true) => -1,
+                    _ => 0,
+                },
+                nudge: if i.modifiers.ctrl { Vec2::ZERO } else { arrows },
+                grid_step: if i.modifiers.ctrl { arrows } else { Vec2::ZERO },
+                nudge_held: !typing
+                    && [egui::Key::ArrowLeft, egui::Key::ArrowRight, egui::Key::ArrowUp, egui::Key::ArrowDown]
+                        .into_iter()
+                        .any(|key| i.key_down(key)),
+                shift: i.modifiers.shift,
+                focus: !typing && i.key_pressed(egui::Key::F) && !i.modifiers.ctrl && !i.modifiers.alt,
+                home: !typing && i.key_pressed(egui::Key::Home),
+                zoom_keys: if i.modifiers.command {
+                    i.key_pressed(egui::Key::Plus) as i32 + i.key_pressed(egui::Key::Equals) as i32
+                        - i.key_pressed(egui::Key::Minus) as i32
+                } else {
+                    0
+                },
+                zoom_reset: i.modifiers.command && i.key_pressed(egui::Key::Num0),
+                duplicate: !typing && i.modifiers.ctrl && i.key_pressed(egui::Key::D),
+                // egui turns Ctrl+C/V into clipboard events rather than keys
+                copy: !typing && i.events.iter().any(|e| matches!(e, egui::Event::Copy)),
+                paste: !typing
+                    && (i.events.iter().any(|e| matches!(e, egui::Event::Paste(_)))
+                        || (i.modifiers.ctrl && i.key_pressed(egui::Key::V))),
+                pinch: if i.modifiers.ctrl { 1.0 } else { i.zoom_delta() },
+                touch_pan: i
+                    .multi_touch()
+                    .filter(|touch| touch.num_touches >= 2)
+                    .map(|touch| (touch.translation_delta, touch.center_pos)),
+            }
         });
 
         // Calculate delta time for animations
@@ -347,13 +864,21 @@ impl CanvasState {
             self.pan += momentum_delta / self.zoom;
         }
 
+        // Animated view changes (entering and leaving focus mode)
+        self.apply_view_springs();
+
         // Update preview positions from their spring animations
         self.update_preview_animations(preview_manager);
+        if self.bounded {
+            self.keep_in_bounds(preview_manager, canvas_rect);
+        }
+        self.layout_containers(preview_manager);
 
         // Reap any previews whose fade/shrink-out animation has finished,
         // keeping the most recent one around briefly for the undo toast.
-        let finished_removals = preview_manager.finalize_removals();
-        if let Some(info) = finished_removals.into_iter().last() {
+        // All of them go to the trash.
+        for (info, layout) in preview_manager.finalize_removals() {
+            self.pending_trash.push((info.id, layout));
             self.last_removed = Some((Instant::now(), info));
         }
 
@@ -370,28 +895,97 @@ impl CanvasState {
         // Get the painter for drawing
         let painter = ui.painter_at(canvas_rect);
 
-        // Draw background - Minimal Void theme (#0d0d0d)
-        painter.rect_filled(canvas_rect, 0.0, Color32::from_rgb(13, 13, 13));
+        // Draw background (Minimal Void #0d0d0d unless themed)
+        painter.rect_filled(canvas_rect, 0.0, self.theme.background());
 
         // Draw grid
-        if self.show_grid {
+        if self.show_grid && !self.presenting {
             self.draw_grid(&painter, canvas_rect);
         }
 
+        // Edge of a bounded canvas
+        if self.bounded && !self.presenting {
+            let half = Vec2::splat(BOUNDED_CANVAS_SIZE / 2.0);
+            let bounds = Rect::from_min_max(
+                self.canvas_to_screen((-half).to_pos2(), canvas_rect),
+                self.canvas_to_screen(half.to_pos2(), canvas_rect),
+            );
+            painter.rect_stroke(bounds, 0.0, Stroke::new(1.0, self.theme.accent_alpha(60)));
+        }
+
+        // Empty slots of an applied layout template
+        if !self.presenting {
+            self.draw_template_slots(&painter, canvas_rect, preview_manager);
+        }
+
         // Empty-canvas hint (only relevant before anything has been added)
         if preview_manager.count() == 0 {
             self.draw_empty_state(&painter, canvas_rect);
         }
 
+        // Container frames and text labels sit on the canvas, under the
+        // previews
+        self.draw_and_interact_containers(ui, canvas_rect, preview_manager);
+        self.draw_and_interact_labels(ui, canvas_rect);
+        self.label_editor_ui(ctx);
+        self.draw_and_interact_widgets(ui, canvas_rect);
+        self.widget_editor_ui(ctx);
+
+        self.take_dropped_watermarks(ctx, preview_manager);
+
         // Draw previews and handle their interactions (AFTER bg allocation)
         self.draw_and_interact_previews(ui, canvas_rect, preview_manager, ctx, capture_coordinator, &input);
 
+        if let Some(id) = self.focus_request.take() {
+            self.enter_focus(id, canvas_rect, preview_manager, capture_coordinator);
+        }
+        if let Some(rect) = self.layers_jump.take().and_then(|id| preview_manager.get(id)).map(|p| p.rect()) {
+            self.reveal(rect, canvas_rect);
+        }
+        // The focused preview was removed
+        if self.focus.is_some_and(|f| preview_manager.get(f.id).map_or(true, |p| p.removing.is_some())) {
+            self.exit_focus(preview_manager, capture_coordinator);
+        }
+
         // Draw selection rectangles and interactive resize handles
         // Handles are allocated AFTER previews so they have higher interaction priority
-        self.draw_and_interact_selection(ui, canvas_rect, preview_manager, &input);
+        if !self.presenting {
+            self.draw_and_interact_selection(ui, canvas_rect, preview_manager, &input);
+        }
+
+        // Ink goes over the previews; with a tool picked it takes the left
+        // button from everything underneath
+        self.draw_and_interact_ink(ui, canvas_rect, &input);
+        self.ink_toolbar_ui(ctx, canvas_rect);
+
+        // Smart guides, minimap and status badge are editing chrome
+        if !self.presenting {
+            // Smart guides for the current drag or resize
+            self.draw_guides(&painter, canvas_rect);
+
+            // Overview of everything on the canvas, above the status badge
+            if self.show_minimap && preview_manager.count() > 0 {
+                self.draw_and_interact_minimap(ui, canvas_rect, preview_manager);
+            }
+
+            // Minimal Void: Floating status indicator (bottom-right corner)
+            self.draw_floating_status(&painter, canvas_rect, preview_manager.count());
+        }
+
+        // "Connecting N of M" while a loaded layout's captures start up
+        if self.blanked {
+            draw_blanked_notice(&painter, canvas_rect);
+        } else if let Some((connected, total)) = capture_coordinator.load_progress() {
+            self.draw_load_progress(&painter, canvas_rect, connected, total);
+            ctx.request_repaint_after(Duration::from_millis(100));
+        } else if self.focus.is_some() && !self.presenting {
+            draw_notice(&painter, canvas_rect, "Focus mode · Esc to return".to_string());
+        }
 
-        // Minimal Void: Floating status indicator (bottom-right corner)
-        self.draw_floating_status(&painter, canvas_rect, preview_manager.count());
+        // "Layer N of M" after a Page Up/Down z-order change
+        if !self.presenting && self.draw_depth_badge(&painter, canvas_rect, preview_manager) {
+            ctx.request_repaint();
+        }
 
         // Undo toast for the most recently removed preview
         self.draw_and_interact_undo_toast(ui, canvas_rect, preview_manager, capture_coordinator);
@@ -405,6 +999,25 @@ impl CanvasState {
         // Viewport culling: pause/resume captures based on visibility
         self.update_viewport_culling(canvas_rect, preview_manager, capture_coordinator);
 
+        // Share group budgets among the members left on screen
+        self.apply_fps_budgets(preview_manager, capture_coordinator);
+        self.fps_groups_ui(ctx, preview_manager);
+
+        self.pip_windows_ui(ctx, preview_manager, capture_coordinator);
+
+        // Once edits settle (nothing held, dragged or springing into place,
+        // no browser tile still being recreated), record them for undo
+        let settling = self.animation.preview_springs.values().any(|s| s.is_animating())
+            || self.animation.size_springs.values().any(|s| s.is_animating());
+        if !input.primary_down
+            && !input.nudge_held
+            && self.drag_state.is_none()
+            && !settling
+            && self.pending_browser_restores.is_empty()
+        {
+            self.history.record(preview_manager, &self.containers);
+        }
+
         // Request repaint if animations are active
         if self.animation.is_animating() {
             ctx.request_repaint();
@@ -420,6 +1033,103 @@ impl CanvasState {
                 }
             }
         }
+        for (id, spring) in &self.animation.size_springs {
+            if spring.is_animating() {
+                if let Some(preview) = preview_manager.get_mut(*id) {
+                    preview.size = spring.current();
+                }
+            }
+        }
+    }
+
+    /// Previews an arrange command applies to: the selection if it has at
+    /// least two, otherwise every tile. Sorted in reading order: tiles whose
+    /// tops are about a row apart are in different rows, otherwise left to
+    /// right.
+    fn arrange_targets<'a>(&self, preview_manager: &'a PreviewManager) -> Vec<&'a Preview> {
+        let mut previews: Vec<&Preview> = preview_manager
+            .all()
+            .filter(|p| p.removing.is_none())
+            .filter(|p| self.selection.len() < 2 || self.selection.contains(&p.id))
+            .collect();
+        let row_height = median_height(&previews);
+        previews.sort_by(|a, b| {
+            (a.position.y / row_height)
+                .round()
+                .total_cmp(&(b.position.y / row_height).round())
+                .then(a.position.x.total_cmp(&b.position.x))
+        });
+        previews
+    }
+
+    /// Spring a preview's position and size to `rect`
+    pub(super) fn animate_to(&mut self, preview: &Preview, rect: Rect) {
+        let spring = self.animation.get_or_create_spring(preview.id, preview.position);
+        spring.set_immediate_pos(preview.position);
+        spring.set_target_pos(rect.min);
+        let spring = self.animation.get_or_create_size_spring(preview.id, preview.size);
+        spring.set_immediate(preview.size);
+        spring.set_target(rect.size());
+    }
+
+    /// Tile the arrange targets into a packed grid and animate them into
+    /// place.
+    pub fn arrange_grid(&mut self, preview_manager: &PreviewManager) {
+        let previews = self.arrange_targets(preview_manager);
+        if previews.len() < 2 {
+            return;
+        }
+
+        let origin = previews.iter().fold(Pos2::splat(f32::INFINITY), |min, p| min.min(p.position));
+        let aspects: Vec<f32> = previews.iter().map(|p| p.size.x / p.size.y.max(1.0)).collect();
+        let rects = grid_layout(&aspects, origin, median_height(&previews), ARRANGE_GAP);
+
+        for (preview, rect) in previews.into_iter().zip(rects) {
+            self.animate_to(preview, rect);
+        }
+    }
+
+    /// Map the arrange targets into a template's slots across the visible
+    /// canvas. The slots stay on screen so empty ones can take drops.
+    pub fn apply_template(&mut self, template: LayoutTemplate, preview_manager: &mut PreviewManager, canvas_rect: Rect) {
+        let area = self.get_viewport(canvas_rect).shrink(TEMPLATE_MARGIN / self.zoom);
+        let mut placed = PlacedTemplate::new(template, area, ARRANGE_GAP);
+        let ids: Vec<PreviewId> = self.arrange_targets(preview_manager).iter().map(|p| p.id).collect();
+
+        for (id, slot) in placed.assign(&ids) {
+            if let Some(preview) = preview_manager.get(id) {
+                self.animate_to(preview, fit_in_slot(preview.size.x / preview.size.y.max(1.0), slot));
+            }
+            // Later slots stack above earlier ones (picture-in-picture's inset)
+            preview_manager.bring_to_front(id);
+        }
+        self.template = Some(placed);
+    }
+
+    /// Drop the applied template's slots (when the canvas is replaced)
+    pub fn clear_template(&mut self) {
+        self.template = None;
+    }
+
+    /// Outline the empty slots of the applied template
+    fn draw_template_slots(&mut self, painter: &egui::Painter, canvas_rect: Rect, preview_manager: &PreviewManager) {
+        if let Some(template) = &mut self.template {
+            template.retain(|id| preview_manager.get(id).is_some_and(|p| p.removing.is_none()));
+        }
+        let Some(template) = &self.template else { return; };
+
+        for slot in template.empty_slots() {
+            let screen_slot = self.canvas_rect_to_screen(slot, canvas_rect);
+            painter.rect_filled(screen_slot, 8.0, self.theme.accent_alpha(6));
+            painter.rect_stroke(screen_slot, 8.0, Stroke::new(1.0, self.theme.accent_alpha(60)));
+            painter.text(
+                screen_slot.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop a preview here",
+                egui::FontId::proportional(11.0),
+                Color32::from_rgb(75, 75, 75),
+            );
+        }
     }
 
     /// Update viewport culling - pause captures for off-screen previews
@@ -435,7 +1145,10 @@ impl CanvasState {
         for id in preview_manager.all_ids() {
             if let Some(preview) = preview_manager.get_mut(id) {
                 let preview_rect = preview.rect();
-                let is_visible = viewport.intersects(preview_rect);
+                let is_visible = (!preview.hidden
+                    && viewport.intersects(preview_rect)
+                    && self.focus.map_or(true, |f| f.id == id))
+                    || self.popped_out.contains(&id);
 
                 // Update pause state based on visibility
                 if is_visible && preview.capture_paused {
@@ -455,111 +1168,1023 @@ impl CanvasState {
         }
     }
 
-    /// Apply any pending FPS changes
-    fn apply_pending_fps_changes(
-        &mut self,
-        preview_manager: &mut PreviewManager,
-        capture_coordinator: &mut CaptureCoordinator,
-    ) {
-        for change in self.pending_fps_changes.drain(..) {
-            if let Some(preview) = preview_manager.get_mut(change.preview_id) {
-                let old_fps = preview.target_fps;
-                preview.set_fps_preset(change.new_fps);
-
-                // The capture thread reads the target live; no restart (and
-                // no black flash) needed.
-                if preview.target_fps != old_fps {
-                    capture_coordinator.set_target_fps(change.preview_id, preview.target_fps);
-                }
+    /// Put the selected previews in a new container frame around them
+    fn frame_selection(&mut self, preview_manager: &mut PreviewManager) {
+        let ids: Vec<PreviewId> = self
+            .selection
+            .iter()
+            .copied()
+            .filter(|&id| preview_manager.get(id).is_some_and(|p| p.removing.is_none()))
+            .collect();
+        let Some(bounds) = ids.iter().filter_map(|&id| preview_manager.get(id)).map(|p| p.rect()).reduce(|a, b| a.union(b)) else {
+            return;
+        };
+        let rect = Rect::from_min_max(
+            bounds.min - Vec2::new(ARRANGE_GAP, CONTAINER_HEADER),
+            bounds.max + Vec2::splat(ARRANGE_GAP),
+        );
+        // Not a name a preview still carries either, or it would join
+        let name = (1..)
+            .map(|n| format!("Frame {}", n))
+            .find(|name| {
+                !self.containers.iter().any(|c| &c.name == name)
+                    && !preview_manager.all().any(|p| p.container.as_ref() == Some(name))
+            })
+            .unwrap_or_default();
+        for id in ids {
+            if let Some(preview) = preview_manager.get_mut(id) {
+                preview.container = Some(name.clone());
             }
         }
+        self.containers.push(Container {
+            name,
+            position: (rect.min.x, rect.min.y),
+            size: (rect.width(), rect.height()),
+            arrangement: Arrangement::Grid,
+        });
+        self.reflow_containers = true;
     }
 
-    /// Handle canvas-level input (background clicks, pan, zoom)
-    fn handle_canvas_input_with_response(
-        &mut self,
-        ui: &mut egui::Ui,
-        canvas_rect: Rect,
-        preview_manager: &mut PreviewManager,
-        capture_coordinator: &mut CaptureCoordinator,
-        bg_response: egui::Response,
-        input: &FrameInput,
-    ) {
-        // Use the pre-allocated background response
+    /// Text labels: drag to move, double-click (or right-click) to edit
+    fn draw_and_interact_labels(&mut self, ui: &mut egui::Ui, canvas_rect: Rect) {
+        let painter = ui.painter_at(canvas_rect);
+        let mut deleted = None;
 
-        // Update cursor based on drag state or handle hover
-        if let Some(mouse_pos) = input.hover_pos {
-            if canvas_rect.contains(mouse_pos) {
-                if let Some((_, handle)) = self.get_handle_at(mouse_pos, canvas_rect, preview_manager) {
-                    ui.ctx().set_cursor_icon(handle.cursor());
-                }
+        for index in 0..self.labels.len() {
+            let label = &self.labels[index];
+            let font_size = label.size * self.zoom;
+            // Too small to read at this zoom
+            if font_size < 2.0 {
+                continue;
             }
-        }
-
-        // Zoom with scroll wheel - works anywhere on canvas, even over previews
-        // We check canvas_rect.contains() instead of bg_response.hovered() because
-        // bg_response.hovered() returns false when the mouse is over a preview widget
-        if let Some(mouse_pos) = input.hover_pos {
-            if canvas_rect.contains(mouse_pos) {
-                let scroll_delta = input.scroll_y;
-                if scroll_delta != 0.0 {
-                    let zoom_factor = if scroll_delta > 0.0 { 1.1 } else { 0.9 };
-                    let new_zoom = (self.zoom * zoom_factor).clamp(self.zoom_min, self.zoom_max);
-
-                    let canvas_pos = self.screen_to_canvas(mouse_pos, canvas_rect);
-                    self.zoom = new_zoom;
-                    let new_canvas_pos = self.screen_to_canvas(mouse_pos, canvas_rect);
-                    self.pan += new_canvas_pos.to_vec2() - canvas_pos.to_vec2();
-                }
+            let [r, g, b] = label.color;
+            let galley = painter.layout_no_wrap(
+                label.text.clone(),
+                egui::FontId::proportional(font_size),
+                Color32::from_rgb(r, g, b),
+            );
+            let min = self.canvas_to_screen(Pos2::new(label.position.0, label.position.1), canvas_rect);
+            let rect = Rect::from_min_size(min, galley.size());
+            if !canvas_rect.intersects(rect) {
+                continue;
             }
-        }
 
-        // Pan with middle mouse button or Alt+Left drag
-        // Works anywhere on canvas, even over previews (similar to zoom)
-        let is_panning = (input.middle_down || (input.alt && input.primary_down))
-            && canvas_rect.contains(input.hover_pos.unwrap_or_default());
-
-        if is_panning {
-            // Start panning
-            if !self.canvas_panning {
-                self.canvas_panning = true;
-                self.pan_drag_tracker.clear();
-                // Stop any existing momentum
-                self.animation.momentum_active = false;
-                self.animation.momentum_velocity = Vec2::ZERO;
+            let response = ui.interact(
+                rect.expand(4.0),
+                ui.id().with(("text_label", index)),
+                Sense::click_and_drag(),
+            );
+            if response.hovered() || response.dragged() || self.label_editor == Some(index) {
+                painter.rect_stroke(rect.expand(4.0), 4.0, Stroke::new(1.0, self.theme.accent_alpha(120)));
             }
+            painter.galley(min, galley, Color32::TRANSPARENT);
 
-            // Track velocity for momentum
-            if let Some(mouse_pos) = input.hover_pos {
-                self.pan_drag_tracker.record(mouse_pos, input.time);
+            if response.dragged() {
+                let delta = response.drag_delta() / self.zoom;
+                let label = &mut self.labels[index];
+                label.position = (label.position.0 + delta.x, label.position.1 + delta.y);
             }
-
-            // Use pointer delta directly instead of bg_response.drag_delta()
-            // because bg_response.dragged() returns false when over a preview
-            let delta = input.pointer_delta;
-            if delta != Vec2::ZERO {
-                self.pan += delta / self.zoom;
+            if response.double_clicked() {
+                self.label_editor = Some(index);
             }
-        } else if self.canvas_panning {
-            // Just released pan - apply momentum
-            self.canvas_panning = false;
-            let velocity = self.pan_drag_tracker.get_velocity();
-            self.animation.start_momentum(velocity);
-        }
-
-        // Click on empty space to deselect
-        if bg_response.clicked() && !input.ctrl {
-            if let Some(mouse_pos) = input.interact_pos {
-                let canvas_pos = self.screen_to_canvas(mouse_pos, canvas_rect);
-                if preview_manager.get_preview_at(canvas_pos).is_none() {
-                    self.selection.clear();
+            response.context_menu(|ui| {
+                if ui.button("Edit Label...").clicked() {
+                    self.label_editor = Some(index);
+                    ui.close_menu();
                 }
-            }
+                if ui.button("Delete Label").clicked() {
+                    deleted = Some(index);
+                    ui.close_menu();
+                }
+            });
         }
 
-        // Canvas context menu (right-click on empty space)
-        if bg_response.secondary_clicked() {
-            self.last_secondary_click = input.interact_pos;
+        if let Some(index) = deleted {
+            self.remove_label(index);
+        }
+    }
+
+    fn remove_label(&mut self, index: usize) {
+        self.labels.remove(index);
+        self.label_editor = match self.label_editor {
+            Some(open) if open == index => None,
+            Some(open) if open > index => Some(open - 1),
+            open => open,
+        };
+    }
+
+    /// Save the current view as a bookmark
+    pub fn add_bookmark(&mut self) {
+        self.bookmarks.push(Bookmark {
+            name: format!("View {}", self.bookmarks.len() + 1),
+            pan: (self.pan.x, self.pan.y),
+            zoom: self.zoom,
+        });
+    }
+
+    /// Glide to a bookmarked view. Returns false if there is no such bookmark.
+    pub fn go_to_bookmark(&mut self, index: usize) -> bool {
+        let Some(bookmark) = self.bookmarks.get(index) else {
+            return false;
+        };
+        let pan = Vec2::new(bookmark.pan.0, bookmark.pan.1);
+        let zoom = bookmark.zoom.clamp(self.zoom_min, self.zoom_max);
+        self.animate_view_to(pan, zoom);
+        true
+    }
+
+    /// Close the text label and widget editors (e.g. when another layout is
+    /// loaded)
+    pub fn close_label_editor(&mut self) {
+        self.label_editor = None;
+        self.widget_editor = None;
+    }
+
+    /// Editor for a text label's text, size and color; changes apply live
+    fn label_editor_ui(&mut self, ctx: &egui::Context) {
+        let Some(index) = self.label_editor else { return; };
+        let Some(label) = self.labels.get_mut(index) else {
+            self.label_editor = None;
+            return;
+        };
+        let mut done = false;
+        let mut delete = false;
+
+        egui::Window::new("Text Label")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style()).fill(Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                let response = ui.add_sized(
+                    [280.0, 24.0],
+                    egui::TextEdit::singleline(&mut label.text).hint_text("e.g. PROD"),
+                );
+                let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                ui.horizontal(|ui| {
+                    ui.label("Size");
+                    ui.add(egui::Slider::new(&mut label.size, 8.0..=160.0).integer());
+                    ui.label("Color");
+                    ui.color_edit_button_srgb(&mut label.color);
+                });
+                ui.add_space(6.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Done").clicked() || entered {
+                        done = true;
+                    }
+                    if ui.button("Delete").clicked() {
+                        delete = true;
+                    }
+                });
+            });
+
+        if delete || (done && self.labels[index].text.trim().is_empty()) {
+            self.remove_label(index);
+        } else if done {
+            self.label_editor = None;
+        }
+    }
+
+    /// Expand a preview to fill the canvas, hiding the others and raising
+    /// its capture rate until focus mode ends
+    fn enter_focus(
+        &mut self,
+        id: PreviewId,
+        canvas_rect: Rect,
+        preview_manager: &PreviewManager,
+        capture_coordinator: &mut CaptureCoordinator,
+    ) {
+        let Some(preview) = preview_manager.get(id) else { return; };
+        let rect = preview.rect();
+        let target_fps = preview.target_fps;
+        // Switching straight to another preview keeps the original view
+        let previous = match self.focus {
+            Some(focus) => {
+                if let Some(old) = preview_manager.get(focus.id) {
+                    capture_coordinator.set_target_fps(focus.id, old.target_fps);
+                }
+                focus
+            }
+            None => FocusMode { id, pan: self.pan, zoom: self.zoom },
+        };
+        self.focus = Some(FocusMode { id, ..previous });
+        capture_coordinator.set_target_fps(id, target_fps.max(FOCUS_FPS));
+        self.selection = vec![id];
+
+        let (pan, zoom) = self.view_fitting(rect, canvas_rect, FOCUS_MARGIN);
+        self.animate_view_to(pan, zoom);
+    }
+
+    /// Pan and zoom that show `rect` (canvas coordinates) as large as
+    /// possible, centered, `margin` screen pixels from the edges
+    fn view_fitting(&self, rect: Rect, canvas_rect: Rect, margin: f32) -> (Vec2, f32) {
+        let room = (canvas_rect.size() - Vec2::splat(margin * 2.0)).max(Vec2::splat(1.0));
+        let zoom = (room.x / rect.width().max(1.0))
+            .min(room.y / rect.height().max(1.0))
+            .clamp(self.zoom_min, self.zoom_max);
+        let pan = canvas_rect.size() / (2.0 * zoom) - rect.center().to_vec2();
+        (pan, zoom)
+    }
+
+    /// Glide the view to center `rect`, zooming out only if it doesn't fit
+    fn reveal(&mut self, rect: Rect, canvas_rect: Rect) {
+        let (_, fit_zoom) = self.view_fitting(rect, canvas_rect, FIT_MARGIN);
+        let zoom = fit_zoom.min(self.zoom);
+        let pan = canvas_rect.size() / (2.0 * zoom) - rect.center().to_vec2();
+        self.animate_view_to(pan, zoom);
+    }
+
+    /// Glide the view to show every preview
+    pub fn zoom_to_fit(&mut self, preview_manager: &PreviewManager, canvas_rect: Rect) {
+        let Some(bounds) = preview_manager
+            .all()
+            .filter(|p| p.removing.is_none())
+            .map(|p| p.rect())
+            .reduce(|a, b| a.union(b))
+        else {
+            self.animate_view_to(Vec2::ZERO, 1.0);
+            return;
+        };
+        let (pan, zoom) = self.view_fitting(bounds, canvas_rect, FIT_MARGIN);
+        self.animate_view_to(pan, zoom);
+    }
+
+    /// Leave focus mode: the other previews come back, the focused one
+    /// returns to its own frame rate and the view springs back
+    pub fn exit_focus(&mut self, preview_manager: &PreviewManager, capture_coordinator: &mut CaptureCoordinator) {
+        let Some(focus) = self.focus.take() else { return; };
+        if let Some(preview) = preview_manager.get(focus.id) {
+            capture_coordinator.set_target_fps(focus.id, preview.target_fps);
+        }
+        self.animate_view_to(focus.pan, focus.zoom);
+    }
+
+    /// Spring the pan and zoom toward a new view
+    fn animate_view_to(&mut self, pan: Vec2, zoom: f32) {
+        self.zoom_anchor = None;
+        let mut pan_spring = SpringVec2::new(self.pan);
+        pan_spring.set_target(pan);
+        let mut zoom_spring = SpringValue::new(self.zoom).with_tolerance(0.001);
+        zoom_spring.set_target(zoom);
+        self.animation.pan_spring = Some(pan_spring);
+        self.animation.zoom_spring = Some(zoom_spring);
+    }
+
+    /// Follow the view springs until they settle
+    fn apply_view_springs(&mut self) {
+        if let Some(spring) = &self.animation.pan_spring {
+            self.pan = spring.current();
+        }
+        if let Some(spring) = &self.animation.zoom_spring {
+            let zoom = spring.current.clamp(self.zoom_min, self.zoom_max);
+            // A smooth scroll zoom keeps the point under the cursor still
+            if let Some(anchor) = self.zoom_anchor {
+                self.pan += anchor / zoom - anchor / self.zoom;
+            }
+            self.zoom = zoom;
+        }
+        let settled = !self.animation.pan_spring.as_ref().is_some_and(|s| s.is_animating())
+            && !self.animation.zoom_spring.as_ref().is_some_and(|s| s.is_animating());
+        if settled {
+            self.stop_view_springs();
+        }
+    }
+
+    /// The user took over the view
+    fn stop_view_springs(&mut self) {
+        self.animation.pan_spring = None;
+        self.animation.zoom_spring = None;
+        self.zoom_anchor = None;
+    }
+
+    /// Zoom about `anchor` (on screen) to `zoom(from)`, where `from` is the
+    /// zoom already being headed for. Smooth Zoom glides there; steps in
+    /// quick succession add up on the zoom spring's target.
+    fn zoom_about(&mut self, anchor: Pos2, canvas_rect: Rect, zoom: impl FnOnce(f32) -> f32) {
+        if self.smooth_zoom {
+            let gliding = self.zoom_anchor.is_some();
+            let mut zoom_spring = self
+                .animation
+                .zoom_spring
+                .take()
+                .filter(|_| gliding)
+                .unwrap_or_else(|| SpringValue::new(self.zoom).with_tolerance(0.001));
+            let target = zoom(zoom_spring.target).clamp(self.zoom_min, self.zoom_max);
+            self.stop_view_springs();
+            zoom_spring.set_target(target);
+            self.animation.zoom_spring = Some(zoom_spring);
+            self.zoom_anchor = Some(anchor - canvas_rect.min);
+        } else {
+            self.stop_view_springs();
+            let canvas_pos = self.screen_to_canvas(anchor, canvas_rect);
+            self.zoom = zoom(self.zoom).clamp(self.zoom_min, self.zoom_max);
+            let new_canvas_pos = self.screen_to_canvas(anchor, canvas_rect);
+            self.pan += new_canvas_pos.to_vec2() - canvas_pos.to_vec2();
+        }
+    }
+
+    /// Pull the view's center and any preview that strayed (dragged, flung
+    /// or sprung) back inside the bounded region
+    fn keep_in_bounds(&mut self, preview_manager: &mut PreviewManager, canvas_rect: Rect) {
+        let bounds = Rect::from_center_size(Pos2::ZERO, Vec2::splat(BOUNDED_CANVAS_SIZE));
+
+        let half_view = canvas_rect.size() / (2.0 * self.zoom);
+        let center = (half_view - self.pan).to_pos2();
+        let clamped = center.clamp(bounds.min, bounds.max);
+        if clamped != center {
+            self.pan = half_view - clamped.to_vec2();
+            self.animation.momentum_active = false;
+        }
+
+        for preview in preview_manager.all_mut() {
+            let position = clamp_into(preview.rect(), bounds);
+            if position != preview.position {
+                preview.position = position;
+                if let Some(spring) = self.animation.preview_springs.get_mut(&preview.id) {
+                    spring.set_immediate_pos(position);
+                }
+            }
+        }
+    }
+
+    /// Take on the zoom limits and scroll behaviour from the app settings
+    pub fn set_zoom_settings(&mut self, settings: &ZoomSettings) {
+        self.zoom_min = settings.min;
+        self.zoom_max = settings.max.max(settings.min);
+        self.zoom_step = settings.step;
+        self.smooth_zoom = settings.smooth;
+        self.zoom = self.zoom.clamp(self.zoom_min, self.zoom_max);
+    }
+
+    /// Always-on-top windows of popped-out previews. They draw the tile's
+    /// own frames, so the capture session is shared. Drag to move, drag the
+    /// corner to resize; × puts the preview back on the canvas only.
+    fn pip_windows_ui(
+        &mut self,
+        ctx: &egui::Context,
+        preview_manager: &mut PreviewManager,
+        capture_coordinator: &mut CaptureCoordinator,
+    ) {
+        self.popped_out.retain(|&id| preview_manager.get(id).is_some_and(|p| p.removing.is_none()));
+        let mut closed = Vec::new();
+
+        for id in self.popped_out.clone() {
+            let Some(preview) = preview_manager.get(id) else { continue; };
+            let aspect = preview.size.x / preview.size.y.max(1.0);
+            let rotation = preview.rotation;
+            let style = FrameStyle {
+                quarter_turns: rotation,
+                effect: preview.effect,
+                chroma_key: preview.chroma_key,
+                corner_radius: 0.0,
+            };
+            let (display_mode, frame_aspect) = (preview.display_mode, preview.tile_aspect_ratio());
+            let builder = egui::ViewportBuilder::default()
+                .with_title(format!("{} - Pluriview", preview.display_name()))
+                .with_inner_size(Vec2::new(PIP_WIDTH, PIP_WIDTH / aspect.max(0.1)))
+                .with_min_inner_size([120.0, 80.0])
+                .with_decorations(false)
+                .with_always_on_top()
+                .with_taskbar(false);
+            capture_coordinator.set_display_size(id, None);
+
+            ctx.show_viewport_immediate(egui::ViewportId::from_hash_of(("pip", id.0)), builder, |ctx, _| {
+                egui::CentralPanel::default()
+                    .frame(egui::Frame::none().fill(Color32::BLACK))
+                    .show(ctx, |ui| {
+                        let rect = ui.max_rect();
+                        let response = ui.interact(rect, ui.id().with("pip_move"), Sense::click_and_drag());
+                        if response.drag_started() {
+                            ctx.send_viewport_cmd(egui::ViewportCommand::StartDrag);
+                        }
+
+                        let painter = ui.painter();
+                        let frame_rect = fit_in_slot(aspect, rect);
+                        if !self.blanked {
+                            if let Some((texture, uv)) = preview_manager.frame_texture(id, ctx, frame_rect.size()) {
+                                let (frame_rect, uv) = display_mode.place(frame_rect, uv, frame_aspect, rotation);
+                                self.paint_frame(painter, texture, frame_rect, uv, Color32::WHITE, style);
+                            }
+                        }
+
+                        let grip = Rect::from_min_max(rect.max - Vec2::splat(16.0), rect.max);
+                        let grip_response = ui.interact(grip, ui.id().with("pip_resize"), Sense::drag());
+                        if grip_response.drag_started() {
+                            ctx.send_viewport_cmd(egui::ViewportCommand::BeginResize(
+                                egui::viewport::ResizeDirection::SouthEast,
+                            ));
+                        }
+                        if grip_response.hovered() {
+                            ctx.set_cursor_icon(CursorIcon::ResizeSouthEast);
+                        }
+
+                        // Controls only while the pointer is over the window
+                        if ui.rect_contains_pointer(rect) {
+                            let line = Stroke::new(1.0, Color32::from_white_alpha(120));
+                            for inset in [4.0, 8.0] {
+                                painter.line_segment(
+                                    [Pos2::new(grip.max.x - inset, grip.max.y - 2.0), Pos2::new(grip.max.x - 2.0, grip.max.y - inset)],
+                                    line,
+                                );
+                            }
+                            let close = Rect::from_center_size(rect.right_top() + Vec2::new(-16.0, 16.0), Vec2::splat(20.0));
+                            let close_response = ui.interact(close, ui.id().with("pip_close"), Sense::click());
+                            painter.circle_filled(close.center(), 10.0, Color32::from_rgba_unmultiplied(0, 0, 0, 170));
+                            painter.text(
+                                close.center(),
+                                egui::Align2::CENTER_CENTER,
+                                egui_phosphor::regular::X,
+                                egui::FontId::proportional(12.0),
+                                if close_response.hovered() { Color32::WHITE } else { Color32::from_gray(190) },
+                            );
+                            if close_response.on_hover_text("Back to the canvas").clicked() {
+                                closed.push(id);
+                            }
+                        }
+                    });
+                if ctx.input(|i| i.viewport().close_requested()) {
+                    closed.push(id);
+                }
+            });
+        }
+
+        self.popped_out.retain(|id| !closed.contains(id));
+    }
+
+    /// Pick up the pen, or put down whichever ink tool is in hand
+    pub fn toggle_pen(&mut self) {
+        self.ink_tool = match self.ink_tool {
+            Some(_) => None,
+            None => Some(InkTool::Pen),
+        };
+    }
+
+    /// Put down the pen or eraser
+    pub fn put_down_ink_tool(&mut self) {
+        self.ink_tool = None;
+    }
+
+    /// Is the pen or eraser in hand?
+    pub fn ink_tool_active(&self) -> bool {
+        self.ink_tool.is_some()
+    }
+
+    /// Previews shown in pop-out windows
+    pub fn popped_out(&self) -> &[PreviewId] {
+        &self.popped_out
+    }
+
+    /// Is a preview expanded in focus mode?
+    pub fn in_focus(&self) -> bool {
+        self.focus.is_some()
+    }
+
+    /// Abandon a move, resize, crop or measurement in progress, putting
+    /// things back as they were. Returns false if nothing was being dragged.
+    pub fn cancel_drag(&mut self, preview_manager: &mut PreviewManager) -> bool {
+        match self.drag_state.take() {
+            Some(DragState::Resizing { id, start_rect, .. }) => {
+                if let Some(preview) = preview_manager.get_mut(id) {
+                    preview.position = start_rect.min;
+                    preview.size = start_rect.size();
+                }
+                self.animation.remove_spring(id);
+            }
+            Some(DragState::Cropping { id, start_crop_uv, .. } | DragState::PanningCrop { id, start_crop_uv, .. }) => {
+                if let Some(preview) = preview_manager.get_mut(id) {
+                    preview.set_crop((start_crop_uv != FULL_CROP).then_some(start_crop_uv));
+                }
+            }
+            Some(DragState::Measuring { .. }) => self.measurement = None,
+            None if self.preview_dragging => {
+                for (id, origin) in std::mem::take(&mut self.drag_origins) {
+                    if let Some(preview) = preview_manager.get_mut(id) {
+                        preview.position = origin;
+                    }
+                    if let Some(spring) = self.animation.preview_springs.get_mut(&id) {
+                        spring.set_immediate_pos(origin);
+                    }
+                }
+                self.preview_dragging = false;
+                self.guides.clear();
+                self.drag_free_pos = None;
+                self.swap_origin = None;
+                self.swap_target = None;
+            }
+            None => return false,
+        }
+        true
+    }
+
+    /// Tab / Shift+Tab: select the next or previous visible preview in
+    /// stacking order (bottom to top), bringing it into view
+    pub fn cycle_selection(&mut self, preview_manager: &PreviewManager, forward: bool) {
+        let mut previews: Vec<&Preview> = preview_manager
+            .all()
+            .filter(|p| !p.hidden && p.removing.is_none())
+            .collect();
+        if previews.is_empty() {
+            return;
+        }
+        previews.sort_by_key(|p| p.z_order);
+        let count = previews.len();
+        let current = self.selection.last().and_then(|id| previews.iter().position(|p| p.id == *id));
+        let next = match (current, forward) {
+            (Some(index), true) => (index + 1) % count,
+            (Some(index), false) => (index + count - 1) % count,
+            (None, true) => 0,
+            (None, false) => count - 1,
+        };
+        self.selection = vec![previews[next].id];
+        self.layers_jump = Some(previews[next].id);
+    }
+
+    /// Draw the ink strokes. With a tool picked, a layer over the whole
+    /// canvas takes left drags: the pen adds a stroke, the eraser removes
+    /// any stroke it passes over. Middle-drag, Alt+drag and Space+drag still pan.
+    fn draw_and_interact_ink(&mut self, ui: &mut egui::Ui, canvas_rect: Rect, input: &FrameInput) {
+        let painter = ui.painter_at(canvas_rect);
+        for stroke in self.ink.iter().chain(&self.current_stroke) {
+            self.draw_ink_stroke(&painter, canvas_rect, stroke);
+        }
+
+        let Some(tool) = self.ink_tool else {
+            if let Some(stroke) = self.current_stroke.take() {
+                self.ink.push(stroke);
+            }
+            return;
+        };
+        let response = ui.interact(canvas_rect, ui.id().with("ink_layer"), Sense::drag());
+        let pointer = input.hover_pos.filter(|pos| canvas_rect.contains(*pos));
+        let drawing = response.dragged_by(egui::PointerButton::Primary) && !input.alt && !input.space;
+
+        match (tool, pointer) {
+            (InkTool::Pen, Some(pos)) if drawing => {
+                let point = self.screen_to_canvas(pos, canvas_rect);
+                let min_spacing = MIN_POINT_SPACING / self.zoom;
+                let (width, color) = (self.pen_width, self.pen_color);
+                let stroke = self
+                    .current_stroke
+                    .get_or_insert_with(|| InkStroke { points: Vec::new(), width, color });
+                let far_enough = stroke
+                    .points
+                    .last()
+                    .map_or(true, |&(x, y)| Pos2::new(x, y).distance(point) >= min_spacing);
+                if far_enough {
+                    stroke.points.push((point.x, point.y));
+                }
+            }
+            (InkTool::Eraser, Some(pos)) => {
+                painter.circle_stroke(pos, ERASER_RADIUS, Stroke::new(1.0, Color32::from_rgb(200, 200, 205)));
+                if drawing {
+                    let point = self.screen_to_canvas(pos, canvas_rect);
+                    let radius = ERASER_RADIUS / self.zoom;
+                    self.ink.retain(|stroke| !ink::touches(stroke, point, radius));
+                }
+            }
+            _ => {}
+        }
+        if !drawing {
+            if let Some(stroke) = self.current_stroke.take() {
+                self.ink.push(stroke);
+            }
+        }
+        if response.hovered() && !input.alt && !input.space {
+            ui.ctx().set_cursor_icon(match tool {
+                InkTool::Pen => CursorIcon::Crosshair,
+                InkTool::Eraser => CursorIcon::None,
+            });
+        }
+    }
+
+    fn draw_ink_stroke(&self, painter: &egui::Painter, canvas_rect: Rect, stroke: &InkStroke) {
+        let [r, g, b] = stroke.color;
+        let color = Color32::from_rgb(r, g, b);
+        let width = (stroke.width * self.zoom).max(1.0);
+        let points: Vec<Pos2> = stroke
+            .points
+            .iter()
+            .map(|&(x, y)| self.canvas_to_screen(Pos2::new(x, y), canvas_rect))
+            .collect();
+        match points.as_slice() {
+            [] => {}
+            [dot] => {
+                painter.circle_filled(*dot, width / 2.0, color);
+            }
+            _ => {
+                painter.add(egui::Shape::line(points, Stroke::new(width, color)));
+            }
+        }
+    }
+
+    /// Floating toolbar while an ink tool is in hand: pen or eraser, color,
+    /// width, clear and done
+    fn ink_toolbar_ui(&mut self, ctx: &egui::Context, canvas_rect: Rect) {
+        let Some(tool) = self.ink_tool else { return; };
+        egui::Area::new(egui::Id::new("ink_toolbar"))
+            .order(egui::Order::Foreground)
+            .pivot(egui::Align2::CENTER_TOP)
+            .fixed_pos(canvas_rect.center_top() + Vec2::new(0.0, 48.0))
+            .show(ctx, |ui| {
+                egui::Frame::none()
+                    .fill(Color32::from_rgba_unmultiplied(24, 24, 28, 235))
+                    .stroke(Stroke::new(1.0, Color32::from_rgb(40, 40, 46)))
+                    .rounding(8.0)
+                    .inner_margin(egui::Margin::symmetric(10.0, 6.0))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            if ui
+                                .selectable_label(tool == InkTool::Pen, format!("{} Pen", egui_phosphor::regular::PENCIL_SIMPLE))
+                                .clicked()
+                            {
+                                self.ink_tool = Some(InkTool::Pen);
+                            }
+                            if ui
+                                .selectable_label(tool == InkTool::Eraser, format!("{} Eraser", egui_phosphor::regular::ERASER))
+                                .clicked()
+                            {
+                                self.ink_tool = Some(InkTool::Eraser);
+                            }
+                            ui.separator();
+                            for swatch in PEN_SWATCHES {
+                                let [r, g, b] = swatch;
+                                let (rect, response) = ui.allocate_exact_size(Vec2::splat(16.0), Sense::click());
+                                ui.painter().circle_filled(rect.center(), 7.0, Color32::from_rgb(r, g, b));
+                                if self.pen_color == swatch {
+                                    ui.painter().circle_stroke(rect.center(), 8.0, Stroke::new(1.5, Color32::WHITE));
+                                }
+                                if response.clicked() {
+                                    self.pen_color = swatch;
+                                    self.ink_tool = Some(InkTool::Pen);
+                                }
+                            }
+                            ui.color_edit_button_srgb(&mut self.pen_color);
+                            ui.separator();
+                            ui.add(egui::Slider::new(&mut self.pen_width, 1.0..=32.0).integer().text("Width"));
+                            ui.separator();
+                            if ui.add_enabled(!self.ink.is_empty(), egui::Button::new("Clear")).clicked() {
+                                self.ink.clear();
+                            }
+                            if ui.button("Done").clicked() {
+                                self.ink_tool = None;
+                            }
+                        });
+                    });
+            });
+    }
+
+    /// Divide each FPS group's budget among its members that are on
+    /// screen; offscreen ones are paused and don't take a share.
+    fn apply_fps_budgets(&self, preview_manager: &PreviewManager, capture_coordinator: &mut CaptureCoordinator) {
+        let mut limits = HashMap::new();
+        for group in &self.fps_groups {
+            let members: Vec<&Preview> = preview_manager
+                .all()
+                .filter(|p| p.fps_group.as_deref() == Some(group.name.as_str()))
+                .filter(|p| !p.capture_paused && p.removing.is_none())
+                .collect();
+            let wants: Vec<u32> = members.iter().map(|p| p.target_fps).collect();
+            for (preview, share) in members.iter().zip(divide_budget(group.budget, &wants)) {
+                limits.insert(preview.id, share);
+            }
+        }
+        for id in preview_manager.all_ids() {
+            capture_coordinator.set_fps_limit(id, limits.get(&id).copied());
+        }
+    }
+
+    /// Put a preview in an FPS group (or take it out with None); a selected
+    /// preview brings the rest of the selection along.
+    fn set_fps_group(&self, id: PreviewId, group: Option<&str>, preview_manager: &mut PreviewManager) {
+        let ids = if self.selection.contains(&id) { self.selection.clone() } else { vec![id] };
+        for id in ids {
+            if let Some(preview) = preview_manager.get_mut(id) {
+                preview.fps_group = group.map(str::to_string);
+            }
+        }
+    }
+
+    /// "FPS Groups" window: name, budget and size of each group
+    fn fps_groups_ui(&mut self, ctx: &egui::Context, preview_manager: &mut PreviewManager) {
+        if !self.show_fps_groups {
+            return;
+        }
+        let mut open = true;
+        let mut renamed = None;
+        let mut deleted = None;
+
+        egui::Window::new("FPS Groups")
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .frame(egui::Frame::window(&ctx.style()).fill(Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                ui.label(
+                    egui::RichText::new("Each group's budget is shared by its previews on screen.")
+                        .weak(),
+                );
+                ui.add_space(6.0);
+                if self.fps_groups.is_empty() {
+                    ui.label(
+                        egui::RichText::new("No groups yet. Add previews from their \"FPS Group\" menu.")
+                            .weak()
+                            .small(),
+                    );
+                }
+                egui::Grid::new("fps_groups_grid").num_columns(4).spacing([12.0, 6.0]).show(ui, |ui| {
+                    for (index, group) in self.fps_groups.iter_mut().enumerate() {
+                        let before = group.name.clone();
+                        // Renamed on Enter (or leaving the field); a name
+                        // that's empty or another group's goes back to the
+                        // old one
+                        let mut text = match &self.fps_group_rename {
+                            Some((of, draft)) if *of == before => draft.clone(),
+                            _ => before.clone(),
+                        };
+                        let response = ui.add_sized(
+                            [160.0, 20.0],
+                            egui::TextEdit::singleline(&mut text).id_salt(("fps_group_name", index)),
+                        );
+                        if response.changed() {
+                            self.fps_group_rename = Some((before.clone(), text));
+                        }
+                        if response.lost_focus() && self.fps_group_rename.as_ref().is_some_and(|(of, _)| *of == before) {
+                            renamed = self.fps_group_rename.take();
+                        }
+                        ui.add(egui::DragValue::new(&mut group.budget).range(1..=240).suffix(" FPS"));
+                        let members = preview_manager
+                            .all()
+                            .filter(|p| p.fps_group.as_deref() == Some(before.as_str()))
+                            .count();
+                        ui.label(
+                            egui::RichText::new(format!("{} preview{}", members, if members == 1 { "" } else { "s" }))
+                                .weak(),
+                        );
+                        if ui.button(egui_phosphor::regular::TRASH).on_hover_text("Delete group").clicked() {
+                            deleted = Some(index);
+                        }
+                        ui.end_row();
+                    }
+                });
+                ui.add_space(6.0);
+                if ui.button("Add Group").clicked() {
+                    self.fps_groups.push(new_fps_group(&self.fps_groups));
+                }
+            });
+
+        if let Some((old, new)) = renamed {
+            let new = new.trim().to_string();
+            let taken = self.fps_groups.iter().any(|g| g.name == new);
+            if !new.is_empty() && !taken {
+                for preview in preview_manager.all_mut().filter(|p| p.fps_group.as_deref() == Some(old.as_str())) {
+                    preview.fps_group = Some(new.clone());
+                }
+                if let Some(group) = self.fps_groups.iter_mut().find(|g| g.name == old) {
+                    group.name = new;
+                }
+            }
+        }
+        if let Some(index) = deleted {
+            let group = self.fps_groups.remove(index);
+            for preview in preview_manager.all_mut().filter(|p| p.fps_group.as_deref() == Some(group.name.as_str())) {
+                preview.fps_group = None;
+            }
+        }
+        if !open {
+            self.fps_group_rename = None;
+        }
+        self.show_fps_groups = open;
+    }
+
+    /// Layers panel: every preview from the top of the stack down. Drag a
+    /// row by its grip to restack, click to select, double-click to bring
+    /// it into view; the eye hides it from the canvas.
+    pub fn layers_ui(&mut self, ui: &mut egui::Ui, preview_manager: &mut PreviewManager) {
+        use egui_phosphor::regular::{APP_WINDOW, DOTS_SIX_VERTICAL, EYE, EYE_SLASH, GLOBE};
+
+        ui.label(egui::RichText::new("Layers").strong());
+        ui.add_space(6.0);
+        let mut stack = preview_manager.stacking();
+        if stack.is_empty() {
+            ui.label(egui::RichText::new("Previews you add are listed here, topmost first.").weak());
+            return;
+        }
+        stack.reverse();
+
+        let mut dropped = None;
+        let mut toggled = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (row, &id) in stack.iter().enumerate() {
+                let Some(preview) = preview_manager.get(id) else { continue; };
+                let hidden = preview.hidden;
+                let icon = if preview.is_browser() { GLOBE } else { APP_WINDOW };
+                let name = match &preview.browser_url {
+                    Some(url) if preview.nickname.is_none() => url.as_str(),
+                    _ => preview.display_name(),
+                };
+                let mut text = egui::RichText::new(format!("{} {}", icon, name));
+                if hidden {
+                    text = text.weak();
+                }
+
+                let fill = if self.selection.contains(&id) { self.theme.accent_alpha(40) } else { Color32::TRANSPARENT };
+                let frame = egui::Frame::none()
+                    .fill(fill)
+                    .rounding(4.0)
+                    .inner_margin(egui::Margin::symmetric(4.0, 3.0))
+                    .show(ui, |ui| {
+                        ui.set_width(ui.available_width());
+                        ui.horizontal(|ui| {
+                            ui.dnd_drag_source(ui.id().with(("layer", id.0)), id, |ui| {
+                                ui.label(egui::RichText::new(DOTS_SIX_VERTICAL).weak());
+                            });
+                            let eye = ui
+                                .add(egui::Button::new(if hidden { EYE_SLASH } else { EYE }).frame(false))
+                                .on_hover_text(if hidden { "Show" } else { "Hide" });
+                            if eye.clicked() {
+                                toggled = Some(id);
+                            }
+                            let label = ui.add(egui::Label::new(text).truncate().selectable(false).sense(Sense::click()));
+                            if label.clicked() {
+                                self.selection = vec![id];
+                            }
+                            if label.double_clicked() {
+                                self.layers_jump = Some(id);
+                            }
+                        });
+                    });
+
+                // Drop above or below this row, whichever half the pointer is in
+                let response = frame.response;
+                if let (Some(pointer), Some(_)) = (ui.input(|i| i.pointer.interact_pos()), response.dnd_hover_payload::<PreviewId>()) {
+                    let below = pointer.y > response.rect.center().y;
+                    let y = if below { response.rect.bottom() } else { response.rect.top() };
+                    ui.painter().hline(response.rect.x_range(), y, Stroke::new(2.0, self.theme.accent()));
+                    if let Some(dragged) = response.dnd_release_payload::<PreviewId>() {
+                        dropped = Some((*dragged, row + below as usize));
+                    }
+                }
+            }
+        });
+
+        if let Some((dragged, mut slot)) = dropped {
+            // `slot` counts from the top with the dragged row still in place
+            if let Some(from) = stack.iter().position(|&p| p == dragged) {
+                if from < slot {
+                    slot -= 1;
+                }
+                preview_manager.move_to_layer(dragged, stack.len() - 1 - slot);
+            }
+        }
+        if let Some(preview) = toggled.and_then(|id| preview_manager.get_mut(id)) {
+            preview.hidden = !preview.hidden;
+            if preview.hidden {
+                let id = preview.id;
+                self.selection.retain(|&selected| selected != id);
+            }
+        }
+    }
+
+    /// Apply any pending FPS changes
+    fn apply_pending_fps_changes(
+        &mut self,
+        preview_manager: &mut PreviewManager,
+        capture_coordinator: &mut CaptureCoordinator,
+    ) {
+        for change in self.pending_fps_changes.drain(..) {
+            if let Some(preview) = preview_manager.get_mut(change.preview_id) {
+                let old_fps = preview.target_fps;
+                preview.set_fps_preset(change.new_fps);
+
+                // The capture thread reads the target live; no restart (and
+                // no black flash) needed.
+                if preview.target_fps != old_fps {
+                    capture_coordinator.set_target_fps(change.preview_id, preview.target_fps);
+                }
+            }
+        }
+    }
+
+    /// Handle canvas-level input (background clicks, pan, zoom)
+    fn handle_canvas_input_with_response(
+        &mut self,
+        ui: &mut egui::Ui,
+        canvas_rect: Rect,
+        preview_manager: &mut PreviewManager,
+        capture_coordinator: &mut CaptureCoordinator,
+        bg_response: egui::Response,
+        input: &FrameInput,
+    ) {
+        // Use the pre-allocated background response
+
+        // Update cursor based on drag state or handle hover
+        if let Some(mouse_pos) = input.hover_pos {
+            if canvas_rect.contains(mouse_pos) {
+                if let Some((_, handle)) = self.get_handle_at(mouse_pos, canvas_rect, preview_manager) {
+                    ui.ctx().set_cursor_icon(handle.cursor());
+                }
+            }
+        }
+
+        // Zoom with scroll wheel - works anywhere on canvas, even over previews
+        // We check canvas_rect.contains() instead of bg_response.hovered() because
+        // bg_response.hovered() returns false when the mouse is over a preview widget
+        if let Some(mouse_pos) = input.hover_pos {
+            if canvas_rect.contains(mouse_pos) {
+                let scroll_delta = input.scroll_y;
+                if scroll_delta != 0.0 {
+                    let zoom_factor = if scroll_delta > 0.0 { 1.0 + self.zoom_step } else { 1.0 - self.zoom_step };
+                    self.zoom_about(mouse_pos, canvas_rect, |zoom| zoom * zoom_factor);
+                }
+            }
+        }
+
+        // Ctrl+Plus / Ctrl+Minus / Ctrl+0, about the middle of the view
+        if !ui.ctx().wants_keyboard_input() {
+            if input.zoom_keys != 0 {
+                let zoom_factor = if input.zoom_keys > 0 { 1.0 + self.zoom_step } else { 1.0 - self.zoom_step };
+                self.zoom_about(canvas_rect.center(), canvas_rect, |zoom| zoom * zoom_factor);
+            }
+            if input.zoom_reset {
+                self.zoom_about(canvas_rect.center(), canvas_rect, |_| 1.0);
+            }
+        }
+
+        // Pinch to zoom, about the fingers on a touch screen
+        let pinch_center = input.touch_pan.map(|(_, center)| center).or(input.hover_pos);
+        if let Some(center) = pinch_center.filter(|&pos| input.pinch != 1.0 && canvas_rect.contains(pos)) {
+            self.stop_view_springs();
+            let canvas_pos = self.screen_to_canvas(center, canvas_rect);
+            self.zoom = (self.zoom * input.pinch).clamp(self.zoom_min, self.zoom_max);
+            let new_canvas_pos = self.screen_to_canvas(center, canvas_rect);
+            self.pan += new_canvas_pos.to_vec2() - canvas_pos.to_vec2();
+        }
+
+        // Pan with middle mouse button, Alt+Left or Space+Left drag or two
+        // fingers. Works anywhere on canvas, even over previews (similar to zoom)
+        let touch_pan = input.touch_pan.filter(|&(_, center)| canvas_rect.contains(center));
+        let is_panning = ((input.middle_down || ((input.alt || input.space) && input.primary_down))
+            && canvas_rect.contains(input.hover_pos.unwrap_or_default()))
+            || touch_pan.is_some();
+        if input.space && canvas_rect.contains(input.hover_pos.unwrap_or_default()) {
+            ui.ctx().set_cursor_icon(if is_panning { CursorIcon::Grabbing } else { CursorIcon::Grab });
+        }
+
+        if is_panning {
+            // Start panning
+            if !self.canvas_panning {
+                self.canvas_panning = true;
+                self.pan_drag_tracker.clear();
+                // Stop any existing momentum or view animation
+                self.animation.momentum_active = false;
+                self.animation.momentum_velocity = Vec2::ZERO;
+                self.stop_view_springs();
+            }
+
+            // Track velocity for momentum
+            if let Some(pos) = touch_pan.map(|(_, center)| center).or(input.hover_pos) {
+                self.pan_drag_tracker.record(pos, input.time);
+            }
+
+            // Use pointer delta directly instead of bg_response.drag_delta()
+            // because bg_response.dragged() returns false when over a preview.
+            // The pointer follows only the first finger, so touch uses the
+            // fingers' shared movement.
+            let delta = touch_pan.map_or(input.pointer_delta, |(translation, _)| translation);
+            if delta != Vec2::ZERO {
+                self.pan += delta / self.zoom;
+            }
+        } else if self.canvas_panning {
+            // Just released pan - apply momentum
+            self.canvas_panning = false;
+            let velocity = self.pan_drag_tracker.get_velocity();
+            self.animation.start_momentum(velocity);
+        }
+
+        // Click on empty space to deselect
+        if bg_response.clicked() && !input.ctrl {
+            if let Some(mouse_pos) = input.interact_pos {
+                let canvas_pos = self.screen_to_canvas(mouse_pos, canvas_rect);
+                if preview_manager.get_preview_at(canvas_pos).is_none() {
+                    self.selection.clear();
+                }
+            }
+        }
+
+        // Double-click empty space to add a window right there
+        if bg_response.double_clicked() && self.ink_tool.is_none() {
+            if let Some(screen_pos) = input.interact_pos {
+                let canvas_pos = self.screen_to_canvas(screen_pos, canvas_rect);
+                if preview_manager.get_preview_at(canvas_pos).is_none() {
+                    self.pending_quick_add = Some((canvas_pos, screen_pos));
+                }
+            }
+        }
+
+        // Canvas context menu (right-click on empty space, or a long press
+        // on a touch screen)
+        if bg_response.secondary_clicked() || bg_response.long_touched() {
+            self.last_secondary_click = input.interact_pos;
         }
 
         bg_response.context_menu(|ui| {
@@ -576,15 +2201,84 @@ impl CanvasState {
                 }
                 ui.close_menu();
             }
+            if ui.button("Pen Tool (P)").clicked() {
+                self.ink_tool = Some(InkTool::Pen);
+                ui.close_menu();
+            }
+            if !self.ink.is_empty() && ui.button("Clear Ink").clicked() {
+                self.ink.clear();
+                ui.close_menu();
+            }
+            if ui.button("Add Text Label").clicked() {
+                if let Some(screen_pos) = self.last_secondary_click {
+                    let position = self.screen_to_canvas(screen_pos, canvas_rect);
+                    self.labels.push(TextLabel {
+                        text: "Label".to_string(),
+                        position: (position.x, position.y),
+                        size: DEFAULT_LABEL_SIZE,
+                        color: [230, 230, 235],
+                    });
+                    self.label_editor = Some(self.labels.len() - 1);
+                }
+                ui.close_menu();
+            }
+            if ui.button("Add Clock").clicked() {
+                if let Some(screen_pos) = self.last_secondary_click {
+                    let position = self.screen_to_canvas(screen_pos, canvas_rect);
+                    self.widgets.push(widgets::new_clock((position.x, position.y)));
+                    self.widget_editor = Some(self.widgets.len() - 1);
+                }
+                ui.close_menu();
+            }
+            if ui.button("Add Countdown").clicked() {
+                if let Some(screen_pos) = self.last_secondary_click {
+                    let position = self.screen_to_canvas(screen_pos, canvas_rect);
+                    self.widgets.push(widgets::new_countdown((position.x, position.y)));
+                    self.widget_editor = Some(self.widgets.len() - 1);
+                }
+                ui.close_menu();
+            }
             ui.separator();
+            if ui.button("Zoom to Fit (Home)").clicked() {
+                self.zoom_to_fit(preview_manager, canvas_rect);
+                ui.close_menu();
+            }
             if ui.button("Reset View").clicked() {
                 self.reset();
                 ui.close_menu();
             }
             ui.separator();
+            ui.menu_button("Arrange", |ui| {
+                if ui.button("Grid").clicked() {
+                    self.arrange_grid(preview_manager);
+                    ui.close_menu();
+                }
+                ui.separator();
+                for template in LayoutTemplate::ALL {
+                    let current = self.template.as_ref().is_some_and(|t| t.template == template);
+                    if ui.selectable_label(current, format!("{} Template", template.label())).clicked() {
+                        self.apply_template(template, preview_manager, canvas_rect);
+                        ui.close_menu();
+                    }
+                }
+                if self.template.is_some() && ui.button("Hide Template Slots").clicked() {
+                    self.template = None;
+                    ui.close_menu();
+                }
+            });
             ui.checkbox(&mut self.show_grid, "Show Grid");
+            ui.checkbox(&mut self.show_heat_map, "Activity Heat Map");
+            ui.checkbox(&mut self.show_minimap, "Minimap");
+            if ui.button("FPS Groups...").clicked() {
+                self.show_fps_groups = true;
+                ui.close_menu();
+            }
             ui.separator();
             if !self.selection.is_empty() {
+                if ui.button("Put Selection in a Frame").clicked() {
+                    self.frame_selection(preview_manager);
+                    ui.close_menu();
+                }
                 if ui.button("Remove Selected").clicked() {
                     for id in self.selection.clone() {
                         capture_coordinator.stop_capture(id);
@@ -607,11 +2301,562 @@ impl CanvasState {
             }
 
             if input.select_all {
-                self.selection = preview_manager.all_ids();
+                self.selection = preview_manager.all().filter(|p| !p.hidden).map(|p| p.id).collect();
+            }
+
+            if input.rotate != 0 && !input.ctrl {
+                for id in &self.selection {
+                    if let Some(preview) = preview_manager.get_mut(*id).filter(|p| !p.is_browser()) {
+                        preview.rotate(input.rotate);
+                    }
+                }
+            }
+
+            if input.page_up || input.page_down {
+                self.restack_selection(preview_manager, input.page_up, input.ctrl);
+                ui.ctx().request_repaint();
+            }
+
+            if input.focus {
+                if self.focus.is_some() {
+                    self.exit_focus(preview_manager, capture_coordinator);
+                } else if let [id] = self.selection[..] {
+                    self.enter_focus(id, canvas_rect, preview_manager, capture_coordinator);
+                }
+            }
+
+            if input.home && self.focus.is_none() {
+                self.zoom_to_fit(preview_manager, canvas_rect);
+            }
+
+            if input.duplicate {
+                let tiles = self.selected_tiles(preview_manager);
+                self.paste_tiles(&tiles, Vec2::splat(DUPLICATE_OFFSET), preview_manager, capture_coordinator);
+            }
+            if input.copy && !self.selection.is_empty() {
+                self.clipboard = self.selected_tiles(preview_manager);
+                self.paste_count = 0;
+            }
+            if input.paste && !self.clipboard.is_empty() {
+                self.paste_count += 1;
+                let offset = Vec2::splat(DUPLICATE_OFFSET * self.paste_count as f32);
+                let tiles = self.clipboard.clone();
+                self.paste_tiles(&tiles, offset, preview_manager, capture_coordinator);
+            }
+
+            // Arrow keys nudge the selection one unit, Shift a grid step
+            if input.nudge != Vec2::ZERO && !input.alt {
+                let step = if input.shift { self.grid_size } else { 1.0 };
+                for &id in &self.selection {
+                    preview_manager.translate(id, input.nudge * step);
+                    if let (Some(preview), Some(spring)) =
+                        (preview_manager.get(id), self.animation.preview_springs.get_mut(&id))
+                    {
+                        spring.set_immediate_pos(preview.position);
+                    }
+                }
+            }
+
+            // Ctrl+arrows step the selection onto the next grid line
+            if input.grid_step != Vec2::ZERO && !input.alt {
+                for &id in &self.selection {
+                    let Some(preview) = preview_manager.get_mut(id) else { continue; };
+                    preview.position = grid_step(preview.position, input.grid_step, self.grid_size);
+                    if let Some(spring) = self.animation.preview_springs.get_mut(&id) {
+                        spring.set_immediate_pos(preview.position);
+                    }
+                }
             }
         }
     }
 
+    /// "Add Note" dialog for the preview picked from its context menu. An
+    /// empty note removes it.
+    fn note_editor_ui(&mut self, ctx: &egui::Context, preview_manager: &mut PreviewManager) {
+        let Some((id, text)) = self.note_editor.as_mut() else { return; };
+        let id = *id;
+        let mut save = false;
+        let mut cancel = false;
+
+        egui::Window::new("Note")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style()).fill(Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                let response = ui.add_sized(
+                    [280.0, 24.0],
+                    egui::TextEdit::singleline(text)
+                        .hint_text("e.g. ignore until Friday")
+                        .char_limit(NOTE_MAX_CHARS),
+                );
+                response.request_focus();
+                let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() || entered {
+                        save = true;
+                    }
+                    if ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if save {
+            if let (Some((_, text)), Some(preview)) = (self.note_editor.take(), preview_manager.get_mut(id)) {
+                let text = text.trim();
+                preview.note = (!text.is_empty()).then(|| text.to_string());
+            }
+        } else if cancel || preview_manager.get(id).is_none() {
+            self.note_editor = None;
+        }
+    }
+
+    /// "Rename" dialog: the nickname shown instead of the window title.
+    /// Saving an empty name goes back to the title.
+    fn rename_editor_ui(&mut self, ctx: &egui::Context, preview_manager: &mut PreviewManager) {
+        let Some((id, text)) = self.rename_editor.as_mut() else { return; };
+        let id = *id;
+        let mut save = false;
+        let mut cancel = false;
+
+        egui::Window::new("Rename")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style()).fill(Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                let title = preview_manager.get(id).map(|p| p.title.clone()).unwrap_or_default();
+                let response = ui.add_sized(
+                    [280.0, 24.0],
+                    egui::TextEdit::singleline(text).hint_text(title),
+                );
+                response.request_focus();
+                let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() || entered {
+                        save = true;
+                    }
+                    if ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if save {
+            if let (Some((_, text)), Some(preview)) = (self.rename_editor.take(), preview_manager.get_mut(id)) {
+                let text = text.trim();
+                preview.nickname = (!text.is_empty()).then(|| text.to_string());
+            }
+        } else if cancel || preview_manager.get(id).is_none() {
+            self.rename_editor = None;
+        }
+    }
+
+    /// "Crop to Pixels" dialog: the crop as a frame-pixel rectangle,
+    /// applied through `Preview::set_crop_pixels`.
+    fn crop_editor_ui(&mut self, ctx: &egui::Context, preview_manager: &mut PreviewManager) {
+        let Some((id, rect)) = self.crop_editor.as_mut() else { return; };
+        let id = *id;
+        let frame_size = preview_manager.get(id).and_then(|p| p.frame_size);
+        let Some((frame_w, frame_h)) = frame_size.filter(|&(w, h)| w > 0 && h > 0) else {
+            self.crop_editor = None;
+            return;
+        };
+        let mut save = false;
+        let mut cancel = false;
+
+        egui::Window::new("Crop to Pixels")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style()).fill(Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(format!("Frame is {} × {}", frame_w, frame_h)).weak().small());
+                egui::Grid::new("crop_pixels").num_columns(4).spacing([8.0, 6.0]).show(ui, |ui| {
+                    let [x, y, width, height] = rect;
+                    ui.label("X");
+                    ui.add(egui::DragValue::new(x).range(0..=frame_w - 1).suffix(" px"));
+                    ui.label("Y");
+                    ui.add(egui::DragValue::new(y).range(0..=frame_h - 1).suffix(" px"));
+                    ui.end_row();
+                    ui.label("Width");
+                    ui.add(egui::DragValue::new(width).range(1..=frame_w - *x).suffix(" px"));
+                    ui.label("Height");
+                    ui.add(egui::DragValue::new(height).range(1..=frame_h - *y).suffix(" px"));
+                    ui.end_row();
+                });
+                // Moving the origin can push the far edge past the frame
+                rect[2] = rect[2].clamp(1, frame_w - rect[0]);
+                rect[3] = rect[3].clamp(1, frame_h - rect[1]);
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Apply").clicked() || ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        save = true;
+                    }
+                    if ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if save {
+            if let (Some((_, [x, y, w, h])), Some(preview)) = (self.crop_editor.take(), preview_manager.get_mut(id)) {
+                preview.set_crop_pixels(x, y, x + w, y + h);
+            }
+        } else if cancel {
+            self.crop_editor = None;
+        }
+    }
+
+    /// "Edit Crop" window: the whole frame dimmed, with the crop drawn over
+    /// it at full brightness. Drag inside to move it, or its handles to
+    /// resize; the readout is in frame pixels. The frame is shown in its
+    /// own orientation (before rotation and flips), like the crop itself.
+    fn crop_edit_ui(&mut self, ctx: &egui::Context, preview_manager: &mut PreviewManager) {
+        let Some(edit) = self.crop_edit else { return; };
+        let frame_size = preview_manager.get(edit.id).and_then(|p| p.frame_size);
+        let Some((frame_w, frame_h)) = frame_size.filter(|&(w, h)| w > 0 && h > 0) else {
+            self.crop_edit = None;
+            return;
+        };
+        let texture = preview_manager.uncropped_texture(edit.id);
+        let mut crop = edit.crop;
+        let mut drag_start = edit.drag_start;
+        let mut apply = false;
+        let mut cancel = false;
+
+        egui::Window::new("Edit Crop")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style()).fill(Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                let bounds = Vec2::new(640.0, 400.0);
+                let scale = (bounds.x / frame_w as f32).min(bounds.y / frame_h as f32);
+                let size = Vec2::new(frame_w as f32, frame_h as f32) * scale;
+                let (frame_rect, _) = ui.allocate_exact_size(size, Sense::hover());
+                let painter = ui.painter_at(frame_rect);
+
+                match texture {
+                    Some((texture, uv)) if !self.blanked => {
+                        self.paint_frame(&painter, texture, frame_rect, uv, Color32::WHITE, FrameStyle::default());
+                    }
+                    _ => painter.rect_filled(frame_rect, 0.0, Color32::from_rgb(22, 22, 24)),
+                }
+
+                // Dim everything outside the crop
+                let crop_rect = Rect::from_min_max(
+                    frame_rect.lerp_inside(Vec2::new(crop.0, crop.1)),
+                    frame_rect.lerp_inside(Vec2::new(crop.2, crop.3)),
+                );
+                let dim = Color32::from_black_alpha(170);
+                for shade in [
+                    Rect::from_min_max(frame_rect.min, Pos2::new(frame_rect.max.x, crop_rect.min.y)),
+                    Rect::from_min_max(Pos2::new(frame_rect.min.x, crop_rect.max.y), frame_rect.max),
+                    Rect::from_min_max(Pos2::new(frame_rect.min.x, crop_rect.min.y), Pos2::new(crop_rect.min.x, crop_rect.max.y)),
+                    Rect::from_min_max(Pos2::new(crop_rect.max.x, crop_rect.min.y), Pos2::new(frame_rect.max.x, crop_rect.max.y)),
+                ] {
+                    painter.rect_filled(shade, 0.0, dim);
+                }
+                let crop_color = Color32::from_rgb(255, 150, 100);
+                painter.rect_stroke(crop_rect, 0.0, Stroke::new(1.5, crop_color));
+
+                // Pointer travel since the press, in frame UVs
+                let drag_delta = |ui: &egui::Ui| {
+                    ui.input(|i| match (i.pointer.press_origin(), i.pointer.interact_pos()) {
+                        (Some(origin), Some(pos)) => (pos - origin) / frame_rect.size(),
+                        _ => Vec2::ZERO,
+                    })
+                };
+                // At least 8 frame pixels either way
+                let min_size = Vec2::new(8.0 / frame_w as f32, 8.0 / frame_h as f32).min(Vec2::splat(1.0));
+
+                let body = ui.interact(crop_rect, ui.id().with("crop_edit_body"), Sense::drag());
+                if body.hovered() || body.dragged() {
+                    ui.ctx().set_cursor_icon(if body.dragged() { CursorIcon::Grabbing } else { CursorIcon::Grab });
+                }
+                if body.drag_started() {
+                    drag_start = Some(crop);
+                }
+                if body.dragged() {
+                    if let Some(start) = drag_start {
+                        crop = slide_crop(start, drag_delta(ui));
+                    }
+                }
+
+                let handles = [
+                    (crop_rect.left_top(), ResizeHandle::TopLeft),
+                    (crop_rect.center_top(), ResizeHandle::Top),
+                    (crop_rect.right_top(), ResizeHandle::TopRight),
+                    (crop_rect.left_center(), ResizeHandle::Left),
+                    (crop_rect.right_center(), ResizeHandle::Right),
+                    (crop_rect.left_bottom(), ResizeHandle::BottomLeft),
+                    (crop_rect.center_bottom(), ResizeHandle::Bottom),
+                    (crop_rect.right_bottom(), ResizeHandle::BottomRight),
+                ];
+                for (handle_pos, handle) in handles {
+                    ui.painter().rect_filled(Rect::from_center_size(handle_pos, Vec2::splat(8.0)), 2.0, crop_color);
+                    let response = ui.interact(
+                        Rect::from_center_size(handle_pos, Vec2::splat(16.0)),
+                        ui.id().with(("crop_edit_handle", handle as u8)),
+                        Sense::drag(),
+                    );
+                    if response.hovered() || response.dragged() {
+                        ui.ctx().set_cursor_icon(handle.cursor());
+                    }
+                    if response.drag_started() {
+                        drag_start = Some(crop);
+                    }
+                    if response.dragged() {
+                        if let Some(start) = drag_start {
+                            crop = adjust_crop(start, handle, drag_delta(ui), min_size);
+                        }
+                    }
+                }
+                if !ui.input(|i| i.pointer.any_down()) {
+                    drag_start = None;
+                }
+
+                let (x, y, w, h) = crop_pixels(crop, frame_w, frame_h);
+                ui.label(
+                    egui::RichText::new(format!("x {}  y {}  ·  {} × {} px  ·  frame {} × {}", x, y, w, h, frame_w, frame_h))
+                        .small()
+                        .monospace(),
+                );
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if ui.button("Apply").clicked() || ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        apply = true;
+                    }
+                    if ui.button("Whole Frame").clicked() {
+                        crop = FULL_CROP;
+                    }
+                    if ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if apply {
+            if let Some(preview) = preview_manager.get_mut(edit.id) {
+                preview.set_crop((crop != FULL_CROP).then_some(crop));
+            }
+            self.crop_edit = None;
+        } else if cancel {
+            self.crop_edit = None;
+        } else {
+            self.crop_edit = Some(CropEdit { crop, drag_start, ..edit });
+        }
+    }
+
+    /// The selected window previews, in stacking order, for duplicating or
+    /// copying. Browser tiles are left out: each is its own WebView.
+    fn selected_tiles(&self, preview_manager: &PreviewManager) -> Vec<RemovedPreviewInfo> {
+        let mut tiles: Vec<RemovedPreviewInfo> = self
+            .selection
+            .iter()
+            .filter_map(|&id| preview_manager.get(id))
+            .filter(|p| !p.is_browser() && p.removing.is_none())
+            .map(RemovedPreviewInfo::of)
+            .collect();
+        tiles.sort_by_key(|tile| tile.z_order);
+        tiles
+    }
+
+    /// Add copies of previews `offset` from where they were, with the same
+    /// window, crop, rotation, frame rate and capture options, and select
+    /// them. A copy whose original is still on the canvas shares its
+    /// capture session rather than capturing the window a second time.
+    fn paste_tiles(
+        &mut self,
+        tiles: &[RemovedPreviewInfo],
+        offset: Vec2,
+        preview_manager: &mut PreviewManager,
+        capture_coordinator: &mut CaptureCoordinator,
+    ) {
+        let mut pasted = Vec::new();
+        for tile in tiles {
+            let Some(handle) = &tile.window_handle else { continue; };
+            let id = preview_manager.add_for_window(
+                handle.hwnd,
+                handle.process_id,
+                tile.title.clone(),
+                tile.position + offset,
+                tile.size,
+            );
+            if let Some(preview) = preview_manager.get_mut(id) {
+                preview.capture_active = true;
+                preview.set_fps_preset(tile.fps_preset);
+                preview.capture = tile.capture;
+                preview.set_crop(tile.crop_uv);
+                apply_layout_fields(preview, &PreviewLayout::from(tile));
+                // Copies land where they're pasted, outside the original's frame
+                preview.container = None;
+                preview.window_exe = tile.window_exe.clone();
+                preview.window_class = tile.window_class.clone();
+            }
+
+            let original = preview_manager.get(tile.id).filter(|p| {
+                p.removing.is_none()
+                    && p.window_handle.as_ref().is_some_and(|h| h.hwnd == handle.hwnd)
+                    && !p.capture.needs_restart(&tile.capture)
+            });
+            let shared = original.is_some() && capture_coordinator.mirror_capture(id, tile.id);
+            if !shared {
+                capture_coordinator.start_capture(
+                    id,
+                    handle.hwnd,
+                    tile.title.clone(),
+                    tile.fps_preset.as_u32(),
+                    tile.capture,
+                );
+            }
+            pasted.push(id);
+        }
+        if !pasted.is_empty() {
+            self.selection = pasted;
+        }
+    }
+
+    /// Page Up/Down: move the selected previews one layer up or down, or
+    /// with Ctrl all the way to the front or back.
+    fn restack_selection(&mut self, preview_manager: &mut PreviewManager, up: bool, to_end: bool) {
+        if to_end {
+            let mut ids = self.selection.clone();
+            ids.sort_by_key(|id| preview_manager.get(*id).map_or(0, |p| p.z_order));
+            // Farthest from the destination first, so they keep their
+            // relative order
+            if !up {
+                ids.reverse();
+            }
+            for &id in &ids {
+                if up {
+                    preview_manager.bring_to_front(id);
+                } else {
+                    preview_manager.send_to_back(id);
+                }
+            }
+        } else {
+            preview_manager.step_z_order(&self.selection, up);
+        }
+
+        if let Some(&id) = self.selection.last() {
+            self.depth_badge = Some((id, Instant::now()));
+        }
+    }
+
+    /// Alignment lines from the current drag or resize
+    fn draw_guides(&self, painter: &egui::Painter, canvas_rect: Rect) {
+        let stroke = Stroke::new(1.0, Color32::from_rgb(255, 150, 100));
+        for guide in &self.guides {
+            let (from, to) = match *guide {
+                Guide::Vertical { x, from, to } => (Pos2::new(x, from), Pos2::new(x, to)),
+                Guide::Horizontal { y, from, to } => (Pos2::new(from, y), Pos2::new(to, y)),
+            };
+            painter.line_segment(
+                [self.canvas_to_screen(from, canvas_rect), self.canvas_to_screen(to, canvas_rect)],
+                stroke,
+            );
+        }
+    }
+
+    /// Badge over the restacked preview showing its layer. Returns whether
+    /// it's still visible (and needs repainting).
+    fn draw_depth_badge(&mut self, painter: &egui::Painter, canvas_rect: Rect, preview_manager: &PreviewManager) -> bool {
+        let Some((id, shown_at)) = self.depth_badge else { return false; };
+        let age = shown_at.elapsed().as_secs_f32();
+        let (Some(preview), Some((depth, total))) = (preview_manager.get(id), preview_manager.depth(id)) else {
+            self.depth_badge = None;
+            return false;
+        };
+        if age >= DEPTH_BADGE_SECS {
+            self.depth_badge = None;
+            return false;
+        }
+
+        let fade = ((DEPTH_BADGE_SECS - age) / 0.3).clamp(0.0, 1.0);
+        let label = match depth {
+            d if d == total => format!("Layer {} of {} · Front", d, total),
+            1 => format!("Layer 1 of {} · Back", total),
+            d => format!("Layer {} of {}", d, total),
+        };
+
+        let tile_rect = self.canvas_rect_to_screen(preview.rect(), canvas_rect);
+        let font = egui::FontId::proportional(11.5);
+        let galley = painter.layout_no_wrap(label, font, Color32::from_rgba_unmultiplied(230, 230, 235, (fade * 255.0) as u8));
+        let badge_rect = Rect::from_center_size(
+            Pos2::new(tile_rect.center().x, tile_rect.min.y + 20.0),
+            galley.size() + Vec2::new(20.0, 10.0),
+        );
+        painter.rect_filled(badge_rect, 8.0, Color32::from_rgba_unmultiplied(24, 24, 28, (fade * 220.0) as u8));
+        painter.galley(badge_rect.center() - galley.size() / 2.0, galley, Color32::TRANSPARENT);
+        true
+    }
+
+    /// Contents of a tile's hover tooltip: title, source process, source
+    /// resolution, capture rate, crop and last-frame age.
+    fn tile_tooltip_ui(&mut self, ui: &mut egui::Ui, preview: &Preview, stats: Option<&CaptureStats>) {
+        ui.set_max_width(320.0);
+        ui.label(egui::RichText::new(preview.display_name()).strong());
+        if preview.nickname.is_some() {
+            ui.label(egui::RichText::new(&preview.title).weak());
+        }
+        if let Some(url) = &preview.browser_url {
+            ui.label(egui::RichText::new(url).weak());
+        }
+
+        egui::Grid::new("tile_tooltip_grid")
+            .num_columns(2)
+            .spacing([16.0, 2.0])
+            .show(ui, |ui| {
+                if let Some(handle) = &preview.window_handle {
+                    let exe = match &self.tooltip_exe {
+                        Some((pid, exe)) if *pid == handle.process_id => exe.clone(),
+                        _ => {
+                            let exe = Native::process_name(handle.process_id);
+                            self.tooltip_exe = Some((handle.process_id, exe.clone()));
+                            exe
+                        }
+                    };
+                    ui.label(egui::RichText::new("Process").weak());
+                    ui.label(format!("{} (PID {})", exe, handle.process_id));
+                    ui.end_row();
+                }
+
+                ui.label(egui::RichText::new("Source").weak());
+                ui.label(match preview.frame_size {
+                    Some((w, h)) => format!("{}×{}", w, h),
+                    None => "—".to_string(),
+                });
+                ui.end_row();
+
+                ui.label(egui::RichText::new("Capture").weak());
+                ui.label(format!(
+                    "{:.1} / {} fps",
+                    stats.map_or(0.0, |s| s.received_fps),
+                    preview.target_fps
+                ));
+                ui.end_row();
+
+                if let Some((min_u, min_v, max_u, max_v)) = preview.crop_uv {
+                    ui.label(egui::RichText::new("Crop").weak());
+                    ui.label(format!("{:.0}% of the window", (max_u - min_u) * (max_v - min_v) * 100.0));
+                    ui.end_row();
+                }
+
+                ui.label(egui::RichText::new("Last frame").weak());
+                ui.label(match stats.and_then(|s| s.last_frame_at) {
+                    Some(at) if at.elapsed() < Duration::from_secs(60) => {
+                        format!("{:.1} s ago", at.elapsed().as_secs_f32())
+                    }
+                    Some(at) => format_age(at.elapsed()),
+                    None => "none yet".to_string(),
+                });
+                ui.end_row();
+            });
+    }
+
     /// Draw previews and handle their individual interactions
     fn draw_and_interact_previews(
         &mut self,
@@ -631,6 +2876,7 @@ impl CanvasState {
                 id: p.id,
                 rect: p.rect(),
                 title: p.title.clone(),
+                name: p.display_name().to_string(),
                 target_fps: p.target_fps,
                 fps_preset: p.fps_preset,
                 has_crop: p.crop_uv.is_some(),
@@ -639,22 +2885,68 @@ impl CanvasState {
                 remove_t: p.removal_progress(),
                 is_browser: p.is_browser(),
                 muted: p.browser_muted,
+                show_stats: p.show_stats,
+                offline: p.is_offline(),
+                last_seen: p.last_seen,
+                last_stats: p.last_stats,
+                frame_size: p.frame_size,
+                uv_size: p.get_uv_rect().size().abs(),
+                rotation: p.rotation,
+                style: FrameStyle {
+                    quarter_turns: p.rotation,
+                    effect: p.effect,
+                    chroma_key: p.chroma_key,
+                    corner_radius: TILE_ROUNDING,
+                },
+                display_mode: p.display_mode,
+                frame_aspect: p.tile_aspect_ratio(),
+                overlays: p.overlays,
+                watermark: p.watermark.clone(),
+                hwnd: p.window_handle.as_ref().map(|handle| handle.hwnd),
+                note: p.note.clone(),
             }).collect()
         };
 
         let mut any_spawn_or_remove_animating = false;
+        let mut note_cards = Vec::new();
+        // Frame under the pointer for the loupe: texture, where it's drawn,
+        // its UVs and style
+        let mut loupe_source = None;
+        // Pixel under the eyedropper
+        let mut eyedropper_sample = None;
 
         for info in preview_info {
             let TileInfo {
-                id, rect, title, target_fps, fps_preset: current_preset, has_crop,
-                is_removing, spawn_t, remove_t, is_browser, muted,
+                id, rect, title, name, target_fps, fps_preset: current_preset, has_crop,
+                is_removing, spawn_t, remove_t, is_browser, muted, show_stats, offline, last_seen, last_stats,
+                frame_size, uv_size, rotation, style, display_mode, frame_aspect, overlays, watermark, hwnd, note,
             } = info;
             let screen_rect = self.canvas_rect_to_screen(rect, canvas_rect);
 
-            if !canvas_rect.intersects(screen_rect) {
+            // Focus mode hides everything but the focused preview
+            if !canvas_rect.intersects(screen_rect) || self.focus.is_some_and(|f| f.id != id) {
                 continue;
             }
 
+            // Frames are delivered at the size the whole (uncropped) frame
+            // would be drawn at, so small tiles don't ship full-size buffers
+            // (in the frame's own orientation, so turned tiles swap sides)
+            let shown_size = if rotation % 2 == 1 { screen_rect.size().yx() } else { screen_rect.size() };
+            // (popped-out previews get full-size frames for their window,
+            // as does the one under the loupe, which magnifies them, or
+            // the eyedropper, which samples them)
+            let full_size = shown_size * ctx.pixels_per_point() / uv_size.max(Vec2::splat(0.01));
+            let inspected = (self.loupe.is_some() || self.eyedropper)
+                && input.hover_pos.is_some_and(|pos| screen_rect.contains(pos));
+            if inspected {
+                capture_coordinator.set_display_size(id, None);
+            } else if !self.popped_out.contains(&id) {
+                capture_coordinator.set_display_size(
+                    id,
+                    Some((full_size.x.ceil().max(1.0) as u32, full_size.y.ceil().max(1.0) as u32)),
+                );
+            }
+
             // Spawn-in / fade-out: ease alpha and a subtle scale toward center.
             let (alpha, scale) = if is_removing {
                 any_spawn_or_remove_animating |= remove_t < 1.0;
@@ -676,11 +2968,11 @@ impl CanvasState {
 
             if is_removing {
                 // Fading out: paint the last frame only, no interaction.
-                if let Some(preview) = preview_manager.get_mut(id) {
-                    let uv_rect = preview.get_uv_rect();
-                    if let Some(texture) = preview.get_texture(ctx) {
-                        painter.image(texture.id(), anim_rect, uv_rect, Color32::from_white_alpha(alpha_u8));
-                    }
+                if self.blanked {
+                    painter.rect_filled(anim_rect, TILE_ROUNDING, Color32::from_black_alpha(alpha_u8));
+                } else if let Some((texture, uv_rect)) = preview_manager.frame_texture(id, ctx, anim_rect.size()) {
+                    let (frame_rect, uv_rect) = display_mode.place(anim_rect, uv_rect, frame_aspect, rotation);
+                    self.paint_frame(&painter, texture, frame_rect, uv_rect, Color32::from_white_alpha(alpha_u8), style);
                 }
                 continue;
             }
@@ -692,44 +2984,217 @@ impl CanvasState {
                 Sense::click_and_drag(),
             );
 
+            // Full details the hover overlay has no room for (egui delays
+            // the tooltip and hides it while dragging; the loupe takes its
+            // place while out)
+            let preview_response = if self.presenting || self.loupe.is_some() {
+                preview_response
+            } else {
+                preview_response.on_hover_ui(|ui| {
+                    if let Some(preview) = preview_manager.get(id) {
+                        self.tile_tooltip_ui(ui, preview, capture_coordinator.stats(id));
+                    }
+                })
+            };
+
             let is_active = self.selection.contains(&id) || preview_response.dragged();
 
             // Soft drop shadow underneath the preview, stronger when selected/dragged.
-            let shadow_alpha = ((if is_active { 90.0 } else { 40.0 }) * alpha) as u8;
-            let shadow_offset = if is_active { Vec2::new(0.0, 6.0) } else { Vec2::new(0.0, 3.0) };
-            painter.rect_filled(
-                anim_rect.translate(shadow_offset),
-                8.0,
-                Color32::from_rgba_unmultiplied(0, 0, 0, shadow_alpha),
-            );
+            // (Keyed tiles have none: it would show through the keyed-out
+            // background.)
+            if style.chroma_key.is_none() {
+                let shadow_alpha = ((if is_active { 90.0 } else { 40.0 }) * alpha) as u8;
+                let shadow_offset = if is_active { Vec2::new(0.0, 6.0) } else { Vec2::new(0.0, 3.0) };
+                painter.rect_filled(
+                    anim_rect.translate(shadow_offset),
+                    TILE_ROUNDING,
+                    Color32::from_rgba_unmultiplied(0, 0, 0, shadow_alpha),
+                );
+            }
 
             // Minimal Void: No background fill - content fills entire area
             // Draw preview content (full rect, no title bar offset)
-            let has_texture = if let Some(preview) = preview_manager.get_mut(id) {
-                // Get UV rect first (immutable borrow ends before get_texture)
-                let uv_rect = preview.get_uv_rect();
-                if let Some(texture) = preview.get_texture(ctx) {
-                    // Minimal Void: content fills entire rect
-                    painter.image(
-                        texture.id(),
-                        anim_rect,
-                        uv_rect,
-                        Color32::from_white_alpha(alpha_u8),
-                    );
-                    true
-                } else {
-                    false
+            // Small tiles are drawn from a shared atlas page, larger ones
+            // from their own texture (see PreviewManager::frame_texture).
+            let has_texture = if self.blanked {
+                // Panic button: nothing of the window shows until restored
+                painter.rect_filled(anim_rect, TILE_ROUNDING, Color32::from_black_alpha(alpha_u8));
+                true
+            } else if let Some((texture, uv_rect)) =
+                preview_manager.frame_texture(id, ctx, anim_rect.size())
+            {
+                // Minimal Void: content fills entire rect (letterboxed
+                // tiles get black bars)
+                if display_mode == DisplayMode::Fit {
+                    painter.rect_filled(anim_rect, TILE_ROUNDING, Color32::from_black_alpha(alpha_u8));
+                }
+                let (frame_rect, uv_rect) = display_mode.place(anim_rect, uv_rect, frame_aspect, rotation);
+                self.paint_frame(
+                    &painter,
+                    texture,
+                    frame_rect,
+                    uv_rect,
+                    Color32::from_white_alpha(alpha_u8),
+                    style,
+                );
+                draw_frame_overlays(&painter, frame_rect, overlays);
+                if let Some(watermark) = &watermark {
+                    if let Some(image) = self.watermark_texture(ctx, &watermark.path) {
+                        let tint = Color32::from_white_alpha((watermark.opacity.clamp(0.0, 1.0) * alpha * 255.0) as u8);
+                        painter.image(
+                            image.id(),
+                            watermark.placement(anim_rect, image.size_vec2()),
+                            Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                            tint,
+                        );
+                    }
+                }
+                if self.loupe.is_some()
+                    && preview_response.hovered()
+                    && input.hover_pos.is_some_and(|pos| frame_rect.contains(pos))
+                {
+                    loupe_source = Some((texture, frame_rect, uv_rect, style));
                 }
+                true
             } else {
                 false
             };
 
-            if !has_texture {
+            if self.eyedropper && !self.blanked && preview_response.hovered() {
+                if let (Some(pos), Some(preview)) = (input.hover_pos, preview_manager.get(id)) {
+                    let (drawn, uv) = display_mode.place(anim_rect, preview.get_uv_rect(), frame_aspect, rotation);
+                    let point = sub_uv_of(drawn, uv, rotation, Rect::from_min_max(pos, pos)).min;
+                    if let Some([r, g, b, _]) = preview.last_frame().and_then(|frame| frame.pixel_at(point)) {
+                        ui.ctx().set_cursor_icon(CursorIcon::Crosshair);
+                        eyedropper_sample = Some((pos, Color32::from_rgb(r, g, b)));
+                    }
+                }
+            }
+
+            if self.measuring {
+                self.measure_tile(ui, canvas_rect, preview_manager, input, &preview_response, id, anim_rect);
+            }
+
+            let capture_error = capture_coordinator.error(id).map(str::to_owned);
+            if let Some(message) = capture_error {
+                // Capture gave up: say why instead of connecting forever
+                if !has_texture {
+                    painter.rect_filled(anim_rect, TILE_ROUNDING, Color32::from_rgb(22, 22, 24));
+                } else {
+                    painter.rect_filled(anim_rect, TILE_ROUNDING, Color32::from_rgba_unmultiplied(0, 0, 0, 170));
+                }
+                painter.text(
+                    anim_rect.center() + Vec2::new(0.0, -22.0),
+                    egui::Align2::CENTER_CENTER,
+                    egui_phosphor::regular::WARNING,
+                    egui::FontId::proportional(18.0),
+                    Color32::from_rgb(255, 150, 100),
+                );
+                painter.text(
+                    anim_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    &message,
+                    egui::FontId::proportional(11.0),
+                    Color32::from_rgb(200, 200, 200),
+                );
+
+                // An elevated window also gets a way out: restarting
+                // Pluriview as administrator
+                let needs_elevation = capture_coordinator.needs_elevation(id);
+                let retry_offset = if needs_elevation { -62.0 } else { 0.0 };
+                let retry_rect = Rect::from_center_size(
+                    anim_rect.center() + Vec2::new(retry_offset, 26.0),
+                    Vec2::new(72.0, 24.0),
+                );
+                let retry_response = ui.interact(
+                    retry_rect,
+                    ui.id().with(("preview_retry", id.0)),
+                    Sense::click(),
+                );
+                let retry_bg = if retry_response.hovered() {
+                    Color32::from_rgba_unmultiplied(255, 255, 255, 45)
+                } else {
+                    Color32::from_rgba_unmultiplied(255, 255, 255, 25)
+                };
+                painter.rect_filled(retry_rect, 6.0, retry_bg);
+                painter.text(
+                    retry_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    format!("{} Retry", egui_phosphor::regular::ARROW_CLOCKWISE),
+                    egui::FontId::proportional(11.0),
+                    Color32::from_rgb(215, 215, 220),
+                );
+                if retry_response.clicked() {
+                    capture_coordinator.retry(id);
+                }
+
+                if needs_elevation {
+                    let admin_rect = Rect::from_center_size(
+                        anim_rect.center() + Vec2::new(42.0, 26.0),
+                        Vec2::new(128.0, 24.0),
+                    );
+                    let admin_response = ui.interact(
+                        admin_rect,
+                        ui.id().with(("preview_restart_admin", id.0)),
+                        Sense::click(),
+                    );
+                    let admin_bg = if admin_response.hovered() {
+                        Color32::from_rgba_unmultiplied(255, 255, 255, 45)
+                    } else {
+                        Color32::from_rgba_unmultiplied(255, 255, 255, 25)
+                    };
+                    painter.rect_filled(admin_rect, 6.0, admin_bg);
+                    painter.text(
+                        admin_rect.center(),
+                        egui::Align2::CENTER_CENTER,
+                        format!("{} Restart as Admin", egui_phosphor::regular::SHIELD_CHECK),
+                        egui::FontId::proportional(11.0),
+                        Color32::from_rgb(215, 215, 220),
+                    );
+                    if admin_response.clicked() {
+                        self.pending_restart_elevated = true;
+                    }
+                }
+            } else if !has_texture && offline {
+                // The window isn't running: the tile keeps its place and
+                // says how old its last picture is until the window is back
+                painter.rect_filled(anim_rect, TILE_ROUNDING, Color32::from_rgb(22, 22, 24));
+                painter.text(
+                    anim_rect.center() + Vec2::new(0.0, -22.0),
+                    egui::Align2::CENTER_CENTER,
+                    egui_phosphor::regular::CLOCK_COUNTER_CLOCKWISE,
+                    egui::FontId::proportional(18.0),
+                    Color32::from_rgb(110, 110, 115),
+                );
+                let age = last_seen.and_then(|t| t.elapsed().ok());
+                painter.text(
+                    anim_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    match age {
+                        Some(age) => format!("Last updated {}", format_age(age)),
+                        None => "Not running".to_string(),
+                    },
+                    egui::FontId::proportional(11.0),
+                    Color32::from_rgb(170, 170, 175),
+                );
+                let details = offline_details(last_stats, frame_size);
+                if !details.is_empty() {
+                    painter.text(
+                        anim_rect.center() + Vec2::new(0.0, 16.0),
+                        egui::Align2::CENTER_CENTER,
+                        details,
+                        egui::FontId::proportional(10.0),
+                        Color32::from_rgb(95, 95, 100),
+                    );
+                }
+                // Keep the age current
+                ctx.request_repaint_after(Duration::from_secs(30));
+            } else if !has_texture {
                 // Shimmering placeholder while the capture connects
                 let t = input.time as f32;
                 let pulse = (t * 1.8).sin() * 0.5 + 0.5;
                 let v = (18.0 + pulse * 14.0) as u8;
-                painter.rect_filled(anim_rect, 8.0, Color32::from_rgb(v, v, v + 2));
+                painter.rect_filled(anim_rect, TILE_ROUNDING, Color32::from_rgb(v, v, v + 2));
                 painter.text(
                     anim_rect.center(),
                     egui::Align2::CENTER_CENTER,
@@ -737,11 +3202,73 @@ impl CanvasState {
                     egui::FontId::proportional(12.0),
                     Color32::from_rgb(95, 95, 95),
                 );
+                // Restored from a saved layout: say how old the source's
+                // last picture is rather than leaving a bare placeholder.
+                if let Some(age) = last_seen.and_then(|t| t.elapsed().ok()) {
+                    painter.text(
+                        anim_rect.center() + Vec2::new(0.0, 16.0),
+                        egui::Align2::CENTER_CENTER,
+                        format!("Last updated {}", format_age(age)),
+                        egui::FontId::proportional(10.0),
+                        Color32::from_rgb(75, 75, 75),
+                    );
+                }
                 any_spawn_or_remove_animating = true;
             }
 
+            // Heat map: tint by how often the content changed lately
+            if self.show_heat_map && !is_browser {
+                let changes = capture_coordinator.stats(id).map_or(0, |s| s.changes_per_minute());
+                draw_heat_tint(&painter, anim_rect, changes);
+            }
+
+            // The source window has focus on the desktop (and is being typed in)
+            if let Some(focus) = self.desktop_focus.filter(|focus| hwnd.is_some_and(|hwnd| focus.is_window(hwnd))) {
+                draw_focus_marker(&painter, anim_rect, focus.typing, input.time);
+                if focus.typing {
+                    ctx.request_repaint_after(Duration::from_millis(50));
+                }
+            }
+
+            // Sticky note flag on the corner: hover to read, click to keep it open
+            if let Some(note) = note.filter(|_| !is_removing) {
+                let flag_rect = Rect::from_min_size(
+                    screen_rect.right_top() + Vec2::new(-8.0, -12.0),
+                    Vec2::splat(20.0),
+                );
+                let flag_response = ui.interact(
+                    flag_rect,
+                    ui.id().with(("preview_note", id.0)),
+                    Sense::click(),
+                );
+                let open = self.open_notes.contains(&id);
+                if flag_response.clicked() {
+                    if open {
+                        self.open_notes.retain(|&x| x != id);
+                    } else {
+                        self.open_notes.push(id);
+                    }
+                }
+                let flag_fill = if flag_response.hovered() || open {
+                    Color32::from_rgb(255, 150, 100)
+                } else {
+                    Color32::from_rgba_unmultiplied(255, 150, 100, 200)
+                };
+                painter.rect_filled(flag_rect, egui::Rounding { nw: 4.0, ne: 4.0, sw: 4.0, se: 0.0 }, flag_fill);
+                painter.text(
+                    flag_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    egui_phosphor::regular::NOTE,
+                    egui::FontId::proportional(12.0),
+                    Color32::from_rgb(13, 13, 13),
+                );
+                if flag_response.hovered() || open {
+                    note_cards.push((flag_rect, note));
+                }
+            }
+
             // Minimal Void: Hover-reveal controls (no permanent title bar)
-            if preview_response.hovered() {
+            if preview_response.hovered() && !self.presenting {
                 // Semi-transparent overlay gradient at top for controls
                 let overlay_rect = Rect::from_min_size(
                     screen_rect.min,
@@ -798,11 +3325,11 @@ impl CanvasState {
                 );
 
                 // Title (truncated, on the left) - handle UTF-8 properly
-                let title_text = if title.chars().count() > 25 {
-                    let truncated: String = title.chars().take(22).collect();
+                let title_text = if name.chars().count() > 25 {
+                    let truncated: String = name.chars().take(22).collect();
                     format!("{}...", truncated)
                 } else {
-                    title.clone()
+                    name.clone()
                 };
                 let title_pos = if is_browser {
                     // Globe badge marks browser tiles; shift the title right.
@@ -915,16 +3442,59 @@ impl CanvasState {
                 );
             }
 
+            // Stalled badge: the source stopped responding and sending
+            // frames, so a frozen tile isn't mistaken for a live one. Hidden under the
+            // hover title bar.
+            if !preview_response.hovered()
+                && capture_coordinator.stats(id).is_some_and(|s| s.is_stalled())
+            {
+                let badge_rect = Rect::from_min_size(
+                    screen_rect.left_top() + Vec2::new(8.0, 8.0),
+                    Vec2::new(62.0, 18.0),
+                );
+                painter.rect_filled(badge_rect, 9.0, Color32::from_rgba_unmultiplied(0, 0, 0, 160));
+                painter.text(
+                    badge_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    format!("{} STALLED", egui_phosphor::regular::PAUSE),
+                    egui::FontId::proportional(9.0),
+                    Color32::from_rgb(255, 150, 100),
+                );
+            }
+
+            // Optional capture stats overlay (toggled from the context menu)
+            if show_stats {
+                if let Some(stats) = capture_coordinator.stats(id) {
+                    draw_stats_overlay(&painter, screen_rect, stats, target_fps);
+                }
+            }
+
+            // Spotlight: everything outside the selection goes dark
+            if self.spotlight && !self.selection.is_empty() && !self.selection.contains(&id) {
+                painter.rect_filled(anim_rect, TILE_ROUNDING, Color32::from_black_alpha((alpha * 190.0) as u8));
+            }
+
             // Minimal Void: Only show border when selected (thin blue accent);
             // green accent marks the browser tile currently in interaction mode.
             if self.interactive_browser == Some(id) {
-                painter.rect_stroke(screen_rect, 8.0, Stroke::new(2.0, Color32::from_rgb(107, 170, 75)));
-            } else if self.selection.contains(&id) {
-                painter.rect_stroke(screen_rect, 8.0, Stroke::new(2.0, Color32::from_rgb(74, 158, 255)));
+                painter.rect_stroke(screen_rect, TILE_ROUNDING, Stroke::new(2.0, Color32::from_rgb(107, 170, 75)));
+            } else if self.selection.contains(&id) && !self.presenting {
+                painter.rect_stroke(screen_rect, TILE_ROUNDING, Stroke::new(2.0, self.theme.accent()));
+            }
+            if self.swap_target == Some(id) {
+                painter.rect_filled(screen_rect, TILE_ROUNDING, self.theme.accent_alpha(40));
+                painter.rect_stroke(screen_rect, TILE_ROUNDING, Stroke::new(2.0, self.theme.accent()));
             }
 
-            // Handle click to select
-            if preview_response.clicked() {
+            // Handle click to select (or, with the eyedropper, to copy
+            // the color)
+            if preview_response.clicked() && self.eyedropper {
+                if let Some((_, color)) = eyedropper_sample {
+                    let hex = color_hex(color);
+                    ui.ctx().copy_text(hex.clone());
+                    self.pending_toast = Some(format!("Copied {}", hex));
+                }
+            } else if preview_response.clicked() {
                 if input.ctrl {
                     if self.selection.contains(&id) {
                         self.selection.retain(|&x| x != id);
@@ -938,25 +3508,70 @@ impl CanvasState {
 
             // Handle double-click: browsers enter interaction mode (the app
             // consumes last_double_clicked); other previews focus their
-            // source window.
-            if preview_response.double_clicked() {
+            // source window. Display tiles have none (their handle is a
+            // monitor).
+            if preview_response.double_clicked() && input.shift {
+                self.focus_request = Some(id);
+            } else if preview_response.double_clicked() {
                 self.last_double_clicked = Some(id);
                 if !is_browser {
-                    if let Some(preview) = preview_manager.get(id) {
+                    if let Some(preview) = preview_manager.get(id).filter(|p| !p.capture.display) {
                         if let Some(ref handle) = preview.window_handle {
-                            #[cfg(windows)]
-                            unsafe {
-                                let hwnd = HWND(handle.hwnd as *mut _);
-                                let _ = ShowWindow(hwnd, SW_RESTORE);
-                                let _ = SetForegroundWindow(hwnd);
+                            // Focusing a window on another virtual desktop
+                            // switches desktops, so ask for a second
+                            // double-click first
+                            let elsewhere = Native::other_desktop_label(handle.hwnd)
+                                .filter(|_| self.desktop_switch_armed != Some(id));
+                            if let Some(desktop) = elsewhere {
+                                self.desktop_switch_armed = Some(id);
+                                self.pending_toast = Some(format!("This window is on {}; double-click again to switch there", desktop));
+                            } else {
+                                self.desktop_switch_armed = None;
+                                Native::focus_window(handle.hwnd, true);
                             }
                         }
                     }
                 }
             }
 
+            // Ctrl+drag inside a cropped preview slides the crop over the
+            // frame instead of moving the tile
+            if preview_response.drag_started() && input.ctrl && has_crop && !is_browser {
+                if let Some(crop) = preview_manager.get(id).and_then(|p| p.crop_uv) {
+                    self.drag_state = Some(DragState::PanningCrop {
+                        id,
+                        start_mouse: input.interact_pos.unwrap_or(screen_rect.center()),
+                        start_crop_uv: crop,
+                    });
+                }
+            }
+            if let Some(DragState::PanningCrop { id: pan_id, start_mouse, start_crop_uv }) = self.drag_state {
+                if pan_id == id {
+                    ui.ctx().set_cursor_icon(CursorIcon::Grabbing);
+                    if let (Some(pos), Some(preview)) = (input.interact_pos, preview_manager.get_mut(id)) {
+                        let (drawn, sampled) = preview.display_mode.place(
+                            screen_rect,
+                            preview.get_uv_rect(),
+                            preview.tile_aspect_ratio(),
+                            preview.rotation,
+                        );
+                        let delta = pan_delta(pos - start_mouse, drawn.size(), sampled.size(), preview.rotation);
+                        preview.crop_uv = Some(slide_crop(start_crop_uv, delta));
+                    }
+                    if preview_response.drag_stopped() {
+                        self.drag_state = None;
+                    }
+                }
+            }
+
             // Handle drag start - initialize spring and tracker
-            if preview_response.drag_started() && !input.alt && !input.middle_down {
+            if preview_response.drag_started()
+                && !input.alt
+                && !input.space
+                && !input.middle_down
+                && self.drag_state.is_none()
+                && !self.measuring
+            {
                 self.preview_dragging = true;
                 self.animation.drag_tracker.clear();
 
@@ -967,17 +3582,21 @@ impl CanvasState {
                     vec![id]
                 };
 
+                self.drag_origins.clear();
                 for sel_id in ids_to_init {
                     if let Some(preview) = preview_manager.get(sel_id) {
                         let spring = self.animation.get_or_create_spring(sel_id, preview.position);
                         spring.set_immediate_pos(preview.position);
+                        self.drag_origins.push((sel_id, preview.position));
                     }
                 }
+                self.drag_free_pos = preview_manager.get(id).map(|p| p.position);
+                self.swap_origin = preview_manager.get(id).map(|p| p.rect());
             }
 
             // Handle drag to move (only when not panning with Alt or middle mouse)
             // Resize is handled separately in draw_and_interact_selection()
-            if preview_response.dragged() && !input.alt && !input.middle_down {
+            if preview_response.dragged() && self.preview_dragging && !input.alt && !input.space && !input.middle_down {
                 // Only move if we're not in a resize operation
                 if self.drag_state.is_none() {
                     let delta = preview_response.drag_delta() / self.zoom;
@@ -987,6 +3606,30 @@ impl CanvasState {
                         self.animation.drag_tracker.record(mouse_pos, input.time);
                     }
 
+                    // Smart guides: align the grabbed preview's edges or
+                    // center with its neighbours, the rest of the selection
+                    // moving along
+                    let moving: Vec<PreviewId> = if self.selection.contains(&id) {
+                        self.selection.clone()
+                    } else {
+                        vec![id]
+                    };
+                    let mut delta = delta;
+                    if let (Some(free_pos), Some(grabbed)) = (self.drag_free_pos.as_mut(), preview_manager.get(id)) {
+                        *free_pos += delta;
+                        let free_rect = Rect::from_min_size(*free_pos, grabbed.size);
+                        let others = guide_targets(preview_manager, &moving);
+                        let snap = &self.animation.snap_config;
+                        let shift = Vec2::new(
+                            snap.alignment_shift(&[free_rect.min.x, free_rect.center().x, free_rect.max.x], &others, true, self.zoom)
+                                .unwrap_or(0.0),
+                            snap.alignment_shift(&[free_rect.min.y, free_rect.center().y, free_rect.max.y], &others, false, self.zoom)
+                                .unwrap_or(0.0),
+                        );
+                        delta = (*free_pos + shift) - grabbed.position;
+                        self.guides = snap.guides(free_rect.translate(shift), &others);
+                    }
+
                     // Move previews directly during drag (immediate feedback)
                     if self.selection.contains(&id) {
                         for sel_id in &self.selection {
@@ -1006,6 +3649,19 @@ impl CanvasState {
                             }
                         }
                     }
+
+                    // Shift: dropping a lone preview on another swaps them
+                    self.swap_target = input
+                        .hover_pos
+                        .filter(|_| input.shift && moving.len() == 1)
+                        .map(|pos| self.screen_to_canvas(pos, canvas_rect))
+                        .and_then(|pos| {
+                            preview_manager
+                                .all()
+                                .filter(|p| p.id != id && p.removing.is_none() && p.rect().contains(pos))
+                                .max_by_key(|p| p.z_order)
+                                .map(|p| p.id)
+                        });
                 }
             }
 
@@ -1013,24 +3669,52 @@ impl CanvasState {
             if preview_response.drag_stopped() && self.preview_dragging {
                 self.preview_dragging = false;
 
+                // Aligned to a guide: stay exactly there, no momentum or
+                // grid snap
+                let guided = !self.guides.is_empty();
+                self.guides.clear();
+                self.drag_free_pos = None;
+
+                // Dropped on another preview with Shift: trade places
+                let swap = self.swap_target.take().zip(self.swap_origin.take());
+                if let Some((target, origin)) = swap {
+                    if let (Some(dragged), Some(other)) = (preview_manager.get(id), preview_manager.get(target)) {
+                        self.animate_to(dragged, other.rect());
+                        self.animate_to(other, origin);
+                    }
+                }
+
                 // Get velocity from tracker
-                let velocity = self.animation.drag_tracker.get_velocity() / self.zoom;
+                let velocity = if guided {
+                    Vec2::ZERO
+                } else {
+                    self.animation.drag_tracker.get_velocity() / self.zoom
+                };
 
                 // Apply to all dragged previews
-                let ids_to_animate: Vec<PreviewId> = if self.selection.contains(&id) {
+                let ids_to_animate: Vec<PreviewId> = if swap.is_some() {
+                    Vec::new()
+                } else if self.selection.contains(&id) {
                     self.selection.clone()
                 } else {
                     vec![id]
                 };
 
-                for sel_id in ids_to_animate {
+                for &sel_id in &ids_to_animate {
                     if let Some(preview) = preview_manager.get(sel_id) {
+                        // Dropped into an empty template slot: fit it there
+                        let slot = self.template.as_mut().and_then(|t| t.drop_at(sel_id, preview.rect().center()));
+                        if let Some(slot) = slot {
+                            self.animate_to(preview, fit_in_slot(preview.size.x / preview.size.y.max(1.0), slot));
+                            continue;
+                        }
+
                         // Calculate target with subtle momentum
                         let momentum_offset = velocity * 0.05; // Very subtle momentum
                         let target_pos = preview.position + momentum_offset;
 
                         // Optionally snap to grid
-                        let final_target = if self.animation.snap_config.enabled {
+                        let final_target = if self.animation.snap_config.enabled && !guided {
                             self.animation.snap_config.snap_position(target_pos)
                         } else {
                             target_pos
@@ -1044,11 +3728,27 @@ impl CanvasState {
                         spring.add_velocity(velocity * 0.1);
                     }
                 }
+
+                // Dropped into (or out of) a container frame
+                for sel_id in ids_to_animate {
+                    let Some(preview) = preview_manager.get_mut(sel_id) else { continue; };
+                    let center = preview.rect().center();
+                    let container = self
+                        .containers
+                        .iter()
+                        .rev()
+                        .find(|c| container_rect(c).contains(center))
+                        .map(|c| c.name.clone());
+                    if container.is_some() || preview.container.is_some() {
+                        preview.container = container;
+                        self.reflow_containers = true;
+                    }
+                }
             }
 
             // Context menu for preview
             preview_response.context_menu(|ui| {
-                ui.label(egui::RichText::new(&title).strong());
+                ui.label(egui::RichText::new(&name).strong());
                 ui.separator();
 
                 ui.label("Frame Rate:");
@@ -1069,6 +3769,115 @@ impl CanvasState {
                     }
                 }
 
+                ui.menu_button("FPS Group", |ui| {
+                    let current = preview_manager.get(id).and_then(|p| p.fps_group.clone());
+                    if ui.selectable_label(current.is_none(), "None").clicked() {
+                        self.set_fps_group(id, None, preview_manager);
+                        ui.close_menu();
+                    }
+                    for group in self.fps_groups.clone() {
+                        let member = current.as_deref() == Some(group.name.as_str());
+                        let label = format!("{} ({} FPS total)", group.name, group.budget);
+                        if ui.selectable_label(member, label).clicked() {
+                            self.set_fps_group(id, Some(&group.name), preview_manager);
+                            ui.close_menu();
+                        }
+                    }
+                    ui.separator();
+                    if ui.button("New Group...").clicked() {
+                        let group = new_fps_group(&self.fps_groups);
+                        self.set_fps_group(id, Some(&group.name), preview_manager);
+                        self.fps_groups.push(group);
+                        self.show_fps_groups = true;
+                        ui.close_menu();
+                    }
+                });
+
+                let mut stats_visible = show_stats;
+                if ui.checkbox(&mut stats_visible, "Show Capture Stats").clicked() {
+                    if let Some(preview) = preview_manager.get_mut(id) {
+                        preview.show_stats = stats_visible;
+                    }
+                    ui.close_menu();
+                }
+
+                let current = preview_manager
+                    .get(id)
+                    .map(|p| p.capture)
+                    .unwrap_or_default();
+                let mut sharpen = current.sharpen;
+                if ui
+                    .checkbox(&mut sharpen, "Sharpen Text")
+                    .on_hover_text("Sharpen the picture while the tile is smaller than the window, for terminals and code")
+                    .clicked()
+                {
+                    let options = CaptureOptions { sharpen, ..current };
+                    if let Some(preview) = preview_manager.get_mut(id) {
+                        preview.capture = options;
+                    }
+                    capture_coordinator.set_options(id, options);
+                    ui.close_menu();
+                }
+
+                // Browser tiles are our own WebViews; Graphics Capture always works there.
+                if !is_browser {
+                    ui.menu_button("Capture Method", |ui| {
+                        let mut requested = None;
+                        for backend in [CaptureBackend::GraphicsCapture, CaptureBackend::PrintWindow] {
+                            if ui.selectable_label(current.backend == backend, backend.label()).clicked() {
+                                requested = Some(CaptureOptions { backend, ..current });
+                            }
+                        }
+                        ui.separator();
+                        let mut hdr = current.hdr;
+                        let hdr_available = current.backend == CaptureBackend::GraphicsCapture;
+                        if ui
+                            .add_enabled(hdr_available, egui::Checkbox::new(&mut hdr, "HDR (tone-mapped)"))
+                            .on_hover_text("Capture 16-bit float and tone-map to SDR so HDR sources don't look washed out")
+                            .clicked()
+                        {
+                            requested = Some(CaptureOptions { hdr, ..current });
+                        }
+                        let mut include_popups = current.include_popups;
+                        let popups_available = current.backend == CaptureBackend::GraphicsCapture
+                            && !current.display
+                            && popups_supported();
+                        if ui
+                            .add_enabled(popups_available, egui::Checkbox::new(&mut include_popups, "Include Popups & Menus"))
+                            .on_hover_text("Also capture the window's menus, dropdowns and tooltips")
+                            .on_disabled_hover_text("Needs Windows 11 and Graphics Capture, on a window rather than a display")
+                            .clicked()
+                        {
+                            requested = Some(CaptureOptions { include_popups, ..current });
+                        }
+
+                        // Overrides for sources that render wrong with the defaults
+                        ui.separator();
+                        ui.label(egui::RichText::new("Alpha").small().weak());
+                        for alpha in [AlphaMode::Straight, AlphaMode::Premultiplied, AlphaMode::Opaque] {
+                            if ui.selectable_label(current.alpha == alpha, alpha.label()).clicked() {
+                                requested = Some(CaptureOptions { alpha, ..current });
+                            }
+                        }
+
+                        if let Some(options) = requested {
+                            if let Some(preview) = preview_manager.get_mut(id) {
+                                preview.capture = options;
+                            }
+                            capture_coordinator.set_options(id, options);
+                            ui.close_menu();
+                        }
+                        if capture_coordinator.is_using_fallback(id) {
+                            ui.separator();
+                            ui.label(
+                                egui::RichText::new("Graphics Capture failed; using PrintWindow")
+                                    .small()
+                                    .color(Color32::from_rgb(255, 150, 100)),
+                            );
+                        }
+                    });
+                }
+
                 ui.separator();
 
                 if is_browser {
@@ -1086,42 +3895,240 @@ impl CanvasState {
                         self.pending_browser_actions.push((id, BrowserAction::Reload));
                         ui.close_menu();
                     }
-                    if ui.button("Change URL...").clicked() {
-                        self.pending_browser_actions.push((id, BrowserAction::EditUrl));
-                        ui.close_menu();
+                    if ui.button("Change URL...").clicked() {
+                        self.pending_browser_actions.push((id, BrowserAction::EditUrl));
+                        ui.close_menu();
+                    }
+                    if ui.button("Copy URL").clicked() {
+                        self.pending_browser_actions.push((id, BrowserAction::CopyUrl));
+                        ui.close_menu();
+                    }
+                    if ui.button("Open in Default Browser").clicked() {
+                        self.pending_browser_actions.push((id, BrowserAction::OpenExternal));
+                        ui.close_menu();
+                    }
+                } else {
+                    // Crop section
+                    ui.menu_button("Crop", |ui| {
+                        // Select Region button (ShareX-style)
+                        if ui.button("Select Region...").clicked() {
+                            self.pending_region_select = Some(id);
+                            ui.close_menu();
+                        }
+
+                        let frame_size = preview_manager.get(id).and_then(|p| p.frame_size);
+                        if let Some((frame_w, frame_h)) = frame_size.filter(|&(w, h)| w > 0 && h > 0) {
+                            if ui.button("Crop to Pixels...").clicked() {
+                                let crop = preview_manager.get(id).and_then(|p| p.crop_uv).unwrap_or(FULL_CROP);
+                                let (x, y, w, h) = crop_pixels(crop, frame_w, frame_h);
+                                let (x, y) = (x.min(frame_w - 1), y.min(frame_h - 1));
+                                self.crop_editor = Some((id, [x, y, w.clamp(1, frame_w - x), h.clamp(1, frame_h - y)]));
+                                ui.close_menu();
+                            }
+                            if ui.button("Edit Crop...").clicked() {
+                                let crop = preview_manager.get(id).and_then(|p| p.crop_uv).unwrap_or(FULL_CROP);
+                                self.crop_edit = Some(CropEdit { id, crop, drag_start: None });
+                                ui.close_menu();
+                            }
+                            ui.separator();
+                            for preset in CropPreset::ALL {
+                                if ui.button(preset.label()).clicked() {
+                                    let (min_x, min_y, max_x, max_y) = preset.pixels(frame_w, frame_h);
+                                    if let Some(preview) = preview_manager.get_mut(id) {
+                                        preview.set_crop_pixels(min_x, min_y, max_x, max_y);
+                                    }
+                                    ui.close_menu();
+                                }
+                            }
+                        }
+
+                        if has_crop {
+                            ui.separator();
+                            if ui.button("Clear Crop").clicked() {
+                                if let Some(preview) = preview_manager.get_mut(id) {
+                                    preview.clear_crop();
+                                }
+                                ui.close_menu();
+                            }
+                        }
+
+                        ui.separator();
+                        ui.label(egui::RichText::new("Tip: Alt+drag corners to fine-tune").weak().small());
+                        ui.label(egui::RichText::new("Ctrl+drag a cropped preview to pan it").weak().small());
+                    });
+                }
+
+                ui.separator();
+
+                if !is_browser {
+                    ui.menu_button("Rotate", |ui| {
+                        for (label, turns) in [("90° Clockwise (R)", 1), ("90° Counter-clockwise (Shift+R)", -1), ("180°", 2)] {
+                            if ui.button(label).clicked() {
+                                if let Some(preview) = preview_manager.get_mut(id) {
+                                    preview.rotate(turns);
+                                }
+                                ui.close_menu();
+                            }
+                        }
+                        if rotation != 0 && ui.button("Reset Rotation").clicked() {
+                            if let Some(preview) = preview_manager.get_mut(id) {
+                                preview.rotate(-i32::from(rotation));
+                            }
+                            ui.close_menu();
+                        }
+                    });
+                    ui.menu_button("Flip", |ui| {
+                        if let Some(preview) = preview_manager.get_mut(id) {
+                            let mirrored = ui.checkbox(&mut preview.flip_h, "Horizontal (Mirror)").clicked();
+                            let upside_down = ui.checkbox(&mut preview.flip_v, "Vertical").clicked();
+                            if mirrored || upside_down {
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                    ui.menu_button("Watermark", |ui| {
+                        let Some(preview) = preview_manager.get_mut(id) else { return; };
+                        let Some(watermark) = preview.watermark.as_mut() else {
+                            ui.label(egui::RichText::new("Drop a PNG on the window to watermark the selected previews").weak());
+                            return;
+                        };
+                        let file = std::path::Path::new(&watermark.path)
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        ui.label(egui::RichText::new(file).weak());
+                        for corner in Corner::ALL {
+                            if ui.selectable_label(watermark.corner == corner, corner.label()).clicked() {
+                                watermark.corner = corner;
+                            }
+                        }
+                        ui.add(egui::Slider::new(&mut watermark.opacity, 0.0..=1.0).text("Opacity"));
+                        ui.add(egui::Slider::new(&mut watermark.scale, 0.05..=1.0).text("Size"));
+                        ui.separator();
+                        if ui.button("Remove Watermark").clicked() {
+                            preview.watermark = None;
+                            ui.close_menu();
+                        }
+                    });
+                    ui.menu_button("Overlays", |ui| {
+                        if let Some(preview) = preview_manager.get_mut(id) {
+                            let overlays = &mut preview.overlays;
+                            ui.checkbox(&mut overlays.crosshair, "Center Crosshair");
+                            ui.checkbox(&mut overlays.thirds, "Thirds Grid");
+                            ui.checkbox(&mut overlays.safe_area, "16:9 Safe Areas");
+                        }
+                    });
+                    ui.menu_button("Display", |ui| {
+                        for mode in DisplayMode::ALL {
+                            if ui.selectable_label(display_mode == mode, mode.label()).clicked() {
+                                if let Some(preview) = preview_manager.get_mut(id) {
+                                    preview.display_mode = mode;
+                                }
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                }
+
+                ui.menu_button("Effect", |ui| {
+                    if self.tile_renderer.is_none() {
+                        ui.label(egui::RichText::new("Needs OpenGL 3.0 or newer").weak());
+                        return;
+                    }
+                    if ui.selectable_label(style.effect.is_none(), "None").clicked() {
+                        if let Some(preview) = preview_manager.get_mut(id) {
+                            preview.effect = None;
+                        }
+                        ui.close_menu();
+                    }
+                    for kind in EffectKind::ALL {
+                        if ui.selectable_label(style.effect == Some(kind), kind.label()).clicked() {
+                            if let Some(preview) = preview_manager.get_mut(id) {
+                                preview.effect = Some(kind);
+                            }
+                            ui.close_menu();
+                        }
+                    }
+                });
+
+                ui.menu_button("Chroma Key", |ui| {
+                    if self.tile_renderer.is_none() {
+                        ui.label(egui::RichText::new("Needs OpenGL 3.0 or newer").weak());
+                        return;
+                    }
+                    let Some(preview) = preview_manager.get_mut(id) else { return; };
+                    let mut enabled = preview.chroma_key.is_some();
+                    if ui.checkbox(&mut enabled, "Key Out a Color").changed() {
+                        preview.chroma_key = enabled.then(ChromaKey::default);
                     }
-                    if ui.button("Copy URL").clicked() {
-                        self.pending_browser_actions.push((id, BrowserAction::CopyUrl));
-                        ui.close_menu();
+                    if let Some(key) = preview.chroma_key.as_mut() {
+                        ui.horizontal(|ui| {
+                            ui.label("Color");
+                            egui::color_picker::color_edit_button_srgb(ui, &mut key.color);
+                        });
+                        ui.add(egui::Slider::new(&mut key.tolerance, 0.0..=1.0).text("Tolerance"));
                     }
-                    if ui.button("Open in Default Browser").clicked() {
-                        self.pending_browser_actions.push((id, BrowserAction::OpenExternal));
+                });
+
+                ui.menu_button("Copy", |ui| {
+                    if ui.button("Window Title").clicked() {
+                        ui.ctx().copy_text(title.clone());
                         ui.close_menu();
                     }
-                } else {
-                    // Crop section
-                    ui.menu_button("Crop", |ui| {
-                        // Select Region button (ShareX-style)
-                        if ui.button("Select Region...").clicked() {
-                            self.pending_region_select = Some(id);
+                    let handle = preview_manager.get(id).and_then(|p| p.window_handle.clone());
+                    if let Some(handle) = handle {
+                        if ui.button("Executable Path").clicked() {
+                            if let Some(path) = Native::process_path(handle.process_id) {
+                                ui.ctx().copy_text(path);
+                            }
                             ui.close_menu();
                         }
-
-                        if has_crop {
-                            if ui.button("Clear Crop").clicked() {
-                                if let Some(preview) = preview_manager.get_mut(id) {
-                                    preview.clear_crop();
-                                }
-                                ui.close_menu();
-                            }
+                        if ui.button(format!("HWND ({:#X})", handle.hwnd)).clicked() {
+                            ui.ctx().copy_text(format!("{:#X}", handle.hwnd));
+                            ui.close_menu();
+                        }
+                        if ui.button(format!("PID ({})", handle.process_id)).clicked() {
+                            ui.ctx().copy_text(handle.process_id.to_string());
+                            ui.close_menu();
                         }
+                    }
+                });
 
-                        ui.separator();
-                        ui.label(egui::RichText::new("Tip: Alt+drag corners to fine-tune").weak().small());
-                    });
+                if ui.button("Rename...").clicked() {
+                    let text = preview_manager.get(id).and_then(|p| p.nickname.clone()).unwrap_or_default();
+                    self.rename_editor = Some((id, text));
+                    ui.close_menu();
                 }
 
-                ui.separator();
+                let has_note = preview_manager.get(id).is_some_and(|p| p.note.is_some());
+                if ui.button(if has_note { "Edit Note..." } else { "Add Note..." }).clicked() {
+                    let text = preview_manager.get(id).and_then(|p| p.note.clone()).unwrap_or_default();
+                    self.note_editor = Some((id, text));
+                    ui.close_menu();
+                }
+
+                if !is_browser && ui.button("Duplicate (Ctrl+D)").clicked() {
+                    if let Some(preview) = preview_manager.get(id) {
+                        let tile = RemovedPreviewInfo::of(preview);
+                        self.paste_tiles(&[tile], Vec2::splat(DUPLICATE_OFFSET), preview_manager, capture_coordinator);
+                    }
+                    ui.close_menu();
+                }
+
+                if ui.button("Focus (F)").clicked() {
+                    self.focus_request = Some(id);
+                    ui.close_menu();
+                }
+
+                let popped = self.popped_out.contains(&id);
+                if ui.button(if popped { "Close Pop-out" } else { "Pop Out" }).clicked() {
+                    if popped {
+                        self.popped_out.retain(|&p| p != id);
+                    } else {
+                        self.popped_out.push(id);
+                    }
+                    ui.close_menu();
+                }
 
                 if ui.button("Bring to Front").clicked() {
                     preview_manager.bring_to_front(id);
@@ -1133,6 +4140,14 @@ impl CanvasState {
                     ui.close_menu();
                 }
 
+                if ui.button("Put in a Frame").on_hover_text("A frame tiles the previews inside it").clicked() {
+                    if !self.selection.contains(&id) {
+                        self.selection = vec![id];
+                    }
+                    self.frame_selection(preview_manager);
+                    ui.close_menu();
+                }
+
                 ui.separator();
 
                 if ui.button("Remove").clicked() {
@@ -1144,6 +4159,23 @@ impl CanvasState {
             });
         }
 
+        // Expanded notes go over every tile
+        let painter = ui.painter_at(canvas_rect);
+        for (flag_rect, note) in note_cards {
+            draw_note_card(&painter, flag_rect, &note);
+        }
+        if let (Some(magnification), Some(source), Some(pointer)) = (self.loupe, loupe_source, input.hover_pos) {
+            self.draw_loupe(&painter, canvas_rect, pointer, magnification, source);
+        }
+        if let Some((pointer, color)) = eyedropper_sample {
+            draw_color_readout(&painter, pointer, color);
+        }
+        self.open_notes.retain(|&id| preview_manager.get(id).is_some_and(|p| p.note.is_some()));
+        self.note_editor_ui(ctx, preview_manager);
+        self.rename_editor_ui(ctx, preview_manager);
+        self.crop_editor_ui(ctx, preview_manager);
+        self.crop_edit_ui(ctx, preview_manager);
+
         // Keep repainting while any preview is spawning in, fading out, or
         // still waiting on its first frame so the animations stay smooth.
         if any_spawn_or_remove_animating {
@@ -1155,7 +4187,7 @@ impl CanvasState {
     fn draw_grid(&self, painter: &egui::Painter, canvas_rect: Rect) {
         let viewport = self.get_viewport(canvas_rect);
         // Minimal Void: very subtle grid (opacity 5 instead of 15)
-        let grid_color = Color32::from_rgba_unmultiplied(255, 255, 255, 5);
+        let grid_color = self.theme.grid();
 
         let screen_grid_size = self.grid_size * self.zoom;
 
@@ -1239,6 +4271,77 @@ impl CanvasState {
         );
     }
 
+    /// Minimap above the status badge: every preview and the current view,
+    /// scaled to fit. Clicking or dragging in it centers the view there.
+    fn draw_and_interact_minimap(&mut self, ui: &mut egui::Ui, canvas_rect: Rect, preview_manager: &PreviewManager) {
+        let padding = 16.0;
+        let status_height = 24.0;
+        let map_rect = Rect::from_min_size(
+            canvas_rect.max - MINIMAP_SIZE - Vec2::new(padding, padding + status_height + 8.0),
+            MINIMAP_SIZE,
+        );
+        if !canvas_rect.contains_rect(map_rect) {
+            return;
+        }
+
+        let viewport = self.get_viewport(canvas_rect);
+        let content = preview_manager.all().fold(viewport, |bounds, preview| bounds.union(preview.rect()));
+        let transform = MinimapTransform::new(content, map_rect.shrink(6.0));
+
+        let response = ui.interact(map_rect, ui.id().with("minimap"), Sense::click_and_drag());
+        if let Some(pos) = response.interact_pointer_pos().filter(|_| response.clicked() || response.dragged()) {
+            let target = transform.to_canvas(pos);
+            self.pan = canvas_rect.size() / (2.0 * self.zoom) - target.to_vec2();
+            self.animation.momentum_active = false;
+            self.animation.momentum_velocity = Vec2::ZERO;
+        }
+
+        let painter = ui.painter_at(map_rect);
+        painter.rect_filled(map_rect, 8.0, Color32::from_rgba_unmultiplied(0, 0, 0, 170));
+        let mut previews: Vec<_> = preview_manager.all().filter(|p| !p.hidden).collect();
+        previews.sort_by_key(|preview| preview.z_order);
+        for preview in previews {
+            let color = if self.selection.contains(&preview.id) {
+                self.theme.accent()
+            } else {
+                Color32::from_rgb(70, 70, 76)
+            };
+            painter.rect_filled(transform.rect_to_minimap(preview.rect()), 1.0, color);
+        }
+        let view_color = if response.hovered() || response.dragged() {
+            Color32::from_rgb(210, 210, 215)
+        } else {
+            Color32::from_rgb(140, 140, 145)
+        };
+        painter.rect_stroke(transform.rect_to_minimap(viewport), 1.0, Stroke::new(1.0, view_color));
+    }
+
+    /// Pill at the top of the canvas showing how many of a loaded layout's
+    /// captures have delivered their first frame.
+    fn draw_load_progress(&self, painter: &egui::Painter, canvas_rect: Rect, connected: usize, total: usize) {
+        let pill_rect = Rect::from_center_size(
+            Pos2::new(canvas_rect.center().x, canvas_rect.min.y + 28.0),
+            Vec2::new(190.0, 26.0),
+        );
+        painter.rect_filled(pill_rect, 13.0, Color32::from_rgba_unmultiplied(0, 0, 0, 170));
+
+        // Thin progress bar along the bottom edge of the pill
+        let fraction = if total > 0 { connected as f32 / total as f32 } else { 0.0 };
+        let bar_rect = Rect::from_min_size(
+            Pos2::new(pill_rect.min.x + 13.0, pill_rect.max.y - 4.0),
+            Vec2::new((pill_rect.width() - 26.0) * fraction, 2.0),
+        );
+        painter.rect_filled(bar_rect, 1.0, Color32::from_rgb(107, 170, 75));
+
+        painter.text(
+            pill_rect.center() - Vec2::new(0.0, 1.0),
+            egui::Align2::CENTER_CENTER,
+            format!("Connecting {} of {}", connected, total),
+            egui::FontId::proportional(11.0),
+            Color32::from_rgb(170, 170, 175),
+        );
+    }
+
     /// Empty-canvas hint shown before any preview has been added.
     fn draw_empty_state(&self, painter: &egui::Painter, canvas_rect: Rect) {
         let center = canvas_rect.center();
@@ -1266,6 +4369,206 @@ impl CanvasState {
         );
     }
 
+    /// Measure tool over one tile: a drag lays a ruler across it, labelled
+    /// with its length in the source window's pixels (crop, scale, rotation
+    /// and flips accounted for). Shift keeps it horizontal or vertical.
+    #[allow(clippy::too_many_arguments)]
+    fn measure_tile(
+        &mut self,
+        ui: &egui::Ui,
+        canvas_rect: Rect,
+        preview_manager: &PreviewManager,
+        input: &FrameInput,
+        response: &egui::Response,
+        id: PreviewId,
+        tile_rect: Rect,
+    ) {
+        if response.hovered() {
+            ui.ctx().set_cursor_icon(CursorIcon::Crosshair);
+        }
+        if response.drag_started() {
+            if let Some(pos) = input.interact_pos {
+                let start = self.screen_to_canvas(pos, canvas_rect);
+                self.measurement = Some((id, start, start));
+                self.drag_state = Some(DragState::Measuring { id });
+            }
+        }
+        let dragging = matches!(self.drag_state, Some(DragState::Measuring { id: measured }) if measured == id);
+        if dragging && response.dragged() {
+            if let (Some(pos), Some((_, start, end))) = (input.interact_pos, self.measurement.as_mut()) {
+                let mut pos = self.screen_to_canvas(pos, canvas_rect);
+                if input.shift {
+                    let delta = pos - *start;
+                    if delta.x.abs() > delta.y.abs() {
+                        pos.y = start.y;
+                    } else {
+                        pos.x = start.x;
+                    }
+                }
+                *end = pos;
+            }
+        }
+        if dragging && response.drag_stopped() {
+            self.drag_state = None;
+        }
+
+        let Some((_, start, end)) = self.measurement.filter(|m| m.0 == id) else { return; };
+        let Some(preview) = preview_manager.get(id) else { return; };
+        let Some((frame_w, frame_h)) = preview.frame_size else { return; };
+        let (start, end) = (self.canvas_to_screen(start, canvas_rect), self.canvas_to_screen(end, canvas_rect));
+        let (drawn, uv) = preview.display_mode.place(tile_rect, preview.get_uv_rect(), preview.tile_aspect_ratio(), preview.rotation);
+        let source_pixel = |pos: Pos2| {
+            let point = sub_uv_of(drawn, uv, preview.rotation, Rect::from_min_max(pos, pos)).min;
+            Vec2::new(point.x * frame_w as f32, point.y * frame_h as f32)
+        };
+
+        let painter = ui.painter_at(canvas_rect);
+        let color = Color32::from_rgb(255, 150, 100);
+        painter.line_segment([start, end], Stroke::new(1.5, color));
+        // End ticks, square to the ruler
+        let across = (end - start).normalized().rot90() * 5.0;
+        for tip in [start, end] {
+            painter.line_segment([tip - across, tip + across], Stroke::new(1.5, color));
+        }
+
+        let label = measure_label(source_pixel(end) - source_pixel(start));
+        let galley = painter.layout_no_wrap(label, egui::FontId::monospace(11.0), Color32::from_rgb(235, 235, 240));
+        let label_rect = Rect::from_center_size(
+            start.lerp(end, 0.5) - Vec2::new(0.0, 16.0),
+            galley.size() + Vec2::new(12.0, 6.0),
+        );
+        painter.rect_filled(label_rect, 4.0, Color32::from_rgba_unmultiplied(18, 18, 20, 230));
+        painter.galley(label_rect.min + Vec2::new(6.0, 3.0), galley, Color32::WHITE);
+    }
+
+    /// A PNG dropped on the window becomes the watermark of the selected
+    /// previews. (Windows doesn't report the pointer during a file drag,
+    /// so the drop can't pick a tile by position.)
+    fn take_dropped_watermarks(&mut self, ctx: &egui::Context, preview_manager: &mut PreviewManager) {
+        let dropped = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter_map(|file| file.path.clone())
+                .find(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png")))
+        });
+        let Some(path) = dropped else { return; };
+        let path = path.to_string_lossy().into_owned();
+        if self.selection.is_empty() {
+            self.pending_toast = Some("Select a preview, then drop the image again to watermark it".to_string());
+            return;
+        }
+        if self.watermark_texture(ctx, &path).is_none() {
+            self.pending_toast = Some("Couldn't read that image".to_string());
+            return;
+        }
+        for &id in &self.selection {
+            if let Some(preview) = preview_manager.get_mut(id) {
+                match preview.watermark.as_mut() {
+                    Some(watermark) => watermark.path = path.clone(),
+                    None => preview.watermark = Some(Watermark::new(path.clone())),
+                }
+            }
+        }
+    }
+
+    /// The image of a watermark, loading it the first time
+    fn watermark_texture(&mut self, ctx: &egui::Context, path: &str) -> Option<egui::TextureHandle> {
+        self.watermark_textures
+            .entry(path.to_string())
+            .or_insert_with(|| match image::open(path) {
+                Ok(image) => {
+                    let image = image.to_rgba8();
+                    let size = [image.width() as usize, image.height() as usize];
+                    let pixels = egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+                    Some(ctx.load_texture(format!("watermark {}", path), pixels, egui::TextureOptions::LINEAR))
+                }
+                Err(error) => {
+                    log::warn!("Watermark {} unavailable: {}", path, error);
+                    None
+                }
+            })
+            .clone()
+    }
+
+    /// Magnified view of the frame around the pointer, beside the pointer
+    /// (flipped to the other side near the canvas edge)
+    fn draw_loupe(
+        &self,
+        painter: &egui::Painter,
+        canvas_rect: Rect,
+        pointer: Pos2,
+        magnification: f32,
+        (texture, drawn, uv, style): (egui::TextureId, Rect, Rect, FrameStyle),
+    ) {
+        // The patch of frame being magnified, kept on the frame
+        let patch_size = Vec2::splat(LOUPE_SIZE / magnification).min(drawn.size());
+        let patch = Rect::from_center_size(pointer, patch_size);
+        let patch = patch.translate(
+            (drawn.min - patch.min).max(Vec2::ZERO) - (patch.max - drawn.max).max(Vec2::ZERO),
+        );
+
+        let offset = Vec2::splat(24.0);
+        let mut view = Rect::from_min_size(pointer + offset, Vec2::splat(LOUPE_SIZE));
+        if view.max.x > canvas_rect.max.x {
+            view = view.translate(Vec2::new(-(LOUPE_SIZE + 2.0 * offset.x), 0.0));
+        }
+        if view.max.y > canvas_rect.max.y {
+            view = view.translate(Vec2::new(0.0, -(LOUPE_SIZE + 2.0 * offset.y)));
+        }
+
+        painter.rect_filled(view.translate(Vec2::new(0.0, 4.0)), TILE_ROUNDING, Color32::from_black_alpha(90));
+        painter.rect_filled(view, TILE_ROUNDING, Color32::from_rgb(18, 18, 18));
+        let style = FrameStyle { corner_radius: TILE_ROUNDING, ..style };
+        self.paint_frame(painter, texture, view, sub_uv_of(drawn, uv, style.quarter_turns, patch), Color32::WHITE, style);
+        painter.rect_stroke(view, TILE_ROUNDING, Stroke::new(1.5, self.theme.accent()));
+        painter.text(
+            view.right_bottom() - Vec2::new(8.0, 6.0),
+            egui::Align2::RIGHT_BOTTOM,
+            format!("{}×", magnification),
+            egui::FontId::proportional(11.0),
+            Color32::from_white_alpha(200),
+        );
+    }
+
+    /// Draw a preview frame through the tile renderer when available.
+    /// Without it (old GL) effects and chroma keys are skipped.
+    fn paint_frame(
+        &self,
+        painter: &egui::Painter,
+        texture: egui::TextureId,
+        rect: Rect,
+        uv: Rect,
+        tint: Color32,
+        style: FrameStyle,
+    ) {
+        let quarter_turns = style.quarter_turns;
+        match &self.tile_renderer {
+            Some(renderer) => renderer.paint(painter, texture, rect, uv, tint, style),
+            None if quarter_turns % 4 == 0 => {
+                let mut shape = egui::epaint::RectShape::filled(rect, style.corner_radius, tint);
+                shape.fill_texture_id = texture;
+                shape.uv = uv;
+                painter.add(shape);
+            }
+            None => {
+                // Rotated: a quad whose corners sample the turned frame
+                let mut mesh = egui::Mesh::with_texture(texture);
+                for corner in [Pos2::new(0.0, 0.0), Pos2::new(1.0, 0.0), Pos2::new(0.0, 1.0), Pos2::new(1.0, 1.0)] {
+                    let source = rotated_corner(corner, quarter_turns);
+                    mesh.vertices.push(egui::epaint::Vertex {
+                        pos: rect.lerp_inside(corner.to_vec2()),
+                        uv: uv.lerp_inside(source.to_vec2()),
+                        color: tint,
+                    });
+                }
+                mesh.add_triangle(0, 1, 2);
+                mesh.add_triangle(2, 1, 3);
+                painter.add(mesh);
+            }
+        }
+    }
+
     /// Floating "Removed '...' · Undo" toast for the most recently removed preview.
     fn draw_and_interact_undo_toast(
         &mut self,
@@ -1327,7 +4630,7 @@ impl CanvasState {
         let undo_color = if undo_response.hovered() {
             Color32::from_rgba_unmultiplied(140, 200, 255, text_alpha)
         } else {
-            Color32::from_rgba_unmultiplied(74, 158, 255, text_alpha)
+            self.theme.accent_alpha(text_alpha)
         };
         painter.text(
             undo_rect.center(),
@@ -1338,25 +4641,7 @@ impl CanvasState {
         );
 
         if undo_response.clicked() {
-            if info.browser_url.is_some() {
-                // The browser's host window was destroyed with the tile, so
-                // the app must recreate the WebView from the saved URL.
-                self.pending_browser_restore = Some(info.clone());
-            } else if let Some(handle) = info.window_handle {
-                let id = preview_manager.add_for_window(
-                    handle.hwnd,
-                    handle.process_id,
-                    info.title.clone(),
-                    info.position,
-                    info.size,
-                );
-                if let Some(preview) = preview_manager.get_mut(id) {
-                    preview.capture_active = true;
-                    preview.set_fps_preset(info.fps_preset);
-                    preview.crop_uv = info.crop_uv;
-                }
-                capture_coordinator.start_capture(id, handle.hwnd, info.title.clone(), info.fps_preset.as_u32());
-            }
+            self.recreate_preview(&info, preview_manager, capture_coordinator);
             self.last_removed = None;
         }
 
@@ -1364,6 +4649,155 @@ impl CanvasState {
         ui.ctx().request_repaint();
     }
 
+    /// Bring back a removed preview. Window tiles come back right away with
+    /// a new id; browser tiles are queued for the app, which owns WebViews.
+    fn recreate_preview(
+        &mut self,
+        info: &RemovedPreviewInfo,
+        preview_manager: &mut PreviewManager,
+        capture_coordinator: &mut CaptureCoordinator,
+    ) -> Option<PreviewId> {
+        if info.browser_url.is_some() {
+            // The browser's host window was destroyed with the tile, so
+            // the app must recreate the WebView from the saved URL.
+            self.pending_browser_restores.push(info.clone());
+            return None;
+        }
+
+        // An offline tile comes back offline, still waiting for its window
+        let id = match &info.window_handle {
+            Some(handle) => preview_manager.add_for_window(
+                handle.hwnd,
+                handle.process_id,
+                info.title.clone(),
+                info.position,
+                info.size,
+            ),
+            None => preview_manager.add(info.title.clone(), info.position, info.size),
+        };
+        if let Some(preview) = preview_manager.get_mut(id) {
+            preview.capture_active = info.window_handle.is_some();
+            preview.set_fps_preset(info.fps_preset);
+            preview.capture = info.capture;
+            preview.set_crop(info.crop_uv);
+            apply_layout_fields(preview, &PreviewLayout::from(info));
+            preview.window_exe = info.window_exe.clone();
+            preview.window_class = info.window_class.clone();
+        }
+        if let Some(handle) = &info.window_handle {
+            capture_coordinator.start_capture(
+                id,
+                handle.hwnd,
+                info.title.clone(),
+                info.fps_preset.as_u32(),
+                info.capture,
+            );
+        }
+        self.remap_preview(info.id, id);
+        self.pending_untrash.push(info.id);
+        Some(id)
+    }
+
+    /// A removed preview was recreated under a new id
+    pub fn remap_preview(&mut self, old: PreviewId, new: PreviewId) {
+        self.history.remap(old, new);
+    }
+
+    /// Start the undo history over from the canvas as it is (after a layout
+    /// is loaded)
+    pub fn reset_history(&mut self, preview_manager: &PreviewManager) {
+        self.history.reset(history::snapshot(preview_manager, &self.containers));
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Ctrl+Z: take the canvas back one step
+    pub fn undo(&mut self, preview_manager: &mut PreviewManager, capture_coordinator: &mut CaptureCoordinator) {
+        if let Some(snapshot) = self.history.undo() {
+            self.restore_snapshot(snapshot, preview_manager, capture_coordinator);
+        }
+    }
+
+    /// Ctrl+Y: redo the step last undone
+    pub fn redo(&mut self, preview_manager: &mut PreviewManager, capture_coordinator: &mut CaptureCoordinator) {
+        if let Some(snapshot) = self.history.redo() {
+            self.restore_snapshot(snapshot, preview_manager, capture_coordinator);
+        }
+    }
+
+    /// Make the canvas match a snapshot from the history: previews not in
+    /// it go, missing ones are recreated, the rest get their geometry,
+    /// crop, rotation, frame rate, capture options and layer back, and the
+    /// container frames are put back as they were.
+    fn restore_snapshot(
+        &mut self,
+        snapshot: Snapshot,
+        preview_manager: &mut PreviewManager,
+        capture_coordinator: &mut CaptureCoordinator,
+    ) {
+        let Snapshot { tiles, containers } = snapshot;
+        let tiles: Vec<RemovedPreviewInfo> = tiles.into_iter().map(|tile| tile.info).collect();
+        self.containers = containers;
+        for id in preview_manager.all_ids() {
+            if !tiles.iter().any(|tile| tile.id == id) {
+                capture_coordinator.stop_capture(id);
+                preview_manager.remove(id);
+                self.animation.remove_spring(id);
+                self.selection.retain(|selected| *selected != id);
+                if let Some(template) = &mut self.template {
+                    template.vacate(id);
+                }
+            }
+        }
+
+        for tile in tiles {
+            let id = if preview_manager.get(tile.id).is_some() {
+                tile.id
+            } else {
+                match self.recreate_preview(&tile, preview_manager, capture_coordinator) {
+                    Some(id) => id,
+                    None => continue,
+                }
+            };
+            let Some(preview) = preview_manager.get_mut(id) else { continue; };
+
+            // Caught mid-removal: its capture was already stopped
+            if preview.removing.take().is_some() {
+                if let Some(handle) = &preview.window_handle {
+                    capture_coordinator.start_capture(
+                        id,
+                        handle.hwnd,
+                        preview.title.clone(),
+                        tile.fps_preset.as_u32(),
+                        tile.capture,
+                    );
+                }
+            }
+            preview.position = tile.position;
+            preview.size = tile.size;
+            apply_layout_fields(preview, &PreviewLayout::from(&tile));
+            if preview.crop_uv != tile.crop_uv {
+                preview.set_crop(tile.crop_uv);
+            }
+            if preview.fps_preset != tile.fps_preset {
+                preview.set_fps_preset(tile.fps_preset);
+                capture_coordinator.set_target_fps(id, preview.target_fps);
+            }
+            if preview.capture != tile.capture {
+                preview.capture = tile.capture;
+                capture_coordinator.set_options(id, tile.capture);
+            }
+            self.animation.remove_spring(id);
+            preview_manager.set_z_order(id, tile.z_order);
+        }
+    }
+
     /// Draw selection indicators and interactive resize handles
     fn draw_and_interact_selection(
         &mut self,
@@ -1378,21 +4812,25 @@ impl CanvasState {
         // Collect selection info to avoid borrow issues
         let selection_info: Vec<_> = self.selection.iter()
             .filter_map(|id| preview_manager.get(*id).map(|p| {
-                (*id, p.rect(), p.source_aspect_ratio, p.crop_uv, p.frame_size, p.is_browser())
+                // Alt+drag cropping works in frame space, so not on turned
+                // tiles (the region selector still does)
+                let croppable = !p.is_browser() && p.rotation == 0;
+                (*id, p.rect(), p.tile_aspect_ratio(), p.crop_uv, p.frame_size, croppable)
             }))
             .collect();
 
-        for (id, preview_rect, aspect_ratio, crop_uv, frame_size, is_browser) in selection_info {
+        for (id, preview_rect, aspect_ratio, crop_uv, frame_size, croppable) in selection_info {
             let screen_rect = self.canvas_rect_to_screen(preview_rect, canvas_rect);
 
             // Minimal Void: Selection border with accent color
-            // (browsers can't be cropped, so no orange crop hint for them)
+            // (browsers and turned tiles can't be cropped, so no orange
+            // crop hint for them)
             let border_color = if self.interactive_browser == Some(id) {
                 Color32::from_rgb(107, 170, 75) // Green: live interaction mode
-            } else if alt_held && !is_browser {
+            } else if alt_held && croppable {
                 Color32::from_rgb(255, 150, 100) // Orange for crop mode
             } else {
-                Color32::from_rgb(74, 158, 255) // #4a9eff blue accent
+                self.theme.accent() // #4a9eff blue unless themed
             };
             painter.rect_stroke(
                 screen_rect,
@@ -1435,10 +4873,10 @@ impl CanvasState {
                 let hit_rect = Rect::from_center_size(handle_pos, Vec2::splat(handle_hit_size));
 
                 // Minimal Void: Clean handles matching selection color
-                let handle_fill = if alt_held && !is_browser {
+                let handle_fill = if alt_held && croppable {
                     Color32::from_rgb(255, 150, 100) // Orange for crop mode
                 } else {
-                    Color32::from_rgb(74, 158, 255) // Match accent color
+                    self.theme.accent() // Match accent color
                 };
                 painter.rect_filled(handle_rect, 3.0, handle_fill);
                 // No stroke - cleaner look
@@ -1458,8 +4896,8 @@ impl CanvasState {
                 // Handle drag start - check if Alt is held for crop mode
                 // (browser tiles never crop: interactive coordinates would
                 // no longer match the page)
-                if handle_response.drag_started() {
-                    if alt_held && frame_size.is_some() && !is_browser {
+                if handle_response.drag_started() && !input.space {
+                    if alt_held && frame_size.is_some() && croppable {
                         // Start crop mode
                         let current_crop = crop_uv.unwrap_or((0.0, 0.0, 1.0, 1.0));
                         self.drag_state = Some(DragState::Cropping {
@@ -1470,6 +4908,7 @@ impl CanvasState {
                         });
                     } else {
                         // Start resize mode with aspect ratio lock
+                        self.animation.size_springs.remove(&id);
                         self.drag_state = Some(DragState::Resizing {
                             id,
                             handle: handle_type,
@@ -1487,7 +4926,26 @@ impl CanvasState {
                         if *resize_id == id && *handle == handle_type {
                             if let Some(current_pos) = input.interact_pos {
                                 let delta = (current_pos - *start_mouse) / self.zoom;
-                                let new_rect = apply_resize(*handle, *start_rect, delta, Some(*ar));
+                                let mut new_rect = apply_resize(*handle, *start_rect, delta, Some(*ar));
+
+                                // Smart guides: pull the dragged edge onto a
+                                // neighbour's edge or center (the nearer axis
+                                // wins, the aspect lock sets the other)
+                                let others = guide_targets(preview_manager, &[id]);
+                                let snap = &self.animation.snap_config;
+                                let (edge_x, edge_y) = moving_edges(*handle, new_rect);
+                                let shift_x = edge_x.and_then(|x| snap.alignment_shift(&[x], &others, true, self.zoom));
+                                let shift_y = edge_y.and_then(|y| snap.alignment_shift(&[y], &others, false, self.zoom));
+                                let shift = match (shift_x, shift_y) {
+                                    (Some(x), Some(y)) if y.abs() < x.abs() => Vec2::new(0.0, y),
+                                    (Some(x), _) => Vec2::new(x, 0.0),
+                                    (None, Some(y)) => Vec2::new(0.0, y),
+                                    (None, None) => Vec2::ZERO,
+                                };
+                                if shift != Vec2::ZERO {
+                                    new_rect = apply_resize(*handle, *start_rect, delta + shift, Some(*ar));
+                                }
+                                self.guides = snap.guides(new_rect, &others);
 
                                 // Apply minimum size
                                 let min_size = 100.0;
@@ -1514,40 +4972,12 @@ impl CanvasState {
                                 let uv_delta_x = delta_screen.x / preview_screen_size.x;
                                 let uv_delta_y = delta_screen.y / preview_screen_size.y;
 
-                                // Apply crop adjustment based on handle
-                                let (min_u, min_v, max_u, max_v) = *start_crop_uv;
-                                let mut new_crop = (min_u, min_v, max_u, max_v);
-
-                                match handle {
-                                    ResizeHandle::TopLeft => {
-                                        new_crop.0 = (min_u + uv_delta_x).clamp(0.0, new_crop.2 - 0.1);
-                                        new_crop.1 = (min_v + uv_delta_y).clamp(0.0, new_crop.3 - 0.1);
-                                    }
-                                    ResizeHandle::Top => {
-                                        new_crop.1 = (min_v + uv_delta_y).clamp(0.0, new_crop.3 - 0.1);
-                                    }
-                                    ResizeHandle::TopRight => {
-                                        new_crop.2 = (max_u + uv_delta_x).clamp(new_crop.0 + 0.1, 1.0);
-                                        new_crop.1 = (min_v + uv_delta_y).clamp(0.0, new_crop.3 - 0.1);
-                                    }
-                                    ResizeHandle::Left => {
-                                        new_crop.0 = (min_u + uv_delta_x).clamp(0.0, new_crop.2 - 0.1);
-                                    }
-                                    ResizeHandle::Right => {
-                                        new_crop.2 = (max_u + uv_delta_x).clamp(new_crop.0 + 0.1, 1.0);
-                                    }
-                                    ResizeHandle::BottomLeft => {
-                                        new_crop.0 = (min_u + uv_delta_x).clamp(0.0, new_crop.2 - 0.1);
-                                        new_crop.3 = (max_v + uv_delta_y).clamp(new_crop.1 + 0.1, 1.0);
-                                    }
-                                    ResizeHandle::Bottom => {
-                                        new_crop.3 = (max_v + uv_delta_y).clamp(new_crop.1 + 0.1, 1.0);
-                                    }
-                                    ResizeHandle::BottomRight => {
-                                        new_crop.2 = (max_u + uv_delta_x).clamp(new_crop.0 + 0.1, 1.0);
-                                        new_crop.3 = (max_v + uv_delta_y).clamp(new_crop.1 + 0.1, 1.0);
-                                    }
-                                }
+                                let new_crop = adjust_crop(
+                                    *start_crop_uv,
+                                    *handle,
+                                    Vec2::new(uv_delta_x, uv_delta_y),
+                                    Vec2::splat(0.1),
+                                );
 
                                 // Apply the new crop
                                 if let Some(preview) = preview_manager.get_mut(id) {
@@ -1571,6 +5001,7 @@ impl CanvasState {
                     if let Some(DragState::Resizing { id: resize_id, handle, .. }) = &self.drag_state {
                         if *resize_id == id && *handle == handle_type {
                             self.drag_state = None;
+                            self.guides.clear();
                         }
                     }
                     if let Some(DragState::Cropping { id: crop_id, handle, .. }) = &self.drag_state {
@@ -1584,6 +5015,273 @@ impl CanvasState {
     }
 }
 
+/// The next grid position from `pos` in `direction` (each axis -1, 0 or
+/// 1): one cell along from a point on the grid, the nearest line ahead from
+/// one between lines. Axes not moving snap to the nearest line.
+fn grid_step(pos: Pos2, direction: Vec2, grid: f32) -> Pos2 {
+    let step = |value: f32, direction: f32| {
+        let cells = value / grid;
+        let cell = if direction > 0.0 {
+            cells.floor() + 1.0
+        } else if direction < 0.0 {
+            cells.ceil() - 1.0
+        } else {
+            cells.round()
+        };
+        cell * grid
+    };
+    Pos2::new(step(pos.x, direction.x), step(pos.y, direction.y))
+}
+
+/// Top-left corner that moves `rect` the least to bring it inside `bounds`
+/// (against the top-left edges if it's too big to fit)
+fn clamp_into(rect: Rect, bounds: Rect) -> Pos2 {
+    rect.min.clamp(bounds.min, (bounds.max - rect.size()).max(bounds.min))
+}
+
+/// The action-safe (93%) and title-safe (90%) boxes of the largest 16:9
+/// area centered in `frame`
+fn safe_areas(frame: Rect) -> [Rect; 2] {
+    let wide = if frame.width() * 9.0 > frame.height() * 16.0 {
+        Vec2::new(frame.height() * 16.0 / 9.0, frame.height())
+    } else {
+        Vec2::new(frame.width(), frame.width() * 9.0 / 16.0)
+    };
+    [0.93, 0.9].map(|share| Rect::from_center_size(frame.center(), wide * share))
+}
+
+/// Crosshair, thirds grid and safe-area markers over a drawn frame
+fn draw_frame_overlays(painter: &egui::Painter, frame: Rect, overlays: FrameOverlays) {
+    let stroke = Stroke::new(1.0, Color32::from_white_alpha(140));
+    if overlays.thirds {
+        for t in [1.0 / 3.0, 2.0 / 3.0] {
+            let x = frame.left() + frame.width() * t;
+            let y = frame.top() + frame.height() * t;
+            painter.line_segment([Pos2::new(x, frame.top()), Pos2::new(x, frame.bottom())], stroke);
+            painter.line_segment([Pos2::new(frame.left(), y), Pos2::new(frame.right(), y)], stroke);
+        }
+    }
+    if overlays.safe_area {
+        let [action, title] = safe_areas(frame);
+        painter.rect_stroke(action, 0.0, stroke);
+        painter.rect_stroke(title, 0.0, Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 150, 100, 160)));
+    }
+    if overlays.crosshair {
+        let arm = frame.size().min_elem() * 0.06;
+        let center = frame.center();
+        let cross = Stroke::new(1.5, Color32::from_white_alpha(200));
+        painter.line_segment([center - Vec2::new(arm, 0.0), center + Vec2::new(arm, 0.0)], cross);
+        painter.line_segment([center - Vec2::new(0.0, arm), center + Vec2::new(0.0, arm)], cross);
+    }
+}
+
+/// "412 px · 400 × 96" for a ruler spanning `delta` source pixels
+fn measure_label(delta: Vec2) -> String {
+    format!(
+        "{:.0} px · {:.0} × {:.0}",
+        delta.length(),
+        delta.x.abs(),
+        delta.y.abs(),
+    )
+}
+
+/// `#RRGGBB`
+fn color_hex(color: Color32) -> String {
+    format!("#{:02X}{:02X}{:02X}", color.r(), color.g(), color.b())
+}
+
+/// Swatch and values of the color under the eyedropper, beside the pointer
+fn draw_color_readout(painter: &egui::Painter, pointer: Pos2, color: Color32) {
+    let text = format!("{}  {} {} {}", color_hex(color), color.r(), color.g(), color.b());
+    let galley = painter.layout_no_wrap(text, egui::FontId::monospace(11.0), Color32::from_rgb(220, 220, 225));
+    let swatch = 14.0;
+    let size = Vec2::new(swatch + 6.0 + galley.size().x, swatch.max(galley.size().y)) + Vec2::splat(12.0);
+    let rect = Rect::from_min_size(pointer + Vec2::new(18.0, 18.0), size);
+    painter.rect_filled(rect, 6.0, Color32::from_rgba_unmultiplied(18, 18, 20, 235));
+    let swatch_rect = Rect::from_min_size(rect.min + Vec2::splat(6.0), Vec2::splat(swatch));
+    painter.rect_filled(swatch_rect, 3.0, color);
+    painter.rect_stroke(swatch_rect, 3.0, Stroke::new(1.0, Color32::from_white_alpha(60)));
+    painter.galley(
+        Pos2::new(swatch_rect.max.x + 6.0, rect.center().y - galley.size().y / 2.0),
+        galley,
+        Color32::WHITE,
+    );
+}
+
+/// What an offline tile remembers of its source: "30 FPS · 12 ms ·
+/// 1920×1080", or as much of it as is known
+fn offline_details(stats: Option<SourceStats>, frame_size: Option<(u32, u32)>) -> String {
+    let mut parts = Vec::new();
+    if let Some(stats) = stats {
+        parts.push(format!("{:.0} FPS", stats.fps));
+        parts.push(format!("{} ms", stats.latency_ms));
+    }
+    if let Some((width, height)) = frame_size {
+        parts.push(format!("{}×{}", width, height));
+    }
+    parts.join(" · ")
+}
+
+/// Rects of the previews smart guides align to: everything except the
+/// previews being moved and tiles on their way out.
+fn guide_targets(preview_manager: &PreviewManager, moving: &[PreviewId]) -> Vec<Rect> {
+    preview_manager
+        .all()
+        .filter(|p| p.removing.is_none() && !p.hidden && !moving.contains(&p.id))
+        .map(|p| p.rect())
+        .collect()
+}
+
+/// The x and y positions of the edges a resize handle moves.
+fn moving_edges(handle: ResizeHandle, rect: Rect) -> (Option<f32>, Option<f32>) {
+    use ResizeHandle::*;
+    let x = match handle {
+        TopLeft | Left | BottomLeft => Some(rect.min.x),
+        TopRight | Right | BottomRight => Some(rect.max.x),
+        Top | Bottom => None,
+    };
+    let y = match handle {
+        TopLeft | Top | TopRight => Some(rect.min.y),
+        BottomLeft | Bottom | BottomRight => Some(rect.max.y),
+        Left | Right => None,
+    };
+    (x, y)
+}
+
+/// Median tile height, the row height for arranging (at least 1).
+pub(super) fn median_height(previews: &[&Preview]) -> f32 {
+    let mut heights: Vec<f32> = previews.iter().map(|p| p.size.y).collect();
+    heights.sort_by(f32::total_cmp);
+    heights.get(heights.len() / 2).copied().unwrap_or(1.0).max(1.0)
+}
+
+/// Heat map overlay: blue for occasional changes through orange for a busy
+/// tile, darkened with an "Idle" label when nothing changed for a minute.
+fn draw_heat_tint(painter: &egui::Painter, rect: Rect, changes: usize) {
+    let (fill, label) = if changes == 0 {
+        (Color32::from_rgba_unmultiplied(0, 0, 0, 170), "Idle".to_string())
+    } else {
+        let heat = (changes as f32 / HEAT_MAP_HOT).min(1.0);
+        let mix = |cold: u8, hot: u8| (cold as f32 + (hot as f32 - cold as f32) * heat).round() as u8;
+        (
+            Color32::from_rgba_unmultiplied(mix(74, 255), mix(158, 150), mix(255, 100), 90),
+            format!("{} changes/min", changes),
+        )
+    };
+    painter.rect_filled(rect, 8.0, fill);
+
+    let font = egui::FontId::proportional(11.0);
+    let galley = painter.layout_no_wrap(label, font, Color32::from_rgb(230, 230, 235));
+    let pill = Rect::from_center_size(
+        Pos2::new(rect.center().x, rect.max.y - 20.0),
+        galley.size() + Vec2::new(16.0, 8.0),
+    );
+    painter.rect_filled(pill, 8.0, Color32::from_rgba_unmultiplied(0, 0, 0, 160));
+    painter.galley(pill.center() - galley.size() / 2.0, galley, Color32::TRANSPARENT);
+}
+
+/// Thin accent edge on the tile of the focused desktop window, plus a
+/// pulsing keyboard badge while it's being typed in
+fn draw_focus_marker(painter: &egui::Painter, rect: Rect, typing: bool, time: f64) {
+    painter.rect_stroke(
+        rect.shrink(1.0),
+        TILE_ROUNDING,
+        Stroke::new(1.5, Color32::from_rgba_unmultiplied(107, 170, 75, 150)),
+    );
+    if !typing {
+        return;
+    }
+
+    let pulse = (time as f32 * 4.0).sin() * 0.5 + 0.5;
+    let badge = Rect::from_min_size(rect.left_bottom() + Vec2::new(8.0, -28.0), Vec2::new(26.0, 20.0));
+    painter.rect_filled(badge, 6.0, Color32::from_rgba_unmultiplied(0, 0, 0, 150));
+    painter.text(
+        badge.center(),
+        egui::Align2::CENTER_CENTER,
+        egui_phosphor::regular::KEYBOARD,
+        egui::FontId::proportional(13.0),
+        Color32::from_rgba_unmultiplied(107, 170, 75, (140.0 + pulse * 115.0) as u8),
+    );
+}
+
+/// Reminder pill at the top of the canvas while the panic button has the
+/// previews blanked
+fn draw_blanked_notice(painter: &egui::Painter, canvas_rect: Rect) {
+    draw_notice(
+        painter,
+        canvas_rect,
+        format!("Previews hidden · {} to show", crate::panic_button::PANIC_HOTKEY_LABEL),
+    );
+}
+
+/// Status pill at the top of the canvas
+fn draw_notice(painter: &egui::Painter, canvas_rect: Rect, label: String) {
+    let galley = painter.layout_no_wrap(label, egui::FontId::proportional(11.0), Color32::from_rgb(170, 170, 175));
+    let pill = Rect::from_center_size(
+        Pos2::new(canvas_rect.center().x, canvas_rect.min.y + 28.0),
+        galley.size() + Vec2::new(28.0, 12.0),
+    );
+    painter.rect_filled(pill, 13.0, Color32::from_rgba_unmultiplied(0, 0, 0, 170));
+    painter.galley(pill.center() - galley.size() / 2.0, galley, Color32::TRANSPARENT);
+}
+
+/// A group with an unused "Group N" name and the default budget
+fn new_fps_group(groups: &[FpsGroup]) -> FpsGroup {
+    let name = (1..)
+        .map(|n| format!("Group {}", n))
+        .find(|name| !groups.iter().any(|g| &g.name == name))
+        .unwrap_or_default();
+    FpsGroup { name, budget: DEFAULT_GROUP_BUDGET }
+}
+
+/// Expanded sticky note, hanging below its flag
+fn draw_note_card(painter: &egui::Painter, flag: Rect, note: &str) {
+    let galley = painter.layout(
+        note.to_string(),
+        egui::FontId::proportional(12.0),
+        Color32::from_rgb(230, 230, 235),
+        NOTE_CARD_WIDTH,
+    );
+    let size = galley.size() + Vec2::splat(16.0);
+    let card = Rect::from_min_size(Pos2::new(flag.max.x - size.x, flag.max.y + 4.0), size);
+    painter.rect_filled(card, 6.0, Color32::from_rgb(25, 25, 28));
+    painter.rect_stroke(card, 6.0, Stroke::new(1.0, Color32::from_rgba_unmultiplied(255, 150, 100, 160)));
+    painter.galley(card.min + Vec2::splat(8.0), galley, Color32::TRANSPARENT);
+}
+
+/// Small stats panel in the bottom-right corner of a preview: measured vs
+/// target FPS, frame size, capture latency and dropped frames.
+fn draw_stats_overlay(painter: &egui::Painter, screen_rect: Rect, stats: &CaptureStats, target_fps: u32) {
+    let size_text = stats
+        .frame_size
+        .map(|(w, h)| format!("{}×{}", w, h))
+        .unwrap_or_else(|| "—".to_string());
+    let lines = [
+        format!("{:.1} / {} fps", stats.received_fps, target_fps),
+        size_text,
+        format!("{:.0} ms latency", stats.latency.as_secs_f32() * 1000.0),
+        format!("{} dropped", stats.frames_dropped),
+    ];
+
+    let line_height = 13.0;
+    let panel_size = Vec2::new(112.0, lines.len() as f32 * line_height + 10.0);
+    let panel_rect = Rect::from_min_size(
+        screen_rect.right_bottom() - panel_size - Vec2::new(8.0, 8.0),
+        panel_size,
+    );
+    painter.rect_filled(panel_rect, 6.0, Color32::from_rgba_unmultiplied(0, 0, 0, 170));
+
+    for (i, line) in lines.iter().enumerate() {
+        painter.text(
+            panel_rect.left_top() + Vec2::new(8.0, 5.0 + i as f32 * line_height),
+            egui::Align2::LEFT_TOP,
+            line,
+            egui::FontId::monospace(10.0),
+            Color32::from_rgb(170, 200, 170),
+        );
+    }
+}
+
 /// Apply resize delta based on handle position, optionally maintaining aspect ratio
 fn apply_resize(handle: ResizeHandle, start_rect: Rect, delta: Vec2, aspect_ratio: Option<f32>) -> Rect {
     let mut min = start_rect.min;
@@ -0,0 +1,311 @@
+use eframe::egui::{self, Color32, Pos2, Rect, TextureId, Vec2};
+use eframe::egui_glow;
+use eframe::glow::{self, HasContext as _};
+use std::collections::HashMap;
+use std::sync::Arc;
+use crate::preview::{ChromaKey, Effect, EffectKind, Passthrough};
+
+const VERTEX_SHADER: &str = r#"
+uniform vec4 u_uv; // min.x, min.y, max.x, max.y
+uniform int u_quarter_turns; // clockwise rotation of the frame in the tile
+out vec2 v_uv;
+out vec2 v_corner;
+
+void main() {
+    // Quad corners from the vertex index (triangle strip), no vertex buffer
+    vec2 corner = vec2(float(gl_VertexID & 1), float(gl_VertexID >> 1));
+    // Same mapping as rotated_corner() on the CPU side
+    vec2 source = corner;
+    if (u_quarter_turns == 1) {
+        source = vec2(corner.y, 1.0 - corner.x);
+    } else if (u_quarter_turns == 2) {
+        source = vec2(1.0 - corner.x, 1.0 - corner.y);
+    } else if (u_quarter_turns == 3) {
+        source = vec2(1.0 - corner.y, corner.x);
+    }
+    v_uv = mix(u_uv.xy, u_uv.zw, source);
+    v_corner = corner;
+    gl_Position = vec4(corner.x * 2.0 - 1.0, 1.0 - corner.y * 2.0, 0.0, 1.0);
+}
+"#;
+
+/// Fragment shader up to the effect: the effect's `vec4 effect(vec2 uv)`
+/// goes between this and `FRAGMENT_MAIN`
+const FRAGMENT_PRELUDE: &str = r#"
+precision mediump float;
+uniform sampler2D u_sampler;
+uniform vec4 u_uv;
+uniform vec4 u_tint; // premultiplied, gamma space (like egui vertex colors)
+in vec2 v_uv;
+in vec2 v_corner;
+out vec4 out_color;
+
+// Size of one source pixel in UV units
+vec2 texel;
+
+vec3 srgb_from_linear(vec3 rgb) {
+    bvec3 cutoff = lessThan(rgb, vec3(0.0031308));
+    vec3 lower = rgb * vec3(12.92);
+    vec3 higher = vec3(1.055) * pow(rgb, vec3(1.0 / 2.4)) - vec3(0.055);
+    return mix(higher, lower, vec3(cutoff));
+}
+
+// Sample the frame, staying inside this tile's part of the texture (an
+// atlas page holds other previews right next to it)
+vec4 source_at(vec2 uv) {
+    vec2 lo = min(u_uv.xy, u_uv.zw) + texel * 0.5;
+    vec2 hi = max(u_uv.xy, u_uv.zw) - texel * 0.5;
+    return texture(u_sampler, clamp(uv, lo, hi));
+}
+"#;
+
+const FRAGMENT_MAIN: &str = r#"
+uniform vec4 u_key; // sRGB key color and tolerance; negative tolerance = off
+uniform vec3 u_shape; // quad width and height, corner radius (physical pixels)
+
+// How much of this pixel lies inside the quad's rounded corners
+float corner_coverage() {
+    vec2 half_size = u_shape.xy * 0.5;
+    vec2 q = abs(v_corner * u_shape.xy - half_size) - (half_size - vec2(u_shape.z));
+    float outside = length(max(q, 0.0)) - u_shape.z;
+    return clamp(0.5 - outside, 0.0, 1.0);
+}
+
+void main() {
+    texel = 1.0 / vec2(textureSize(u_sampler, 0));
+    // egui stores textures as SRGB8_ALPHA8, so sampling returns linear
+    // color; egui blends in gamma space, so encode back before tinting.
+    vec4 color = effect(v_uv);
+    vec3 rgb = srgb_from_linear(color.rgb);
+    if (u_key.a >= 0.0) {
+        // Fade out near the key color, with a soft edge against fringes
+        float keep = smoothstep(u_key.a, u_key.a + 0.1, distance(rgb, u_key.rgb) / sqrt(3.0));
+        rgb *= keep;
+        color.a *= keep;
+    }
+    out_color = u_tint * vec4(rgb, color.a) * corner_coverage();
+}
+"#;
+
+/// The point of the frame (in 0..1 frame coordinates) shown at `corner`
+/// of a tile whose frame is rotated clockwise by `quarter_turns`.
+pub fn rotated_corner(corner: Pos2, quarter_turns: u8) -> Pos2 {
+    match quarter_turns % 4 {
+        1 => Pos2::new(corner.y, 1.0 - corner.x),
+        2 => Pos2::new(1.0 - corner.x, 1.0 - corner.y),
+        3 => Pos2::new(1.0 - corner.y, corner.x),
+        _ => corner,
+    }
+}
+
+/// The part of `uv` seen through `part` (screen space) of a frame drawn
+/// over `drawn` and rotated by `quarter_turns`. Flipped axes stay flipped.
+pub fn sub_uv_of(drawn: Rect, uv: Rect, quarter_turns: u8, part: Rect) -> Rect {
+    let on_tile = |p: Pos2| Pos2::ZERO + (p - drawn.min) / drawn.size().max(Vec2::splat(1.0));
+    let in_frame = Rect::from_two_pos(
+        rotated_corner(on_tile(part.min), quarter_turns),
+        rotated_corner(on_tile(part.max), quarter_turns),
+    );
+    Rect::from_min_max(uv.lerp_inside(in_frame.min.to_vec2()), uv.lerp_inside(in_frame.max.to_vec2()))
+}
+
+/// How a frame is drawn, beyond where and which part of it
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameStyle {
+    /// Clockwise rotation of the frame in the tile
+    pub quarter_turns: u8,
+    pub effect: Option<EffectKind>,
+    pub chroma_key: Option<ChromaKey>,
+    /// Rounding the frame is clipped to, in points
+    pub corner_radius: f32,
+}
+
+/// Draws preview frames with our own shader through egui's paint callback
+/// instead of `Painter::image`. Per-tile effects (crop, filters, letterboxing)
+/// can then happen on the GPU rather than by rewriting frames on the CPU.
+pub struct TileRenderer {
+    /// Frames without an effect
+    program: glow::Program,
+    /// One program per effect. An effect whose shader didn't build is left
+    /// out and its previews draw plain.
+    effect_programs: HashMap<EffectKind, glow::Program>,
+    vertex_array: glow::VertexArray,
+}
+
+impl TileRenderer {
+    /// Compile the tile shaders. Fails on GL contexts too old for
+    /// `gl_VertexID` (GLSL 1.40 / ES 3.00); callers then use `Painter::image`.
+    pub fn new(gl: &glow::Context) -> Result<Self, String> {
+        let shader_version = egui_glow::ShaderVersion::get(gl);
+        if !shader_version.is_new_shader_interface() {
+            return Err(format!("unsupported shader version {:?}", shader_version));
+        }
+
+        let program = compile_program(gl, shader_version, &Passthrough)?;
+        let mut effect_programs = HashMap::new();
+        for kind in EffectKind::ALL {
+            match compile_program(gl, shader_version, kind.effect()) {
+                Ok(effect_program) => {
+                    effect_programs.insert(kind, effect_program);
+                }
+                Err(error) => log::warn!("{} effect unavailable: {}", kind.label(), error),
+            }
+        }
+        let vertex_array = unsafe { gl.create_vertex_array()? };
+        Ok(Self { program, effect_programs, vertex_array })
+    }
+
+    /// Free the GL objects (call from `App::on_exit`).
+    pub fn destroy(&self, gl: &glow::Context) {
+        unsafe {
+            gl.delete_program(self.program);
+            for program in self.effect_programs.values() {
+                gl.delete_program(*program);
+            }
+            gl.delete_vertex_array(self.vertex_array);
+        }
+    }
+
+    /// Queue a textured quad, the callback equivalent of
+    /// `painter.image(texture, rect, uv, tint)`, with the frame rotated,
+    /// filtered, keyed and corner-clipped as `style` says.
+    pub fn paint(
+        self: &Arc<Self>,
+        painter: &egui::Painter,
+        texture: TextureId,
+        rect: Rect,
+        uv: Rect,
+        tint: Color32,
+        style: FrameStyle,
+    ) {
+        let renderer = self.clone();
+        let program = style
+            .effect
+            .and_then(|kind| self.effect_programs.get(&kind).copied())
+            .unwrap_or(self.program);
+        let tint = tint.to_normalized_gamma_f32();
+        let key = match style.chroma_key {
+            Some(ChromaKey { color: [r, g, b], tolerance }) => {
+                [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, tolerance.max(0.0)]
+            }
+            None => [0.0, 0.0, 0.0, -1.0],
+        };
+        let callback = egui_glow::CallbackFn::new(move |info, painter| {
+            let Some(texture) = painter.texture(texture) else { return; };
+            let gl = painter.gl();
+            unsafe {
+                gl.use_program(Some(program));
+                gl.bind_vertex_array(Some(renderer.vertex_array));
+                gl.uniform_4_f32(
+                    gl.get_uniform_location(program, "u_uv").as_ref(),
+                    uv.min.x,
+                    uv.min.y,
+                    uv.max.x,
+                    uv.max.y,
+                );
+                gl.uniform_4_f32(
+                    gl.get_uniform_location(program, "u_tint").as_ref(),
+                    tint[0],
+                    tint[1],
+                    tint[2],
+                    tint[3],
+                );
+                gl.uniform_4_f32(
+                    gl.get_uniform_location(program, "u_key").as_ref(),
+                    key[0],
+                    key[1],
+                    key[2],
+                    key[3],
+                );
+                let size = rect.size() * info.pixels_per_point;
+                let radius = (style.corner_radius * info.pixels_per_point).min(size.min_elem() / 2.0);
+                gl.uniform_3_f32(gl.get_uniform_location(program, "u_shape").as_ref(), size.x, size.y, radius);
+                gl.uniform_1_i32(
+                    gl.get_uniform_location(program, "u_quarter_turns").as_ref(),
+                    i32::from(style.quarter_turns % 4),
+                );
+                gl.uniform_1_i32(gl.get_uniform_location(program, "u_sampler").as_ref(), 0);
+                gl.active_texture(glow::TEXTURE0);
+                gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+                gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
+            }
+        });
+
+        painter.add(egui::PaintCallback {
+            rect,
+            callback: Arc::new(callback),
+        });
+    }
+}
+
+/// Link the tile vertex shader with the fragment shader for one effect
+fn compile_program(
+    gl: &glow::Context,
+    shader_version: egui_glow::ShaderVersion,
+    effect: &dyn Effect,
+) -> Result<glow::Program, String> {
+    let fragment = format!("{}\n{}\n{}", FRAGMENT_PRELUDE, effect.glsl(), FRAGMENT_MAIN);
+    unsafe {
+        let program = gl.create_program()?;
+        let mut shaders = Vec::new();
+        for (kind, source) in [
+            (glow::VERTEX_SHADER, VERTEX_SHADER),
+            (glow::FRAGMENT_SHADER, fragment.as_str()),
+        ] {
+            let shader = gl.create_shader(kind)?;
+            gl.shader_source(shader, &format!("{}\n{}", shader_version.version_declaration(), source));
+            gl.compile_shader(shader);
+            if !gl.get_shader_compile_status(shader) {
+                let log = gl.get_shader_info_log(shader);
+                gl.delete_shader(shader);
+                gl.delete_program(program);
+                return Err(log);
+            }
+            gl.attach_shader(program, shader);
+            shaders.push(shader);
+        }
+
+        gl.link_program(program);
+        let linked = gl.get_program_link_status(program);
+        for shader in shaders {
+            gl.detach_shader(program, shader);
+            gl.delete_shader(shader);
+        }
+        if !linked {
+            let log = gl.get_program_info_log(program);
+            gl.delete_program(program);
+            return Err(log);
+        }
+
+        Ok(program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rotated_corner, sub_uv_of};
+    use eframe::egui::{Pos2, Rect};
+
+    #[test]
+    fn quarter_turns_rotate_clockwise() {
+        let top_left = Pos2::new(0.0, 0.0);
+        // Turned once, the frame's bottom-left corner ends up top-left
+        assert_eq!(rotated_corner(top_left, 1), Pos2::new(0.0, 1.0));
+        assert_eq!(rotated_corner(top_left, 2), Pos2::new(1.0, 1.0));
+        assert_eq!(rotated_corner(top_left, 3), Pos2::new(1.0, 0.0));
+        assert_eq!(rotated_corner(top_left, 4), top_left);
+    }
+
+    #[test]
+    fn sub_uvs_follow_rotation_and_flips() {
+        let drawn = Rect::from_min_max(Pos2::new(100.0, 100.0), Pos2::new(300.0, 200.0));
+        let left_half = Rect::from_min_max(drawn.min, Pos2::new(200.0, 200.0));
+        let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+        assert_eq!(sub_uv_of(drawn, uv, 0, left_half), Rect::from_min_max(Pos2::ZERO, Pos2::new(0.5, 1.0)));
+        // Turned once, the tile's left half shows the frame's bottom half
+        assert_eq!(sub_uv_of(drawn, uv, 1, left_half), Rect::from_min_max(Pos2::new(0.0, 0.5), Pos2::new(1.0, 1.0)));
+        // Mirrored, it shows the frame's right half, still mirrored
+        let mirrored = Rect::from_min_max(Pos2::new(1.0, 0.0), Pos2::new(0.0, 1.0));
+        assert_eq!(sub_uv_of(drawn, mirrored, 0, left_half), Rect::from_min_max(Pos2::new(1.0, 0.0), Pos2::new(0.5, 1.0)));
+    }
+}
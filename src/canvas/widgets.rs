@@ -0,0 +1,354 @@
+use eframe::egui::{self, Color32, CursorIcon, Pos2, Rect, Sense, Stroke, Vec2};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::persistence::{CanvasWidget, ClockZone, WidgetKind};
+use super::state::{CanvasState, TILE_ROUNDING};
+
+const DAY_SECS: i64 = 24 * 60 * 60;
+
+/// Size of a new widget, in canvas units
+pub const DEFAULT_WIDGET_SIZE: (f32, f32) = (260.0, 90.0);
+
+/// Widgets can't be resized smaller than this, in canvas units
+pub const MIN_WIDGET_SIZE: f32 = 60.0;
+
+/// Seconds since the Unix epoch
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// The local time zone's offset from UTC in minutes, worked out from the
+/// local and UTC times of day (rounded to the quarter hour every real zone
+/// sits on).
+pub fn local_offset_minutes(now: u64) -> i32 {
+    let local = crate::schedule::local_seconds_of_day() as i64;
+    let utc = now as i64 % DAY_SECS;
+    let mut diff = (local - utc).rem_euclid(DAY_SECS);
+    // Zones run from UTC-12 to UTC+14
+    if diff > 14 * 3600 {
+        diff -= DAY_SECS;
+    }
+    ((diff as f32 / 900.0).round() * 15.0) as i32
+}
+
+/// "HH:MM:SS" at `now` (Unix seconds) in a zone `offset_minutes` east of UTC
+pub fn clock_text(now: u64, offset_minutes: i32) -> String {
+    let secs = (now as i64 + offset_minutes as i64 * 60).rem_euclid(DAY_SECS);
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// "UTC", "UTC+2" or "UTC-9:30"
+pub fn offset_label(offset_minutes: i32) -> String {
+    if offset_minutes == 0 {
+        return "UTC".to_string();
+    }
+    let sign = if offset_minutes < 0 { '-' } else { '+' };
+    let minutes = offset_minutes.abs();
+    match minutes % 60 {
+        0 => format!("UTC{}{}", sign, minutes / 60),
+        rest => format!("UTC{}{}:{:02}", sign, minutes / 60, rest),
+    }
+}
+
+/// Seconds a countdown has left at `now`; the full duration until started
+pub fn remaining_secs(duration_secs: u32, ends_at: Option<u64>, now: u64) -> u32 {
+    match ends_at {
+        Some(end) => end.saturating_sub(now).min(u32::MAX as u64) as u32,
+        None => duration_secs,
+    }
+}
+
+/// "MM:SS", or "H:MM:SS" from an hour up
+pub fn countdown_text(secs: u32) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// A clock showing local time, placed at `position`
+pub fn new_clock(position: (f32, f32)) -> CanvasWidget {
+    CanvasWidget {
+        kind: WidgetKind::Clock {
+            zones: vec![ClockZone { name: "Local".to_string(), utc_offset_minutes: None }],
+        },
+        position,
+        size: DEFAULT_WIDGET_SIZE,
+    }
+}
+
+/// A stopped five-minute countdown, placed at `position`
+pub fn new_countdown(position: (f32, f32)) -> CanvasWidget {
+    CanvasWidget {
+        kind: WidgetKind::Countdown {
+            title: "Countdown".to_string(),
+            duration_secs: 5 * 60,
+            ends_at: None,
+        },
+        position,
+        size: DEFAULT_WIDGET_SIZE,
+    }
+}
+
+impl CanvasState {
+    /// Clocks and countdowns: drag to move, drag the corner grip to resize,
+    /// double-click (or right-click) to edit
+    pub(super) fn draw_and_interact_widgets(&mut self, ui: &mut egui::Ui, canvas_rect: Rect) {
+        if self.widgets.is_empty() {
+            return;
+        }
+        // The seconds tick over
+        ui.ctx().request_repaint_after(Duration::from_millis(250));
+
+        let painter = ui.painter_at(canvas_rect);
+        let now = unix_now();
+        let local_offset = local_offset_minutes(now);
+        let mut deleted = None;
+
+        for index in 0..self.widgets.len() {
+            let widget = &self.widgets[index];
+            let min = self.canvas_to_screen(Pos2::new(widget.position.0, widget.position.1), canvas_rect);
+            let rect = Rect::from_min_size(min, Vec2::new(widget.size.0, widget.size.1) * self.zoom);
+            if !canvas_rect.intersects(rect) || rect.height() < 8.0 {
+                continue;
+            }
+
+            let response = ui.interact(rect, ui.id().with(("canvas_widget", index)), Sense::click_and_drag());
+            let grip = Rect::from_min_max(rect.max - Vec2::splat(14.0), rect.max);
+            let grip_response = ui.interact(grip, ui.id().with(("canvas_widget_grip", index)), Sense::drag());
+
+            painter.rect_filled(rect, TILE_ROUNDING, Color32::from_rgba_unmultiplied(20, 20, 24, 230));
+            let active = response.hovered() || response.dragged() || grip_response.hovered() || grip_response.dragged();
+            let outline = if active || self.widget_editor == Some(index) {
+                self.theme.accent_alpha(160)
+            } else {
+                Color32::from_white_alpha(24)
+            };
+            painter.rect_stroke(rect, TILE_ROUNDING, Stroke::new(1.0, outline));
+
+            let pad = (rect.height() * 0.08).clamp(2.0, 16.0);
+            let inner = rect.shrink(pad);
+            match &widget.kind {
+                WidgetKind::Clock { zones } => {
+                    let row_height = inner.height() / zones.len().max(1) as f32;
+                    for (row, zone) in zones.iter().enumerate() {
+                        let row_rect = Rect::from_min_size(
+                            inner.min + Vec2::new(0.0, row_height * row as f32),
+                            Vec2::new(inner.width(), row_height),
+                        );
+                        let offset = zone.utc_offset_minutes.unwrap_or(local_offset);
+                        painter.text(
+                            row_rect.left_center(),
+                            egui::Align2::LEFT_CENTER,
+                            &zone.name,
+                            egui::FontId::proportional(row_height * 0.3),
+                            Color32::from_gray(150),
+                        );
+                        painter.text(
+                            row_rect.right_center(),
+                            egui::Align2::RIGHT_CENTER,
+                            clock_text(now, offset),
+                            egui::FontId::monospace(row_height * 0.6),
+                            Color32::from_gray(235),
+                        );
+                    }
+                }
+                WidgetKind::Countdown { title, duration_secs, ends_at } => {
+                    let remaining = remaining_secs(*duration_secs, *ends_at, now);
+                    let color = match (ends_at, remaining) {
+                        (Some(_), 0) => Color32::from_rgb(255, 90, 90),
+                        (Some(_), _) => Color32::from_gray(235),
+                        (None, _) => Color32::from_gray(150),
+                    };
+                    painter.text(
+                        inner.left_top(),
+                        egui::Align2::LEFT_TOP,
+                        title,
+                        egui::FontId::proportional(inner.height() * 0.22),
+                        Color32::from_gray(150),
+                    );
+                    painter.text(
+                        inner.center_bottom(),
+                        egui::Align2::CENTER_BOTTOM,
+                        countdown_text(remaining),
+                        egui::FontId::monospace(inner.height() * 0.6),
+                        color,
+                    );
+                }
+            }
+
+            if active {
+                for step in 1..=3 {
+                    let inset = step as f32 * 4.0;
+                    painter.line_segment(
+                        [grip.right_top() + Vec2::new(-2.0, inset - 2.0), grip.left_bottom() + Vec2::new(inset - 2.0, -2.0)],
+                        Stroke::new(1.0, Color32::from_white_alpha(90)),
+                    );
+                }
+            }
+            if grip_response.hovered() || grip_response.dragged() {
+                ui.ctx().set_cursor_icon(CursorIcon::ResizeNwSe);
+            }
+
+            let widget = &mut self.widgets[index];
+            if grip_response.dragged() {
+                let delta = grip_response.drag_delta() / self.zoom;
+                widget.size = (
+                    (widget.size.0 + delta.x).max(MIN_WIDGET_SIZE),
+                    (widget.size.1 + delta.y).max(MIN_WIDGET_SIZE / 2.0),
+                );
+            } else if response.dragged() {
+                let delta = response.drag_delta() / self.zoom;
+                widget.position = (widget.position.0 + delta.x, widget.position.1 + delta.y);
+            }
+            if response.double_clicked() {
+                self.widget_editor = Some(index);
+            }
+            response.context_menu(|ui| {
+                if let WidgetKind::Countdown { ends_at, duration_secs, .. } = &mut self.widgets[index].kind {
+                    if ends_at.is_none() {
+                        if ui.button("Start").clicked() {
+                            *ends_at = Some(now + *duration_secs as u64);
+                            ui.close_menu();
+                        }
+                    } else if ui.button("Reset").clicked() {
+                        *ends_at = None;
+                        ui.close_menu();
+                    }
+                }
+                if ui.button("Edit Widget...").clicked() {
+                    self.widget_editor = Some(index);
+                    ui.close_menu();
+                }
+                if ui.button("Delete Widget").clicked() {
+                    deleted = Some(index);
+                    ui.close_menu();
+                }
+            });
+        }
+
+        if let Some(index) = deleted {
+            self.widgets.remove(index);
+            self.widget_editor = match self.widget_editor {
+                Some(open) if open == index => None,
+                Some(open) if open > index => Some(open - 1),
+                open => open,
+            };
+        }
+    }
+
+    /// Editor for a clock's zones or a countdown's title and length
+    pub(super) fn widget_editor_ui(&mut self, ctx: &egui::Context) {
+        let Some(index) = self.widget_editor else { return; };
+        let Some(widget) = self.widgets.get_mut(index) else {
+            self.widget_editor = None;
+            return;
+        };
+        let mut done = false;
+
+        let title = match widget.kind {
+            WidgetKind::Clock { .. } => "Clock",
+            WidgetKind::Countdown { .. } => "Countdown",
+        };
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style()).fill(Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                match &mut widget.kind {
+                    WidgetKind::Clock { zones } => {
+                        let mut removed = None;
+                        egui::Grid::new("clock_zones").num_columns(4).spacing([8.0, 6.0]).show(ui, |ui| {
+                            for (row, zone) in zones.iter_mut().enumerate() {
+                                ui.add_sized([120.0, 20.0], egui::TextEdit::singleline(&mut zone.name));
+                                let mut local = zone.utc_offset_minutes.is_none();
+                                if ui.checkbox(&mut local, "Local").changed() {
+                                    zone.utc_offset_minutes = if local { None } else { Some(0) };
+                                }
+                                match &mut zone.utc_offset_minutes {
+                                    Some(minutes) => {
+                                        ui.add(
+                                            egui::DragValue::new(minutes)
+                                                .range(-12 * 60..=14 * 60)
+                                                .speed(15.0)
+                                                .custom_formatter(|minutes, _| offset_label(minutes as i32)),
+                                        );
+                                    }
+                                    None => {
+                                        ui.label("");
+                                    }
+                                }
+                                if ui.add_enabled(row > 0, egui::Button::new("Remove")).clicked() {
+                                    removed = Some(row);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                        if let Some(row) = removed {
+                            zones.remove(row);
+                        }
+                        if ui.button("Add Zone").clicked() {
+                            zones.push(ClockZone { name: "UTC".to_string(), utc_offset_minutes: Some(0) });
+                        }
+                    }
+                    WidgetKind::Countdown { title, duration_secs, ends_at } => {
+                        ui.add_sized([240.0, 20.0], egui::TextEdit::singleline(title).hint_text("e.g. Maintenance"));
+                        let mut minutes = *duration_secs / 60;
+                        let mut seconds = *duration_secs % 60;
+                        ui.horizontal(|ui| {
+                            ui.label("Length");
+                            ui.add(egui::DragValue::new(&mut minutes).range(0..=24 * 60).suffix(" min"));
+                            ui.add(egui::DragValue::new(&mut seconds).range(0..=59).suffix(" s"));
+                        });
+                        *duration_secs = (minutes * 60 + seconds).max(1);
+                        ui.horizontal(|ui| {
+                            if ends_at.is_none() {
+                                if ui.button("Start").clicked() {
+                                    *ends_at = Some(unix_now() + *duration_secs as u64);
+                                }
+                            } else if ui.button("Reset").clicked() {
+                                *ends_at = None;
+                            }
+                        });
+                    }
+                }
+                ui.add_space(6.0);
+                if ui.button("Done").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    done = true;
+                }
+            });
+
+        if done {
+            self.widget_editor = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clock_text, countdown_text, offset_label, remaining_secs};
+
+    #[test]
+    fn clocks_wrap_around_midnight() {
+        // 2024-01-01 23:30:05 UTC
+        let now = 1_704_151_805;
+        assert_eq!(clock_text(now, 0), "23:30:05");
+        assert_eq!(clock_text(now, 60), "00:30:05");
+        assert_eq!(clock_text(now, -9 * 60 - 30), "14:00:05");
+        assert_eq!(offset_label(-9 * 60 - 30), "UTC-9:30");
+        assert_eq!(offset_label(2 * 60), "UTC+2");
+    }
+
+    #[test]
+    fn countdowns_stop_at_zero() {
+        assert_eq!(remaining_secs(300, None, 1000), 300);
+        assert_eq!(remaining_secs(300, Some(1090), 1000), 90);
+        assert_eq!(remaining_secs(300, Some(1090), 2000), 0);
+        assert_eq!(countdown_text(90), "01:30");
+        assert_eq!(countdown_text(3725), "1:02:05");
+    }
+}
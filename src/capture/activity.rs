@@ -0,0 +1,123 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// The luma grid is this many cells on each side.
+const GRID: usize = 8;
+
+/// A cell whose average brightness moves by at least this much (0-255)
+/// counts as changed. Averaging over a whole cell keeps cursor blinks and
+/// compression flicker well below it.
+const CHANGE_THRESHOLD: f32 = 2.0;
+
+/// Changes are counted over this trailing window.
+const ACTIVITY_WINDOW: Duration = Duration::from_secs(60);
+
+/// Coarse brightness map of a frame, compared between frames to tell
+/// content changes from frames that merely arrived.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LumaGrid([f32; GRID * GRID]);
+
+impl LumaGrid {
+    /// Average brightness of each cell of an RGBA8 frame, sampling every
+    /// few pixels (the frame is usually already downscaled to the tile).
+    pub fn of(rgba: &[u8], width: u32, height: u32) -> Self {
+        let (width, height) = (width as usize, height as usize);
+        let mut sums = [0.0f32; GRID * GRID];
+        let mut counts = [0u32; GRID * GRID];
+        if width == 0 || height == 0 || rgba.len() < width * height * 4 {
+            return Self(sums);
+        }
+
+        let step = (width.max(height) / 128).max(1);
+        for y in (0..height).step_by(step) {
+            let row = y * GRID / height * GRID;
+            for x in (0..width).step_by(step) {
+                let i = (y * width + x) * 4;
+                let luma = 0.299 * rgba[i] as f32 + 0.587 * rgba[i + 1] as f32 + 0.114 * rgba[i + 2] as f32;
+                let cell = row + x * GRID / width;
+                sums[cell] += luma;
+                counts[cell] += 1;
+            }
+        }
+        for (sum, count) in sums.iter_mut().zip(counts) {
+            *sum /= count.max(1) as f32;
+        }
+        Self(sums)
+    }
+
+    /// Whether any cell changed enough to count as new content
+    pub fn differs_from(&self, other: &Self) -> bool {
+        self.0.iter().zip(&other.0).any(|(a, b)| (a - b).abs() >= CHANGE_THRESHOLD)
+    }
+}
+
+/// Content changes of one capture over the last minute
+#[derive(Clone, Debug, Default)]
+pub struct ActivityMeter {
+    last: Option<LumaGrid>,
+    changes: VecDeque<Instant>,
+}
+
+impl ActivityMeter {
+    /// Compare a new frame with the previous one
+    pub fn record(&mut self, grid: LumaGrid, now: Instant) {
+        if self.last.is_some_and(|last| grid.differs_from(&last)) {
+            self.changes.push_back(now);
+        }
+        self.last = Some(grid);
+        while self.changes.front().is_some_and(|&t| now.duration_since(t) > ACTIVITY_WINDOW) {
+            self.changes.pop_front();
+        }
+    }
+
+    /// Content changes in the last minute
+    pub fn changes_per_minute(&self, now: Instant) -> usize {
+        self.changes
+            .iter()
+            .filter(|&&t| now.saturating_duration_since(t) <= ACTIVITY_WINDOW)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ActivityMeter, LumaGrid};
+    use std::time::{Duration, Instant};
+
+    fn frame(width: u32, height: u32, paint: impl Fn(u32, u32) -> u8) -> Vec<u8> {
+        let mut data = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                let v = paint(x, y);
+                data.extend_from_slice(&[v, v, v, 255]);
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn small_flicker_is_not_a_change() {
+        let base = LumaGrid::of(&frame(256, 256, |_, _| 40), 256, 256);
+        // A blinking text cursor in one 32x32 cell
+        let cursor = frame(256, 256, |x, y| if (4..6).contains(&x) && (4..8).contains(&y) { 255 } else { 40 });
+        assert!(!LumaGrid::of(&cursor, 256, 256).differs_from(&base));
+        // A whole cell redrawn
+        let redrawn = frame(256, 256, |x, y| if x < 32 && y < 32 { 200 } else { 40 });
+        assert!(LumaGrid::of(&redrawn, 256, 256).differs_from(&base));
+    }
+
+    #[test]
+    fn counts_changes_within_the_last_minute() {
+        let still = LumaGrid::of(&frame(8, 8, |_, _| 0), 8, 8);
+        let lit = LumaGrid::of(&frame(8, 8, |_, _| 255), 8, 8);
+        let start = Instant::now();
+        let mut meter = ActivityMeter::default();
+
+        meter.record(still, start);
+        meter.record(still, start + Duration::from_secs(1));
+        meter.record(lit, start + Duration::from_secs(2));
+        meter.record(still, start + Duration::from_secs(3));
+        assert_eq!(meter.changes_per_minute(start + Duration::from_secs(3)), 2);
+        assert_eq!(meter.changes_per_minute(start + Duration::from_millis(62_500)), 1);
+    }
+}
@@ -1,18 +1,246 @@
 use crate::privacy;
-use crate::preview::{PreviewManager, PreviewId};
+use crate::preview::{PreviewManager, PreviewId, CaptureOptions, SourceStats};
+use super::activity::{ActivityMeter, LumaGrid};
+use super::pipeline::{FrameControls, FramePipeline, Job};
+use super::host::{CaptureHost, CaptureStart};
+use super::NativeCapture;
 use eframe::egui;
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use parking_lot::RwLock;
 use std::sync::mpsc::{self, Sender, Receiver};
+use std::time::{Duration, Instant};
 
-/// Frame data sent from capture threads
+/// How many queued sessions may be initializing Graphics Capture at once.
+/// Starting dozens simultaneously stalls the first seconds after a load.
+const MAX_CONCURRENT_STARTS: usize = 4;
+
+/// A session that hasn't produced a frame after this long stops counting
+/// against the start limit (the window may simply not be redrawing).
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// A running (unpaused) session with no new frame for this long is flagged
+/// as stalled once its source stops responding. Graphics Capture only
+/// delivers frames when the window redraws, so silence alone means nothing:
+/// a static window that still answers is left alone.
+const STALL_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long `CaptureStats::received_fps` is measured over. A session that
+/// gets no frame for this long reads 0.
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Why a window of an elevated process shows nothing
+pub(super) const ELEVATED_MESSAGE: &str = "This window runs as administrator";
+
+/// Display-ready frame from the conversion pipeline
 pub struct CapturedFrame {
     pub preview_id: PreviewId,
+    /// Session that captured it; a restarted preview briefly has two
+    pub generation: u64,
+    /// Size of `data` (may be downscaled to the on-screen tile)
     pub width: u32,
     pub height: u32,
-    pub data: Vec<u8>,
+    /// Size the window was captured at
+    pub source_size: (u32, u32),
+    /// Coarse brightness map, for telling content changes apart
+    pub luma: LumaGrid,
+    /// RGBA pixels, shared with the duplicates drawing the same session
+    pub data: Arc<Vec<u8>>,
+    /// When the capture thread copied the frame (for latency stats)
+    pub captured_at: Instant,
+}
+
+/// A capture thread giving up, sent back so the preview can show why
+pub struct CaptureError {
+    pub preview_id: PreviewId,
+    /// Session that failed (errors from a replaced session are dropped)
+    pub generation: u64,
+    /// Short, user-facing reason
+    pub message: String,
+}
+
+/// What a capture thread (or GDI worker) shares with its session
+#[derive(Clone)]
+pub(super) struct SessionLink {
+    pub preview_id: PreviewId,
+    pub target_fps: Arc<AtomicU32>,
+    pub active: Arc<RwLock<bool>>,
+    pub paused: Arc<RwLock<bool>>,
+    pub controls: Arc<FrameControls>,
+    pub fallback_active: Arc<AtomicBool>,
+    /// Set once nothing is capturing for the session any more
+    pub finished: Arc<AtomicBool>,
+    pub frames: Sender<Job>,
+    pub errors: Sender<CaptureError>,
+}
+
+impl SessionLink {
+    pub fn is_active(&self) -> bool {
+        *self.active.read()
+    }
+
+    /// Report why capture ended, unless the session was stopped meanwhile
+    pub fn report(&self, message: String) {
+        if self.is_active() {
+            let _ = self.errors.send(CaptureError {
+                preview_id: self.preview_id,
+                generation: self.controls.generation,
+                message,
+            });
+        }
+    }
+}
+
+/// Rolling statistics for one capture session, updated on the UI thread
+/// as frames are drained from the channel.
+#[derive(Clone, Debug, Default)]
+pub struct CaptureStats {
+    /// Frames actually received per second (measured, not the target)
+    pub received_fps: f32,
+    /// Total frames received since the session started
+    pub frames_received: u64,
+    /// Frames replaced by a newer one before they were ever displayed
+    pub frames_dropped: u64,
+    /// Smoothed capture-to-UI latency
+    pub latency: Duration,
+    /// Size of the most recent frame in pixels
+    pub frame_size: Option<(u32, u32)>,
+    /// When the most recent frame was received
+    pub last_frame_at: Option<Instant>,
+    /// Set while the session is stalled: time of the last good frame (or of
+    /// the start/resume if none arrived since)
+    pub stalled_since: Option<Instant>,
+    /// Start of the current FPS measurement window
+    window_start: Option<Instant>,
+    /// Frames received in the current FPS measurement window
+    window_frames: u32,
+    /// Frames whose content actually changed, for the activity heat map
+    activity: ActivityMeter,
+}
+
+impl CaptureStats {
+    /// Record a received frame.
+    fn record_frame(&mut self, width: u32, height: u32, latency: Duration) {
+        self.frames_received += 1;
+        self.frame_size = Some((width, height));
+        self.stalled_since = None;
+
+        // Exponential moving average keeps the readout stable
+        self.latency = if self.frames_received == 1 {
+            latency
+        } else {
+            self.latency.mul_f32(0.9) + latency.mul_f32(0.1)
+        };
+
+        let now = Instant::now();
+        self.last_frame_at = Some(now);
+        let start = *self.window_start.get_or_insert(now);
+        self.window_frames += 1;
+        let elapsed = now.duration_since(start).as_secs_f32();
+        if elapsed >= FPS_WINDOW.as_secs_f32() {
+            self.received_fps = self.window_frames as f32 / elapsed;
+            self.window_start = Some(now);
+            self.window_frames = 0;
+        }
+    }
+
+    /// Frames in the last minute whose content visibly changed (frames
+    /// that arrive with the same picture don't count).
+    pub fn changes_per_minute(&self) -> usize {
+        self.activity.changes_per_minute(Instant::now())
+    }
+
+    /// Drop the measured rate to 0 once no frame has arrived for a whole
+    /// `FPS_WINDOW`; otherwise a source that went quiet keeps showing the
+    /// rate it last had. The next frame starts a new window.
+    fn decay_fps(&mut self, now: Instant) {
+        if self.last_frame_at.is_some_and(|last| now.saturating_duration_since(last) >= FPS_WINDOW) {
+            self.received_fps = 0.0;
+            self.window_start = None;
+            self.window_frames = 0;
+        }
+    }
+
+    /// Record frames that were superseded before reaching the screen.
+    fn record_dropped(&mut self, count: u64) {
+        self.frames_dropped += count;
+    }
+
+    /// Flag the session stalled if its source isn't `live` and nothing
+    /// arrived within `STALL_TIMEOUT` of the later of the last frame and
+    /// `watch_from`. A source that comes back to life clears the flag.
+    /// Returns true when the session has just become stalled.
+    fn check_stalled(&mut self, watch_from: Instant, now: Instant, live: bool) -> bool {
+        if live {
+            self.stalled_since = None;
+            return false;
+        }
+        if self.stalled_since.is_some() {
+            return false;
+        }
+        let last = self.last_frame_at.map_or(watch_from, |t| t.max(watch_from));
+        if now.saturating_duration_since(last) >= STALL_TIMEOUT {
+            self.stalled_since = Some(last);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// True while the source is unresponsive and no frame has arrived for
+    /// `STALL_TIMEOUT`
+    pub fn is_stalled(&self) -> bool {
+        self.stalled_since.is_some()
+    }
+}
+
+/// What a session's capture thread is doing, for the health dashboard
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionState {
+    /// Waiting in the load queue (or offscreen, deferred)
+    Queued,
+    /// Started, no frame yet
+    Connecting,
+    Running,
+    Paused,
+    /// Running but no frame for a while
+    Stalled,
+    /// The thread reported an error
+    Failed,
+    /// The thread exited without reporting anything
+    Finished,
+}
+
+impl SessionState {
+    pub fn label(self) -> &'static str {
+        match self {
+            SessionState::Queued => "Queued",
+            SessionState::Connecting => "Connecting",
+            SessionState::Running => "Running",
+            SessionState::Paused => "Paused",
+            SessionState::Stalled => "Stalled",
+            SessionState::Failed => "Failed",
+            SessionState::Finished => "Exited",
+        }
+    }
+}
+
+/// Snapshot of one session (or queued capture) for the health dashboard
+#[derive(Clone, Debug)]
+pub struct SessionHealth {
+    pub preview_id: PreviewId,
+    pub window_title: String,
+    /// FPS the preview asked for
+    pub requested_fps: u32,
+    /// FPS actually applied (after the power saver cap)
+    pub target_fps: u32,
+    /// Measured frames per second
+    pub received_fps: f32,
+    pub state: SessionState,
+    /// Running on the PrintWindow fallback
+    pub fallback: bool,
+    pub error: Option<String>,
 }
 
 /// Manages all window capture sessions
@@ -20,11 +248,75 @@ pub struct CaptureCoordinator {
     /// Active capture sessions by preview ID
     sessions: HashMap<PreviewId, CaptureSession>,
 
+    /// Sessions being replaced by a restart, kept capturing (and drawn)
+    /// until their successor delivers its first frame
+    outgoing: HashMap<PreviewId, CaptureSession>,
+
+    /// Generation handed to the next session
+    next_generation: u64,
+
     /// Channel receiver for captured frames
     frame_receiver: Receiver<CapturedFrame>,
 
-    /// Channel sender (cloned to capture threads)
-    frame_sender: Sender<CapturedFrame>,
+    /// Converts raw frames from the capture threads on worker threads
+    pipeline: FramePipeline,
+
+    /// The platform's capture backends
+    host: NativeCapture,
+
+    /// Downscale frames in linear light
+    linear_light: bool,
+
+    /// Channel receiver for errors reported by capture threads
+    error_receiver: Receiver<CaptureError>,
+
+    /// Error channel sender (cloned to capture threads)
+    error_sender: Sender<CaptureError>,
+
+    /// Captures waiting to be started (layout load), oldest first
+    queued: VecDeque<QueuedCapture>,
+
+    /// Previews in the current load batch, for the "N of M" indicator
+    load_batch: Vec<PreviewId>,
+
+    /// Upper bound applied to every session's FPS (power saver), if any
+    fps_cap: Option<u32>,
+
+    /// All sessions paused regardless of their own state (app minimized)
+    suspended: bool,
+
+    /// Previews that keep capturing while suspended (popped out, so
+    /// their windows are still on screen)
+    awake: Vec<PreviewId>,
+
+    /// Duplicated previews drawing another preview's session instead of
+    /// capturing the window again, by duplicate
+    mirrors: HashMap<PreviewId, Mirror>,
+}
+
+/// A preview shown from another preview's capture session (a duplicate)
+struct Mirror {
+    /// Preview whose session captures the window
+    source: PreviewId,
+    /// FPS the duplicate asked for; the session runs at the highest of its
+    /// previews' rates
+    fps: u32,
+    /// Options the duplicate was made with; it gets a session of its own
+    /// once they no longer match the source's
+    options: CaptureOptions,
+    /// Pause state asked for the duplicate (viewport culling); the session
+    /// only pauses once all of its previews are paused
+    pause_requested: bool,
+}
+
+/// A capture requested through `queue_capture`, not yet started
+struct QueuedCapture {
+    preview_id: PreviewId,
+    hwnd: isize,
+    window_title: String,
+    target_fps: u32,
+    options: CaptureOptions,
+    paused: bool,
 }
 
 /// A single capture session
@@ -33,15 +325,26 @@ struct CaptureSession {
     #[allow(dead_code)]
     preview_id: PreviewId,
 
-    /// Window handle being captured (kept for reference)
-    #[allow(dead_code)]
+    /// Window handle being captured
     hwnd: isize,
 
-    /// Window title for matching (kept for reference)
-    #[allow(dead_code)]
+    /// Window title (for logging when the session is restarted)
     window_title: String,
 
-    /// Target FPS, shared with the capture thread so changes apply live
+    /// Capture method and format requested for this session
+    options: CaptureOptions,
+
+    /// Set by the capture thread when Graphics Capture failed and it fell
+    /// back to PrintWindow
+    fallback_active: Arc<AtomicBool>,
+
+    /// FPS the preview asked for, before any cap
+    requested_fps: u32,
+
+    /// The preview's share of its FPS group's budget, if it's in one
+    fps_limit: Option<u32>,
+
+    /// Effective FPS, shared with the capture thread so changes apply live
     /// without restarting the capture session.
     target_fps: Arc<AtomicU32>,
 
@@ -51,79 +354,574 @@ struct CaptureSession {
     /// Is capture paused? (shared with capture thread)
     paused: Arc<RwLock<bool>>,
 
-    /// Handle to the capture task
-    #[allow(dead_code)]
-    handle: Option<std::thread::JoinHandle<()>>,
+    /// Sharpening, on-screen size and color handling for the pipeline
+    /// (shared with capture thread)
+    controls: Arc<FrameControls>,
+
+    /// Pause state asked for this preview (viewport culling); the effective
+    /// `paused` is also set while the coordinator is suspended
+    pause_requested: bool,
+
+    /// Received FPS, drops, latency and frame size
+    stats: CaptureStats,
+
+    /// Why the capture thread gave up, until a retry or the next frame
+    error: Option<String>,
+
+    /// The window belongs to an elevated process while Pluriview isn't,
+    /// so a capture that fails or never delivers is put down to that
+    elevated: bool,
+
+    /// When the session was handed to its worker
+    started_at: Instant,
+
+    /// Start of the current stall watch (spawn or last resume); paused time
+    /// never counts towards a stall
+    watch_from: Instant,
+
+    /// Set once the backend stopped capturing (its worker closed or
+    /// dropped the session)
+    finished: Arc<AtomicBool>,
+
+    /// The host's handle for telling it the session was stopped, if it
+    /// asked for one
+    token: Option<usize>,
 }
 
 impl CaptureCoordinator {
     pub fn new() -> Self {
         let (sender, receiver) = mpsc::channel();
+        let (error_sender, error_receiver) = mpsc::channel();
 
         Self {
             sessions: HashMap::new(),
+            outgoing: HashMap::new(),
+            next_generation: 1,
             frame_receiver: receiver,
-            frame_sender: sender,
+            pipeline: FramePipeline::new(sender),
+            host: NativeCapture::new(),
+            linear_light: false,
+            error_receiver,
+            error_sender,
+            queued: VecDeque::new(),
+            load_batch: Vec::new(),
+            fps_cap: None,
+            suspended: false,
+            awake: Vec::new(),
+            mirrors: HashMap::new(),
         }
     }
 
     /// Start capturing a window for a preview
-    pub fn start_capture(&mut self, preview_id: PreviewId, hwnd: isize, window_title: String, target_fps: u32) {
+    pub fn start_capture(
+        &mut self,
+        preview_id: PreviewId,
+        hwnd: isize,
+        window_title: String,
+        target_fps: u32,
+        options: CaptureOptions,
+    ) {
         // Stop existing capture for this preview if any
         self.stop_capture(preview_id);
+        self.spawn_session(preview_id, hwnd, window_title, target_fps, options, false);
+    }
 
+    /// Queue a capture to be started progressively by `start_queued`, at most
+    /// `MAX_CONCURRENT_STARTS` connecting at a time. Used on layout load.
+    ///
+    /// Queued captures start paused and are only started once resumed, which
+    /// viewport culling does when the preview is on screen; offscreen
+    /// previews of a huge layout don't start until scrolled into view.
+    pub fn queue_capture(
+        &mut self,
+        preview_id: PreviewId,
+        hwnd: isize,
+        window_title: String,
+        target_fps: u32,
+        options: CaptureOptions,
+    ) {
+        self.stop_capture(preview_id);
+        self.queued.push_back(QueuedCapture {
+            preview_id,
+            hwnd,
+            window_title,
+            target_fps,
+            options,
+            paused: true,
+        });
+        self.load_batch.push(preview_id);
+    }
+
+    /// Start resumed queued captures while fewer than `MAX_CONCURRENT_STARTS`
+    /// sessions are still waiting for their first frame. Call once per frame.
+    pub fn start_queued(&mut self) {
+        if self.queued.is_empty() {
+            return;
+        }
+        let connecting = self.sessions.values().filter(|s| s.is_connecting()).count();
+        for _ in connecting..MAX_CONCURRENT_STARTS {
+            let Some(index) = self.queued.iter().position(|q| !q.paused) else { break; };
+            let Some(next) = self.queued.remove(index) else { break; };
+            self.spawn_session(
+                next.preview_id,
+                next.hwnd,
+                next.window_title,
+                next.target_fps,
+                next.options,
+                next.paused,
+            );
+        }
+    }
+
+    /// Progress of the current layout load as (connected, total), or None
+    /// while nothing is connecting. Previews still deferred offscreen don't
+    /// count towards the total until they're resumed.
+    pub fn load_progress(&mut self) -> Option<(usize, usize)> {
+        if self.load_batch.is_empty() {
+            return None;
+        }
+        let sessions = &self.sessions;
+        let queued = &self.queued;
+        self.load_batch.retain(|id| sessions.contains_key(id) || queued.iter().any(|q| q.preview_id == *id));
+        if self.queued.is_empty() && !self.sessions.values().any(|s| s.is_connecting()) {
+            self.load_batch.clear();
+            return None;
+        }
+
+        let waiting = self.queued.iter().filter(|q| !q.paused).count();
+        let connecting = self.load_batch.iter()
+            .filter(|id| self.sessions.get(id).is_some_and(|s| s.is_connecting()))
+            .count();
+        if waiting + connecting == 0 {
+            return None;
+        }
+
+        let connected = self.load_batch.iter()
+            .filter(|id| self.sessions.get(id).is_some_and(|s| s.stats.frames_received > 0))
+            .count();
+        let started = self.load_batch.iter().filter(|id| self.sessions.contains_key(id)).count();
+        Some((connected, started + waiting))
+    }
+
+    fn spawn_session(
+        &mut self,
+        preview_id: PreviewId,
+        hwnd: isize,
+        window_title: String,
+        target_fps: u32,
+        options: CaptureOptions,
+        start_paused: bool,
+    ) {
         let active = Arc::new(RwLock::new(true));
-        let paused = Arc::new(RwLock::new(false));
-        let fps = Arc::new(AtomicU32::new(target_fps.max(1)));
-        let active_clone = active.clone();
-        let paused_clone = paused.clone();
-        let fps_clone = fps.clone();
-        let sender = self.frame_sender.clone();
-        let title_clone = window_title.clone();
-
-        // Start capture in a new thread
-        let handle = std::thread::spawn(move || {
-            capture_window_loop(preview_id, hwnd, title_clone, fps_clone, active_clone, paused_clone, sender);
+        let paused = Arc::new(RwLock::new(start_paused || self.is_suspended(preview_id)));
+        let fps = Arc::new(AtomicU32::new(self.effective_fps(target_fps)));
+        let fallback_active = Arc::new(AtomicBool::new(false));
+        let finished = Arc::new(AtomicBool::new(false));
+        let generation = self.next_generation;
+        self.next_generation += 1;
+        let controls = Arc::new(FrameControls { generation, ..Default::default() });
+        controls.sharpen.store(options.sharpen, Ordering::Relaxed);
+        controls.linear_light.store(self.linear_light, Ordering::Relaxed);
+        let link = SessionLink {
+            preview_id,
+            target_fps: fps.clone(),
+            active: active.clone(),
+            paused: paused.clone(),
+            controls: controls.clone(),
+            fallback_active: fallback_active.clone(),
+            finished: finished.clone(),
+            frames: self.pipeline.sender_for(preview_id),
+            errors: self.error_sender.clone(),
+        };
+        let elevated = !options.display && NativeCapture::blocks_capture(hwnd);
+        let token = self.host.start(CaptureStart {
+            preview_id,
+            hwnd,
+            window_title: window_title.clone(),
+            options,
+            link,
+            elevated,
         });
 
         let session = CaptureSession {
             preview_id,
             hwnd,
             window_title,
+            options,
+            fallback_active,
+            requested_fps: target_fps,
+            fps_limit: None,
             target_fps: fps,
             active,
             paused,
-            handle: Some(handle),
+            controls,
+            pause_requested: start_paused,
+            stats: CaptureStats::default(),
+            error: None,
+            elevated,
+            started_at: Instant::now(),
+            watch_from: Instant::now(),
+            finished,
+            token,
         };
 
         self.sessions.insert(preview_id, session);
+        self.refresh_shared(preview_id);
+    }
+
+    /// Show another preview's capture on `preview_id` as well (a duplicate)
+    /// instead of starting a second session on the same window. While
+    /// shared, frames are delivered at the window's size since the two
+    /// tiles may crop and scale it differently. Returns false if `source`
+    /// has nothing to share.
+    pub fn mirror_capture(&mut self, preview_id: PreviewId, source: PreviewId) -> bool {
+        self.stop_capture(preview_id);
+        let source = self.session_of(source);
+        let (fps, options) = if let Some(session) = self.sessions.get(&source) {
+            (session.requested_fps, session.options)
+        } else if let Some(queued) = self.queued.iter().find(|q| q.preview_id == source) {
+            (queued.target_fps, queued.options)
+        } else {
+            return false;
+        };
+
+        self.mirrors.insert(
+            preview_id,
+            Mirror { source, fps, options, pause_requested: false },
+        );
+        if let Some(session) = self.sessions.get(&source) {
+            if session.controls.set_display_size(None) {
+                self.pipeline.refresh(source, None);
+            }
+        }
+        self.refresh_shared(source);
+        true
+    }
+
+    /// Preview whose session draws `preview_id` (itself unless a duplicate)
+    fn session_of(&self, preview_id: PreviewId) -> PreviewId {
+        self.mirrors.get(&preview_id).map_or(preview_id, |m| m.source)
     }
 
-    /// Stop capturing for a preview
+    /// Duplicates drawing a preview's session
+    fn mirrors_of(&self, source: PreviewId) -> impl Iterator<Item = (PreviewId, &Mirror)> {
+        self.mirrors
+            .iter()
+            .filter(move |(_, mirror)| mirror.source == source)
+            .map(|(&id, mirror)| (id, mirror))
+    }
+
+    /// Apply the FPS and pause state of a session shared with duplicates:
+    /// the highest rate any of its previews asked for (within its group
+    /// budget share and the power saver cap), paused only when all of its
+    /// previews are.
+    fn refresh_shared(&mut self, source: PreviewId) {
+        let fps = self.mirrors_of(source).map(|(_, m)| m.fps).max();
+        let watched = self.mirrors_of(source).any(|(_, m)| !m.pause_requested);
+        let cap = self.fps_cap;
+        let suspended = self.is_suspended(source);
+        if let Some(session) = self.sessions.get_mut(&source) {
+            let requested = fps.map_or(session.requested_fps, |fps| fps.max(session.requested_fps));
+            let limited = session.fps_limit.map_or(requested, |limit| requested.min(limit));
+            let effective = cap.map_or(limited, |cap| limited.min(cap)).max(1);
+            session.target_fps.store(effective, Ordering::Relaxed);
+            let paused = session.pause_requested && !watched;
+            session.set_effective_paused(paused || suspended);
+        } else if let Some(queued) = self.queued.iter_mut().find(|q| q.preview_id == source) {
+            if watched {
+                queued.paused = false;
+            }
+        }
+    }
+
+    /// Give a duplicate a session of its own on the window it was sharing
+    fn detach_mirror(&mut self, preview_id: PreviewId, options: CaptureOptions) {
+        let Some(mirror) = self.mirrors.remove(&preview_id) else { return; };
+        self.refresh_shared(mirror.source);
+        let window = match self.sessions.get(&mirror.source) {
+            Some(session) => Some((session.hwnd, session.window_title.clone())),
+            None => self.queued.iter()
+                .find(|q| q.preview_id == mirror.source)
+                .map(|q| (q.hwnd, q.window_title.clone())),
+        };
+        if let Some((hwnd, window_title)) = window {
+            self.spawn_session(preview_id, hwnd, window_title, mirror.fps, options, mirror.pause_requested);
+        }
+    }
+
+    /// Stop capturing for a preview. Duplicates that were sharing its
+    /// session carry on: the oldest one takes over with a session of its
+    /// own, and the rest share that.
     pub fn stop_capture(&mut self, preview_id: PreviewId) {
+        if let Some(mirror) = self.mirrors.remove(&preview_id) {
+            self.refresh_shared(mirror.source);
+            return;
+        }
+
+        let mut window = None;
+        if let Some(index) = self.queued.iter().position(|q| q.preview_id == preview_id) {
+            if let Some(queued) = self.queued.remove(index) {
+                window = Some((queued.hwnd, queued.window_title, queued.options));
+            }
+        }
+        if let Some(session) = self.outgoing.remove(&preview_id) {
+            session.stop(&self.host);
+        }
         if let Some(session) = self.sessions.remove(&preview_id) {
-            // Signal the capture thread to stop
-            *session.active.write() = false;
+            window = Some((session.hwnd, session.window_title.clone(), session.options));
+            session.stop(&self.host);
+            self.pipeline.forget(preview_id);
+        }
+
+        let Some((hwnd, window_title, options)) = window else { return; };
+        let heir = self.mirrors_of(preview_id).map(|(id, _)| id).min_by_key(|id| id.0);
+        let Some(heir) = heir else { return; };
+        for mirror in self.mirrors.values_mut().filter(|m| m.source == preview_id) {
+            mirror.source = heir;
+        }
+        if let Some(mirror) = self.mirrors.remove(&heir) {
+            self.spawn_session(heir, hwnd, window_title, mirror.fps, options, mirror.pause_requested);
         }
     }
 
     /// Update target FPS for a capture session; applies live on the
     /// capture thread's next frame, no restart needed.
     pub fn set_target_fps(&mut self, preview_id: PreviewId, fps: u32) {
-        if let Some(session) = self.sessions.get_mut(&preview_id) {
-            session.target_fps.store(fps.max(1), Ordering::Relaxed);
+        if let Some(mirror) = self.mirrors.get_mut(&preview_id) {
+            mirror.fps = fps;
+            let source = mirror.source;
+            self.refresh_shared(source);
+        } else if let Some(session) = self.sessions.get_mut(&preview_id) {
+            session.requested_fps = fps;
+            self.refresh_shared(preview_id);
+        } else if let Some(queued) = self.queued.iter_mut().find(|q| q.preview_id == preview_id) {
+            queued.target_fps = fps;
+        }
+    }
+
+    /// Hold a preview below its own FPS (its share of an FPS group's
+    /// budget), or lift the limit with None. Applies live; cheap to call
+    /// every frame.
+    pub fn set_fps_limit(&mut self, preview_id: PreviewId, limit: Option<u32>) {
+        let Some(session) = self.sessions.get_mut(&preview_id) else { return; };
+        if session.fps_limit != limit {
+            session.fps_limit = limit;
+            self.refresh_shared(preview_id);
         }
     }
 
+    /// Cap every session's FPS (power saver), or lift the cap with None.
+    /// Applies live; each session's own target is restored when lifted.
+    pub fn set_fps_cap(&mut self, cap: Option<u32>) {
+        if self.fps_cap == cap {
+            return;
+        }
+        self.fps_cap = cap;
+        let ids: Vec<PreviewId> = self.sessions.keys().copied().collect();
+        for id in ids {
+            self.refresh_shared(id);
+        }
+    }
+
+    /// A requested FPS after applying the current cap
+    fn effective_fps(&self, fps: u32) -> u32 {
+        match self.fps_cap {
+            Some(cap) => fps.min(cap),
+            None => fps,
+        }
+        .max(1)
+    }
+
+    /// Change a preview's capture method or format. A running session is
+    /// restarted (keeping its FPS and pause state) when the change affects
+    /// capture itself; a queued one just starts with the new options.
+    pub fn set_options(&mut self, preview_id: PreviewId, options: CaptureOptions) {
+        if let Some(mirror) = self.mirrors.get_mut(&preview_id) {
+            if mirror.options.needs_restart(&options) {
+                self.detach_mirror(preview_id, options);
+            } else {
+                mirror.options = options;
+            }
+            return;
+        }
+        if let Some(queued) = self.queued.iter_mut().find(|q| q.preview_id == preview_id) {
+            queued.options = options;
+            return;
+        }
+        let Some(session) = self.sessions.get_mut(&preview_id) else { return; };
+        if !session.options.needs_restart(&options) {
+            session.options = options;
+            session.controls.sharpen.store(options.sharpen, Ordering::Relaxed);
+            return;
+        }
+        // Duplicates keep capturing the way they were set up
+        let detached: Vec<(PreviewId, CaptureOptions)> = self
+            .mirrors_of(preview_id)
+            .map(|(id, mirror)| (id, mirror.options))
+            .collect();
+        for (id, options) in detached {
+            self.detach_mirror(id, options);
+        }
+        self.restart(preview_id, options);
+    }
+
+    /// Restart a preview's capture from scratch (e.g. after an error),
+    /// keeping its window, options, FPS and pause state.
+    pub fn retry(&mut self, preview_id: PreviewId) {
+        let preview_id = self.session_of(preview_id);
+        if let Some(session) = self.sessions.get(&preview_id) {
+            self.restart(preview_id, session.options);
+        }
+    }
+
+    /// Replace a session with a new one. A healthy old session keeps
+    /// capturing until the new one's first frame arrives, so the tile
+    /// doesn't freeze (or flash "Connecting...") in between.
+    fn restart(&mut self, preview_id: PreviewId, options: CaptureOptions) {
+        let Some(session) = self.sessions.remove(&preview_id) else { return; };
+        let hwnd = session.hwnd;
+        let window_title = session.window_title.clone();
+        let target_fps = session.requested_fps;
+        let paused = session.pause_requested;
+        log::info!(
+            "Restarting capture for {} with {:?}",
+            privacy::redact_title(&window_title),
+            options
+        );
+
+        if let Some(older) = self.outgoing.remove(&preview_id) {
+            older.stop(&self.host);
+        }
+        let healthy = session.error.is_none()
+            && session.stats.frames_received > 0
+            && !session.finished.load(Ordering::Relaxed);
+        if healthy {
+            self.outgoing.insert(preview_id, session);
+        } else {
+            session.stop(&self.host);
+        }
+        self.spawn_session(preview_id, hwnd, window_title, target_fps, options, paused);
+    }
+
+    /// Stop replaced sessions whose successor took over, failed, or is
+    /// taking too long to connect (its tile keeps the last frame)
+    fn finish_handovers(&mut self) {
+        let sessions = &self.sessions;
+        let done: Vec<PreviewId> = self
+            .outgoing
+            .keys()
+            .copied()
+            .filter(|id| sessions.get(id).map_or(true, |s| !s.is_connecting() || s.error.is_some()))
+            .collect();
+        for id in done {
+            if let Some(session) = self.outgoing.remove(&id) {
+                session.stop(&self.host);
+            }
+        }
+    }
+
+    /// Tell the pipeline how large the whole frame would be drawn on
+    /// screen, in pixels, so frames are delivered at that size instead of
+    /// the window's. Cheap; call every frame.
+    pub fn set_display_size(&mut self, preview_id: PreviewId, size: Option<(u32, u32)>) {
+        if self.mirrors.contains_key(&preview_id) {
+            return;
+        }
+        let size = if self.mirrors_of(preview_id).next().is_some() { None } else { size };
+        if let Some(session) = self.sessions.get(&preview_id) {
+            if session.controls.set_display_size(size) {
+                self.pipeline.refresh(preview_id, size);
+            }
+        }
+    }
+
+    /// Downscale frames in linear light
+    pub fn set_linear_scaling(&mut self, enabled: bool) {
+        self.linear_light = enabled;
+        for session in self.sessions.values() {
+            session.controls.linear_light.store(enabled, Ordering::Relaxed);
+        }
+    }
+
+    /// Why a preview's capture failed, if it did (cleared by a new frame
+    /// or a retry)
+    pub fn error(&self, preview_id: PreviewId) -> Option<&str> {
+        self.sessions.get(&self.session_of(preview_id)).and_then(|s| s.error.as_deref())
+    }
+
+    /// True if the preview's capture failed because its window runs as
+    /// administrator and Pluriview doesn't
+    pub fn needs_elevation(&self, preview_id: PreviewId) -> bool {
+        self.sessions
+            .get(&self.session_of(preview_id))
+            .is_some_and(|s| s.elevated && s.error.is_some())
+    }
+
+    /// True if the preview asked for Graphics Capture but is running on the
+    /// PrintWindow fallback because Graphics Capture failed to start
+    pub fn is_using_fallback(&self, preview_id: PreviewId) -> bool {
+        self.sessions
+            .get(&self.session_of(preview_id))
+            .is_some_and(|s| s.fallback_active.load(Ordering::Relaxed))
+    }
+
     /// Process any pending captured frames. Drains the channel completely:
     /// each preview keeps only its newest frame, so a stalled UI can never
     /// accumulate a backlog of multi-megabyte video frames.
-    pub fn process_frames(&mut self, preview_manager: &mut PreviewManager, _ctx: &egui::Context) {
+    ///
+    /// Returns the errors capture threads reported since the last call.
+    pub fn process_frames(
+        &mut self,
+        preview_manager: &mut PreviewManager,
+        _ctx: &egui::Context,
+    ) -> Vec<CaptureError> {
         loop {
             match self.frame_receiver.try_recv() {
                 Ok(frame) => {
+                    let current = self.sessions.get(&frame.preview_id).map(|s| s.controls.generation);
+                    if current != Some(frame.generation) {
+                        // From a session being replaced: keep drawing it
+                        // until the new session's first frame
+                        let outgoing = self.outgoing.get(&frame.preview_id)
+                            .is_some_and(|s| s.controls.generation == frame.generation);
+                        if outgoing {
+                            self.deliver_to_mirrors(preview_manager, &frame);
+                        }
+                        if let (true, Some(preview)) = (outgoing, preview_manager.get_mut(frame.preview_id)) {
+                            preview.update_frame(frame.source_size, frame.width, frame.height, frame.data);
+                        }
+                        continue;
+                    }
+                    if let Some(old) = self.outgoing.remove(&frame.preview_id) {
+                        old.stop(&self.host);
+                    }
+                    self.deliver_to_mirrors(preview_manager, &frame);
                     if let Some(preview) = preview_manager.get_mut(frame.preview_id) {
-                        preview.update_frame(frame.width, frame.height, frame.data);
+                        // A frame still waiting for upload is about to be
+                        // replaced without ever being shown.
+                        let superseded = preview.has_pending_frame();
+                        if let Some(session) = self.sessions.get_mut(&frame.preview_id) {
+                            if superseded {
+                                session.stats.record_dropped(1);
+                            }
+                            if session.stats.is_stalled() {
+                                log::info!("Capture for preview {:?} recovered from stall", frame.preview_id);
+                            }
+                            session.stats.record_frame(
+                                frame.source_size.0,
+                                frame.source_size.1,
+                                frame.captured_at.elapsed(),
+                            );
+                            session.stats.activity.record(frame.luma, Instant::now());
+                            session.error = None;
+                            if session.stats.received_fps > 0.0 {
+                                preview.last_stats = Some(SourceStats {
+                                    fps: session.stats.received_fps,
+                                    latency_ms: session.stats.latency.as_millis() as u32,
+                                });
+                            }
+                        }
+                        preview.update_frame(frame.source_size, frame.width, frame.height, frame.data);
                     }
                 }
                 Err(mpsc::TryRecvError::Empty) => break,
@@ -133,19 +931,92 @@ impl CaptureCoordinator {
                 }
             }
         }
+
+        let mut errors = Vec::new();
+        while let Ok(mut error) = self.error_receiver.try_recv() {
+            let session = self.sessions.get_mut(&error.preview_id)
+                .filter(|s| s.controls.generation == error.generation);
+            if let Some(session) = session {
+                if session.elevated {
+                    error.message = ELEVATED_MESSAGE.to_string();
+                }
+                log::warn!("Capture for preview {:?} failed: {}", error.preview_id, error.message);
+                session.stats.stalled_since = None;
+                session.error = Some(error.message.clone());
+                errors.push(error);
+            }
+        }
+
+        self.check_stalls(&mut errors);
+        self.finish_handovers();
+        errors
+    }
+
+    /// Hand a frame to the duplicates sharing its session (the pixels
+    /// themselves are shared, not copied)
+    fn deliver_to_mirrors(&self, preview_manager: &mut PreviewManager, frame: &CapturedFrame) {
+        for (id, _) in self.mirrors_of(frame.preview_id) {
+            if let Some(preview) = preview_manager.get_mut(id) {
+                preview.update_frame(frame.source_size, frame.width, frame.height, frame.data.clone());
+            }
+        }
+    }
+
+    /// Flag running sessions whose source stopped responding and sending
+    /// frames; a window of an elevated process that never delivered one
+    /// fails with the reason. Every session's measured rate decays to 0
+    /// once its frames stop, paused or not.
+    fn check_stalls(&mut self, errors: &mut Vec<CaptureError>) {
+        let now = Instant::now();
+        for (id, session) in &mut self.sessions {
+            session.stats.decay_fps(now);
+            if !*session.active.read() || *session.paused.read() || session.error.is_some() {
+                continue;
+            }
+            // An elevated window can leave Graphics Capture silently
+            // blank: say why rather than showing nothing
+            if session.elevated
+                && session.stats.frames_received == 0
+                && now.saturating_duration_since(session.watch_from) >= STALL_TIMEOUT
+            {
+                session.error = Some(ELEVATED_MESSAGE.to_string());
+                errors.push(CaptureError {
+                    preview_id: *id,
+                    generation: session.controls.generation,
+                    message: ELEVATED_MESSAGE.to_string(),
+                });
+                continue;
+            }
+            let live = session.is_live();
+            if session.stats.check_stalled(session.watch_from, now, live) {
+                log::warn!(
+                    "Capture for preview {:?} stalled: source not responding, no frame for {}s",
+                    id,
+                    STALL_TIMEOUT.as_secs()
+                );
+            }
+        }
+    }
+
+    /// Capture statistics for a preview's session, if it has one
+    pub fn stats(&self, preview_id: PreviewId) -> Option<&CaptureStats> {
+        self.sessions.get(&self.session_of(preview_id)).map(|s| &s.stats)
     }
 
     /// Check if a preview has an active capture
     #[allow(dead_code)]
     pub fn is_capturing(&self, preview_id: PreviewId) -> bool {
-        self.sessions.get(&preview_id)
+        self.sessions.get(&self.session_of(preview_id))
             .map(|s| *s.active.read())
             .unwrap_or(false)
     }
 
     /// Stop all captures
     pub fn stop_all(&mut self) {
-        let ids: Vec<_> = self.sessions.keys().copied().collect();
+        self.mirrors.clear();
+        self.queued.clear();
+        self.load_batch.clear();
+        let ids: Vec<_> = self.sessions.keys().chain(self.outgoing.keys()).copied().collect();
         for id in ids {
             self.stop_capture(id);
         }
@@ -153,22 +1024,111 @@ impl CaptureCoordinator {
 
     /// Pause capturing for a preview (viewport culling)
     pub fn pause_capture(&mut self, preview_id: PreviewId) {
-        if let Some(session) = self.sessions.get(&preview_id) {
-            *session.paused.write() = true;
-        }
+        self.set_paused(preview_id, true);
     }
 
     /// Resume capturing for a preview
     pub fn resume_capture(&mut self, preview_id: PreviewId) {
-        if let Some(session) = self.sessions.get(&preview_id) {
-            *session.paused.write() = false;
+        self.set_paused(preview_id, false);
+    }
+
+    /// Pause or resume a session, or remember the state for a queued one
+    fn set_paused(&mut self, preview_id: PreviewId, paused: bool) {
+        if let Some(mirror) = self.mirrors.get_mut(&preview_id) {
+            mirror.pause_requested = paused;
+            let source = mirror.source;
+            self.refresh_shared(source);
+        } else if let Some(session) = self.sessions.get_mut(&preview_id) {
+            session.pause_requested = paused;
+            self.refresh_shared(preview_id);
+        } else if let Some(queued) = self.queued.iter_mut().find(|q| q.preview_id == preview_id) {
+            queued.paused = paused;
+            self.refresh_shared(preview_id);
+        }
+    }
+
+    /// Pause every session at once (e.g. while the app is minimized) without
+    /// losing each one's own pause state, which is restored on resume.
+    pub fn set_suspended(&mut self, suspended: bool) {
+        if self.suspended == suspended {
+            return;
         }
+        self.suspended = suspended;
+        self.refresh_suspended();
+    }
+
+    /// Previews exempt from `set_suspended` (pop-outs). Cheap; call every
+    /// frame.
+    pub fn set_awake(&mut self, awake: &[PreviewId]) {
+        if self.awake == awake {
+            return;
+        }
+        self.awake = awake.to_vec();
+        self.refresh_suspended();
+    }
+
+    /// Is a session paused by the suspension? Not if it (or a duplicate
+    /// drawing it) is awake.
+    fn is_suspended(&self, source: PreviewId) -> bool {
+        self.suspended && !self.awake.iter().any(|&id| self.session_of(id) == source)
+    }
+
+    fn refresh_suspended(&mut self) {
+        let outgoing: Vec<(PreviewId, bool)> = self
+            .outgoing
+            .keys()
+            .map(|&id| (id, self.is_suspended(id)))
+            .collect();
+        for (id, suspended) in outgoing {
+            if let Some(session) = self.outgoing.get_mut(&id) {
+                session.set_effective_paused(session.pause_requested || suspended);
+            }
+        }
+        let ids: Vec<PreviewId> = self.sessions.keys().copied().collect();
+        for id in ids {
+            self.refresh_shared(id);
+        }
+    }
+
+    /// Health of every session and queued capture, ordered by preview ID
+    pub fn health(&self) -> Vec<SessionHealth> {
+        let mut rows: Vec<SessionHealth> = self
+            .sessions
+            .iter()
+            .map(|(&preview_id, session)| SessionHealth {
+                preview_id,
+                window_title: session.window_title.clone(),
+                requested_fps: session.requested_fps,
+                target_fps: session.target_fps.load(Ordering::Relaxed),
+                received_fps: session.stats.received_fps,
+                state: session.state(),
+                fallback: session.fallback_active.load(Ordering::Relaxed),
+                error: session.error.clone(),
+            })
+            .chain(self.queued.iter().map(|queued| SessionHealth {
+                preview_id: queued.preview_id,
+                window_title: queued.window_title.clone(),
+                requested_fps: queued.target_fps,
+                target_fps: self.effective_fps(queued.target_fps),
+                received_fps: 0.0,
+                state: SessionState::Queued,
+                fallback: false,
+                error: None,
+            }))
+            .collect();
+        rows.sort_by_key(|row| row.preview_id.0);
+        rows
+    }
+
+    /// Threads used for capture, for the health dashboard
+    pub fn thread_count(&self) -> usize {
+        self.host.thread_count()
     }
 
     /// Check if a preview's capture is paused
     #[allow(dead_code)]
     pub fn is_paused(&self, preview_id: PreviewId) -> bool {
-        self.sessions.get(&preview_id)
+        self.sessions.get(&self.session_of(preview_id))
             .map(|s| *s.paused.read())
             .unwrap_or(false)
     }
@@ -180,6 +1140,65 @@ impl CaptureCoordinator {
     }
 }
 
+impl CaptureSession {
+    /// Signal the backend to stop, telling the host (if it asked) so the
+    /// session is closed right away rather than on its next frame
+    fn stop(self, host: &NativeCapture) {
+        *self.active.write() = false;
+        if let Some(token) = self.token {
+            host.stop(token);
+        }
+    }
+
+    /// Update the pause flag the capture thread sees
+    fn set_effective_paused(&mut self, paused: bool) {
+        let was_paused = std::mem::replace(&mut *self.paused.write(), paused);
+        if was_paused && !paused {
+            self.watch_from = Instant::now();
+        }
+        if paused {
+            self.stats.stalled_since = None;
+        }
+    }
+
+    fn state(&self) -> SessionState {
+        let finished = self.finished.load(Ordering::Relaxed);
+        if self.error.is_some() {
+            SessionState::Failed
+        } else if finished {
+            SessionState::Finished
+        } else if *self.paused.read() {
+            SessionState::Paused
+        } else if self.stats.is_stalled() {
+            SessionState::Stalled
+        } else if self.stats.frames_received == 0 {
+            SessionState::Connecting
+        } else {
+            SessionState::Running
+        }
+    }
+
+    /// Is the session's source still there to send frames? A quiet window
+    /// may simply not be redrawing; one that's hung or gone, or a session
+    /// whose capture ended, won't send any.
+    fn is_live(&self) -> bool {
+        if self.finished.load(Ordering::Relaxed) {
+            return false;
+        }
+        if self.options.display {
+            return true;
+        }
+        NativeCapture::is_responsive(self.hwnd)
+    }
+
+    /// Started but still waiting for its first frame (and not given up on)
+    fn is_connecting(&self) -> bool {
+        self.stats.frames_received == 0
+            && self.started_at.elapsed() < CONNECT_TIMEOUT
+            && !self.finished.load(Ordering::Relaxed)
+    }
+}
+
 impl Default for CaptureCoordinator {
     fn default() -> Self {
         Self::new()
@@ -189,162 +1208,109 @@ impl Default for CaptureCoordinator {
 impl Drop for CaptureCoordinator {
     fn drop(&mut self) {
         self.stop_all();
+        self.host.shutdown();
     }
 }
 
-fn capture_target_from_hwnd(hwnd: isize) -> windows_capture::window::Window {
-    windows_capture::window::Window::from_raw_hwnd(hwnd as *mut std::ffi::c_void)
+/// Can window captures include popups and menus on this system?
+pub fn popups_supported() -> bool {
+    NativeCapture::supports_popups()
 }
 
-/// Capture loop running in a separate thread
-fn capture_window_loop(
-    preview_id: PreviewId,
-    hwnd: isize,
-    window_title: String,
-    target_fps: Arc<AtomicU32>,
-    active: Arc<RwLock<bool>>,
-    paused: Arc<RwLock<bool>>,
-    sender: Sender<CapturedFrame>,
-) {
-    use windows_capture::{
-        capture::{Context, GraphicsCaptureApiHandler},
-        frame::Frame,
-        graphics_capture_api::InternalCaptureControl,
-        settings::{
-            ColorFormat, CursorCaptureSettings, DrawBorderSettings,
-            SecondaryWindowSettings, MinimumUpdateIntervalSettings,
-            DirtyRegionSettings, Settings,
-        },
-    };
-
-    // Capture flags passed to the handler
-    struct CaptureFlags {
-        preview_id: PreviewId,
-        sender: Sender<CapturedFrame>,
-        active: Arc<RwLock<bool>>,
-        paused: Arc<RwLock<bool>>,
-        fps: Arc<AtomicU32>,
-    }
-
-    struct Capture {
-        preview_id: PreviewId,
-        sender: Sender<CapturedFrame>,
-        active: Arc<RwLock<bool>>,
-        paused: Arc<RwLock<bool>>,
-        fps: Arc<AtomicU32>,
-        last_frame: std::time::Instant,
-    }
-
-    impl GraphicsCaptureApiHandler for Capture {
-        type Flags = CaptureFlags;
-        type Error = Box<dyn std::error::Error + Send + Sync>;
-
-        fn new(ctx: Context<Self::Flags>) -> Result<Self, Self::Error> {
-            Ok(Self {
-                preview_id: ctx.flags.preview_id,
-                sender: ctx.flags.sender,
-                active: ctx.flags.active,
-                paused: ctx.flags.paused,
-                fps: ctx.flags.fps,
-                last_frame: std::time::Instant::now(),
-            })
-        }
+/// One still of the whole window, shrunk so its longer side is at most
+/// `max_side` pixels, as RGBA. For thumbnails; far too slow to poll.
+pub fn snapshot_window(hwnd: isize, max_side: u32) -> Option<(u32, u32, Vec<u8>)> {
+    NativeCapture::snapshot(hwnd, max_side)
+}
 
-        fn on_frame_arrived(
-            &mut self,
-            frame: &mut Frame,
-            capture_control: InternalCaptureControl,
-        ) -> Result<(), Self::Error> {
-            // Check if we should stop
-            if !*self.active.read() {
-                capture_control.stop();
-                return Ok(());
-            }
+#[cfg(test)]
+mod tests {
+    use super::{CaptureCoordinator, CaptureStats, FPS_WINDOW, STALL_TIMEOUT};
+    use crate::preview::{CaptureOptions, PreviewId};
+    use std::time::{Duration, Instant};
 
-            // Check if we're paused (viewport culling)
-            if *self.paused.read() {
-                return Ok(());
-            }
+    #[test]
+    fn stats_track_frames_drops_and_size() {
+        let mut stats = CaptureStats::default();
+        stats.record_frame(640, 480, Duration::from_millis(10));
+        stats.record_frame(800, 600, Duration::from_millis(10));
+        stats.record_dropped(1);
 
-            // Throttle frame rate (read live so preset changes apply instantly)
-            let fps = self.fps.load(Ordering::Relaxed).max(1);
-            let frame_interval = std::time::Duration::from_secs_f64(1.0 / fps as f64);
-            let elapsed = self.last_frame.elapsed();
-            if elapsed < frame_interval {
-                return Ok(());
-            }
-            self.last_frame = std::time::Instant::now();
+        assert_eq!(stats.frames_received, 2);
+        assert_eq!(stats.frames_dropped, 1);
+        assert_eq!(stats.frame_size, Some((800, 600)));
+        assert!((stats.latency.as_secs_f32() - 0.010).abs() < 1e-4);
+    }
 
-            // Get frame buffer
-            let mut buffer = frame.buffer()?;
-            let width = buffer.width();
-            let height = buffer.height();
+    #[test]
+    fn received_fps_drops_to_zero_when_frames_stop() {
+        let mut stats = CaptureStats::default();
+        stats.record_frame(640, 480, Duration::from_millis(10));
+        stats.received_fps = 30.0;
+        let last = stats.last_frame_at.unwrap();
 
-            // Copy frame data without row padding
-            let data = buffer.as_nopadding_buffer()?.to_vec();
+        stats.decay_fps(last + FPS_WINDOW / 2);
+        assert_eq!(stats.received_fps, 30.0);
+        stats.decay_fps(last + FPS_WINDOW);
+        assert_eq!(stats.received_fps, 0.0);
+        assert_eq!(stats.window_start, None);
+    }
 
-            // Send frame to main thread
-            let captured_frame = CapturedFrame {
-                preview_id: self.preview_id,
-                width,
-                height,
-                data,
-            };
+    #[test]
+    fn stall_is_flagged_once_and_cleared_by_next_frame() {
+        let mut stats = CaptureStats::default();
+        let start = Instant::now();
 
-            if self.sender.send(captured_frame).is_err() {
-                capture_control.stop();
-            }
+        // A quiet source that still responds is never stalled
+        assert!(!stats.check_stalled(start, start + STALL_TIMEOUT * 2, true));
+        assert!(!stats.check_stalled(start, start + STALL_TIMEOUT / 2, false));
+        assert!(stats.check_stalled(start, start + STALL_TIMEOUT, false));
+        assert!(!stats.check_stalled(start, start + STALL_TIMEOUT * 2, false));
+        assert_eq!(stats.stalled_since, Some(start));
 
-            Ok(())
-        }
+        stats.record_frame(640, 480, Duration::from_millis(10));
+        assert!(!stats.is_stalled());
 
-        fn on_closed(&mut self) -> Result<(), Self::Error> {
-            log::info!("Capture closed for preview {:?}", self.preview_id);
-            Ok(())
-        }
+        // Nor once it answers again
+        assert!(stats.check_stalled(start, Instant::now() + STALL_TIMEOUT, false));
+        assert!(!stats.check_stalled(start, Instant::now() + STALL_TIMEOUT, true));
+        assert!(!stats.is_stalled());
     }
 
-    let window = capture_target_from_hwnd(hwnd);
-    log::info!("Capturing HWND for {}", privacy::redact_title(&window_title));
-
-    // Use default minimum update interval (windows-capture handles FPS internally)
-    // We do our own throttling in on_frame_arrived
-    let min_interval = MinimumUpdateIntervalSettings::Default;
-
-    // Configure capture settings
-    let flags = CaptureFlags {
-        preview_id,
-        sender,
-        active: active.clone(),
-        paused: paused.clone(),
-        fps: target_fps,
-    };
-
-    let settings = Settings::new(
-        window,
-        CursorCaptureSettings::WithoutCursor,
-        DrawBorderSettings::WithoutBorder,
-        SecondaryWindowSettings::Default,
-        min_interval,
-        DirtyRegionSettings::Default,
-        ColorFormat::Rgba8,
-        flags,
-    );
+    #[test]
+    fn popped_out_previews_stay_awake_while_suspended() {
+        let mut coordinator = CaptureCoordinator::new();
+        let (source, copy, other) = (PreviewId(1), PreviewId(2), PreviewId(3));
+        coordinator.queue_capture(source, 0x1234, "Editor".to_string(), 30, CaptureOptions::default());
+        assert!(coordinator.mirror_capture(copy, source));
+        coordinator.set_suspended(true);
+        assert!(coordinator.is_suspended(source));
 
-    // Start capture - this blocks until capture is stopped
-    if let Err(e) = Capture::start(settings) {
-        log::error!("Failed to start capture: {}", e);
+        // A popped-out duplicate keeps the session it draws running
+        coordinator.set_awake(&[copy]);
+        assert!(!coordinator.is_suspended(source));
+        assert!(coordinator.is_suspended(other));
+        coordinator.set_suspended(false);
+        assert!(!coordinator.is_suspended(other));
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::capture_target_from_hwnd;
 
     #[test]
-    fn capture_target_preserves_supplied_hwnd() {
-        let hwnd = 0x1234isize;
-        let target = capture_target_from_hwnd(hwnd);
-        assert_eq!(target.as_raw_hwnd() as isize, hwnd);
+    fn duplicates_share_the_source_capture() {
+        let mut coordinator = CaptureCoordinator::new();
+        let (source, copy) = (PreviewId(1), PreviewId(2));
+        coordinator.queue_capture(source, 0x1234, "Editor".to_string(), 30, CaptureOptions::default());
+        assert!(!coordinator.mirror_capture(PreviewId(3), PreviewId(9)));
+
+        // A visible duplicate lets the (offscreen) source's capture start
+        assert!(coordinator.mirror_capture(copy, source));
+        assert!(coordinator.queued.iter().all(|q| !q.paused));
+        coordinator.pause_capture(copy);
+        coordinator.set_target_fps(copy, 60);
+        assert_eq!(coordinator.mirrors[&copy].fps, 60);
+
+        // Dropping the duplicate leaves the source's capture alone
+        coordinator.stop_capture(copy);
+        assert!(coordinator.mirrors.is_empty());
+        assert_eq!(coordinator.queued.len(), 1);
     }
 }
@@ -0,0 +1,210 @@
+use super::coordinator::SessionLink;
+use super::pipeline::{Job, PixelFormat};
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::{HWND, RECT};
+use windows::Win32::Graphics::Gdi::{
+    BitBlt, CreateCompatibleBitmap, CreateCompatibleDC, DeleteDC, DeleteObject, GetDIBits,
+    GetWindowDC, ReleaseDC, SelectObject, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS,
+    SRCCOPY,
+};
+use windows::Win32::Storage::Xps::{PrintWindow, PRINT_WINDOW_FLAGS};
+use windows::Win32::UI::WindowsAndMessaging::{GetWindowRect, IsWindow, PW_RENDERFULLCONTENT};
+
+/// How often a paused session checks whether it was resumed.
+const PAUSED_POLL: Duration = Duration::from_millis(100);
+
+/// Consecutive failed grabs after which the window is reported as
+/// uncapturable instead of silently retrying forever.
+const MAX_GRAB_FAILURES: u32 = 50;
+
+/// A window polled with `PrintWindow` (BitBlt as a last resort) by one of
+/// the shared GDI workers until the session is stopped or the window goes
+/// away. Much more expensive than Graphics Capture, but works for windows
+/// it can't capture.
+pub(super) struct GdiSession {
+    link: SessionLink,
+    hwnd: isize,
+    /// Why Graphics Capture failed, when this session is its fallback
+    fallback_reason: Option<String>,
+    failures: u32,
+    next_due: Instant,
+}
+
+impl GdiSession {
+    pub fn new(link: SessionLink, hwnd: isize, fallback_reason: Option<String>) -> Self {
+        Self {
+            link,
+            hwnd,
+            fallback_reason,
+            failures: 0,
+            next_due: Instant::now(),
+        }
+    }
+
+    /// When the session next wants to be polled
+    pub fn next_due(&self) -> Instant {
+        self.next_due
+    }
+
+    /// Grab and send one frame (or just check in while paused). Returns
+    /// false once the session is over; anything other than the session
+    /// being stopped is reported to the preview first.
+    pub fn poll(&mut self, now: Instant) -> bool {
+        match self.grab(now) {
+            Ok(Some(next_due)) => {
+                self.next_due = next_due;
+                true
+            }
+            Ok(None) => false,
+            Err(message) => {
+                let message = match &self.fallback_reason {
+                    Some(reason) => format!("Failed to start capture ({}); {}", reason, message),
+                    None => message,
+                };
+                self.link.report(message);
+                false
+            }
+        }
+    }
+
+    fn grab(&mut self, now: Instant) -> Result<Option<Instant>, String> {
+        let preview_id = self.link.preview_id;
+        if !self.link.is_active() {
+            return Ok(None);
+        }
+        if !unsafe { IsWindow(HWND(self.hwnd as *mut _)) }.as_bool() {
+            log::info!("PrintWindow capture closed for preview {:?}", preview_id);
+            return Err("The window was closed".to_string());
+        }
+        if *self.link.paused.read() {
+            return Ok(Some(now + PAUSED_POLL));
+        }
+
+        if let Some((width, height, data)) = grab_window(self.hwnd) {
+            let frame = self.link.controls.raw_frame(preview_id, width, height, PixelFormat::Bgrx8, data);
+            if self.link.frames.send(Job::Frame(frame)).is_err() {
+                return Ok(None);
+            }
+            self.failures = 0;
+        } else {
+            self.failures += 1;
+            if self.failures >= MAX_GRAB_FAILURES {
+                return Err("PrintWindow could not capture this window".to_string());
+            }
+        }
+
+        let fps = self.link.target_fps.load(Ordering::Relaxed).max(1);
+        Ok(Some(now + Duration::from_secs_f64(1.0 / fps as f64)))
+    }
+}
+
+impl Drop for GdiSession {
+    fn drop(&mut self) {
+        self.link.finished.store(true, Ordering::Relaxed);
+    }
+}
+
+/// One still of the whole window, shrunk so its longer side is at most
+/// `max_side` pixels, as RGBA. For thumbnails; far too slow to poll.
+pub(super) fn snapshot_window(hwnd: isize, max_side: u32) -> Option<(u32, u32, Vec<u8>)> {
+    let (width, height, data) = grab_window(hwnd)?;
+    Some(shrink_bgrx(width, height, &data, max_side))
+}
+
+/// Nearest-neighbour downscale of a BGRX buffer to RGBA with its longer
+/// side at most `max_side` (never upscaled).
+fn shrink_bgrx(width: u32, height: u32, data: &[u8], max_side: u32) -> (u32, u32, Vec<u8>) {
+    let scale = (max_side as f32 / width.max(height).max(1) as f32).min(1.0);
+    let out_w = ((width as f32 * scale).round() as u32).max(1);
+    let out_h = ((height as f32 * scale).round() as u32).max(1);
+    let mut out = Vec::with_capacity(out_w as usize * out_h as usize * 4);
+    for y in 0..out_h {
+        let src_y = (y as u64 * height as u64 / out_h as u64) as usize;
+        for x in 0..out_w {
+            let src_x = (x as u64 * width as u64 / out_w as u64) as usize;
+            let i = (src_y * width as usize + src_x) * 4;
+            out.extend_from_slice(&[data[i + 2], data[i + 1], data[i], 255]);
+        }
+    }
+    (out_w, out_h, out)
+}
+
+/// Copy the whole window into a BGRX buffer.
+fn grab_window(hwnd: isize) -> Option<(u32, u32, Vec<u8>)> {
+    let hwnd = HWND(hwnd as *mut _);
+    unsafe {
+        let mut rect = RECT::default();
+        GetWindowRect(hwnd, &mut rect).ok()?;
+        let width = rect.right - rect.left;
+        let height = rect.bottom - rect.top;
+        if width <= 0 || height <= 0 {
+            return None;
+        }
+
+        let window_dc = GetWindowDC(hwnd);
+        if window_dc.is_invalid() {
+            return None;
+        }
+        let memory_dc = CreateCompatibleDC(window_dc);
+        let bitmap = CreateCompatibleBitmap(window_dc, width, height);
+        let previous = SelectObject(memory_dc, bitmap);
+
+        // PrintWindow asks the window to render itself (works while
+        // occluded); BitBlt only copies what's visible on screen.
+        let printed = PrintWindow(hwnd, memory_dc, PRINT_WINDOW_FLAGS(PW_RENDERFULLCONTENT)).as_bool();
+        if !printed {
+            let _ = BitBlt(memory_dc, 0, 0, width, height, window_dc, 0, 0, SRCCOPY);
+        }
+        SelectObject(memory_dc, previous);
+
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // Negative height requests top-down rows
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut data = vec![0u8; width as usize * height as usize * 4];
+        let lines = GetDIBits(
+            memory_dc,
+            bitmap,
+            0,
+            height as u32,
+            Some(data.as_mut_ptr().cast()),
+            &mut info,
+            DIB_RGB_COLORS,
+        );
+
+        let _ = DeleteObject(bitmap);
+        let _ = DeleteDC(memory_dc);
+        ReleaseDC(hwnd, window_dc);
+
+        if lines == 0 {
+            return None;
+        }
+        Some((width as u32, height as u32, data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::shrink_bgrx;
+
+    #[test]
+    fn shrinking_keeps_the_aspect_and_swaps_to_rgba() {
+        // 4x2 BGRX, each pixel's blue channel is its index
+        let data: Vec<u8> = (0..8u8).flat_map(|i| [i, 10, 20, 0]).collect();
+        let (w, h, out) = shrink_bgrx(4, 2, &data, 2);
+        assert_eq!((w, h), (2, 1));
+        assert_eq!(out, vec![20, 10, 0, 255, 20, 10, 2, 255]);
+        // Never upscaled
+        assert_eq!(shrink_bgrx(4, 2, &data, 100).0, 4);
+    }
+}
@@ -0,0 +1,345 @@
+use crate::preview::CaptureOptions;
+use crate::{elevation, privacy};
+use super::coordinator::{SessionLink, ELEVATED_MESSAGE};
+use super::gdi::GdiSession;
+use super::pipeline::{Job, PixelFormat};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+use windows_capture::capture::{Context, GraphicsCaptureApiHandler};
+use windows_capture::frame::Frame;
+use windows_capture::graphics_capture_api::{GraphicsCaptureApi, InternalCaptureControl};
+use windows_capture::monitor::Monitor;
+use windows_capture::settings::{
+    ColorFormat, CursorCaptureSettings, DirtyRegionSettings, DrawBorderSettings,
+    MinimumUpdateIntervalSettings, SecondaryWindowSettings, TryIntoCaptureItemWithType,
+};
+use windows_gc::System::DispatcherQueueController;
+use windows_gc::Win32::Foundation::HMODULE;
+use windows_gc::Win32::Graphics::Direct3D::{
+    D3D_DRIVER_TYPE_HARDWARE, D3D_FEATURE_LEVEL_11_0, D3D_FEATURE_LEVEL_11_1,
+};
+use windows_gc::Win32::Graphics::Direct3D11::{
+    D3D11CreateDevice, ID3D11Device, ID3D11DeviceContext, D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+    D3D11_SDK_VERSION,
+};
+use windows_gc::Win32::System::WinRT::{
+    CreateDispatcherQueueController, RoInitialize, DispatcherQueueOptions, DQTAT_COM_NONE,
+    DQTYPE_THREAD_CURRENT, RO_INIT_MULTITHREADED,
+};
+
+type HandlerError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Whether Graphics Capture can include a window's popups and menus
+/// (Windows 11); asked once
+static POPUPS_SUPPORTED: Lazy<bool> = Lazy::new(|| {
+    GraphicsCaptureApi::is_secondary_windows_supported().unwrap_or(false)
+});
+
+/// Can window captures include popups and menus on this system?
+pub(super) fn popups_supported() -> bool {
+    *POPUPS_SUPPORTED
+}
+
+/// A Graphics Capture session for a capture worker to start
+pub(super) struct GraphicsStart {
+    pub hwnd: isize,
+    pub window_title: String,
+    pub options: CaptureOptions,
+    pub link: SessionLink,
+    /// Where to hand the session if Graphics Capture fails
+    pub gdi: Sender<GdiSession>,
+}
+
+/// What every session on a capture worker shares: the thread's dispatcher
+/// queue (frame pools deliver through it) and one Direct3D device.
+pub(super) struct CaptureDevice {
+    _queue: DispatcherQueueController,
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+}
+
+impl CaptureDevice {
+    /// Set the calling thread up for Graphics Capture
+    pub fn new() -> Result<Self, String> {
+        unsafe {
+            RoInitialize(RO_INIT_MULTITHREADED).map_err(|e| e.to_string())?;
+            let queue = CreateDispatcherQueueController(DispatcherQueueOptions {
+                dwSize: std::mem::size_of::<DispatcherQueueOptions>() as u32,
+                threadType: DQTYPE_THREAD_CURRENT,
+                apartmentType: DQTAT_COM_NONE,
+            })
+            .map_err(|e| e.to_string())?;
+
+            let mut device = None;
+            let mut context = None;
+            D3D11CreateDevice(
+                None,
+                D3D_DRIVER_TYPE_HARDWARE,
+                HMODULE::default(),
+                D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+                Some(&[D3D_FEATURE_LEVEL_11_1, D3D_FEATURE_LEVEL_11_0]),
+                D3D11_SDK_VERSION,
+                Some(&mut device),
+                None,
+                Some(&mut context),
+            )
+            .map_err(|e| e.to_string())?;
+            match (device, context) {
+                (Some(device), Some(context)) => Ok(Self { _queue: queue, device, context }),
+                _ => Err("no Direct3D 11 device".to_string()),
+            }
+        }
+    }
+}
+
+/// A window (or display) captured with Graphics Capture on one of the
+/// capture workers. Its frames arrive through the worker's message loop;
+/// the worker checks `is_running` whenever it's woken and ends the session
+/// once it was stopped or halted by itself.
+pub(super) struct GraphicsSession {
+    capture: GraphicsCaptureApi,
+    /// Set by the capture library when the item closed or the handler
+    /// stopped the capture
+    halt: Arc<AtomicBool>,
+    /// Error returned by the frame handler, if that's what halted it
+    result: Arc<Mutex<Option<HandlerError>>>,
+    request: GraphicsStart,
+}
+
+impl GraphicsSession {
+    /// Start capturing on the calling worker thread (`thread_id`), whose
+    /// message loop will deliver the frames. If Graphics Capture can't
+    /// start, the session is handed to a GDI worker (PrintWindow) so the
+    /// preview isn't left blank, or the reason is reported.
+    pub fn start(request: GraphicsStart, device: Option<&CaptureDevice>, thread_id: u32) -> Option<Self> {
+        if !request.link.is_active() {
+            request.link.finished.store(true, Ordering::Relaxed);
+            return None;
+        }
+        let hwnd = request.hwnd;
+        if !request.options.display && !unsafe { IsWindow(HWND(hwnd as *mut _)) }.as_bool() {
+            log::error!("Could not find window for {}", privacy::redact_title(&request.window_title));
+            request.link.report("Could not find the window".to_string());
+            request.link.finished.store(true, Ordering::Relaxed);
+            return None;
+        }
+
+        log::info!(
+            "Capturing {} for {}",
+            if request.options.display { "HMONITOR" } else { "HWND" },
+            privacy::redact_title(&request.window_title)
+        );
+        let device = match device {
+            Some(device) => device,
+            None => {
+                fall_back(request, "Graphics Capture is unavailable on this thread".to_string());
+                return None;
+            }
+        };
+        match Self::create(&request, device, thread_id) {
+            Ok((capture, result)) => Some(Self { halt: capture.halt_handle(), capture, result, request }),
+            Err(reason) => {
+                log::error!("Failed to start capture: {}", reason);
+                fall_back(request, reason);
+                None
+            }
+        }
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn create(
+        request: &GraphicsStart,
+        device: &CaptureDevice,
+        thread_id: u32,
+    ) -> Result<(GraphicsCaptureApi, Arc<Mutex<Option<HandlerError>>>), String> {
+        let options = request.options;
+        let (item, item_type) = if options.display {
+            Monitor::from_raw_hmonitor(request.hwnd as *mut std::ffi::c_void).try_into_capture_item()
+        } else {
+            capture_target_from_hwnd(request.hwnd).try_into_capture_item()
+        }
+        .map_err(|e| e.to_string())?;
+
+        let flags = CaptureFlags {
+            link: request.link.clone(),
+            format: if options.hdr { PixelFormat::Rgba16F } else { PixelFormat::Rgba8 },
+            display: options.display,
+        };
+        let ctx = Context {
+            flags,
+            device: device.device.clone(),
+            device_context: device.context.clone(),
+        };
+        let handler = Arc::new(Mutex::new(Capture::new(ctx).map_err(|e| e.to_string())?));
+
+        // Popups are a window's own; asking for them anywhere they aren't
+        // supported fails the whole capture
+        let popups = if options.include_popups && !options.display && popups_supported() {
+            SecondaryWindowSettings::Include
+        } else {
+            SecondaryWindowSettings::Default
+        };
+        let color_format = if options.hdr { ColorFormat::Rgba16F } else { ColorFormat::Rgba8 };
+        let result = Arc::new(Mutex::new(None));
+        // Frames are throttled in `on_frame_arrived`, so the update
+        // interval is left at its default
+        let mut capture = GraphicsCaptureApi::new(
+            device.device.clone(),
+            device.context.clone(),
+            item,
+            item_type,
+            handler,
+            CursorCaptureSettings::WithoutCursor,
+            DrawBorderSettings::WithoutBorder,
+            popups,
+            MinimumUpdateIntervalSettings::Default,
+            DirtyRegionSettings::Default,
+            color_format,
+            thread_id,
+            result.clone(),
+        )
+        .map_err(|e| e.to_string())?;
+        capture.start_capture().map_err(|e| e.to_string())?;
+        Ok((capture, result))
+    }
+
+    /// False once the session was stopped or the capture halted by itself
+    pub fn is_running(&self) -> bool {
+        self.request.link.is_active() && !self.halt.load(Ordering::Relaxed)
+    }
+
+    /// Close the capture. A frame handler that failed (rather than being
+    /// stopped) falls back to PrintWindow like a failed start.
+    pub fn end(self) {
+        self.capture.stop_capture();
+        let error = self.result.lock().take();
+        match error {
+            Some(error) => {
+                log::error!("Capture for preview {:?} failed: {}", self.request.link.preview_id, error);
+                fall_back(self.request, error.to_string());
+            }
+            None => self.request.link.finished.store(true, Ordering::Relaxed),
+        }
+    }
+}
+
+/// Graphics Capture failed for `request`: report why, or hand the window
+/// to a GDI worker unless PrintWindow can't do better
+fn fall_back(request: GraphicsStart, reason: String) {
+    let GraphicsStart { hwnd, window_title, options, link, gdi } = request;
+    if link.is_active() && options.display {
+        link.report(format!("Failed to capture the display ({})", reason));
+    } else if link.is_active() && elevation::window_blocks_capture(hwnd) {
+        // PrintWindow would only deliver blank frames of it, which hides
+        // why nothing shows
+        link.report(ELEVATED_MESSAGE.to_string());
+    } else if link.is_active() {
+        log::warn!("Falling back to PrintWindow for {}", privacy::redact_title(&window_title));
+        link.fallback_active.store(true, Ordering::Relaxed);
+        // The GDI worker owns the session from here and marks it finished
+        if gdi.send(GdiSession::new(link, hwnd, Some(reason))).is_err() {
+            log::error!("PrintWindow workers are gone");
+        }
+        return;
+    }
+    link.finished.store(true, Ordering::Relaxed);
+}
+
+fn capture_target_from_hwnd(hwnd: isize) -> windows_capture::window::Window {
+    windows_capture::window::Window::from_raw_hwnd(hwnd as *mut std::ffi::c_void)
+}
+
+/// Capture flags passed to the handler
+struct CaptureFlags {
+    link: SessionLink,
+    format: PixelFormat,
+    display: bool,
+}
+
+struct Capture {
+    link: SessionLink,
+    format: PixelFormat,
+    display: bool,
+    last_frame: Instant,
+}
+
+impl GraphicsCaptureApiHandler for Capture {
+    type Flags = CaptureFlags;
+    type Error = HandlerError;
+
+    fn new(ctx: Context<Self::Flags>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            link: ctx.flags.link,
+            format: ctx.flags.format,
+            display: ctx.flags.display,
+            last_frame: Instant::now(),
+        })
+    }
+
+    fn on_frame_arrived(
+        &mut self,
+        frame: &mut Frame,
+        capture_control: InternalCaptureControl,
+    ) -> Result<(), Self::Error> {
+        // Check if we should stop
+        if !self.link.is_active() {
+            capture_control.stop();
+            return Ok(());
+        }
+
+        // Check if we're paused (viewport culling)
+        if *self.link.paused.read() {
+            return Ok(());
+        }
+
+        // Throttle frame rate (read live so preset changes apply instantly)
+        let fps = self.link.target_fps.load(Ordering::Relaxed).max(1);
+        let frame_interval = Duration::from_secs_f64(1.0 / fps as f64);
+        if self.last_frame.elapsed() < frame_interval {
+            return Ok(());
+        }
+        self.last_frame = Instant::now();
+
+        // Get frame buffer
+        let mut buffer = frame.buffer()?;
+        let width = buffer.width();
+        let height = buffer.height();
+
+        // Copy frame data without row padding; conversion (swizzle,
+        // tone-mapping, downscale) happens on the pipeline's workers so
+        // the worker is back serving its other sessions right away.
+        let data = buffer.as_nopadding_buffer()?.to_vec();
+        let raw = self.link.controls.raw_frame(self.link.preview_id, width, height, self.format, data);
+
+        if self.link.frames.send(Job::Frame(raw)).is_err() {
+            capture_control.stop();
+        }
+
+        Ok(())
+    }
+
+    fn on_closed(&mut self) -> Result<(), Self::Error> {
+        log::info!("Capture closed for preview {:?}", self.link.preview_id);
+        let what = if self.display { "The display was disconnected" } else { "The window was closed" };
+        self.link.report(what.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::capture_target_from_hwnd;
+
+    #[test]
+    fn capture_target_preserves_supplied_hwnd() {
+        let hwnd = 0x1234isize;
+        let target = capture_target_from_hwnd(hwnd);
+        assert_eq!(target.as_raw_hwnd() as isize, hwnd);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::preview::{CaptureOptions, PreviewId};
+use super::coordinator::SessionLink;
+
+/// One session for the platform to capture
+pub(super) struct CaptureStart {
+    pub preview_id: PreviewId,
+    /// Window (or, with `options.display`, monitor) handle
+    pub hwnd: isize,
+    pub window_title: String,
+    pub options: CaptureOptions,
+    /// Where frames and errors go, and how the session is stopped
+    pub link: SessionLink,
+    /// The window's process is elevated and Pluriview isn't
+    pub elevated: bool,
+}
+
+/// The platform half of capture. The coordinator keeps every session's
+/// state (stats, pausing, handovers, stalls) and hands each one to the
+/// host, which runs it with whatever the OS offers and feeds its frames
+/// and errors through the session's link until the link is deactivated.
+pub(super) trait CaptureHost: Sized {
+    fn new() -> Self;
+
+    /// Start capturing. Returns a token for `stop` if the host wants to be
+    /// told when the session is stopped.
+    fn start(&self, start: CaptureStart) -> Option<usize>;
+
+    /// The session behind `token` was stopped (its link deactivated): let
+    /// it go now rather than when it next checks
+    fn stop(&self, token: usize);
+
+    /// Threads used for capture, for the health dashboard
+    fn thread_count(&self) -> usize;
+
+    /// Wait (bounded) for the host's threads to exit; every session has
+    /// been stopped
+    fn shutdown(&mut self);
+
+    /// Can `hwnd` still send frames: it exists and isn't hung?
+    fn is_responsive(hwnd: isize) -> bool;
+
+    /// Does the OS keep Pluriview from capturing `hwnd`?
+    fn blocks_capture(hwnd: isize) -> bool;
+
+    /// Can window captures include the window's popups and menus?
+    fn supports_popups() -> bool;
+
+    /// One still of the whole window, shrunk so its longer side is at most
+    /// `max_side` pixels, as RGBA
+    fn snapshot(hwnd: isize, max_side: u32) -> Option<(u32, u32, Vec<u8>)>;
+}
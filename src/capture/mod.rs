@@ -1,3 +1,21 @@
+mod activity;
 mod coordinator;
+#[cfg(windows)]
+mod gdi;
+#[cfg(windows)]
+mod graphics;
+mod host;
+mod pipeline;
+mod sharpen;
+mod tonemap;
+#[cfg(windows)]
+mod workers;
 
-pub use coordinator::CaptureCoordinator;
+pub use coordinator::{
+    popups_supported, snapshot_window, CaptureCoordinator, CaptureError, CaptureStats, SessionHealth, SessionState,
+};
+pub(crate) use pipeline::downscale_box;
+
+/// Capture backends for the platform being built for
+#[cfg(windows)]
+type NativeCapture = workers::CaptureWorkers;
@@ -0,0 +1,364 @@
+use crate::preview::PreviewId;
+use eframe::egui::ecolor;
+use super::activity::LumaGrid;
+use super::coordinator::CapturedFrame;
+use super::{sharpen, tonemap};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::Instant;
+
+/// Upper bound on conversion workers; more buys nothing for a UI-bound app.
+const MAX_WORKERS: usize = 4;
+
+/// Downscale targets are rounded up to this many pixels so zooming doesn't
+/// change the buffer size (and reallocate the texture) every frame.
+const SIZE_STEP: u32 = 32;
+
+/// Pixel layout of a frame as it comes off a capture backend
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum PixelFormat {
+    Rgba8,
+    /// GDI: BGR with an undefined fourth byte
+    Bgrx8,
+    /// scRGB half floats, 8 bytes per pixel (HDR)
+    Rgba16F,
+}
+
+/// Per-session processing settings, shared between the coordinator (which
+/// writes them) and the capture thread (which stamps them on each frame).
+#[derive(Default)]
+pub(super) struct FrameControls {
+    /// Which session of the preview the frames come from (see
+    /// `CapturedFrame::generation`)
+    pub generation: u64,
+    /// Sharpen frames that get drawn below 100%
+    pub sharpen: AtomicBool,
+    /// Downscale in linear light
+    pub linear_light: AtomicBool,
+    /// Size (width << 32 | height) the whole frame would be drawn at on
+    /// screen, in pixels; 0 while unknown
+    display_size: AtomicU64,
+}
+
+impl FrameControls {
+    /// Store the on-screen size. Returns true if it grew past the current
+    /// size step, i.e. the last delivered frame is now too small.
+    pub fn set_display_size(&self, size: Option<(u32, u32)>) -> bool {
+        let packed = size.map_or(0, |(w, h)| (w as u64) << 32 | h as u64);
+        let previous = self.display_size.swap(packed, Ordering::Relaxed);
+        let step = |v: u64| (v as u32).div_ceil(SIZE_STEP);
+        match (previous, packed) {
+            (0, _) => false,
+            (_, 0) => true,
+            (old, new) => step(new >> 32) > step(old >> 32) || step(new & 0xffff_ffff) > step(old & 0xffff_ffff),
+        }
+    }
+
+    fn display_size(&self) -> Option<(u32, u32)> {
+        match self.display_size.load(Ordering::Relaxed) {
+            0 => None,
+            packed => Some(((packed >> 32) as u32, packed as u32)),
+        }
+    }
+
+    /// Package a frame fresh off the backend for the pipeline
+    pub fn raw_frame(
+        &self,
+        preview_id: PreviewId,
+        width: u32,
+        height: u32,
+        format: PixelFormat,
+        data: Vec<u8>,
+    ) -> RawFrame {
+        RawFrame {
+            preview_id,
+            generation: self.generation,
+            width,
+            height,
+            format,
+            data,
+            captured_at: Instant::now(),
+            sharpen: self.sharpen.load(Ordering::Relaxed),
+            linear_light: self.linear_light.load(Ordering::Relaxed),
+            display_size: self.display_size(),
+        }
+    }
+}
+
+/// Work for a pipeline worker
+pub(super) enum Job {
+    /// A new frame from a capture thread
+    Frame(RawFrame),
+    /// The tile grew: re-deliver the last frame at the new size (static
+    /// windows may not send another frame for a long time)
+    Refresh { preview_id: PreviewId, display_size: Option<(u32, u32)> },
+    /// The session ended; drop its cached frame
+    Forget(PreviewId),
+}
+
+/// A frame waiting for conversion
+#[derive(Clone)]
+pub(super) struct RawFrame {
+    preview_id: PreviewId,
+    generation: u64,
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    data: Vec<u8>,
+    captured_at: Instant,
+    sharpen: bool,
+    linear_light: bool,
+    display_size: Option<(u32, u32)>,
+}
+
+/// Conversion stage between the capture threads and the UI: a few worker
+/// threads turn raw backend frames into display-ready RGBA (swizzle, HDR
+/// tone-mapping, downscale to the on-screen size, sharpening). Each preview
+/// always goes to the same worker so its frames stay in order. Cropping
+/// stays a UV rect applied when drawing, so it costs nothing here.
+pub(super) struct FramePipeline {
+    workers: Vec<Sender<Job>>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl FramePipeline {
+    pub fn new(output: Sender<CapturedFrame>) -> Self {
+        let count = std::thread::available_parallelism()
+            .map_or(2, |n| n.get() / 2)
+            .clamp(1, MAX_WORKERS);
+
+        let mut workers = Vec::with_capacity(count);
+        let mut handles = Vec::with_capacity(count);
+        for index in 0..count {
+            let (sender, receiver) = mpsc::channel();
+            let output = output.clone();
+            let handle = std::thread::Builder::new()
+                .name(format!("frame-convert-{}", index))
+                .spawn(move || convert_loop(receiver, output))
+                .expect("failed to spawn frame conversion thread");
+            workers.push(sender);
+            handles.push(handle);
+        }
+        Self { workers, handles }
+    }
+
+    /// Queue for a preview's frames (clone into its capture thread)
+    pub fn sender_for(&self, preview_id: PreviewId) -> Sender<Job> {
+        self.worker(preview_id).clone()
+    }
+
+    /// Re-deliver a preview's last frame at a new display size
+    pub fn refresh(&self, preview_id: PreviewId, display_size: Option<(u32, u32)>) {
+        let _ = self.worker(preview_id).send(Job::Refresh { preview_id, display_size });
+    }
+
+    /// Drop whatever the pipeline keeps for a preview
+    pub fn forget(&self, preview_id: PreviewId) {
+        let _ = self.worker(preview_id).send(Job::Forget(preview_id));
+    }
+
+    fn worker(&self, preview_id: PreviewId) -> &Sender<Job> {
+        &self.workers[preview_id.0 as usize % self.workers.len()]
+    }
+}
+
+impl Drop for FramePipeline {
+    fn drop(&mut self) {
+        // Workers exit once every sender (ours and the capture threads') is
+        // gone; capture threads may outlive us briefly, so don't block on them.
+        self.workers.clear();
+        for handle in self.handles.drain(..) {
+            if handle.is_finished() {
+                let _ = handle.join();
+            }
+        }
+    }
+}
+
+fn convert_loop(receiver: Receiver<Job>, output: Sender<CapturedFrame>) {
+    // Last raw frame of each preview that was delivered downscaled, so it
+    // can be redone at a larger size
+    let mut last_raw: HashMap<PreviewId, RawFrame> = HashMap::new();
+
+    while let Ok(job) = receiver.recv() {
+        let frame = match job {
+            Job::Frame(raw) => {
+                let frame = convert(&raw);
+                if (frame.width, frame.height) != frame.source_size {
+                    last_raw.insert(raw.preview_id, raw);
+                } else {
+                    last_raw.remove(&raw.preview_id);
+                }
+                frame
+            }
+            Job::Refresh { preview_id, display_size } => {
+                let Some(raw) = last_raw.get_mut(&preview_id) else { continue; };
+                raw.display_size = display_size;
+                raw.captured_at = Instant::now();
+                convert(raw)
+            }
+            Job::Forget(preview_id) => {
+                last_raw.remove(&preview_id);
+                continue;
+            }
+        };
+        if output.send(frame).is_err() {
+            break;
+        }
+    }
+}
+
+/// Turn a raw frame into display-ready RGBA8
+fn convert(raw: &RawFrame) -> CapturedFrame {
+    let (width, height) = (raw.width, raw.height);
+    let rgba: Cow<[u8]> = match raw.format {
+        PixelFormat::Rgba8 => Cow::Borrowed(&raw.data),
+        PixelFormat::Bgrx8 => {
+            let mut data = raw.data.clone();
+            bgrx_to_rgba(&mut data);
+            Cow::Owned(data)
+        }
+        PixelFormat::Rgba16F => Cow::Owned(tonemap::rgba16f_to_rgba8(&raw.data)),
+    };
+
+    let (out_width, out_height) = raw
+        .display_size
+        .map_or((width, height), |display| fit_within((width, height), display));
+    let downscaled = (out_width, out_height) != (width, height);
+    let data = if downscaled {
+        let mut data = downscale_box(
+            &rgba,
+            (width as usize, height as usize),
+            (out_width as usize, out_height as usize),
+            raw.linear_light,
+        );
+        if raw.sharpen {
+            sharpen::sharpen(&mut data, out_width as usize, out_height as usize);
+        }
+        data
+    } else {
+        rgba.into_owned()
+    };
+
+    CapturedFrame {
+        preview_id: raw.preview_id,
+        generation: raw.generation,
+        width: out_width,
+        height: out_height,
+        source_size: (width, height),
+        luma: LumaGrid::of(&data, out_width, out_height),
+        data: Arc::new(data),
+        captured_at: raw.captured_at,
+    }
+}
+
+/// Largest size with the source's aspect ratio that covers `display`,
+/// rounded up to `SIZE_STEP`; never larger than the source.
+fn fit_within(source: (u32, u32), display: (u32, u32)) -> (u32, u32) {
+    let (sw, sh) = source;
+    let (dw, dh) = display;
+    if sw == 0 || sh == 0 {
+        return source;
+    }
+    let step = |v: u32| v.div_ceil(SIZE_STEP) * SIZE_STEP;
+    let scale = (step(dw) as f32 / sw as f32).max(step(dh) as f32 / sh as f32);
+    if scale >= 1.0 {
+        return source;
+    }
+    (
+        ((sw as f32 * scale).round() as u32).clamp(1, sw),
+        ((sh as f32 * scale).round() as u32).clamp(1, sh),
+    )
+}
+
+/// Average every source pixel that falls into each output pixel, either
+/// on the sRGB values directly or (`linear_light`) on decoded light, which
+/// keeps thin bright/dark detail from shifting in brightness.
+pub(crate) fn downscale_box(
+    data: &[u8],
+    (src_w, src_h): (usize, usize),
+    (width, height): (usize, usize),
+    linear_light: bool,
+) -> Vec<u8> {
+    let mut out = vec![0u8; width * height * 4];
+    if data.len() < src_w * src_h * 4 {
+        return out;
+    }
+    let decode = |v: u8| if linear_light { ecolor::linear_f32_from_gamma_u8(v) } else { v as f32 / 255.0 };
+    let encode = |v: f32| if linear_light { ecolor::gamma_u8_from_linear_f32(v) } else { (v * 255.0).round() as u8 };
+
+    for y in 0..height {
+        let y0 = y * src_h / height;
+        let y1 = ((y + 1) * src_h / height).max(y0 + 1);
+        for x in 0..width {
+            let x0 = x * src_w / width;
+            let x1 = ((x + 1) * src_w / width).max(x0 + 1);
+
+            let mut sum = [0.0f32; 4];
+            for sy in y0..y1 {
+                for pixel in data[(sy * src_w + x0) * 4..(sy * src_w + x1) * 4].chunks_exact(4) {
+                    for (s, &v) in sum.iter_mut().zip(&pixel[..3]) {
+                        *s += decode(v);
+                    }
+                    sum[3] += pixel[3] as f32 / 255.0;
+                }
+            }
+            let count = ((y1 - y0) * (x1 - x0)) as f32;
+            let dst = &mut out[(y * width + x) * 4..][..4];
+            for (d, s) in dst.iter_mut().zip(&sum[..3]) {
+                *d = encode(s / count);
+            }
+            dst[3] = (sum[3] / count * 255.0).round() as u8;
+        }
+    }
+    out
+}
+
+/// GDI bitmaps are BGR with an undefined fourth byte; swap to RGBA and
+/// force the alpha opaque.
+fn bgrx_to_rgba(data: &mut [u8]) {
+    for pixel in data.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+        pixel[3] = 255;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bgrx_to_rgba, downscale_box, fit_within, FrameControls};
+
+    #[test]
+    fn gdi_pixels_become_opaque_rgba() {
+        let mut data = vec![10, 20, 30, 0, 1, 2, 3, 77];
+        bgrx_to_rgba(&mut data);
+        assert_eq!(data, vec![30, 20, 10, 255, 3, 2, 1, 255]);
+    }
+
+    #[test]
+    fn frames_shrink_to_the_display_size_but_never_grow() {
+        assert_eq!(fit_within((1920, 1080), (480, 270)), (512, 288));
+        assert_eq!(fit_within((640, 480), (1920, 1080)), (640, 480));
+    }
+
+    #[test]
+    fn only_growing_past_a_size_step_asks_for_a_refresh() {
+        let controls = FrameControls::default();
+        assert!(!controls.set_display_size(Some((300, 200))));
+        assert!(!controls.set_display_size(Some((310, 205))));
+        assert!(!controls.set_display_size(Some((200, 100))));
+        assert!(controls.set_display_size(Some((400, 100))));
+    }
+
+    #[test]
+    fn box_downscale_averages_pixels() {
+        let data = vec![0, 0, 0, 255, 200, 100, 50, 255];
+        assert_eq!(downscale_box(&data, (2, 1), (1, 1), false), vec![100, 50, 25, 255]);
+        // Black + white averages to ~188 in linear light, not 128
+        let data = vec![0, 0, 0, 255, 255, 255, 255, 255];
+        assert_eq!(downscale_box(&data, (2, 1), (1, 1), true), vec![188, 188, 188, 255]);
+    }
+}
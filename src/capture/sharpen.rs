@@ -0,0 +1,74 @@
+/// How strongly edges are boosted, 0.0 (subtle) ..= 1.0 (strong).
+const SHARPNESS: f32 = 0.6;
+
+/// Contrast-adaptive sharpening (after AMD's CAS) of an RGBA frame, in
+/// place. Each channel is pushed away from its four neighbours by an amount
+/// that shrinks where local contrast is already high, so small text gets
+/// crisper without halos on hard edges. Border pixels and alpha are kept.
+pub(super) fn sharpen(data: &mut [u8], width: usize, height: usize) {
+    if width < 3 || height < 3 || data.len() < width * height * 4 {
+        return;
+    }
+
+    // Negative lobe weight: -1/8 (soft) to -1/5 (strong)
+    let peak = -1.0 / (8.0 - 3.0 * SHARPNESS);
+    let source = data.to_vec();
+    let at = |x: usize, y: usize, c: usize| source[(y * width + x) * 4 + c] as f32 / 255.0;
+
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            for c in 0..3 {
+                let center = at(x, y, c);
+                let north = at(x, y - 1, c);
+                let south = at(x, y + 1, c);
+                let west = at(x - 1, y, c);
+                let east = at(x + 1, y, c);
+
+                let min = center.min(north).min(south).min(west).min(east);
+                let max = center.max(north).max(south).max(west).max(east);
+                if max <= 0.0 {
+                    continue;
+                }
+                // Headroom before clipping either way, relative to the peak
+                let amount = (min.min(1.0 - max) / max).clamp(0.0, 1.0).sqrt();
+                let weight = amount * peak;
+
+                let value = (center + weight * (north + south + west + east)) / (1.0 + 4.0 * weight);
+                data[(y * width + x) * 4 + c] = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sharpen;
+
+    fn grey_image(values: &[u8]) -> Vec<u8> {
+        values.iter().flat_map(|&v| [v, v, v, 255]).collect()
+    }
+
+    #[test]
+    fn flat_areas_are_untouched() {
+        let mut data = grey_image(&[100; 9]);
+        let before = data.clone();
+        sharpen(&mut data, 3, 3);
+        assert_eq!(data, before);
+    }
+
+    #[test]
+    fn edges_gain_contrast() {
+        // A mid-grey centre between darker columns gets brighter
+        #[rustfmt::skip]
+        let mut data = grey_image(&[
+            80, 128, 80,
+            80, 128, 80,
+            80, 128, 80,
+        ]);
+        sharpen(&mut data, 3, 3);
+
+        let center = data[4 * 4];
+        assert!(center > 128);
+        assert_eq!(data[4 * 4 + 3], 255);
+    }
+}
@@ -0,0 +1,106 @@
+use std::sync::OnceLock;
+
+/// Linear values above this are compressed into the remaining headroom
+/// below 1.0; everything under it passes through untouched.
+const KNEE: f32 = 0.8;
+
+/// Resolution of the linear → sRGB lookup table.
+const SRGB_LUT_SIZE: usize = 4096;
+
+/// Convert an `Rgba16F` (scRGB, linear, 1.0 = SDR white) frame into 8-bit
+/// sRGB RGBA. Highlights are rolled off per pixel on the brightest channel,
+/// which keeps hues stable instead of clipping each channel to white.
+pub(super) fn rgba16f_to_rgba8(data: &[u8]) -> Vec<u8> {
+    let half = half_table();
+    let srgb = srgb_table();
+    let mut out = Vec::with_capacity(data.len() / 2);
+
+    for pixel in data.chunks_exact(8) {
+        let channel = |i: usize| half[u16::from_le_bytes([pixel[i], pixel[i + 1]]) as usize].max(0.0);
+        let (r, g, b, a) = (channel(0), channel(2), channel(4), channel(6));
+
+        let scale = roll_off_scale(r.max(g).max(b));
+        for value in [r * scale, g * scale, b * scale] {
+            let index = (value.min(1.0) * (SRGB_LUT_SIZE - 1) as f32).round() as usize;
+            out.push(srgb[index]);
+        }
+        out.push((a.min(1.0) * 255.0).round() as u8);
+    }
+    out
+}
+
+/// Factor that maps a pixel's peak channel into [0, 1] with a soft
+/// shoulder above `KNEE`.
+fn roll_off_scale(peak: f32) -> f32 {
+    if peak <= KNEE {
+        return 1.0;
+    }
+    let t = (peak - KNEE) / (1.0 - KNEE);
+    let compressed = KNEE + (1.0 - KNEE) * t / (1.0 + t);
+    compressed / peak
+}
+
+/// Every IEEE half-float bit pattern decoded to f32.
+fn half_table() -> &'static [f32] {
+    static TABLE: OnceLock<Vec<f32>> = OnceLock::new();
+    TABLE.get_or_init(|| (0..=u16::MAX).map(half_to_f32).collect())
+}
+
+/// Linear [0, 1] → 8-bit sRGB.
+fn srgb_table() -> &'static [u8] {
+    static TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        (0..SRGB_LUT_SIZE)
+            .map(|i| {
+                let linear = i as f32 / (SRGB_LUT_SIZE - 1) as f32;
+                let encoded = if linear <= 0.003_130_8 {
+                    linear * 12.92
+                } else {
+                    1.055 * linear.powf(1.0 / 2.4) - 0.055
+                };
+                (encoded * 255.0).round() as u8
+            })
+            .collect()
+    })
+}
+
+fn half_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent as i32 - 15),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{half_to_f32, rgba16f_to_rgba8};
+
+    fn pixel(r: u16, g: u16, b: u16, a: u16) -> Vec<u8> {
+        [r, g, b, a].iter().flat_map(|c| c.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn half_floats_decode() {
+        assert_eq!(half_to_f32(0x3c00), 1.0);
+        assert_eq!(half_to_f32(0x3800), 0.5);
+        assert_eq!(half_to_f32(0xc000), -2.0);
+        assert_eq!(half_to_f32(0x0000), 0.0);
+    }
+
+    #[test]
+    fn sdr_range_passes_through_and_highlights_roll_off() {
+        // 0.5 linear is below the knee: plain sRGB encode (188)
+        assert_eq!(rgba16f_to_rgba8(&pixel(0x3800, 0x3800, 0x3800, 0x3c00)), vec![188, 188, 188, 255]);
+
+        // 1.0 and 4.0 (HDR highlight) stay ordered and below full white
+        let white = rgba16f_to_rgba8(&pixel(0x3c00, 0x3c00, 0x3c00, 0x3c00));
+        let bright = rgba16f_to_rgba8(&pixel(0x4400, 0x4400, 0x4400, 0x3c00));
+        assert!(white[0] < bright[0]);
+        assert!(bright[0] < 255);
+    }
+}
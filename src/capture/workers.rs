@@ -0,0 +1,258 @@
+use crate::preview::{CaptureBackend, PreviewId};
+use crate::{elevation, privacy};
+use super::coordinator::ELEVATED_MESSAGE;
+use super::gdi::{self, GdiSession};
+use super::graphics::{self, CaptureDevice, GraphicsSession, GraphicsStart};
+use super::host::{CaptureHost, CaptureStart};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::{
+    DispatchMessageW, GetMessageW, IsHungAppWindow, IsWindow, PeekMessageW, PostThreadMessageW,
+    TranslateMessage, MSG, PM_NOREMOVE, WM_QUIT,
+};
+
+/// Threads polling PrintWindow sessions, shared by every such session.
+const GDI_WORKERS: usize = 2;
+
+/// Threads running Graphics Capture sessions. Each serves any number of
+/// them from one message loop and one Direct3D device.
+const GRAPHICS_WORKERS: usize = 4;
+
+/// Graphics Capture workers only run a message loop and buffer copies, so
+/// they don't need Rust's default 2 MiB stack.
+const CAPTURE_STACK_SIZE: usize = 512 * 1024;
+
+/// How long dropping the coordinator waits for capture threads to exit.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Runs the capture backends on a fixed set of shared threads: PrintWindow
+/// sessions are polled by the GDI workers, Graphics Capture sessions are
+/// spread over the Graphics Capture workers. A session goes to the worker
+/// picked by its preview ID and stays there until it ends.
+pub(super) struct CaptureWorkers {
+    gdi: Vec<Sender<GdiSession>>,
+    graphics: Vec<Sender<GraphicsStart>>,
+    /// Win32 IDs of the Graphics Capture workers, 0 until running
+    graphics_ids: Vec<Arc<AtomicU32>>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl CaptureWorkers {
+    /// Queue for handing a preview's PrintWindow session to its GDI worker
+    fn gdi_sender(&self, preview_id: PreviewId) -> Sender<GdiSession> {
+        self.gdi[preview_id.0 as usize % self.gdi.len()].clone()
+    }
+
+    /// Hand a Graphics Capture session to its worker. Returns the worker,
+    /// for waking it when the session is stopped.
+    fn start_graphics(&self, preview_id: PreviewId, request: GraphicsStart) -> Option<usize> {
+        let worker = preview_id.0 as usize % self.graphics.len();
+        if self.graphics[worker].send(request).is_err() {
+            log::error!("Graphics Capture workers are gone");
+            return None;
+        }
+        self.wake(worker);
+        Some(worker)
+    }
+
+    /// Break a Graphics Capture worker out of its message loop so it looks
+    /// at its sessions now: new ones start, stopped ones are closed.
+    /// Otherwise a stopped session lingers until its window next redraws.
+    fn wake(&self, worker: usize) {
+        let thread_id = self.graphics_ids[worker].load(Ordering::Relaxed);
+        if thread_id != 0 {
+            // Fails harmlessly if the thread is already gone; one that
+            // isn't running yet checks its queue first thing anyway
+            let _ = unsafe { PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0)) };
+        }
+    }
+}
+
+impl CaptureHost for CaptureWorkers {
+    fn new() -> Self {
+        let mut gdi = Vec::with_capacity(GDI_WORKERS);
+        let mut graphics = Vec::with_capacity(GRAPHICS_WORKERS);
+        let mut graphics_ids = Vec::with_capacity(GRAPHICS_WORKERS);
+        let mut handles = Vec::with_capacity(GDI_WORKERS + GRAPHICS_WORKERS);
+        for index in 0..GDI_WORKERS {
+            let (sender, receiver) = mpsc::channel();
+            let handle = std::thread::Builder::new()
+                .name(format!("capture-gdi-{}", index))
+                .spawn(move || gdi_loop(receiver))
+                .expect("failed to spawn PrintWindow capture thread");
+            gdi.push(sender);
+            handles.push(handle);
+        }
+        for index in 0..GRAPHICS_WORKERS {
+            let (sender, receiver) = mpsc::channel();
+            let thread_id = Arc::new(AtomicU32::new(0));
+            let thread_id_clone = thread_id.clone();
+            let handle = std::thread::Builder::new()
+                .name(format!("capture-gc-{}", index))
+                .stack_size(CAPTURE_STACK_SIZE)
+                .spawn(move || graphics_loop(receiver, thread_id_clone))
+                .expect("failed to spawn Graphics Capture thread");
+            graphics.push(sender);
+            graphics_ids.push(thread_id);
+            handles.push(handle);
+        }
+        Self { gdi, graphics, graphics_ids, handles }
+    }
+
+    /// Each backend has its own shared workers. Displays only have
+    /// Graphics Capture.
+    fn start(&self, start: CaptureStart) -> Option<usize> {
+        let CaptureStart { preview_id, hwnd, window_title, options, link, elevated } = start;
+        let gdi = self.gdi_sender(preview_id);
+        if options.backend == CaptureBackend::PrintWindow && !options.display {
+            log::info!("Capturing HWND via PrintWindow for {}", privacy::redact_title(&window_title));
+            if elevated {
+                // PrintWindow only gets a blank (or stale) picture of it
+                link.report(ELEVATED_MESSAGE.to_string());
+                link.finished.store(true, Ordering::Relaxed);
+            } else if gdi.send(GdiSession::new(link, hwnd, None)).is_err() {
+                log::error!("PrintWindow workers are gone");
+            }
+            return None;
+        }
+        self.start_graphics(preview_id, GraphicsStart { hwnd, window_title, options, link, gdi })
+    }
+
+    /// PrintWindow sessions notice on their next poll; Graphics Capture
+    /// workers are woken
+    fn stop(&self, token: usize) {
+        self.wake(token);
+    }
+
+    fn thread_count(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Threads still busy after the timeout are left detached.
+    fn shutdown(&mut self) {
+        self.gdi.clear();
+        self.graphics.clear();
+        for worker in 0..self.graphics_ids.len() {
+            self.wake(worker);
+        }
+        let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+        while !self.handles.iter().all(|h| h.is_finished()) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let mut stuck = 0;
+        for handle in std::mem::take(&mut self.handles) {
+            if !handle.is_finished() {
+                stuck += 1;
+            } else if handle.join().is_err() {
+                log::error!("A capture thread panicked");
+            }
+        }
+        if stuck > 0 {
+            log::warn!("{} capture threads did not exit in time", stuck);
+        }
+    }
+
+    fn is_responsive(hwnd: isize) -> bool {
+        let hwnd = HWND(hwnd as *mut _);
+        unsafe { IsWindow(hwnd).as_bool() && !IsHungAppWindow(hwnd).as_bool() }
+    }
+
+    /// Windows of elevated processes, unless Pluriview is elevated too
+    fn blocks_capture(hwnd: isize) -> bool {
+        elevation::window_blocks_capture(hwnd)
+    }
+
+    fn supports_popups() -> bool {
+        graphics::popups_supported()
+    }
+
+    fn snapshot(hwnd: isize, max_side: u32) -> Option<(u32, u32, Vec<u8>)> {
+        gdi::snapshot_window(hwnd, max_side)
+    }
+}
+
+/// One Graphics Capture worker: starts the sessions it's handed and pumps
+/// the message loop that delivers all of their frames. WM_QUIT doesn't end
+/// the loop: Graphics Capture posts it when a session halts (closed window,
+/// stopped handler) and the coordinator when it stops or hands over a
+/// session, so it only means "look at the sessions again".
+fn graphics_loop(receiver: Receiver<GraphicsStart>, thread_id: Arc<AtomicU32>) {
+    let mut message = MSG::default();
+    // Create the message queue before anyone is told where to post
+    let _ = unsafe { PeekMessageW(&mut message, None, 0, 0, PM_NOREMOVE) };
+    let device = CaptureDevice::new()
+        .map_err(|e| log::error!("Graphics Capture unavailable, using PrintWindow: {}", e))
+        .ok();
+    let id = unsafe { GetCurrentThreadId() };
+    thread_id.store(id, Ordering::Relaxed);
+
+    let mut sessions: Vec<GraphicsSession> = Vec::new();
+    let mut connected = true;
+    loop {
+        loop {
+            match receiver.try_recv() {
+                Ok(request) => sessions.extend(GraphicsSession::start(request, device.as_ref(), id)),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    connected = false;
+                    break;
+                }
+            }
+        }
+
+        let (running, ended): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut sessions).into_iter().partition(|s| s.is_running());
+        sessions = running;
+        for session in ended {
+            session.end();
+        }
+        // Coordinator gone: every session was stopped before it went
+        if !connected && sessions.is_empty() {
+            break;
+        }
+
+        unsafe {
+            if GetMessageW(&mut message, None, 0, 0).0 > 0 {
+                let _ = TranslateMessage(&message);
+                DispatchMessageW(&message);
+            }
+        }
+    }
+}
+
+/// One GDI worker: grabs each of its sessions when due, sleeping until the
+/// earliest next grab or a new session, whichever comes first.
+fn gdi_loop(receiver: Receiver<GdiSession>) {
+    let mut sessions: Vec<GdiSession> = Vec::new();
+    let mut connected = true;
+
+    loop {
+        let next_due = sessions.iter().map(|s| s.next_due()).min();
+        if connected {
+            let incoming = match next_due {
+                Some(due) => receiver.recv_timeout(due.saturating_duration_since(Instant::now())),
+                None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            };
+            match incoming {
+                Ok(session) => sessions.push(session),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => connected = false,
+            }
+        } else {
+            // Coordinator gone: finish the stragglers (all stopped by now)
+            match next_due {
+                Some(due) => std::thread::sleep(due.saturating_duration_since(Instant::now())),
+                None => break,
+            }
+        }
+
+        let now = Instant::now();
+        sessions.retain_mut(|session| session.next_due() > now || session.poll(now));
+    }
+}
@@ -0,0 +1,289 @@
+use eframe::egui::{self, Color32, RichText};
+use std::path::Path;
+
+/// Folders whose paths name the user, most specific first; the report
+/// shows these variables instead
+const USER_FOLDERS: [&str; 3] = ["APPDATA", "LOCALAPPDATA", "USERPROFILE"];
+
+/// How a self-test came out
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    /// Works, but in a degraded way worth mentioning in a bug report
+    Warning,
+    Failed,
+}
+
+impl CheckStatus {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::Warning => "WARN",
+            Self::Failed => "FAIL",
+        }
+    }
+
+    fn color(self) -> Color32 {
+        match self {
+            Self::Ok => Color32::from_rgb(107, 170, 75),
+            Self::Warning => Color32::from_rgb(255, 150, 100),
+            Self::Failed => Color32::from_rgb(255, 90, 90),
+        }
+    }
+}
+
+/// One line of the diagnostics report
+#[derive(Clone, Debug)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self { name, status, detail: detail.into() }
+    }
+}
+
+/// What the app knows about itself that the checks can't look up
+pub struct Environment<'a> {
+    /// OpenGL renderer string, read at startup
+    pub gpu_adapter: Option<&'a str>,
+    pub safe_mode: bool,
+    pub tray_available: bool,
+    pub storage: Option<&'a crate::persistence::Storage>,
+    /// egui's scale factor for the main window
+    pub pixels_per_point: f32,
+}
+
+/// Run every self-test. All are quick, so this is done on the UI thread
+/// when the panel opens.
+pub fn run_checks(env: &Environment) -> Vec<Check> {
+    vec![
+        graphics_capture_check(),
+        match env.gpu_adapter {
+            Some(adapter) => Check::new("GPU adapter", CheckStatus::Ok, adapter),
+            None => Check::new("GPU adapter", CheckStatus::Warning, "No OpenGL context"),
+        },
+        match (env.tray_available, env.safe_mode) {
+            (true, _) => Check::new("System tray", CheckStatus::Ok, "Tray icon created"),
+            (false, true) => Check::new("System tray", CheckStatus::Warning, "Disabled in safe mode"),
+            (false, false) => Check::new("System tray", CheckStatus::Failed, "Could not create the tray icon"),
+        },
+        match env.storage.map(|s| s.check_write_access()) {
+            Some(Ok(dir)) => Check::new("Storage", CheckStatus::Ok, format!("Writable: {}", redact_path(&dir))),
+            Some(Err(e)) => Check::new("Storage", CheckStatus::Failed, format!("Not writable: {}", e)),
+            None => Check::new("Storage", CheckStatus::Failed, "No data directory; layouts and settings can't be saved"),
+        },
+        dpi_check(env.pixels_per_point),
+        monitors_check(),
+    ]
+}
+
+/// A path as it can go in a bug report: the user's own folders become
+/// `%APPDATA%` and the like, anything else is cut down to its last part
+fn redact_path(path: &Path) -> String {
+    let folders: Vec<(&str, String)> = USER_FOLDERS
+        .iter()
+        .filter_map(|var| std::env::var(var).ok().map(|dir| (*var, dir)))
+        .collect();
+    redact_with(&path.to_string_lossy(), &folders)
+}
+
+fn redact_with(path: &str, folders: &[(&str, String)]) -> String {
+    for (var, dir) in folders {
+        let dir = dir.trim_end_matches('\\');
+        let inside = path.get(..dir.len()).is_some_and(|head| head.eq_ignore_ascii_case(dir))
+            && matches!(path[dir.len()..].chars().next(), None | Some('\\'));
+        if !dir.is_empty() && inside {
+            return format!("%{}%{}", var, &path[dir.len()..]);
+        }
+    }
+    match path.rsplit('\\').next() {
+        Some(last) if last.len() < path.len() => format!("…\\{}", last),
+        _ => path.to_string(),
+    }
+}
+
+/// Plain-text report for pasting into a bug
+pub fn report(checks: &[Check]) -> String {
+    let mut text = format!(
+        "Pluriview {} diagnostics ({} {})\n",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    for check in checks {
+        text.push_str(&format!("[{}] {}: {}\n", check.status.label(), check.name, check.detail));
+    }
+    text
+}
+
+/// What the user asked for in the diagnostics window
+pub enum DiagnosticsAction {
+    RunAgain,
+    Copied,
+}
+
+/// "Diagnostics" window: the result of each check and a button to copy
+/// the report. Closing it clears `checks`.
+pub fn diagnostics_ui(ctx: &egui::Context, checks: &mut Option<Vec<Check>>) -> Option<DiagnosticsAction> {
+    let results = checks.as_ref()?;
+    let mut open = true;
+    let mut action = None;
+
+    egui::Window::new("Diagnostics")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .default_width(520.0)
+        .frame(egui::Frame::window(&ctx.style()).fill(Color32::from_rgb(25, 25, 28)))
+        .show(ctx, |ui| {
+            egui::Grid::new("diagnostics_grid")
+                .num_columns(3)
+                .striped(true)
+                .spacing([12.0, 6.0])
+                .show(ui, |ui| {
+                    for check in results {
+                        ui.label(RichText::new(check.status.label()).color(check.status.color()).strong());
+                        ui.label(check.name);
+                        ui.add(egui::Label::new(RichText::new(&check.detail).weak()).wrap());
+                        ui.end_row();
+                    }
+                });
+            ui.add_space(6.0);
+            ui.horizontal(|ui| {
+                if ui.button(format!("{} Copy Report", egui_phosphor::regular::COPY)).clicked() {
+                    ui.ctx().copy_text(report(results));
+                    action = Some(DiagnosticsAction::Copied);
+                }
+                if ui.button(format!("{} Run Again", egui_phosphor::regular::ARROW_CLOCKWISE)).clicked() {
+                    action = Some(DiagnosticsAction::RunAgain);
+                }
+            });
+        });
+
+    if !open {
+        *checks = None;
+    }
+    action
+}
+
+fn graphics_capture_check() -> Check {
+    const NAME: &str = "Graphics Capture";
+    #[cfg(windows)]
+    {
+        use windows::Graphics::Capture::GraphicsCaptureSession;
+
+        match GraphicsCaptureSession::IsSupported() {
+            Ok(true) => Check::new(NAME, CheckStatus::Ok, "Windows.Graphics.Capture is available"),
+            Ok(false) => Check::new(NAME, CheckStatus::Warning, "Not supported; captures fall back to GDI"),
+            Err(e) => Check::new(NAME, CheckStatus::Failed, format!("Could not query support: {}", e)),
+        }
+    }
+    #[cfg(not(windows))]
+    Check::new(NAME, CheckStatus::Failed, "Only available on Windows")
+}
+
+fn dpi_check(pixels_per_point: f32) -> Check {
+    const NAME: &str = "DPI";
+    let scale = format!("{:.0}% scale", pixels_per_point * 100.0);
+    #[cfg(windows)]
+    {
+        use windows::Win32::UI::HiDpi::{
+            GetAwarenessFromDpiAwarenessContext, GetThreadDpiAwarenessContext, DPI_AWARENESS_PER_MONITOR_AWARE,
+            DPI_AWARENESS_SYSTEM_AWARE,
+        };
+
+        let awareness = unsafe { GetAwarenessFromDpiAwarenessContext(GetThreadDpiAwarenessContext()) };
+        if awareness == DPI_AWARENESS_PER_MONITOR_AWARE {
+            Check::new(NAME, CheckStatus::Ok, format!("{}, per-monitor aware", scale))
+        } else if awareness == DPI_AWARENESS_SYSTEM_AWARE {
+            Check::new(NAME, CheckStatus::Warning, format!("{}, system aware only (blurry on other monitors)", scale))
+        } else {
+            Check::new(NAME, CheckStatus::Warning, format!("{}, DPI unaware (Windows scales the window)", scale))
+        }
+    }
+    #[cfg(not(windows))]
+    Check::new(NAME, CheckStatus::Ok, scale)
+}
+
+fn monitors_check() -> Check {
+    const NAME: &str = "Monitors";
+    #[cfg(windows)]
+    {
+        use windows::Win32::Foundation::{BOOL, LPARAM, RECT};
+        use windows::Win32::Graphics::Gdi::{
+            EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFO, MONITORINFOEXW,
+        };
+        use windows::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+        use windows::Win32::UI::WindowsAndMessaging::MONITORINFOF_PRIMARY;
+
+        unsafe extern "system" fn collect(monitor: HMONITOR, _: HDC, _: *mut RECT, data: LPARAM) -> BOOL {
+            let monitors = &mut *(data.0 as *mut Vec<String>);
+            let mut info = MONITORINFOEXW::default();
+            info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+            if GetMonitorInfoW(monitor, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO).as_bool() {
+                let rect = info.monitorInfo.rcMonitor;
+                let name_len = info.szDevice.iter().position(|&c| c == 0).unwrap_or(info.szDevice.len());
+                let (mut dpi_x, mut dpi_y) = (96, 96);
+                let _ = GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y);
+                monitors.push(format!(
+                    "{} {}x{} at ({}, {}) {}%{}",
+                    String::from_utf16_lossy(&info.szDevice[..name_len]),
+                    rect.right - rect.left,
+                    rect.bottom - rect.top,
+                    rect.left,
+                    rect.top,
+                    dpi_x * 100 / 96,
+                    if info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0 { " primary" } else { "" },
+                ));
+            }
+            true.into()
+        }
+
+        let mut monitors: Vec<String> = Vec::new();
+        unsafe {
+            let _ = EnumDisplayMonitors(HDC::default(), None, Some(collect), LPARAM(&mut monitors as *mut Vec<String> as isize));
+        }
+        if monitors.is_empty() {
+            Check::new(NAME, CheckStatus::Failed, "No monitors found")
+        } else {
+            Check::new(NAME, CheckStatus::Ok, format!("{}: {}", monitors.len(), monitors.join("; ")))
+        }
+    }
+    #[cfg(not(windows))]
+    Check::new(NAME, CheckStatus::Warning, "Not enumerated on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{redact_with, report, Check, CheckStatus};
+
+    #[test]
+    fn report_has_one_line_per_check() {
+        let checks = vec![
+            Check::new("Storage", CheckStatus::Ok, "Writable: C:\\data"),
+            Check::new("System tray", CheckStatus::Failed, "Could not create the tray icon"),
+        ];
+        let text = report(&checks);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("Pluriview "));
+        assert_eq!(lines[2], "[FAIL] System tray: Could not create the tray icon");
+    }
+
+    #[test]
+    fn paths_lose_the_user_name() {
+        let folders = [
+            ("APPDATA", r"C:\Users\Ada\AppData\Roaming".to_string()),
+            ("USERPROFILE", r"C:\Users\Ada".to_string()),
+        ];
+        let data = r"c:\users\ada\AppData\Roaming\pluriview\Pluriview\data";
+        assert_eq!(redact_with(data, &folders), r"%APPDATA%\pluriview\Pluriview\data");
+        assert_eq!(redact_with(r"C:\Users\Ada\Tools\data", &folders), r"%USERPROFILE%\Tools\data");
+        // Not fooled by a longer name that starts the same
+        assert_eq!(redact_with(r"C:\Users\Adam\data", &folders), r"…\data");
+    }
+}
@@ -0,0 +1,112 @@
+//! Administrator (elevation) checks
+//!
+//! Windows won't let a process that isn't elevated capture the windows of
+//! one that is, so those windows are marked in the picker and their
+//! previews offer to restart Pluriview as administrator.
+
+use once_cell::sync::Lazy;
+use windows::core::{w, HSTRING};
+use windows::Win32::Foundation::{CloseHandle, HANDLE, HWND};
+use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, OpenProcess, OpenProcessToken, WaitForSingleObject, PROCESS_QUERY_LIMITED_INFORMATION,
+    PROCESS_SYNCHRONIZE,
+};
+use windows::Win32::UI::Shell::ShellExecuteW;
+use windows::Win32::UI::WindowsAndMessaging::{GetWindowThreadProcessId, SW_SHOWNORMAL};
+
+/// Whether Pluriview itself runs elevated (fixed for the life of the process)
+static SELF_ELEVATED: Lazy<bool> = Lazy::new(|| token_elevated(unsafe { GetCurrentProcess() }) == Some(true));
+
+/// True if the process runs elevated while Pluriview doesn't, so its
+/// windows can't be captured
+pub fn blocks_capture(process_id: u32) -> bool {
+    blocked(*SELF_ELEVATED, process_elevated(process_id))
+}
+
+/// `blocks_capture` for the process owning a window
+pub fn window_blocks_capture(hwnd: isize) -> bool {
+    let mut process_id = 0;
+    unsafe { GetWindowThreadProcessId(HWND(hwnd as *mut _), Some(&mut process_id)) };
+    process_id != 0 && blocks_capture(process_id)
+}
+
+/// How long a restarted Pluriview waits for the old one to exit
+const RESTART_WAIT_MS: u32 = 10_000;
+
+/// Launch Pluriview again as administrator; Windows asks for consent first.
+/// The new instance is told to wait for this one to exit (`--after`)
+/// before it reads the autosave or takes the hotkey. False if it couldn't
+/// be started (or the prompt was declined).
+pub fn restart_elevated() -> bool {
+    let Ok(exe) = std::env::current_exe() else {
+        return false;
+    };
+    let exe = HSTRING::from(exe.as_os_str());
+    let args = HSTRING::from(format!("--after {}", std::process::id()));
+    let instance = unsafe { ShellExecuteW(None, w!("runas"), &exe, &args, None, SW_SHOWNORMAL) };
+    // Anything above 32 means it started
+    instance.0 as isize > 32
+}
+
+/// Block until the instance that restarted us (`--after <pid>`) has
+/// exited, or a few seconds have passed
+pub fn wait_for_exit(process_id: u32) {
+    unsafe {
+        let Ok(handle) = OpenProcess(PROCESS_SYNCHRONIZE, false, process_id) else {
+            return;
+        };
+        WaitForSingleObject(handle, RESTART_WAIT_MS);
+        let _ = CloseHandle(handle);
+    }
+}
+
+/// Whether a process runs elevated, None if it can't be opened at all.
+/// A process that opens but hides its token from us is one running
+/// elevated while we aren't.
+fn process_elevated(process_id: u32) -> Option<bool> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+        let elevated = token_elevated(handle).unwrap_or(true);
+        let _ = CloseHandle(handle);
+        Some(elevated)
+    }
+}
+
+fn token_elevated(process: HANDLE) -> Option<bool> {
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(process, TOKEN_QUERY, &mut token).ok()?;
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut size = 0;
+        let result = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut TOKEN_ELEVATION as *mut _),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut size,
+        );
+        let _ = CloseHandle(token);
+        result.ok()?;
+        Some(elevation.TokenIsElevated != 0)
+    }
+}
+
+/// Only an elevated target stops a Pluriview that isn't elevated; one we
+/// couldn't open at all is left for capture to try
+fn blocked(self_elevated: bool, target: Option<bool>) -> bool {
+    !self_elevated && target == Some(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::blocked;
+
+    #[test]
+    fn only_elevated_targets_block_a_standard_pluriview() {
+        assert!(blocked(false, Some(true)));
+        assert!(!blocked(false, Some(false)));
+        assert!(!blocked(false, None));
+        assert!(!blocked(true, Some(true)));
+    }
+}
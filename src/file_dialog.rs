@@ -0,0 +1,88 @@
+//! Native open/save dialogs for the export and import commands
+//!
+//! The shell's file dialog runs a modal message loop; shown on the UI
+//! thread it would feed winit events back into the app mid-frame, so it
+//! gets a thread of its own and the answer is picked up on a later frame.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use windows::core::{w, HSTRING};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoTaskMemFree, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED,
+};
+use windows::Win32::UI::Shell::Common::COMDLG_FILTERSPEC;
+use windows::Win32::UI::Shell::{
+    FileOpenDialog, FileSaveDialog, IFileDialog, IShellItem, SHCreateItemFromParsingName,
+    FOS_FILEMUSTEXIST, FOS_FORCEFILESYSTEM, FOS_OVERWRITEPROMPT, SIGDN_FILESYSPATH,
+};
+
+/// An open or save dialog for a JSON file, while it's showing
+pub struct FileDialog {
+    result: Receiver<Option<PathBuf>>,
+}
+
+impl FileDialog {
+    /// Show the dialog, starting in `initial`'s folder with its file name
+    /// filled in. `owner` (the main window) is disabled while it's open.
+    pub fn show(title: &str, initial: &Path, save: bool, owner: Option<isize>) -> Self {
+        let (sender, result) = mpsc::channel();
+        let title = title.to_string();
+        let initial = initial.to_path_buf();
+        let spawned = std::thread::Builder::new()
+            .name("file-dialog".to_string())
+            .spawn(move || {
+                let _ = sender.send(pick_json_file(&title, &initial, save, owner));
+            });
+        if let Err(e) = spawned {
+            log::error!("Could not open the file dialog: {}", e);
+        }
+        Self { result }
+    }
+
+    /// Some once the dialog closed: the chosen file, or None if cancelled
+    pub fn poll(&self) -> Option<Option<PathBuf>> {
+        match self.result.try_recv() {
+            Ok(path) => Some(path),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+}
+
+fn pick_json_file(title: &str, initial: &Path, save: bool, owner: Option<isize>) -> Option<PathBuf> {
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+        let class = if save { &FileSaveDialog } else { &FileOpenDialog };
+        let dialog: IFileDialog = CoCreateInstance(class, None, CLSCTX_INPROC_SERVER)
+            .map_err(|e| log::error!("Could not create the file dialog: {}", e))
+            .ok()?;
+
+        let filters = [
+            COMDLG_FILTERSPEC { pszName: w!("JSON files (*.json)"), pszSpec: w!("*.json") },
+            COMDLG_FILTERSPEC { pszName: w!("All files (*.*)"), pszSpec: w!("*.*") },
+        ];
+        let _ = dialog.SetFileTypes(&filters);
+        let _ = dialog.SetDefaultExtension(w!("json"));
+        let _ = dialog.SetTitle(&HSTRING::from(title));
+        let mut options = dialog.GetOptions().unwrap_or_default() | FOS_FORCEFILESYSTEM;
+        options |= if save { FOS_OVERWRITEPROMPT } else { FOS_FILEMUSTEXIST };
+        let _ = dialog.SetOptions(options);
+
+        if let Some(folder) = initial.parent().filter(|folder| folder.is_dir()) {
+            if let Ok(item) = SHCreateItemFromParsingName::<_, _, IShellItem>(&HSTRING::from(folder), None) {
+                let _ = dialog.SetFolder(&item);
+            }
+        }
+        if let Some(name) = initial.file_name() {
+            let _ = dialog.SetFileName(&HSTRING::from(name));
+        }
+
+        // Fails with ERROR_CANCELLED when the dialog is dismissed
+        dialog.Show(owner.map(|hwnd| HWND(hwnd as *mut _)).unwrap_or_default()).ok()?;
+        let name = dialog.GetResult().ok()?.GetDisplayName(SIGDN_FILESYSPATH).ok()?;
+        let path = name.to_string().ok().map(PathBuf::from);
+        CoTaskMemFree(Some(name.0 as *const _));
+        path
+    }
+}
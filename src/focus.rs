@@ -0,0 +1,59 @@
+use crate::platform::{Native, WindowFocus};
+use std::time::{Duration, Instant};
+
+/// How often the foreground window is re-read
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Keyboard input this recent counts as typing
+const TYPING_WINDOW: Duration = Duration::from_millis(1500);
+
+/// The window that has keyboard focus on the real desktop
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DesktopFocus {
+    pub hwnd: isize,
+    /// Top-level owner, so a focused dialog or popup counts for its app
+    pub owner: isize,
+    /// Keys were pressed lately (only tracked when the option is on)
+    pub typing: bool,
+}
+
+impl DesktopFocus {
+    /// Is this the window (or the owner of the window) with focus?
+    pub fn is_window(&self, hwnd: isize) -> bool {
+        hwnd == self.hwnd || hwnd == self.owner
+    }
+}
+
+/// Polls the foreground window a few times a second. Typing is inferred
+/// from system input that arrived without the cursor moving, since there
+/// is no way to watch another app's keystrokes without a hook.
+#[derive(Default)]
+pub struct FocusTracker {
+    focus: Option<DesktopFocus>,
+    last_poll: Option<Instant>,
+    /// Last system input tick and cursor position seen
+    last_input: Option<(u32, (i32, i32))>,
+    last_typed: Option<Instant>,
+}
+
+impl FocusTracker {
+    /// Call once per frame
+    pub fn poll(&mut self, track_typing: bool) -> Option<DesktopFocus> {
+        if self.last_poll.is_some_and(|t| t.elapsed() < POLL_INTERVAL) {
+            return self.focus;
+        }
+        self.last_poll = Some(Instant::now());
+
+        let input = Native::last_input();
+        if let (Some((tick, cursor)), Some((last_tick, last_cursor))) = (input, self.last_input) {
+            if tick != last_tick && cursor == last_cursor {
+                self.last_typed = Some(Instant::now());
+            }
+        }
+        self.last_input = input;
+
+        let typing = track_typing && self.last_typed.is_some_and(|t| t.elapsed() < TYPING_WINDOW);
+        self.focus = Native::foreground_window().map(|(hwnd, owner)| DesktopFocus { hwnd, owner, typing });
+        self.focus
+    }
+}
@@ -0,0 +1,116 @@
+use eframe::egui::{self, Color32, RichText};
+use crate::capture::{CaptureCoordinator, SessionState};
+use crate::preview::PreviewManager;
+
+/// "Capture Health" window: every capture session with its target vs
+/// measured FPS, state and last error, with restart/stop per row.
+pub fn health_ui(
+    ctx: &egui::Context,
+    open: &mut bool,
+    capture_coordinator: &mut CaptureCoordinator,
+    preview_manager: &mut PreviewManager,
+) {
+    let rows = capture_coordinator.health();
+
+    egui::Window::new("Capture Health")
+        .open(open)
+        .collapsible(true)
+        .resizable(true)
+        .default_width(560.0)
+        .frame(egui::Frame::window(&ctx.style()).fill(Color32::from_rgb(25, 25, 28)))
+        .show(ctx, |ui| {
+            if rows.is_empty() {
+                ui.label(RichText::new("No captures running.").weak());
+                return;
+            }
+
+            let running = rows.iter().filter(|r| r.state == SessionState::Running).count();
+            ui.label(
+                RichText::new(format!(
+                    "{} sessions, {} delivering frames, {} capture threads",
+                    rows.len(),
+                    running,
+                    capture_coordinator.thread_count()
+                ))
+                    .small()
+                    .weak(),
+            );
+            ui.add_space(4.0);
+
+            egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                egui::Grid::new("capture_health_grid")
+                    .num_columns(5)
+                    .striped(true)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("Window").strong());
+                        ui.label(RichText::new("State").strong());
+                        ui.label(RichText::new("FPS").strong());
+                        ui.label(RichText::new("Last Error").strong());
+                        ui.label("");
+                        ui.end_row();
+
+                        for row in &rows {
+                            let title = if row.window_title.chars().count() > 32 {
+                                let truncated: String = row.window_title.chars().take(29).collect();
+                                format!("{}...", truncated)
+                            } else {
+                                row.window_title.clone()
+                            };
+                            ui.label(title).on_hover_text(&row.window_title);
+
+                            let state = if row.fallback {
+                                format!("{} (PrintWindow)", row.state.label())
+                            } else {
+                                row.state.label().to_string()
+                            };
+                            ui.label(RichText::new(state).color(state_color(row.state)));
+
+                            // Measured vs target; the requested rate shows when capped
+                            let fps = if row.target_fps < row.requested_fps {
+                                format!("{:.1} / {} (of {})", row.received_fps, row.target_fps, row.requested_fps)
+                            } else {
+                                format!("{:.1} / {}", row.received_fps, row.target_fps)
+                            };
+                            ui.label(RichText::new(fps).monospace());
+
+                            match &row.error {
+                                Some(error) => ui.label(RichText::new(error).color(Color32::from_rgb(255, 150, 100))),
+                                None => ui.label(RichText::new("—").weak()),
+                            };
+
+                            ui.horizontal(|ui| {
+                                if row.state != SessionState::Queued
+                                    && ui
+                                        .small_button(egui_phosphor::regular::ARROW_CLOCKWISE)
+                                        .on_hover_text("Restart capture")
+                                        .clicked()
+                                {
+                                    capture_coordinator.retry(row.preview_id);
+                                }
+                                if ui
+                                    .small_button(egui_phosphor::regular::STOP)
+                                    .on_hover_text("Stop capture (the tile keeps its last frame)")
+                                    .clicked()
+                                {
+                                    capture_coordinator.stop_capture(row.preview_id);
+                                    if let Some(preview) = preview_manager.get_mut(row.preview_id) {
+                                        preview.capture_active = false;
+                                    }
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+}
+
+fn state_color(state: SessionState) -> Color32 {
+    match state {
+        SessionState::Running => Color32::from_rgb(107, 170, 75),
+        SessionState::Connecting | SessionState::Queued => Color32::from_rgb(74, 158, 255),
+        SessionState::Paused | SessionState::Finished => Color32::from_rgb(140, 140, 145),
+        SessionState::Stalled | SessionState::Failed => Color32::from_rgb(255, 150, 100),
+    }
+}
@@ -2,13 +2,25 @@
 
 mod app;
 mod canvas;
+mod diagnostics;
+mod elevation;
+mod file_dialog;
+mod health;
 mod preview;
 mod capture;
+mod focus;
 mod window_picker;
 mod persistence;
 mod tray;
 mod overlay;
+mod panic_button;
+mod platform;
+mod power;
 mod privacy;
+mod schedule;
+mod toast;
+mod util;
+mod welcome;
 #[cfg(windows)]
 mod browser;
 
@@ -18,6 +30,20 @@ use eframe::egui;
 fn main() -> eframe::Result<()> {
     env_logger::init();
 
+    // --safe-mode: start with an empty canvas and no integrations, so a
+    // corrupt layout or misbehaving source can't keep the app from opening.
+    let safe_mode = std::env::args().any(|arg| arg == "--safe-mode");
+
+    // --after <pid>: we were restarted (as administrator); let the old
+    // instance finish its autosave and release the hotkey first.
+    let after = std::env::args()
+        .skip_while(|arg| arg != "--after")
+        .nth(1)
+        .and_then(|pid| pid.parse().ok());
+    if let Some(process_id) = after {
+        elevation::wait_for_exit(process_id);
+    }
+
     // Create the window icon (leaf)
     let icon = create_window_icon();
 
@@ -36,7 +62,7 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Pluriview",
         options,
-        Box::new(|cc| Ok(Box::new(PluriviewApp::new(cc)))),
+        Box::new(move |cc| Ok(Box::new(PluriviewApp::new(cc, safe_mode)))),
     )
 }
 
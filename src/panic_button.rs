@@ -0,0 +1,51 @@
+use eframe::egui;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use windows::Win32::UI::Input::KeyboardAndMouse::{RegisterHotKey, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, VK_B};
+use windows::Win32::UI::WindowsAndMessaging::{GetMessageW, MSG, WM_HOTKEY};
+
+/// The global hotkey, as shown in menus and hints
+pub const PANIC_HOTKEY_LABEL: &str = "Ctrl+Alt+B";
+
+/// Set by the hotkey thread or the tray menu, taken by the app's next frame
+static PRESSED: AtomicBool = AtomicBool::new(false);
+
+/// Woken on a press so an idle app reacts at once
+static CONTEXT: OnceLock<egui::Context> = OnceLock::new();
+
+/// Press the panic button: blanks every preview, or restores them if
+/// they're already blanked. Callable from any thread.
+pub fn press() {
+    PRESSED.store(true, Ordering::Relaxed);
+    if let Some(ctx) = CONTEXT.get() {
+        ctx.request_repaint();
+    }
+}
+
+/// Was the panic button pressed since the last call?
+pub fn take_press() -> bool {
+    PRESSED.swap(false, Ordering::Relaxed)
+}
+
+/// Register the global hotkey. Hotkey messages go to the registering
+/// thread's queue, which winit never reads, so it gets a thread of its own.
+pub fn register_hotkey(ctx: egui::Context) {
+    let _ = CONTEXT.set(ctx);
+    let spawned = std::thread::Builder::new()
+        .name("panic-hotkey".to_string())
+        .spawn(|| unsafe {
+            if let Err(error) = RegisterHotKey(None, 1, MOD_CONTROL | MOD_ALT | MOD_NOREPEAT, VK_B.0 as u32) {
+                log::warn!("Could not register the panic hotkey {}: {}", PANIC_HOTKEY_LABEL, error);
+                return;
+            }
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                if msg.message == WM_HOTKEY {
+                    press();
+                }
+            }
+        });
+    if let Err(error) = spawned {
+        log::warn!("Could not start the panic hotkey thread: {}", error);
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Serialize, Deserialize};
+use super::{AppSettings, SavedLayout, Theme};
+
+/// File name suggested when exporting.
+pub const BUNDLE_FILE_NAME: &str = "pluriview-settings.json";
+
+/// Everything needed to set Pluriview up the same way on another machine,
+/// in one file: the app settings and theme and, optionally, every saved
+/// layout.
+/// Missing fields default, so bundles from other versions import what
+/// they can.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SettingsBundle {
+    pub version: u32,
+    pub settings: AppSettings,
+    pub theme: Theme,
+    pub layouts: Vec<SavedLayout>,
+}
+
+impl Default for SettingsBundle {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            settings: AppSettings::default(),
+            theme: Theme::default(),
+            layouts: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SettingsBundle;
+
+    #[test]
+    fn partial_bundles_import_with_defaults() {
+        let bundle: SettingsBundle = serde_json::from_str(r#"{ "settings": { "power_saver": true } }"#).unwrap();
+        assert!(bundle.settings.power_saver);
+        assert!(!bundle.settings.linear_scaling);
+        assert!(bundle.layouts.is_empty());
+    }
+}
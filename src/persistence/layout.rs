@@ -20,6 +20,30 @@ pub struct SavedLayout {
     #[serde(default)]
     pub recent_browser_urls: Vec<String>,
 
+    /// FPS budgets shared by groups of previews
+    #[serde(default)]
+    pub fps_groups: Vec<FpsGroup>,
+
+    /// Text placed on the canvas
+    #[serde(default)]
+    pub labels: Vec<TextLabel>,
+
+    /// Freehand pen strokes drawn over the canvas
+    #[serde(default)]
+    pub ink: Vec<InkStroke>,
+
+    /// Saved viewpoints; the first nine are on the number keys
+    #[serde(default)]
+    pub bookmarks: Vec<Bookmark>,
+
+    /// Clocks and countdowns placed on the canvas
+    #[serde(default)]
+    pub widgets: Vec<CanvasWidget>,
+
+    /// Regions that tile the previews inside them
+    #[serde(default)]
+    pub containers: Vec<Container>,
+
     /// Creation timestamp
     pub created_at: String,
 
@@ -35,6 +59,108 @@ pub struct CanvasLayout {
     pub show_grid: bool,
 }
 
+/// A named group of previews sharing one frame rate budget. Previews join
+/// by name (`PreviewLayout::fps_group`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FpsGroup {
+    pub name: String,
+    /// Total FPS divided among the group's visible previews
+    pub budget: u32,
+}
+
+/// Free text placed on the canvas, e.g. "PROD" over a group of tiles
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TextLabel {
+    pub text: String,
+    /// Top-left corner in canvas coordinates
+    pub position: (f32, f32),
+    /// Font size in canvas units, so it scales with zoom like the tiles
+    pub size: f32,
+    pub color: [u8; 3],
+}
+
+/// A freehand pen stroke, drawn over the previews
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InkStroke {
+    /// Canvas coordinates, in drawing order
+    pub points: Vec<(f32, f32)>,
+    /// Line width in canvas units
+    pub width: f32,
+    pub color: [u8; 3],
+}
+
+/// A named pan/zoom viewpoint, e.g. "Build servers"
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub name: String,
+    pub pan: (f32, f32),
+    pub zoom: f32,
+}
+
+/// A region of the canvas that tiles the previews inside it, e.g. "All
+/// browsers". Previews join by name (`PreviewLayout::container`).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Container {
+    pub name: String,
+    /// Top-left corner in canvas coordinates
+    pub position: (f32, f32),
+    /// Width and height in canvas units
+    pub size: (f32, f32),
+    #[serde(default)]
+    pub arrangement: Arrangement,
+}
+
+/// How a container lays out its previews
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Arrangement {
+    /// One preview per row, stacked top to bottom
+    Rows,
+    /// One preview per column, side by side
+    Columns,
+    /// As many rows and columns as shows the previews largest
+    #[default]
+    Grid,
+}
+
+impl Arrangement {
+    pub const ALL: [Arrangement; 3] = [Arrangement::Rows, Arrangement::Columns, Arrangement::Grid];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Arrangement::Rows => "Rows",
+            Arrangement::Columns => "Columns",
+            Arrangement::Grid => "Grid",
+        }
+    }
+}
+
+/// A live, non-capture tile on the canvas
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CanvasWidget {
+    pub kind: WidgetKind,
+    /// Top-left corner in canvas coordinates
+    pub position: (f32, f32),
+    /// Width and height in canvas units
+    pub size: (f32, f32),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WidgetKind {
+    /// The time now, one row per zone
+    Clock { zones: Vec<ClockZone> },
+    /// Counts down from `duration_secs`; `ends_at` (Unix seconds) is set
+    /// while it runs, so a running countdown survives a restart
+    Countdown { title: String, duration_secs: u32, ends_at: Option<u64> },
+}
+
+/// One row of a clock
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClockZone {
+    pub name: String,
+    /// Minutes east of UTC; None follows the local time zone
+    pub utc_offset_minutes: Option<i32>,
+}
+
 impl Default for CanvasLayout {
     fn default() -> Self {
         Self {
@@ -59,6 +185,12 @@ impl SavedLayout {
             },
             previews: Vec::new(),
             recent_browser_urls: Vec::new(),
+            fps_groups: Vec::new(),
+            labels: Vec::new(),
+            ink: Vec::new(),
+            bookmarks: Vec::new(),
+            widgets: Vec::new(),
+            containers: Vec::new(),
             created_at: now.clone(),
             modified_at: now,
         }
@@ -69,6 +201,20 @@ impl SavedLayout {
     pub fn touch(&mut self) {
         self.modified_at = chrono_now();
     }
+
+    /// Hash of what the user edits (the tiles, their settings, the
+    /// bookmarks, widgets and containers), for unsaved-changes tracking.
+    /// Panning/zooming, names, timestamps, capture history (last frame
+    /// time, size and stats) and which executable and class the window had
+    /// don't count.
+    pub fn content_fingerprint(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+
+        let previews: Vec<PreviewLayout> = self.previews.iter().cloned().map(PreviewLayout::edits_only).collect();
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        serde_json::to_string(&(&previews, &self.fps_groups, &self.labels, &self.ink, &self.bookmarks, &self.widgets, &self.containers)).unwrap_or_default().hash(&mut hasher);
+        hasher.finish()
+    }
 }
 
 /// Get current timestamp as string
@@ -81,3 +227,33 @@ fn chrono_now() -> String {
 
     format!("{}", duration.as_secs())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::SavedLayout;
+    use crate::preview::{PreviewLayout, SourceStats};
+
+    fn tile(x: f32) -> PreviewLayout {
+        let mut tile = PreviewLayout::for_title("Notepad");
+        tile.position = (x, 0.0);
+        tile
+    }
+
+    #[test]
+    fn fingerprint_ignores_view_and_history_but_not_edits() {
+        let mut saved = SavedLayout::new("a".to_string());
+        saved.previews.push(tile(0.0));
+
+        let mut viewed = saved.clone();
+        viewed.name = "b".to_string();
+        viewed.canvas.zoom = 2.0;
+        viewed.previews[0].last_seen = Some(1_700_000_000);
+        viewed.previews[0].frame_size = Some((1920, 1080));
+        viewed.previews[0].last_stats = Some(SourceStats { fps: 30.0, latency_ms: 12 });
+        assert_eq!(saved.content_fingerprint(), viewed.content_fingerprint());
+
+        let mut moved = saved.clone();
+        moved.previews[0] = tile(40.0);
+        assert_ne!(saved.content_fingerprint(), moved.content_fingerprint());
+    }
+}
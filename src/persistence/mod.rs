@@ -1,5 +1,19 @@
+mod bundle;
 mod layout;
+mod session;
+mod settings;
 mod storage;
+mod theme;
+mod thumbnail;
+mod trash;
 
-pub use layout::{SavedLayout, CanvasLayout};
+pub use bundle::{SettingsBundle, BUNDLE_FILE_NAME};
+pub use layout::{
+    Arrangement, SavedLayout, Bookmark, CanvasLayout, CanvasWidget, ClockZone, Container, FpsGroup, InkStroke, TextLabel, WidgetKind,
+};
+pub use session::{SessionManifest, WorkspaceSlot};
+pub use settings::{AppSettings, PickerSort, RecentWindow, ScheduleEntry, WindowBlocklist, ZoomSettings};
 pub use storage::Storage;
+pub use theme::Theme;
+pub use thumbnail::{THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT};
+pub use trash::Trash;
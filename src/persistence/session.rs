@@ -0,0 +1,103 @@
+use serde::{Serialize, Deserialize};
+
+/// Slot of the first workspace; its autosave is the original `autosave.json`
+/// so sessions from before workspaces existed restore unchanged.
+pub const DEFAULT_SLOT: &str = "autosave";
+
+/// Which workspaces were open (in tab order) and which one was active.
+/// Each workspace's canvas is autosaved to its own slot (`session.json`
+/// only lists them).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionManifest {
+    pub version: u32,
+    pub workspaces: Vec<WorkspaceSlot>,
+    /// Index into `workspaces`
+    pub active: usize,
+}
+
+/// One open workspace
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkspaceSlot {
+    /// Autosave slot (file name stem), stable across renames
+    pub slot: String,
+    /// Name shown on the tab
+    pub name: String,
+}
+
+impl Default for SessionManifest {
+    fn default() -> Self {
+        Self {
+            version: 1,
+            workspaces: vec![WorkspaceSlot {
+                slot: DEFAULT_SLOT.to_string(),
+                name: "Workspace 1".to_string(),
+            }],
+            active: 0,
+        }
+    }
+}
+
+impl SessionManifest {
+    /// Fix up a manifest read from disk: at least one workspace, unique
+    /// slots and an active index in range.
+    pub fn repair(&mut self) {
+        let mut seen = Vec::new();
+        self.workspaces.retain(|w| {
+            let unique = !w.slot.is_empty() && !seen.contains(&w.slot);
+            seen.push(w.slot.clone());
+            unique
+        });
+        if self.workspaces.is_empty() {
+            *self = Self::default();
+        }
+        self.active = self.active.min(self.workspaces.len() - 1);
+    }
+
+    /// Autosave slot of the active workspace
+    pub fn active_slot(&self) -> &str {
+        self.workspaces
+            .get(self.active)
+            .map_or(DEFAULT_SLOT, |w| w.slot.as_str())
+    }
+
+    /// A slot name not used by any open workspace
+    pub fn unused_slot(&self) -> String {
+        (2..)
+            .map(|n| format!("workspace-{}", n))
+            .find(|slot| !self.workspaces.iter().any(|w| &w.slot == slot))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SessionManifest, WorkspaceSlot, DEFAULT_SLOT};
+
+    fn slot(slot: &str) -> WorkspaceSlot {
+        WorkspaceSlot { slot: slot.to_string(), name: slot.to_string() }
+    }
+
+    #[test]
+    fn repair_drops_duplicates_and_clamps_active() {
+        let mut manifest = SessionManifest {
+            version: 1,
+            workspaces: vec![slot("a"), slot("a"), slot("b")],
+            active: 7,
+        };
+        manifest.repair();
+        assert_eq!(manifest.workspaces, vec![slot("a"), slot("b")]);
+        assert_eq!(manifest.active_slot(), "b");
+
+        let mut empty = SessionManifest { version: 1, workspaces: Vec::new(), active: 0 };
+        empty.repair();
+        assert_eq!(empty.active_slot(), DEFAULT_SLOT);
+    }
+
+    #[test]
+    fn unused_slot_skips_taken_names() {
+        let mut manifest = SessionManifest::default();
+        manifest.workspaces.push(slot("workspace-2"));
+        assert_eq!(manifest.unused_slot(), "workspace-3");
+    }
+}
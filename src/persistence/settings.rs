@@ -0,0 +1,172 @@
+use serde::{Serialize, Deserialize};
+
+/// App-wide preferences. Saved separately from layouts (`settings.json`)
+/// so switching or reloading a layout never resets them.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    /// Hide the Pluriview window from screen capture and sharing
+    pub exclude_from_capture: bool,
+    /// Drop all captures to a low FPS while unfocused or on battery
+    pub power_saver: bool,
+    /// Keep capturing while minimized or hidden to the tray (for people
+    /// recording the previews in the background)
+    pub capture_while_minimized: bool,
+    /// Filter and downscale previews in linear light so shrunk tiles keep
+    /// the source's brightness and color (saved as `color_managed` before)
+    #[serde(alias = "color_managed")]
+    pub linear_scaling: bool,
+    /// Mark previews whose source window is being typed into
+    pub typing_indicator: bool,
+    /// Switch layouts at set times of day
+    pub schedule_enabled: bool,
+    pub schedule: Vec<ScheduleEntry>,
+    /// Keep the trash of removed previews between sessions
+    pub keep_trash: bool,
+    /// How far the canvas zooms, and how
+    pub zoom: ZoomSettings,
+    /// Keep previews and panning inside a fixed region of the canvas, so
+    /// nothing gets flung out of reach
+    pub bounded_canvas: bool,
+    /// Windows added before, most recent first, listed under Recent in
+    /// the window picker
+    pub recent_windows: Vec<RecentWindow>,
+    /// Order of the window picker's list
+    pub picker_sort: PickerSort,
+    /// Windows never listed in the picker or quick-add popup
+    pub blocklist: WindowBlocklist,
+}
+
+/// Noisy windows to leave out of the window lists, on top of the built-in
+/// skip rules (shell windows, tool windows, sensitive apps)
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowBlocklist {
+    /// Executable names, e.g. "NVIDIA Share.exe", ignoring case
+    pub exe_names: Vec<String>,
+    /// Text the title contains, ignoring case; `*` stands for anything,
+    /// e.g. "GeForce*Overlay"
+    pub title_patterns: Vec<String>,
+    /// Window class names, exactly
+    pub class_names: Vec<String>,
+}
+
+impl WindowBlocklist {
+    /// Should a window with these details be left out?
+    pub fn blocks(&self, exe_name: &str, title: &str, class_name: &str) -> bool {
+        self.exe_names.iter().any(|exe| exe.eq_ignore_ascii_case(exe_name))
+            || self.title_patterns.iter().any(|pattern| pattern_matches(pattern, title))
+            || self.class_names.iter().any(|class| class == class_name)
+    }
+}
+
+/// Does `text` contain `pattern`, ignoring case, with each `*` in the
+/// pattern matching any run of characters? A blank pattern matches nothing.
+fn pattern_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    if pattern.chars().all(|c| c == '*') {
+        return false;
+    }
+    let text = text.to_lowercase();
+    let mut rest = text.as_str();
+    for piece in pattern.split('*').filter(|piece| !piece.is_empty()) {
+        match rest.find(piece) {
+            Some(at) => rest = &rest[at + piece.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// How the window picker orders windows
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PickerSort {
+    #[default]
+    Title,
+    /// By executable name, then title
+    Process,
+    /// Most recently in the foreground first
+    RecentlyActive,
+    /// Front to back, as stacked on the desktop
+    ZOrder,
+}
+
+impl PickerSort {
+    pub const ALL: [PickerSort; 4] = [PickerSort::Title, PickerSort::Process, PickerSort::RecentlyActive, PickerSort::ZOrder];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PickerSort::Title => "Title",
+            PickerSort::Process => "Process",
+            PickerSort::RecentlyActive => "Recently Active",
+            PickerSort::ZOrder => "Z-Order",
+        }
+    }
+}
+
+/// A window added from the picker. Titles change (browser tabs), so the
+/// picker matches by executable first and title second.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecentWindow {
+    pub exe_name: String,
+    pub title: String,
+}
+
+/// Canvas zoom limits and scroll wheel behaviour
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ZoomSettings {
+    /// Furthest out, e.g. 0.1 for 10%
+    pub min: f32,
+    /// Furthest in, e.g. 5.0 for 500%
+    pub max: f32,
+    /// Zoom change per scroll wheel notch, e.g. 0.1 for ±10%
+    pub step: f32,
+    /// Glide to the new zoom instead of jumping there
+    pub smooth: bool,
+}
+
+impl Default for ZoomSettings {
+    fn default() -> Self {
+        Self {
+            min: 0.1,
+            max: 5.0,
+            step: 0.1,
+            smooth: false,
+        }
+    }
+}
+
+/// Open a saved layout at a time of day
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduleEntry {
+    /// Minutes after local midnight
+    pub minute: u16,
+    /// Name of the saved layout
+    pub layout: String,
+}
+
+impl ScheduleEntry {
+    pub fn second_of_day(&self) -> u32 {
+        self.minute as u32 * 60
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WindowBlocklist;
+
+    #[test]
+    fn blocklist_patterns_match_pieces_in_order() {
+        let blocklist = WindowBlocklist {
+            exe_names: vec!["NVIDIA Share.exe".to_string()],
+            title_patterns: vec!["geforce*overlay".to_string(), " ".to_string()],
+            class_names: vec!["CEF-OSC-WIDGET".to_string()],
+        };
+        assert!(blocklist.blocks("nvidia share.exe", "Anything", ""));
+        assert!(blocklist.blocks("x.exe", "NVIDIA GeForce Overlay", ""));
+        assert!(!blocklist.blocks("x.exe", "Overlay for GeForce", ""));
+        assert!(blocklist.blocks("x.exe", "Untitled", "CEF-OSC-WIDGET"));
+        assert!(!blocklist.blocks("x.exe", "Untitled", "cef-osc-widget"));
+    }
+}
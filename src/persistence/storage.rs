@@ -1,6 +1,8 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
-use super::SavedLayout;
+use super::{AppSettings, SavedLayout, SessionManifest, SettingsBundle, Theme, Trash, BUNDLE_FILE_NAME};
+use super::session::DEFAULT_SLOT;
+use super::thumbnail::render_thumbnail;
 
 /// File storage for layouts and config
 pub struct Storage {
@@ -33,24 +35,49 @@ impl Storage {
     }
 
     /// Get the layouts directory
-    #[allow(dead_code)]
     fn layouts_dir(&self) -> PathBuf {
         let dir = self.data_dir.join("layouts");
         let _ = fs::create_dir_all(&dir);
         dir
     }
 
-    /// Save a layout
-    #[allow(dead_code)]
+    /// Save a layout, along with its thumbnail for the welcome screen
     pub fn save_layout(&self, layout: &SavedLayout) -> Result<(), std::io::Error> {
         let path = self.layouts_dir().join(format!("{}.json", sanitize_filename(&layout.name)));
         let json = serde_json::to_string_pretty(layout)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        fs::write(path, json)
+        fs::write(path, json)?;
+
+        // A missing thumbnail only costs the welcome screen a picture
+        if let Err(e) = render_thumbnail(layout).save(self.thumbnail_path(&layout.name)) {
+            log::warn!("Failed to save layout thumbnail: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Path of a layout's thumbnail image
+    fn thumbnail_path(&self, name: &str) -> PathBuf {
+        self.layouts_dir().join(format!("{}.png", sanitize_filename(name)))
+    }
+
+    /// Load a layout's thumbnail, if one was saved
+    pub fn load_thumbnail(&self, name: &str) -> Option<image::RgbaImage> {
+        image::open(self.thumbnail_path(name)).ok().map(|img| img.to_rgba8())
+    }
+
+    /// Saved layouts, most recently modified first
+    pub fn recent_layouts(&self, limit: usize) -> Vec<SavedLayout> {
+        let mut layouts: Vec<SavedLayout> = self
+            .list_layouts()
+            .iter()
+            .filter_map(|name| self.load_layout(name).ok())
+            .collect();
+        layouts.sort_by_key(|layout| std::cmp::Reverse(layout.modified_at.parse::<u64>().unwrap_or(0)));
+        layouts.truncate(limit);
+        layouts
     }
 
     /// Load a layout by name
-    #[allow(dead_code)]
     pub fn load_layout(&self, name: &str) -> Result<SavedLayout, Box<dyn std::error::Error>> {
         let path = self.layouts_dir().join(format!("{}.json", sanitize_filename(name)));
         let json = fs::read_to_string(path)?;
@@ -59,7 +86,6 @@ impl Storage {
     }
 
     /// List all saved layouts
-    #[allow(dead_code)]
     pub fn list_layouts(&self) -> Vec<String> {
         fs::read_dir(self.layouts_dir())
             .map(|entries| {
@@ -82,27 +108,213 @@ impl Storage {
     #[allow(dead_code)]
     pub fn delete_layout(&self, name: &str) -> Result<(), std::io::Error> {
         let path = self.layouts_dir().join(format!("{}.json", sanitize_filename(name)));
+        let _ = fs::remove_file(self.thumbnail_path(name));
         fs::remove_file(path)
     }
 
-    /// Get auto-save path
-    pub fn autosave_path(&self) -> PathBuf {
-        self.data_dir.join("autosave.json")
+    /// Get the auto-save path of a workspace slot (the first workspace
+    /// keeps the original `autosave.json`)
+    pub fn autosave_path(&self, slot: &str) -> PathBuf {
+        if slot == DEFAULT_SLOT {
+            return self.data_dir.join("autosave.json");
+        }
+        let dir = self.data_dir.join("autosaves");
+        let _ = fs::create_dir_all(&dir);
+        dir.join(format!("{}.json", sanitize_filename(slot)))
+    }
+
+    /// Save a workspace's autosave
+    pub fn save_autosave(&self, slot: &str, layout: &SavedLayout) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string_pretty(layout)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        fs::write(self.autosave_path(slot), json)
+    }
+
+    /// Load a workspace's autosave
+    pub fn load_autosave(&self, slot: &str) -> Result<SavedLayout, Box<dyn std::error::Error>> {
+        let json = fs::read_to_string(self.autosave_path(slot))?;
+        let layout: SavedLayout = serde_json::from_str(&json)?;
+        Ok(layout)
+    }
+
+    /// Delete a closed workspace's autosave
+    pub fn delete_autosave(&self, slot: &str) -> Result<(), std::io::Error> {
+        fs::remove_file(self.autosave_path(slot))
+    }
+
+    /// Get the session manifest path
+    fn session_path(&self) -> PathBuf {
+        self.data_dir.join("session.json")
+    }
+
+    /// Save which workspaces are open, their order and the active one
+    pub fn save_session(&self, session: &SessionManifest) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string_pretty(session)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        fs::write(self.session_path(), json)
+    }
+
+    /// Load the session manifest; a missing one (first run, or data from
+    /// before workspaces) yields the default single workspace
+    pub fn load_session(&self) -> SessionManifest {
+        let mut session = fs::read_to_string(self.session_path())
+            .ok()
+            .and_then(|json| serde_json::from_str::<SessionManifest>(&json).ok())
+            .unwrap_or_default();
+        session.repair();
+        session
+    }
+
+    /// Path of the startup marker: the number of starts that haven't yet
+    /// been confirmed healthy by `mark_startup_ok`
+    fn startup_marker_path(&self) -> PathBuf {
+        self.data_dir.join("startup_attempts")
+    }
+
+    /// Record a startup attempt, returning how many earlier starts never
+    /// got as far as `mark_startup_ok` (i.e. likely crashed)
+    pub fn begin_startup(&self) -> u32 {
+        let failed = fs::read_to_string(self.startup_marker_path())
+            .ok()
+            .and_then(|s| s.trim().parse::<u32>().ok())
+            .unwrap_or(0);
+        let _ = fs::write(self.startup_marker_path(), (failed + 1).to_string());
+        failed
+    }
+
+    /// The app started up fine; reset the failed-start count
+    pub fn mark_startup_ok(&self) {
+        let _ = fs::remove_file(self.startup_marker_path());
+    }
+
+    /// Write and remove a scratch file in the data directory, returning the
+    /// directory on success (for the diagnostics panel)
+    pub fn check_write_access(&self) -> Result<&Path, std::io::Error> {
+        let probe = self.data_dir.join(".write-test");
+        fs::write(&probe, b"ok")?;
+        fs::remove_file(&probe)?;
+        Ok(&self.data_dir)
+    }
+
+    /// Get the theme path
+    fn theme_path(&self) -> PathBuf {
+        self.data_dir.join("theme.json")
+    }
+
+    /// Save the canvas theme
+    pub fn save_theme(&self, theme: &Theme) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string_pretty(theme)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        fs::write(self.theme_path(), json)
     }
 
-    /// Save autosave
-    pub fn save_autosave(&self, layout: &SavedLayout) -> Result<(), std::io::Error> {
+    /// Load the canvas theme; missing or unreadable yields Minimal Void
+    pub fn load_theme(&self) -> Theme {
+        fs::read_to_string(self.theme_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Get the trash path
+    fn trash_path(&self) -> PathBuf {
+        self.data_dir.join("trash.json")
+    }
+
+    /// Save removed previews, for users who keep them between sessions
+    pub fn save_trash(&self, trash: &Trash) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string_pretty(trash)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        fs::write(self.trash_path(), json)
+    }
+
+    /// Load the saved trash; missing or unreadable yields an empty one
+    pub fn load_trash(&self) -> Trash {
+        fs::read_to_string(self.trash_path())
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    /// Forget the saved trash (when the user stops keeping it)
+    pub fn delete_trash(&self) {
+        let _ = fs::remove_file(self.trash_path());
+    }
+
+    /// Get app settings path
+    fn settings_path(&self) -> PathBuf {
+        self.data_dir.join("settings.json")
+    }
+
+    /// Save app settings
+    pub fn save_settings(&self, settings: &AppSettings) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string_pretty(settings)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        fs::write(self.settings_path(), json)
+    }
+
+    /// Load app settings
+    pub fn load_settings(&self) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        let json = fs::read_to_string(self.settings_path())?;
+        let settings: AppSettings = serde_json::from_str(&json)?;
+        Ok(settings)
+    }
+
+    /// The user's Documents folder, where exports are suggested to go
+    fn documents_dir(&self) -> PathBuf {
+        directories::UserDirs::new()
+            .and_then(|dirs| dirs.document_dir().map(Path::to_path_buf))
+            .unwrap_or_else(|| self.data_dir.clone())
+    }
+
+    /// Where the export dialog suggests writing a settings bundle
+    pub fn default_bundle_path(&self) -> PathBuf {
+        self.documents_dir().join(BUNDLE_FILE_NAME)
+    }
+
+    /// Where the layout export dialog suggests writing layout `name`
+    pub fn default_layout_export_path(&self, name: &str) -> PathBuf {
+        self.documents_dir().join(format!("{}.json", sanitize_filename(name)))
+    }
+
+    /// Write a layout to a file of the user's choosing (for sharing it or
+    /// keeping it in a repository)
+    pub fn export_layout(&self, path: &Path, layout: &SavedLayout) -> Result<(), std::io::Error> {
         let json = serde_json::to_string_pretty(layout)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        fs::write(self.autosave_path(), json)
+        fs::write(path, json)
     }
 
-    /// Load autosave
-    pub fn load_autosave(&self) -> Result<SavedLayout, Box<dyn std::error::Error>> {
-        let json = fs::read_to_string(self.autosave_path())?;
+    /// Read a layout written by `export_layout`
+    pub fn import_layout(&self, path: &Path) -> Result<SavedLayout, Box<dyn std::error::Error>> {
+        let json = fs::read_to_string(path)?;
         let layout: SavedLayout = serde_json::from_str(&json)?;
         Ok(layout)
     }
+
+    /// `name`, or "name 2", "name 3"... if a saved layout already has it
+    pub fn unused_layout_name(&self, name: &str) -> String {
+        let taken: Vec<String> = self.list_layouts().iter().map(|n| n.to_lowercase()).collect();
+        let is_free = |candidate: &String| !taken.contains(&sanitize_filename(candidate).to_lowercase());
+        (1..)
+            .map(|n| if n == 1 { name.to_string() } else { format!("{} {}", name, n) })
+            .find(is_free)
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Write a settings bundle (for moving to another machine)
+    pub fn write_bundle(&self, path: &Path, bundle: &SettingsBundle) -> Result<(), std::io::Error> {
+        let json = serde_json::to_string_pretty(bundle)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        fs::write(path, json)
+    }
+
+    /// Read a settings bundle written by `write_bundle`
+    pub fn read_bundle(&self, path: &Path) -> Result<SettingsBundle, Box<dyn std::error::Error>> {
+        let json = fs::read_to_string(path)?;
+        let bundle: SettingsBundle = serde_json::from_str(&json)?;
+        Ok(bundle)
+    }
 }
 
 impl Default for Storage {
@@ -112,7 +324,6 @@ impl Default for Storage {
 }
 
 /// Sanitize a filename to be safe for the filesystem
-#[allow(dead_code)]
 fn sanitize_filename(name: &str) -> String {
     name.chars()
         .map(|c| {
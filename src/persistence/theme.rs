@@ -0,0 +1,60 @@
+use eframe::egui::Color32;
+use serde::{Serialize, Deserialize};
+
+/// Canvas colors. Saved in `theme.json`, apart from the other settings so
+/// a theme can be shared or reset on its own. Defaults are Minimal Void.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    /// Canvas, title bar and tab strip fill
+    pub background: [u8; 3],
+    /// Selection, handles and other highlights
+    pub accent: [u8; 3],
+    /// Alpha of the white grid lines (0-255)
+    pub grid_opacity: u8,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            background: [13, 13, 13],
+            accent: [74, 158, 255],
+            grid_opacity: 5,
+        }
+    }
+}
+
+impl Theme {
+    pub fn background(&self) -> Color32 {
+        let [r, g, b] = self.background;
+        Color32::from_rgb(r, g, b)
+    }
+
+    pub fn accent(&self) -> Color32 {
+        self.accent_alpha(255)
+    }
+
+    /// The accent at a given alpha, for fills and faint outlines
+    pub fn accent_alpha(&self, alpha: u8) -> Color32 {
+        let [r, g, b] = self.accent;
+        Color32::from_rgba_unmultiplied(r, g, b, alpha)
+    }
+
+    pub fn grid(&self) -> Color32 {
+        Color32::from_rgba_unmultiplied(255, 255, 255, self.grid_opacity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Theme;
+    use eframe::egui::Color32;
+
+    #[test]
+    fn missing_fields_fall_back_to_minimal_void() {
+        let theme: Theme = serde_json::from_str(r#"{ "accent": [255, 150, 100] }"#).unwrap();
+        assert_eq!(theme.background(), Color32::from_rgb(13, 13, 13));
+        assert_eq!(theme.accent(), Color32::from_rgb(255, 150, 100));
+        assert_eq!(theme.grid_opacity, Theme::default().grid_opacity);
+    }
+}
@@ -0,0 +1,68 @@
+use image::{Rgba, RgbaImage};
+use super::SavedLayout;
+
+/// Thumbnail size in pixels (16:9).
+pub const THUMBNAIL_WIDTH: u32 = 240;
+pub const THUMBNAIL_HEIGHT: u32 = 135;
+
+/// Padding around the tiles inside the thumbnail.
+const MARGIN: f32 = 8.0;
+
+const BACKGROUND: Rgba<u8> = Rgba([13, 13, 13, 255]);
+const WINDOW_TILE: Rgba<u8> = Rgba([58, 58, 64, 255]);
+const BROWSER_TILE: Rgba<u8> = Rgba([62, 92, 48, 255]);
+const TILE_EDGE: Rgba<u8> = Rgba([95, 95, 102, 255]);
+
+/// Draw a schematic of the layout: every preview as a filled rect, fitted
+/// to the thumbnail and drawn in z-order. Pixel content isn't available
+/// when saving, so this shows the arrangement rather than the windows.
+pub fn render_thumbnail(layout: &SavedLayout) -> RgbaImage {
+    let mut image = RgbaImage::from_pixel(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT, BACKGROUND);
+    if layout.previews.is_empty() {
+        return image;
+    }
+
+    // Bounding box of all previews in canvas units
+    let (mut min_x, mut min_y) = (f32::MAX, f32::MAX);
+    let (mut max_x, mut max_y) = (f32::MIN, f32::MIN);
+    for preview in &layout.previews {
+        min_x = min_x.min(preview.position.0);
+        min_y = min_y.min(preview.position.1);
+        max_x = max_x.max(preview.position.0 + preview.size.0);
+        max_y = max_y.max(preview.position.1 + preview.size.1);
+    }
+
+    let avail_w = THUMBNAIL_WIDTH as f32 - 2.0 * MARGIN;
+    let avail_h = THUMBNAIL_HEIGHT as f32 - 2.0 * MARGIN;
+    let scale = (avail_w / (max_x - min_x).max(1.0)).min(avail_h / (max_y - min_y).max(1.0));
+    // Center the arrangement
+    let offset_x = MARGIN + (avail_w - (max_x - min_x) * scale) / 2.0;
+    let offset_y = MARGIN + (avail_h - (max_y - min_y) * scale) / 2.0;
+
+    let mut previews: Vec<_> = layout.previews.iter().collect();
+    previews.sort_by_key(|p| p.z_order);
+    for preview in previews {
+        let x0 = offset_x + (preview.position.0 - min_x) * scale;
+        let y0 = offset_y + (preview.position.1 - min_y) * scale;
+        let x1 = x0 + preview.size.0 * scale;
+        let y1 = y0 + preview.size.1 * scale;
+        let fill = if preview.browser_url.is_some() { BROWSER_TILE } else { WINDOW_TILE };
+        fill_rect(&mut image, x0, y0, x1, y1, fill);
+    }
+    image
+}
+
+/// Fill a rect with a one-pixel edge, clipped to the image.
+fn fill_rect(image: &mut RgbaImage, x0: f32, y0: f32, x1: f32, y1: f32, fill: Rgba<u8>) {
+    let left = x0.round().max(0.0) as u32;
+    let top = y0.round().max(0.0) as u32;
+    let right = (x1.round() as u32).min(image.width());
+    let bottom = (y1.round() as u32).min(image.height());
+
+    for y in top..bottom {
+        for x in left..right {
+            let edge = x == left || y == top || x + 1 == right || y + 1 == bottom;
+            image.put_pixel(x, y, if edge { TILE_EDGE } else { fill });
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use serde::{Serialize, Deserialize};
+use crate::preview::{PreviewId, PreviewLayout};
+
+/// The trash keeps this many previews; older ones fall off the end.
+pub const TRASH_LIMIT: usize = 200;
+
+/// Previews removed from the canvas, newest first, with everything needed
+/// to bring them back (crop, rotation, note, capture options...).
+/// Persisted to `trash.json` only when the user opts in.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Trash {
+    pub entries: Vec<TrashedPreview>,
+}
+
+/// One removed preview
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrashedPreview {
+    /// Seconds since the Unix epoch
+    pub removed_at: u64,
+    pub tile: PreviewLayout,
+    /// The preview it was, while this session lasts, so undoing the
+    /// removal takes it back out
+    #[serde(skip)]
+    pub id: Option<PreviewId>,
+}
+
+impl Trash {
+    /// Add a removed preview at the front, dropping the oldest past the limit
+    pub fn push(&mut self, id: PreviewId, tile: PreviewLayout, removed_at: u64) {
+        self.entries.insert(0, TrashedPreview { removed_at, tile, id: Some(id) });
+        self.entries.truncate(TRASH_LIMIT);
+    }
+
+    /// Drop the entry of a preview whose removal was undone. Returns true
+    /// if there was one.
+    pub fn forget(&mut self, id: PreviewId) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.id != Some(id));
+        self.entries.len() != before
+    }
+
+    /// Take an entry out to restore it
+    pub fn take(&mut self, index: usize) -> Option<TrashedPreview> {
+        (index < self.entries.len()).then(|| self.entries.remove(index))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Trash, TRASH_LIMIT};
+    use crate::preview::{PreviewId, PreviewLayout};
+
+    fn tile(title: &str) -> PreviewLayout {
+        let mut tile = PreviewLayout::for_title(title);
+        tile.crop_uv = Some((0.1, 0.1, 0.5, 0.5));
+        tile
+    }
+
+    #[test]
+    fn newest_first_and_bounded() {
+        let mut trash = Trash::default();
+        for i in 0..TRASH_LIMIT + 5 {
+            trash.push(PreviewId(i as u64), tile(&format!("Window {}", i)), i as u64);
+        }
+        assert_eq!(trash.len(), TRASH_LIMIT);
+        assert_eq!(trash.entries[0].tile.window_title, format!("Window {}", TRASH_LIMIT + 4));
+
+        let restored = trash.take(0).unwrap();
+        assert_eq!(restored.tile.crop_uv, Some((0.1, 0.1, 0.5, 0.5)));
+        assert_eq!(trash.len(), TRASH_LIMIT - 1);
+        assert!(trash.take(TRASH_LIMIT).is_none());
+    }
+
+    #[test]
+    fn undone_removals_leave_by_id() {
+        let mut trash = Trash::default();
+        trash.push(PreviewId(1), tile("Terminal"), 0);
+        trash.push(PreviewId(2), tile("Terminal"), 1);
+
+        assert!(trash.forget(PreviewId(1)));
+        assert!(!trash.forget(PreviewId(1)));
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash.entries[0].id, Some(PreviewId(2)));
+    }
+}
@@ -0,0 +1,141 @@
+//! What Pluriview needs from the operating system, behind traits
+//!
+//! The canvas, picker and app list windows and displays through
+//! `WindowSource` and follow or move keyboard focus through `WindowFocus`;
+//! capture backends implement `capture::CaptureHost`, which stays in the
+//! capture module because it hands sessions the frame pipeline. `Native` is
+//! the implementation for the platform being built for. Windows (`win32`)
+//! is the only one so far.
+
+use std::sync::Arc;
+
+#[cfg(windows)]
+mod win32;
+
+#[cfg(windows)]
+pub use win32::Win32 as Native;
+
+/// Information about a window
+#[derive(Clone, Debug)]
+pub struct WindowInfo {
+    /// Window handle
+    pub hwnd: isize,
+
+    /// Window title
+    pub title: String,
+
+    /// Process ID
+    pub process_id: u32,
+
+    /// Executable name
+    pub exe_name: String,
+
+    /// Window class name
+    pub class_name: String,
+
+    /// The executable's small icon, if it has one
+    pub icon: Option<Arc<AppIcon>>,
+
+    /// Place in the desktop's stacking order, 0 frontmost
+    pub z_order: usize,
+
+    /// Virtual desktop the window is on; None when it shows on all of
+    /// them or Windows can't say
+    pub desktop: Option<u128>,
+
+    /// On the virtual desktop being shown right now
+    pub on_current_desktop: bool,
+
+    /// Belongs to a process running as administrator while Pluriview
+    /// isn't, so capturing it will fail
+    pub elevated: bool,
+}
+
+/// An executable's icon as RGBA pixels
+#[derive(Debug)]
+pub struct AppIcon {
+    /// Full path of the executable; the same for every window of it
+    pub key: String,
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl WindowInfo {
+    /// Get a display name (title or exe name)
+    pub fn display_name(&self) -> &str {
+        if self.title.is_empty() {
+            &self.exe_name
+        } else {
+            &self.title
+        }
+    }
+}
+
+/// A display that can be captured whole
+#[derive(Clone, Debug)]
+pub struct DisplayInfo {
+    /// Monitor handle (HMONITOR)
+    pub handle: isize,
+
+    /// "Display 2"; stable across restarts, so layouts match displays by it
+    pub name: String,
+
+    /// Resolution in pixels
+    pub width: u32,
+    pub height: u32,
+
+    /// The primary display
+    pub primary: bool,
+}
+
+/// "Desktop 2", numbered as in Task View
+pub fn desktop_label(desktop: u128, order: &[u128]) -> String {
+    match order.iter().position(|&d| d == desktop) {
+        Some(index) => format!("Desktop {}", index + 1),
+        None => "Another desktop".to_string(),
+    }
+}
+
+/// Listing what can be captured, and what's known about a window's
+/// process and virtual desktop
+pub trait WindowSource {
+    /// Visible top-level windows worth capturing, sorted by title
+    fn windows() -> Vec<WindowInfo>;
+
+    /// The connected displays, in the system's numbering order
+    fn displays() -> Vec<DisplayInfo>;
+
+    /// A window's current title; None if it has none
+    fn window_title(hwnd: isize) -> Option<String>;
+
+    /// Executable name of a process ("Unknown" if it can't be read)
+    fn process_name(process_id: u32) -> String;
+
+    /// Full executable path of a process
+    fn process_path(process_id: u32) -> Option<String>;
+
+    /// The virtual desktops in the order the system shows them
+    fn desktop_order() -> Vec<u128>;
+
+    /// "Desktop 2" when `hwnd` is on a virtual desktop other than the one
+    /// being shown, None when it's on this one (or all of them)
+    fn other_desktop_label(hwnd: isize) -> Option<String>;
+}
+
+/// Following and moving the desktop's keyboard focus
+pub trait WindowFocus {
+    /// The foreground window and its top-level owner
+    fn foreground_window() -> Option<(isize, isize)>;
+
+    /// Tick of the last keyboard or mouse input anywhere, and the cursor
+    /// position
+    fn last_input() -> Option<(u32, (i32, i32))>;
+
+    /// Bring `hwnd` to the front with keyboard focus; with `restore`, a
+    /// minimized (or maximized) window is restored first
+    fn focus_window(hwnd: isize, restore: bool);
+
+    /// Is `hwnd` minimized or hidden?
+    fn is_hidden(hwnd: isize) -> bool;
+}
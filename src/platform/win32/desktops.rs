@@ -0,0 +1,109 @@
+use crate::platform::desktop_label;
+use windows::core::{w, GUID};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_APARTMENTTHREADED};
+use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_BINARY};
+use windows::Win32::UI::Shell::{IVirtualDesktopManager, VirtualDesktopManager};
+
+/// Asks the shell which virtual desktop windows are on. Desktops are
+/// identified by their GUID (as a u128).
+pub struct VirtualDesktops {
+    /// None where the shell doesn't offer it (Server Core, old builds)
+    manager: Option<IVirtualDesktopManager>,
+}
+
+impl VirtualDesktops {
+    pub fn new() -> Self {
+        let manager = unsafe {
+            // Already initialized on the UI thread by winit; harmless then
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            CoCreateInstance(&VirtualDesktopManager, None, CLSCTX_ALL).ok()
+        };
+        Self { manager }
+    }
+
+    /// The desktop `hwnd` is on
+    pub fn desktop_of(&self, hwnd: isize) -> Option<u128> {
+        let manager = self.manager.as_ref()?;
+        let id = unsafe { manager.GetWindowDesktopId(HWND(hwnd as *mut _)) }.ok()?;
+        // Windows shown on every desktop report a zero GUID
+        Some(id.to_u128()).filter(|&id| id != 0)
+    }
+
+    /// Is `hwnd` on the desktop being shown? True when it can't be told.
+    pub fn is_on_current(&self, hwnd: isize) -> bool {
+        let Some(manager) = &self.manager else { return true; };
+        unsafe { manager.IsWindowOnCurrentVirtualDesktop(HWND(hwnd as *mut _)) }
+            .map(|on| on.as_bool())
+            .unwrap_or(true)
+    }
+}
+
+/// "Desktop 2" when `hwnd` is on a virtual desktop other than the one
+/// being shown, None when it's on this one (or all of them)
+pub fn other_desktop_label(hwnd: isize) -> Option<String> {
+    let desktops = VirtualDesktops::new();
+    if desktops.is_on_current(hwnd) {
+        return None;
+    }
+    let desktop = desktops.desktop_of(hwnd)?;
+    Some(desktop_label(desktop, &desktop_order()))
+}
+
+/// The virtual desktops in Task View order, as Explorer records them
+pub fn desktop_order() -> Vec<u128> {
+    let mut data = vec![0u8; 16 * 64];
+    let mut size = data.len() as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            w!("Software\\Microsoft\\Windows\\CurrentVersion\\Explorer\\VirtualDesktops"),
+            w!("VirtualDesktopIDs"),
+            RRF_RT_REG_BINARY,
+            None,
+            Some(data.as_mut_ptr().cast()),
+            Some(&mut size),
+        )
+    };
+    if status.is_err() {
+        return Vec::new();
+    }
+    data.truncate(size as usize);
+    parse_desktop_ids(&data)
+}
+
+/// Split the registry's run of 16-byte GUIDs (Windows' mixed-endian
+/// layout) into ids matching `GUID::to_u128`
+fn parse_desktop_ids(data: &[u8]) -> Vec<u128> {
+    data.chunks_exact(16)
+        .map(|b| {
+            let data1 = u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+            let data2 = u16::from_le_bytes([b[4], b[5]]);
+            let data3 = u16::from_le_bytes([b[6], b[7]]);
+            let mut data4 = [0u8; 8];
+            data4.copy_from_slice(&b[8..16]);
+            GUID::from_values(data1, data2, data3, data4).to_u128()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_desktop_ids;
+    use crate::platform::desktop_label;
+    use windows::core::GUID;
+
+    #[test]
+    fn registry_ids_match_window_desktop_ids() {
+        let guid = GUID::from_u128(0x0123_4567_89ab_cdef_0011_2233_4455_6677);
+        let mut data = Vec::new();
+        data.extend_from_slice(&guid.data1.to_le_bytes());
+        data.extend_from_slice(&guid.data2.to_le_bytes());
+        data.extend_from_slice(&guid.data3.to_le_bytes());
+        data.extend_from_slice(&guid.data4);
+        let order = parse_desktop_ids(&data);
+        assert_eq!(order, vec![guid.to_u128()]);
+        assert_eq!(desktop_label(guid.to_u128(), &order), "Desktop 1");
+        assert_eq!(desktop_label(7, &order), "Another desktop");
+    }
+}
@@ -0,0 +1,416 @@
+use super::desktops::VirtualDesktops;
+use crate::platform::{AppIcon, DisplayInfo, WindowInfo};
+use crate::privacy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::os::windows::ffi::{OsStrExt, OsStringExt};
+use std::sync::Arc;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{CloseHandle, BOOL, HWND, LPARAM, RECT};
+use windows::Win32::Graphics::Gdi::{
+    DeleteObject, EnumDisplayMonitors, GetDC, GetDIBits, GetMonitorInfoW, GetObjectW, ReleaseDC,
+    BITMAP, BITMAPINFO, BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HDC, HMONITOR, MONITORINFO,
+    MONITORINFOEXW,
+};
+use windows::Win32::Graphics::Dwm::{
+    DwmGetWindowAttribute, DWMWA_CLOAKED, DWM_CLOAKED_APP, DWM_CLOAKED_INHERITED, DWM_CLOAKED_SHELL,
+};
+use windows::Win32::UI::Shell::ExtractIconExW;
+use windows::Win32::UI::WindowsAndMessaging::{
+    EnumChildWindows, EnumWindows, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
+    IsWindowVisible, GetWindowLongW, GWL_EXSTYLE,
+    WS_EX_TOOLWINDOW, WS_EX_APPWINDOW,
+    GetClassNameW, GetAncestor, GA_ROOTOWNER, MONITORINFOF_PRIMARY,
+    DestroyIcon, GetIconInfo, HICON, ICONINFO,
+};
+use windows::Win32::System::Threading::{
+    OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+    PROCESS_QUERY_LIMITED_INFORMATION,
+};
+
+/// Icons already extracted, by executable path (None: the exe has none).
+/// Windows come and go every refresh, their executables rarely do.
+static ICON_CACHE: Mutex<Option<HashMap<String, Option<Arc<AppIcon>>>>> = Mutex::new(None);
+
+/// Enumerate the connected displays, in Windows' numbering order
+pub fn enumerate_displays() -> Vec<DisplayInfo> {
+    let mut displays: Vec<DisplayInfo> = Vec::new();
+
+    unsafe {
+        let _ = EnumDisplayMonitors(
+            HDC::default(),
+            None,
+            Some(enum_display_callback),
+            LPARAM(&mut displays as *mut _ as isize),
+        );
+    }
+
+    displays.sort_by_key(|d| display_number(&d.name));
+    displays
+}
+
+/// Callback for EnumDisplayMonitors
+unsafe extern "system" fn enum_display_callback(monitor: HMONITOR, _: HDC, _: *mut RECT, lparam: LPARAM) -> BOOL {
+    let displays = &mut *(lparam.0 as *mut Vec<DisplayInfo>);
+
+    let mut info = MONITORINFOEXW::default();
+    info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+    if !GetMonitorInfoW(monitor, &mut info as *mut MONITORINFOEXW as *mut MONITORINFO).as_bool() {
+        return BOOL(1);
+    }
+
+    let rect = info.monitorInfo.rcMonitor;
+    let name_len = info.szDevice.iter().position(|&c| c == 0).unwrap_or(info.szDevice.len());
+    let device = String::from_utf16_lossy(&info.szDevice[..name_len]);
+    displays.push(DisplayInfo {
+        handle: monitor.0 as isize,
+        name: name_for_device(&device),
+        width: (rect.right - rect.left).max(0) as u32,
+        height: (rect.bottom - rect.top).max(0) as u32,
+        primary: info.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0,
+    });
+
+    BOOL(1) // Continue enumeration
+}
+
+/// "Display 2" for the device `\\.\DISPLAY2`
+fn name_for_device(device: &str) -> String {
+    match device.rsplit_once("DISPLAY") {
+        Some((_, number)) if !number.is_empty() => format!("Display {}", number),
+        _ => device.to_string(),
+    }
+}
+
+/// Sort key putting "Display 10" after "Display 9"
+fn display_number(name: &str) -> u32 {
+    name.trim_start_matches("Display ").parse().unwrap_or(u32::MAX)
+}
+
+/// Enumerate all visible windows suitable for capture
+pub fn enumerate_windows() -> Vec<WindowInfo> {
+    let mut windows: Vec<WindowInfo> = Vec::new();
+
+    unsafe {
+        let _ = EnumWindows(
+            Some(enum_window_callback),
+            LPARAM(&mut windows as *mut _ as isize),
+        );
+    }
+
+    let desktops = VirtualDesktops::new();
+    for window in &mut windows {
+        window.desktop = desktops.desktop_of(window.hwnd);
+        window.on_current_desktop = desktops.is_on_current(window.hwnd);
+    }
+    windows.retain(|w| shows_despite_cloak(cloak_of(w.hwnd), w.on_current_desktop));
+
+    // Sort by title; same-titled windows on this desktop come first, so
+    // restoring a layout doesn't pick one from another desktop
+    windows.sort_by_cached_key(|w| (w.display_name().to_lowercase(), !w.on_current_desktop));
+
+    windows
+}
+
+/// Callback for EnumWindows
+unsafe extern "system" fn enum_window_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let windows = &mut *(lparam.0 as *mut Vec<WindowInfo>);
+
+    // Skip invisible windows
+    if !IsWindowVisible(hwnd).as_bool() {
+        return BOOL(1);
+    }
+
+    // Get extended window style
+    let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
+
+    // Skip tool windows unless they have app window style
+    if (ex_style & WS_EX_TOOLWINDOW.0) != 0 && (ex_style & WS_EX_APPWINDOW.0) == 0 {
+        return BOOL(1);
+    }
+
+    // Note: Removed WS_CAPTION check - it was too strict and excluded video players
+    // (VLC, MPV, PotPlayer) that use borderless/custom windows
+
+    // Skip windows that aren't root owners
+    let root_owner = GetAncestor(hwnd, GA_ROOTOWNER);
+    if root_owner != hwnd {
+        return BOOL(1);
+    }
+
+    // Skip windows without titles
+    let Some(title) = window_title(hwnd.0 as isize) else {
+        return BOOL(1);
+    };
+
+    // Get class name
+    let mut class_buffer: Vec<u16> = vec![0; 256];
+    let class_len = GetClassNameW(hwnd, &mut class_buffer);
+    let class_name = if class_len > 0 {
+        OsString::from_wide(&class_buffer[..class_len as usize])
+            .to_string_lossy()
+            .to_string()
+    } else {
+        String::new()
+    };
+
+    // Get process ID. UWP apps live inside an ApplicationFrameHost frame;
+    // name them after the app's own process, which owns the frame's content.
+    let mut process_id: u32 = 0;
+    GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+    if class_name == "ApplicationFrameWindow" {
+        match uwp_app_process(hwnd, process_id) {
+            Some(app_process) => process_id = app_process,
+            // An empty frame: the app is suspended or still starting
+            None => return BOOL(1),
+        }
+    }
+
+    // Get executable name and icon
+    let exe_path = get_process_path(process_id);
+    let exe_name = match &exe_path {
+        Some(path) => path.rsplit('\\').next().unwrap_or(path).to_string(),
+        None => String::from("Unknown"),
+    };
+
+    // Skip certain system classes
+    let skip_classes = [
+        "Progman",            // Program Manager
+        "WorkerW",            // Desktop background
+        "Shell_TrayWnd",      // Taskbar
+        "Windows.UI.Core.CoreWindow", // Some UWP overlay windows
+    ];
+
+    if skip_classes.iter().any(|&c| class_name == c) {
+        return BOOL(1);
+    }
+
+    // Skip our own window
+    if title.contains("Pluriview") {
+        return BOOL(1);
+    }
+
+    // Skip sensitive windows (Password managers, browsers with "private" in title, etc.)
+    if privacy::is_sensitive_window(&exe_name, &title) {
+        return BOOL(1);
+    }
+
+    windows.push(WindowInfo {
+        hwnd: hwnd.0 as isize,
+        title,
+        process_id,
+        exe_name,
+        class_name,
+        icon: exe_path.and_then(|path| app_icon(&path)),
+        // EnumWindows walks the windows front to back
+        z_order: windows.len(),
+        desktop: None,
+        on_current_desktop: true,
+        elevated: crate::elevation::blocks_capture(process_id),
+    });
+
+    BOOL(1) // Continue enumeration
+}
+
+/// The process owning the content of a UWP app's frame: a child window from
+/// another process than the frame host
+unsafe fn uwp_app_process(frame: HWND, host_process: u32) -> Option<u32> {
+    struct Search {
+        host_process: u32,
+        found: Option<u32>,
+    }
+
+    unsafe extern "system" fn visit(child: HWND, lparam: LPARAM) -> BOOL {
+        let search = &mut *(lparam.0 as *mut Search);
+        let mut process_id: u32 = 0;
+        GetWindowThreadProcessId(child, Some(&mut process_id));
+        if process_id != 0 && process_id != search.host_process {
+            search.found = Some(process_id);
+            return BOOL(0); // Stop
+        }
+        BOOL(1)
+    }
+
+    let mut search = Search { host_process, found: None };
+    let _ = EnumChildWindows(frame, Some(visit), LPARAM(&mut search as *mut _ as isize));
+    search.found
+}
+
+/// Why DWM isn't drawing a window (DWM_CLOAKED_* flags), 0 if it is
+fn cloak_of(hwnd: isize) -> u32 {
+    let mut cloaked: u32 = 0;
+    let result = unsafe {
+        DwmGetWindowAttribute(
+            HWND(hwnd as *mut _),
+            DWMWA_CLOAKED,
+            &mut cloaked as *mut u32 as *mut _,
+            std::mem::size_of::<u32>() as u32,
+        )
+    };
+    if result.is_ok() { cloaked } else { 0 }
+}
+
+/// Cloaked windows look visible but draw nothing: hidden UWP shells and
+/// windows an app cloaked itself. The shell also cloaks the windows of other
+/// virtual desktops, which are real and stay listed.
+fn shows_despite_cloak(cloak: u32, on_current_desktop: bool) -> bool {
+    if cloak & (DWM_CLOAKED_APP | DWM_CLOAKED_INHERITED) != 0 {
+        return false;
+    }
+    cloak & DWM_CLOAKED_SHELL == 0 || !on_current_desktop
+}
+
+/// The small icon of the executable at `path`, extracted once and cached
+fn app_icon(path: &str) -> Option<Arc<AppIcon>> {
+    let mut cache = ICON_CACHE.lock();
+    let cache = cache.get_or_insert_with(HashMap::new);
+    cache
+        .entry(path.to_string())
+        .or_insert_with(|| extract_icon(path).map(Arc::new))
+        .clone()
+}
+
+/// Read the first small icon out of an executable
+fn extract_icon(path: &str) -> Option<AppIcon> {
+    let wide: Vec<u16> = std::ffi::OsStr::new(path).encode_wide().chain(Some(0)).collect();
+    unsafe {
+        let mut icon = HICON::default();
+        if ExtractIconExW(PCWSTR(wide.as_ptr()), 0, None, Some(&mut icon), 1) == 0 || icon.is_invalid() {
+            return None;
+        }
+        let pixels = icon_pixels(icon);
+        let _ = DestroyIcon(icon);
+        let (width, height, rgba) = pixels?;
+        Some(AppIcon { key: path.to_string(), width, height, rgba })
+    }
+}
+
+/// Copy an icon's color bitmap out as RGBA. Icons without alpha (all
+/// zero) are shown opaque rather than invisible.
+unsafe fn icon_pixels(icon: HICON) -> Option<(u32, u32, Vec<u8>)> {
+    let mut info = ICONINFO::default();
+    GetIconInfo(icon, &mut info).ok()?;
+    let color = info.hbmColor;
+    let _ = DeleteObject(info.hbmMask);
+    if color.is_invalid() {
+        return None;
+    }
+
+    let mut bitmap = BITMAP::default();
+    let got = GetObjectW(color, std::mem::size_of::<BITMAP>() as i32, Some(&mut bitmap as *mut BITMAP as *mut _));
+    let (width, height) = (bitmap.bmWidth, bitmap.bmHeight);
+    if got == 0 || width <= 0 || height <= 0 {
+        let _ = DeleteObject(color);
+        return None;
+    }
+
+    let mut header = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            // Negative height requests top-down rows
+            biHeight: -height,
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut data = vec![0u8; width as usize * height as usize * 4];
+    let screen = GetDC(HWND::default());
+    let lines = GetDIBits(screen, color, 0, height as u32, Some(data.as_mut_ptr().cast()), &mut header, DIB_RGB_COLORS);
+    ReleaseDC(HWND::default(), screen);
+    let _ = DeleteObject(color);
+    if lines == 0 {
+        return None;
+    }
+
+    bgra_to_rgba(&mut data);
+    Some((width as u32, height as u32, data))
+}
+
+/// Swap BGRA to RGBA in place, making the image opaque if it carries no
+/// alpha at all
+fn bgra_to_rgba(data: &mut [u8]) {
+    let has_alpha = data.chunks_exact(4).any(|p| p[3] != 0);
+    for pixel in data.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+        if !has_alpha {
+            pixel[3] = 255;
+        }
+    }
+}
+
+/// A window's current title; None if it has none
+pub fn window_title(hwnd: isize) -> Option<String> {
+    let hwnd = HWND(hwnd as *mut _);
+    let title_len = unsafe { GetWindowTextLengthW(hwnd) };
+    if title_len == 0 {
+        return None;
+    }
+
+    let mut title_buffer: Vec<u16> = vec![0; (title_len + 1) as usize];
+    let actual_len = unsafe { GetWindowTextW(hwnd, &mut title_buffer) };
+    if actual_len <= 0 {
+        return None;
+    }
+    Some(OsString::from_wide(&title_buffer[..actual_len as usize]).to_string_lossy().to_string())
+}
+
+/// Get the process name from a process ID
+pub fn get_process_name(process_id: u32) -> String {
+    match get_process_path(process_id) {
+        // Extract just the filename
+        Some(path) => path.rsplit('\\').next().unwrap_or(&path).to_string(),
+        None => String::from("Unknown"),
+    }
+}
+
+/// Get the full executable path of a process
+pub fn get_process_path(process_id: u32) -> Option<String> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process_id).ok()?;
+
+        let mut buffer: Vec<u16> = vec![0; 260];
+        let mut size = buffer.len() as u32;
+
+        let result = QueryFullProcessImageNameW(handle, PROCESS_NAME_WIN32, windows::core::PWSTR(buffer.as_mut_ptr()), &mut size);
+        let _ = CloseHandle(handle);
+        result.ok()?;
+        Some(OsString::from_wide(&buffer[..size as usize]).to_string_lossy().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bgra_to_rgba, display_number, name_for_device, shows_despite_cloak};
+    use windows::Win32::Graphics::Dwm::{DWM_CLOAKED_APP, DWM_CLOAKED_SHELL};
+
+    #[test]
+    fn displays_are_named_by_their_device_number() {
+        assert_eq!(name_for_device("\\\\.\\DISPLAY2"), "Display 2");
+        assert_eq!(name_for_device("Virtual"), "Virtual");
+        assert!(display_number("Display 9") < display_number("Display 10"));
+    }
+
+    #[test]
+    fn only_other_desktops_windows_stay_while_cloaked() {
+        assert!(shows_despite_cloak(0, true));
+        // A suspended UWP shell on this desktop
+        assert!(!shows_despite_cloak(DWM_CLOAKED_SHELL, true));
+        // A window on another virtual desktop
+        assert!(shows_despite_cloak(DWM_CLOAKED_SHELL, false));
+        assert!(!shows_despite_cloak(DWM_CLOAKED_APP, false));
+    }
+
+    #[test]
+    fn icons_without_alpha_become_opaque() {
+        let mut pixels = vec![1, 2, 3, 0, 4, 5, 6, 0];
+        bgra_to_rgba(&mut pixels);
+        assert_eq!(pixels, vec![3, 2, 1, 255, 6, 5, 4, 255]);
+        let mut pixels = vec![1, 2, 3, 128, 4, 5, 6, 0];
+        bgra_to_rgba(&mut pixels);
+        assert_eq!(pixels, vec![3, 2, 1, 128, 6, 5, 4, 0]);
+    }
+}
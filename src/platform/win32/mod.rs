@@ -0,0 +1,86 @@
+//! Windows implementation of the platform traits
+
+mod desktops;
+mod enumerator;
+
+use super::{DisplayInfo, WindowFocus, WindowInfo, WindowSource};
+use windows::Win32::Foundation::{HWND, POINT};
+use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+use windows::Win32::UI::WindowsAndMessaging::{
+    GetAncestor, GetCursorPos, GetForegroundWindow, IsIconic, IsWindowVisible, SetForegroundWindow,
+    ShowWindow, GA_ROOTOWNER, SW_RESTORE,
+};
+
+/// Win32, DWM and the shell's virtual desktop manager
+pub struct Win32;
+
+impl WindowSource for Win32 {
+    fn windows() -> Vec<WindowInfo> {
+        enumerator::enumerate_windows()
+    }
+
+    fn displays() -> Vec<DisplayInfo> {
+        enumerator::enumerate_displays()
+    }
+
+    fn window_title(hwnd: isize) -> Option<String> {
+        enumerator::window_title(hwnd)
+    }
+
+    fn process_name(process_id: u32) -> String {
+        enumerator::get_process_name(process_id)
+    }
+
+    fn process_path(process_id: u32) -> Option<String> {
+        enumerator::get_process_path(process_id)
+    }
+
+    fn desktop_order() -> Vec<u128> {
+        desktops::desktop_order()
+    }
+
+    fn other_desktop_label(hwnd: isize) -> Option<String> {
+        desktops::other_desktop_label(hwnd)
+    }
+}
+
+impl WindowFocus for Win32 {
+    fn foreground_window() -> Option<(isize, isize)> {
+        let hwnd = unsafe { GetForegroundWindow() };
+        if hwnd.0.is_null() {
+            return None;
+        }
+        let owner = unsafe { GetAncestor(hwnd, GA_ROOTOWNER) };
+        let owner = if owner.0.is_null() { hwnd } else { owner };
+        Some((hwnd.0 as isize, owner.0 as isize))
+    }
+
+    fn last_input() -> Option<(u32, (i32, i32))> {
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        let mut cursor = POINT::default();
+        unsafe {
+            if !GetLastInputInfo(&mut info).as_bool() || GetCursorPos(&mut cursor).is_err() {
+                return None;
+            }
+        }
+        Some((info.dwTime, (cursor.x, cursor.y)))
+    }
+
+    fn focus_window(hwnd: isize, restore: bool) {
+        let hwnd = HWND(hwnd as *mut _);
+        unsafe {
+            if restore {
+                let _ = ShowWindow(hwnd, SW_RESTORE);
+            }
+            let _ = SetForegroundWindow(hwnd);
+        }
+    }
+
+    fn is_hidden(hwnd: isize) -> bool {
+        let hwnd = HWND(hwnd as *mut _);
+        unsafe { IsIconic(hwnd).as_bool() || !IsWindowVisible(hwnd).as_bool() }
+    }
+}
@@ -0,0 +1,43 @@
+use std::time::{Duration, Instant};
+
+/// Capture rate while power saver is throttling.
+pub const POWER_SAVER_FPS: u32 = 2;
+
+/// How often the battery state is re-read (it's a syscall; focus is free).
+const BATTERY_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Decides when power saver should throttle: the option is on and the
+/// Pluriview window is unfocused or the machine is running on battery.
+#[derive(Default)]
+pub struct PowerSaver {
+    on_battery: bool,
+    last_battery_check: Option<Instant>,
+}
+
+impl PowerSaver {
+    /// Call once per frame; returns true while captures should be throttled.
+    pub fn should_throttle(&mut self, enabled: bool, window_focused: bool) -> bool {
+        if !enabled {
+            return false;
+        }
+        if self.last_battery_check.map_or(true, |t| t.elapsed() >= BATTERY_POLL_INTERVAL) {
+            self.on_battery = on_battery_power();
+            self.last_battery_check = Some(Instant::now());
+        }
+        !window_focused || self.on_battery
+    }
+}
+
+/// True if the system reports it is running on battery (not AC) power.
+fn on_battery_power() -> bool {
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+        let mut status = SYSTEM_POWER_STATUS::default();
+        // ACLineStatus: 0 = offline (battery), 1 = online, 255 = unknown
+        unsafe { GetSystemPowerStatus(&mut status) }.is_ok() && status.ACLineStatus == 0
+    }
+    #[cfg(not(windows))]
+    false
+}
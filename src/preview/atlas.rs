@@ -0,0 +1,247 @@
+use eframe::egui::{self, ecolor, Pos2, Rect, TextureHandle, TextureId};
+use crate::capture::downscale_box;
+use super::preview::{AlphaMode, FrameData};
+
+/// Side length of one atlas page texture, in pixels.
+const PAGE_SIZE: usize = 2048;
+
+/// Side length of one slot inside a page. Frames are downscaled to fit.
+const SLOT_SIZE: usize = 256;
+
+/// Empty border kept around each slot's content so linear filtering never
+/// samples a neighbouring tile.
+const GUTTER: usize = 1;
+
+const SLOTS_PER_ROW: usize = PAGE_SIZE / SLOT_SIZE;
+const SLOTS_PER_PAGE: usize = SLOTS_PER_ROW * SLOTS_PER_ROW;
+
+/// Tiles whose largest on-screen side is at or below this (physical
+/// pixels) are packed into the atlas instead of owning a texture.
+pub const ATLAS_MAX_SIDE: f32 = SLOT_SIZE as f32;
+
+/// Atlas tiles enlarged past this get promoted to a dedicated texture. The
+/// gap to `ATLAS_MAX_SIDE` keeps zooming near the boundary from thrashing.
+pub const PROMOTE_SIDE: f32 = 320.0;
+
+/// A reserved slot in the atlas.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AtlasSlot {
+    page: usize,
+    index: usize,
+}
+
+struct AtlasPage {
+    texture: TextureHandle,
+    used: [bool; SLOTS_PER_PAGE],
+}
+
+/// Shared texture pages for small previews. A wall of 50+ thumbnails would
+/// otherwise mean 50+ full-resolution textures; here they share a handful of
+/// pages, each frame downscaled to its slot on upload.
+#[derive(Default)]
+pub struct TextureAtlas {
+    pages: Vec<AtlasPage>,
+}
+
+impl TextureAtlas {
+    /// Reserve a free slot, adding a page if every page is full.
+    pub fn allocate(&mut self, ctx: &egui::Context) -> AtlasSlot {
+        for (page_index, page) in self.pages.iter_mut().enumerate() {
+            if let Some(index) = page.used.iter().position(|used| !used) {
+                page.used[index] = true;
+                return AtlasSlot { page: page_index, index };
+            }
+        }
+
+        let texture = ctx.load_texture(
+            format!("preview_atlas_{}", self.pages.len()),
+            egui::ColorImage::new([PAGE_SIZE, PAGE_SIZE], egui::Color32::TRANSPARENT),
+            egui::TextureOptions::LINEAR,
+        );
+        let mut used = [false; SLOTS_PER_PAGE];
+        used[0] = true;
+        self.pages.push(AtlasPage { texture, used });
+        AtlasSlot { page: self.pages.len() - 1, index: 0 }
+    }
+
+    /// Return a slot to the pool.
+    pub fn release(&mut self, slot: AtlasSlot) {
+        if let Some(page) = self.pages.get_mut(slot.page) {
+            page.used[slot.index] = false;
+        }
+    }
+
+    /// Number of slots currently in use (for diagnostics).
+    #[allow(dead_code)]
+    pub fn used_slots(&self) -> usize {
+        self.pages
+            .iter()
+            .map(|page| page.used.iter().filter(|used| **used).count())
+            .sum()
+    }
+
+    /// Texture holding the given slot.
+    pub fn texture_id(&self, slot: AtlasSlot) -> Option<TextureId> {
+        self.pages.get(slot.page).map(|page| page.texture.id())
+    }
+
+    /// Downscale `frame` into `slot` and return the UV rect of the content
+    /// within the page texture. With `linear_scaling` the frame is averaged
+    /// in linear light instead of on its sRGB values.
+    pub fn upload(
+        &mut self,
+        slot: AtlasSlot,
+        frame: &FrameData,
+        alpha: AlphaMode,
+        linear_scaling: bool,
+    ) -> Rect {
+        let (width, height) = fit_to_slot(frame.width as usize, frame.height as usize);
+        let pixels = if linear_scaling {
+            downscale_linear_light(frame, width, height)
+        } else {
+            downscale_box(&frame.data, (frame.width as usize, frame.height as usize), (width, height), false)
+        };
+        let image = alpha.to_color_image([width, height], &pixels);
+
+        let origin = slot_origin(slot.index);
+        if let Some(page) = self.pages.get_mut(slot.page) {
+            page.texture.set_partial(origin, image, egui::TextureOptions::LINEAR);
+        }
+
+        // Inset by half a texel so sampling stays inside the content.
+        let page = PAGE_SIZE as f32;
+        Rect::from_min_max(
+            Pos2::new(
+                (origin[0] as f32 + 0.5) / page,
+                (origin[1] as f32 + 0.5) / page,
+            ),
+            Pos2::new(
+                (origin[0] + width) as f32 / page - 0.5 / page,
+                (origin[1] + height) as f32 / page - 0.5 / page,
+            ),
+        )
+    }
+}
+
+/// Map a crop UV rect (relative to the whole frame) into the slot's UV rect.
+pub fn sub_uv(slot_uv: Rect, crop_uv: Rect) -> Rect {
+    Rect::from_min_max(
+        slot_uv.min + crop_uv.min.to_vec2() * slot_uv.size(),
+        slot_uv.min + crop_uv.max.to_vec2() * slot_uv.size(),
+    )
+}
+
+/// Top-left pixel of a slot's content area within its page.
+fn slot_origin(index: usize) -> [usize; 2] {
+    [
+        (index % SLOTS_PER_ROW) * SLOT_SIZE + GUTTER,
+        (index / SLOTS_PER_ROW) * SLOT_SIZE + GUTTER,
+    ]
+}
+
+/// Largest size with the frame's aspect ratio that fits inside a slot.
+fn fit_to_slot(width: usize, height: usize) -> (usize, usize) {
+    let max = (SLOT_SIZE - 2 * GUTTER) as f32;
+    let scale = (max / width.max(1) as f32).min(max / height.max(1) as f32).min(1.0);
+    (
+        ((width as f32 * scale).round() as usize).max(1),
+        ((height as f32 * scale).round() as usize).max(1),
+    )
+}
+
+/// Box-filter an sRGB RGBA frame down to `width` x `height`, averaging in
+/// linear light. Averaging the encoded values (what a naive filter does)
+/// darkens fine detail like text and thin lines.
+fn downscale_linear_light(frame: &FrameData, width: usize, height: usize) -> Vec<u8> {
+    let src_w = frame.width as usize;
+    let src_h = frame.height as usize;
+    let mut out = vec![0u8; width * height * 4];
+    if src_w == 0 || src_h == 0 || frame.data.len() < src_w * src_h * 4 {
+        return out;
+    }
+
+    for y in 0..height {
+        let (y0, y1) = (y * src_h / height, ((y + 1) * src_h / height).max(y * src_h / height + 1));
+        for x in 0..width {
+            let (x0, x1) = (x * src_w / width, ((x + 1) * src_w / width).max(x * src_w / width + 1));
+
+            // Colors are weighted by alpha so transparent pixels don't bleed
+            let mut sum = [0.0f32; 3];
+            let mut alpha_sum = 0.0f32;
+            for sy in y0..y1 {
+                for sx in x0..x1 {
+                    let src = &frame.data[(sy * src_w + sx) * 4..][..4];
+                    let a = src[3] as f32 / 255.0;
+                    for (c, value) in sum.iter_mut().zip(src) {
+                        *c += ecolor::linear_f32_from_gamma_u8(*value) * a;
+                    }
+                    alpha_sum += a;
+                }
+            }
+
+            let count = ((y1 - y0) * (x1 - x0)) as f32;
+            let dst = &mut out[(y * width + x) * 4..][..4];
+            if alpha_sum > 0.0 {
+                for (d, c) in dst.iter_mut().zip(sum) {
+                    *d = ecolor::gamma_u8_from_linear_f32(c / alpha_sum);
+                }
+            }
+            dst[3] = (alpha_sum / count * 255.0).round() as u8;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{downscale_linear_light, fit_to_slot, sub_uv, TextureAtlas};
+    use crate::preview::preview::FrameData;
+    use eframe::egui::{Context, Pos2, Rect};
+    use std::sync::Arc;
+
+    #[test]
+    fn released_slots_are_reused() {
+        let ctx = Context::default();
+        let mut atlas = TextureAtlas::default();
+
+        let first = atlas.allocate(&ctx);
+        let second = atlas.allocate(&ctx);
+        atlas.release(first);
+
+        assert_eq!(atlas.allocate(&ctx), first);
+        assert_ne!(first, second);
+        assert_eq!(atlas.used_slots(), 2);
+    }
+
+    #[test]
+    fn frames_fit_inside_a_slot_keeping_aspect() {
+        assert_eq!(fit_to_slot(1920, 1080), (254, 143));
+        assert_eq!(fit_to_slot(100, 50), (100, 50));
+    }
+
+    #[test]
+    fn linear_light_downscale_keeps_perceived_brightness() {
+        // One black and one white pixel average to mid-grey in linear light,
+        // which encodes to ~188, not the 128 of averaging sRGB values.
+        let frame = FrameData {
+            width: 2,
+            height: 1,
+            data: Arc::new(vec![0, 0, 0, 255, 255, 255, 255, 255]),
+        };
+
+        let pixels = downscale_linear_light(&frame, 1, 1);
+
+        assert_eq!(pixels, vec![188, 188, 188, 255]);
+    }
+
+    #[test]
+    fn crop_maps_into_slot_uv() {
+        let slot = Rect::from_min_max(Pos2::new(0.5, 0.5), Pos2::new(0.75, 0.75));
+        let crop = Rect::from_min_max(Pos2::new(0.0, 0.5), Pos2::new(0.5, 1.0));
+
+        let uv = sub_uv(slot, crop);
+
+        assert_eq!(uv.min, Pos2::new(0.5, 0.625));
+        assert_eq!(uv.max, Pos2::new(0.625, 0.75));
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+/// A post-processing filter the tile shader runs over a preview's frame.
+///
+/// Each effect is a GLSL function `vec4 effect(vec2 uv)` spliced into the
+/// tile fragment shader. It can call `source_at(uv)` to sample the frame
+/// (linear color, clamped to the tile's own crop so atlas neighbours don't
+/// bleed in) and read `texel`, the size of one source pixel in UV units.
+/// Adding a filter means implementing this trait and listing it in
+/// [`EffectKind`].
+pub trait Effect: Sync {
+    /// GLSL source of `vec4 effect(vec2 uv)`
+    fn glsl(&self) -> &'static str;
+}
+
+/// The effects a preview can be drawn with; saved with the layout
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EffectKind {
+    Blur,
+    Sharpen,
+    EdgeDetect,
+    Grayscale,
+}
+
+impl EffectKind {
+    pub const ALL: [Self; 4] = [Self::Blur, Self::Sharpen, Self::EdgeDetect, Self::Grayscale];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Blur => "Blur",
+            Self::Sharpen => "Sharpen",
+            Self::EdgeDetect => "Edge Detect",
+            Self::Grayscale => "Grayscale",
+        }
+    }
+
+    pub fn effect(self) -> &'static dyn Effect {
+        match self {
+            Self::Blur => &Blur,
+            Self::Sharpen => &Sharpen,
+            Self::EdgeDetect => &EdgeDetect,
+            Self::Grayscale => &Grayscale,
+        }
+    }
+}
+
+/// Color made transparent, so a capture with a solid background can be
+/// layered over other previews
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChromaKey {
+    /// sRGB
+    pub color: [u8; 3],
+    /// How far (0-1, distance in RGB) a pixel can be from `color` and
+    /// still be keyed out
+    pub tolerance: f32,
+}
+
+impl Default for ChromaKey {
+    fn default() -> Self {
+        Self { color: [0, 255, 0], tolerance: 0.3 }
+    }
+}
+
+/// The frame as it is, for previews without an effect
+pub struct Passthrough;
+
+impl Effect for Passthrough {
+    fn glsl(&self) -> &'static str {
+        "vec4 effect(vec2 uv) { return source_at(uv); }"
+    }
+}
+
+/// 5×5 box blur, a few source pixels wide; enough to hide text on screen
+struct Blur;
+
+impl Effect for Blur {
+    fn glsl(&self) -> &'static str {
+        r#"
+vec4 effect(vec2 uv) {
+    vec4 sum = vec4(0.0);
+    for (int y = -2; y <= 2; y++) {
+        for (int x = -2; x <= 2; x++) {
+            sum += source_at(uv + vec2(float(x), float(y)) * texel * 2.0);
+        }
+    }
+    return sum / 25.0;
+}
+"#
+    }
+}
+
+/// Unsharp mask over the four direct neighbours
+struct Sharpen;
+
+impl Effect for Sharpen {
+    fn glsl(&self) -> &'static str {
+        r#"
+vec4 effect(vec2 uv) {
+    vec4 center = source_at(uv);
+    vec4 around = source_at(uv + vec2(texel.x, 0.0)) + source_at(uv - vec2(texel.x, 0.0))
+        + source_at(uv + vec2(0.0, texel.y)) + source_at(uv - vec2(0.0, texel.y));
+    return vec4(clamp(center.rgb * 5.0 - around.rgb, 0.0, 1.0), center.a);
+}
+"#
+    }
+}
+
+/// Sobel edge magnitude, white edges on black
+struct EdgeDetect;
+
+impl Effect for EdgeDetect {
+    fn glsl(&self) -> &'static str {
+        r#"
+float luma_at(vec2 uv) {
+    return dot(source_at(uv).rgb, vec3(0.2126, 0.7152, 0.0722));
+}
+
+vec4 effect(vec2 uv) {
+    vec2 t = texel;
+    float tl = luma_at(uv + vec2(-t.x, -t.y));
+    float tc = luma_at(uv + vec2(0.0, -t.y));
+    float tr = luma_at(uv + vec2(t.x, -t.y));
+    float ml = luma_at(uv + vec2(-t.x, 0.0));
+    float mr = luma_at(uv + vec2(t.x, 0.0));
+    float bl = luma_at(uv + vec2(-t.x, t.y));
+    float bc = luma_at(uv + vec2(0.0, t.y));
+    float br = luma_at(uv + vec2(t.x, t.y));
+    float gx = (tr + 2.0 * mr + br) - (tl + 2.0 * ml + bl);
+    float gy = (bl + 2.0 * bc + br) - (tl + 2.0 * tc + tr);
+    float edge = clamp(length(vec2(gx, gy)), 0.0, 1.0);
+    return vec4(vec3(edge), source_at(uv).a);
+}
+"#
+    }
+}
+
+/// Luminance only
+struct Grayscale;
+
+impl Effect for Grayscale {
+    fn glsl(&self) -> &'static str {
+        r#"
+vec4 effect(vec2 uv) {
+    vec4 color = source_at(uv);
+    return vec4(vec3(dot(color.rgb, vec3(0.2126, 0.7152, 0.0722))), color.a);
+}
+"#
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EffectKind;
+
+    #[test]
+    fn every_effect_defines_the_entry_point() {
+        for kind in EffectKind::ALL {
+            assert!(kind.effect().glsl().contains("vec4 effect(vec2 uv)"), "{}", kind.label());
+        }
+    }
+}
@@ -1,23 +1,121 @@
-use eframe::egui::{Pos2, Vec2, Rect};
+use eframe::egui::{self, Pos2, Vec2, Rect, TextureId};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
-use super::{Preview, PreviewId, FpsPreset, WindowHandle};
+use super::{Preview, PreviewId, PreviewLayout, FpsPreset, CaptureOptions, ChromaKey, DisplayMode, EffectKind, FrameOverlays, Watermark, WindowHandle};
+use super::atlas::{self, TextureAtlas};
 
-/// Snapshot of a preview captured right before it's actually dropped from
-/// the manager, so the canvas can offer an "Undo" toast that restores it.
+/// Source of preview ids. It is process-wide rather than per manager so
+/// previews in different workspaces never share an id: capture sessions
+/// and browser hosts are keyed by it across all of them.
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Snapshot of a preview, enough to recreate it: taken right before it's
+/// dropped from the manager so the canvas can offer an "Undo" toast, and
+/// for every tile in the canvas's undo history.
 #[derive(Clone)]
 pub struct RemovedPreviewInfo {
+    /// The id it had; a recreated preview gets a new one
+    pub id: PreviewId,
     pub title: String,
     pub window_handle: Option<WindowHandle>,
+    pub window_exe: Option<String>,
+    pub window_class: Option<String>,
     pub position: Pos2,
     pub size: Vec2,
     pub fps_preset: FpsPreset,
+    pub capture: CaptureOptions,
     pub crop_uv: Option<(f32, f32, f32, f32)>,
+    pub rotation: u8,
+    pub flip_h: bool,
+    pub flip_v: bool,
+    pub display_mode: DisplayMode,
+    pub z_order: u32,
     /// Set for browser tiles; undo recreates the WebView from this URL
     /// because the original host window is destroyed on removal.
     pub browser_url: Option<String>,
     /// Reapplied when undo recreates the browser tile.
     pub browser_muted: bool,
+    pub note: Option<String>,
+    pub fps_group: Option<String>,
+    pub container: Option<String>,
+    pub hidden: bool,
+    pub nickname: Option<String>,
+    pub effect: Option<EffectKind>,
+    pub chroma_key: Option<ChromaKey>,
+    pub overlays: FrameOverlays,
+    pub watermark: Option<Watermark>,
+}
+
+impl RemovedPreviewInfo {
+    pub fn of(preview: &Preview) -> Self {
+        Self {
+            id: preview.id,
+            title: preview.title.clone(),
+            window_handle: preview.window_handle.clone(),
+            window_exe: preview.window_exe.clone(),
+            window_class: preview.window_class.clone(),
+            position: preview.position,
+            size: preview.size,
+            fps_preset: preview.fps_preset,
+            capture: preview.capture,
+            crop_uv: preview.crop_uv,
+            rotation: preview.rotation,
+            flip_h: preview.flip_h,
+            flip_v: preview.flip_v,
+            display_mode: preview.display_mode,
+            z_order: preview.z_order,
+            browser_url: preview.browser_url.clone(),
+            browser_muted: preview.browser_muted,
+            note: preview.note.clone(),
+            fps_group: preview.fps_group.clone(),
+            container: preview.container.clone(),
+            hidden: preview.hidden,
+            nickname: preview.nickname.clone(),
+            effect: preview.effect,
+            chroma_key: preview.chroma_key,
+            overlays: preview.overlays,
+            watermark: preview.watermark.clone(),
+        }
+    }
+}
+
+/// The layout a removed preview would have saved with. What removal
+/// doesn't keep (aspect lock, last-seen time, frame size, stats) comes out
+/// as a new preview's.
+impl From<&RemovedPreviewInfo> for PreviewLayout {
+    fn from(info: &RemovedPreviewInfo) -> Self {
+        Self {
+            position: (info.position.x, info.position.y),
+            size: (info.size.x, info.size.y),
+            window_title: info.title.clone(),
+            window_exe: info.window_exe.clone(),
+            window_class: info.window_class.clone(),
+            lock_aspect_ratio: true,
+            z_order: info.z_order,
+            fps_preset: info.fps_preset,
+            capture: info.capture,
+            crop_uv: info.crop_uv,
+            browser_url: info.browser_url.clone(),
+            browser_muted: info.browser_muted,
+            last_seen: None,
+            frame_size: None,
+            last_stats: None,
+            rotation: info.rotation,
+            flip_h: info.flip_h,
+            flip_v: info.flip_v,
+            display_mode: info.display_mode,
+            note: info.note.clone(),
+            fps_group: info.fps_group.clone(),
+            container: info.container.clone(),
+            hidden: info.hidden,
+            nickname: info.nickname.clone(),
+            effect: info.effect,
+            chroma_key: info.chroma_key,
+            overlays: info.overlays,
+            watermark: info.watermark.clone(),
+        }
+    }
 }
 
 /// Manages all preview windows
@@ -25,31 +123,54 @@ pub struct PreviewManager {
     /// All previews by ID
     previews: HashMap<PreviewId, Preview>,
 
-    /// Next available ID
-    next_id: u64,
-
     /// Highest z-order
     max_z_order: u32,
+
+    /// Shared texture pages for small previews
+    atlas: TextureAtlas,
+
+    /// Downscale frames in linear light (mipmaps, filtered atlas uploads)
+    linear_scaling: bool,
 }
 
 impl PreviewManager {
     pub fn new() -> Self {
         Self {
             previews: HashMap::new(),
-            next_id: 1,
             max_z_order: 0,
+            atlas: TextureAtlas::default(),
+            linear_scaling: false,
+        }
+    }
+
+    /// Switch linear-light scaling on or off. Applies from each
+    /// preview's next uploaded frame.
+    pub fn set_linear_scaling(&mut self, enabled: bool) {
+        self.linear_scaling = enabled;
+    }
+
+    /// Sampling for dedicated preview textures. egui stores textures as
+    /// sRGB, so mipmaps are built and filtered in linear light; without
+    /// them a heavily shrunk tile skips source pixels and shimmers. Only
+    /// sampling is linear: egui blends into an sRGB-encoded framebuffer,
+    /// so translucent tiles still mix on encoded values.
+    fn texture_options(&self) -> egui::TextureOptions {
+        if self.linear_scaling {
+            egui::TextureOptions {
+                mipmap_mode: Some(egui::TextureFilter::Linear),
+                ..egui::TextureOptions::LINEAR
+            }
+        } else {
+            egui::TextureOptions::LINEAR
         }
     }
 
     /// Generate a new unique ID
     fn generate_id(&mut self) -> PreviewId {
-        let id = PreviewId(self.next_id);
-        self.next_id += 1;
-        id
+        PreviewId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
     }
 
-    /// Add a new preview
-    #[allow(dead_code)]
+    /// Add a new preview without a window (an offline tile)
     pub fn add(&mut self, title: String, position: Pos2, size: Vec2) -> PreviewId {
         let id = self.generate_id();
         self.max_z_order += 1;
@@ -82,9 +203,10 @@ impl PreviewManager {
 
     /// Remove a preview immediately (no fade-out). Prefer `start_removal`
     /// for anything triggered by user interaction so it can animate out.
-    #[allow(dead_code)]
     pub fn remove(&mut self, id: PreviewId) {
-        self.previews.remove(&id);
+        if let Some(preview) = self.previews.remove(&id) {
+            self.release_atlas_slot(&preview);
+        }
     }
 
     /// Begin the fade/shrink-out animation for a preview. The preview stays
@@ -97,8 +219,9 @@ impl PreviewManager {
     }
 
     /// Drop any previews whose removal animation has finished, returning a
-    /// snapshot of each one so the caller can offer an "Undo".
-    pub fn finalize_removals(&mut self) -> Vec<RemovedPreviewInfo> {
+    /// snapshot of each one so the caller can offer an "Undo", along with
+    /// its saved form for the trash.
+    pub fn finalize_removals(&mut self) -> Vec<(RemovedPreviewInfo, PreviewLayout)> {
         let done: Vec<PreviewId> = self.previews.values()
             .filter(|p| p.is_removal_complete())
             .map(|p| p.id)
@@ -107,16 +230,8 @@ impl PreviewManager {
         let mut removed = Vec::with_capacity(done.len());
         for id in done {
             if let Some(preview) = self.previews.remove(&id) {
-                removed.push(RemovedPreviewInfo {
-                    title: preview.title,
-                    window_handle: preview.window_handle,
-                    position: preview.position,
-                    size: preview.size,
-                    fps_preset: preview.fps_preset,
-                    crop_uv: preview.crop_uv,
-                    browser_url: preview.browser_url,
-                    browser_muted: preview.browser_muted,
-                });
+                self.release_atlas_slot(&preview);
+                removed.push((RemovedPreviewInfo::of(&preview), PreviewLayout::from(&preview)));
             }
         }
         removed
@@ -125,17 +240,19 @@ impl PreviewManager {
     /// Clear all previews
     pub fn clear(&mut self) {
         self.previews.clear();
-        self.next_id = 1;
+        self.atlas = TextureAtlas::default();
         self.max_z_order = 0;
     }
 
-    /// Add a preview with window handle and specific settings (for restoring from layout)
+    /// Add a preview with window handle and specific settings (for
+    /// restoring from layout); without a window it's offline until one is
+    /// attached
     pub fn add_with_window(
         &mut self,
         title: String,
         position: Pos2,
         size: Vec2,
-        hwnd: isize,
+        hwnd: Option<isize>,
         fps_preset: FpsPreset,
         z_order: u32,
     ) -> PreviewId {
@@ -144,7 +261,10 @@ impl PreviewManager {
             self.max_z_order = z_order;
         }
 
-        let mut preview = Preview::for_window(id, hwnd, 0, title, position, size);
+        let mut preview = match hwnd {
+            Some(hwnd) => Preview::for_window(id, hwnd, 0, title, position, size),
+            None => Preview::new(id, title, position, size),
+        };
         preview.z_order = z_order;
         preview.set_fps_preset(fps_preset);
         // Restored layouts should appear instantly, not all spawn-animate at once.
@@ -164,6 +284,74 @@ impl PreviewManager {
         self.previews.get_mut(&id)
     }
 
+    /// Upload the preview's pending frame and return what to draw: the
+    /// texture and the UV rect (crop already applied). Tiles drawn at or
+    /// below `atlas::ATLAS_MAX_SIDE` physical pixels (`screen_size` is in
+    /// points) share an atlas page; enlarged ones are promoted to a
+    /// dedicated texture. Switching happens on the next frame so the old
+    /// representation keeps drawing until the new one has content.
+    pub fn frame_texture(
+        &mut self,
+        id: PreviewId,
+        ctx: &egui::Context,
+        screen_size: Vec2,
+    ) -> Option<(TextureId, Rect)> {
+        let options = self.texture_options();
+        let preview = self.previews.get_mut(&id)?;
+        let side = screen_size.x.max(screen_size.y) * ctx.pixels_per_point();
+        let wants_atlas = if preview.atlas_slot.is_some() {
+            side <= atlas::PROMOTE_SIDE
+        } else {
+            side <= atlas::ATLAS_MAX_SIDE
+        };
+
+        if wants_atlas {
+            if let Some(frame) = preview.take_pending_frame() {
+                let slot = match preview.atlas_slot {
+                    Some((slot, _)) => slot,
+                    None => self.atlas.allocate(ctx),
+                };
+                let slot_uv =
+                    self.atlas.upload(slot, &frame, preview.capture.alpha, self.linear_scaling);
+                preview.atlas_slot = Some((slot, slot_uv));
+                preview.drop_texture();
+                preview.uploaded(frame);
+            }
+        } else if preview.has_pending_frame() {
+            preview.get_texture(ctx, options);
+            if let Some((slot, _)) = preview.atlas_slot.take() {
+                self.atlas.release(slot);
+            }
+        }
+
+        let crop_uv = preview.get_uv_rect();
+        if let Some((slot, slot_uv)) = preview.atlas_slot {
+            return self
+                .atlas
+                .texture_id(slot)
+                .map(|texture| (texture, atlas::sub_uv(slot_uv, crop_uv)));
+        }
+        preview.get_texture(ctx, options).map(|texture| (texture.id(), crop_uv))
+    }
+
+    /// What a preview was last drawn from, with the UV rect of its whole
+    /// frame (no crop or flips). Uploads nothing; for the crop editor.
+    pub fn uncropped_texture(&self, id: PreviewId) -> Option<(TextureId, Rect)> {
+        let preview = self.previews.get(&id)?;
+        if let Some((slot, slot_uv)) = preview.atlas_slot {
+            return self.atlas.texture_id(slot).map(|texture| (texture, slot_uv));
+        }
+        preview
+            .texture_id()
+            .map(|texture| (texture, Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0))))
+    }
+
+    fn release_atlas_slot(&mut self, preview: &Preview) {
+        if let Some((slot, _)) = preview.atlas_slot {
+            self.atlas.release(slot);
+        }
+    }
+
     /// Get all preview IDs
     pub fn all_ids(&self) -> Vec<PreviewId> {
         self.previews.keys().copied().collect()
@@ -184,7 +372,7 @@ impl PreviewManager {
     pub fn get_preview_at(&self, pos: Pos2) -> Option<PreviewId> {
         let mut candidates: Vec<_> = self.previews
             .values()
-            .filter(|p| p.contains(pos))
+            .filter(|p| !p.hidden && p.contains(pos))
             .collect();
 
         // Sort by z-order descending (topmost first)
@@ -197,7 +385,7 @@ impl PreviewManager {
     pub fn get_visible_previews(&self, viewport: &Rect) -> Vec<&Preview> {
         let mut visible: Vec<_> = self.previews
             .values()
-            .filter(|p| p.rect().intersects(*viewport))
+            .filter(|p| !p.hidden && p.rect().intersects(*viewport))
             .collect();
 
         // Sort by z-order ascending (draw bottom to top)
@@ -207,7 +395,6 @@ impl PreviewManager {
     }
 
     /// Get all previews as mutable (for updating textures)
-    #[allow(dead_code)]
     pub fn all_mut(&mut self) -> impl Iterator<Item = &mut Preview> {
         self.previews.values_mut()
     }
@@ -268,6 +455,65 @@ impl PreviewManager {
         self.max_z_order = self.previews.len() as u32;
     }
 
+    /// Previews from bottom to top
+    pub fn stacking(&self) -> Vec<PreviewId> {
+        let mut stack: Vec<_> = self.previews.values().map(|p| (p.z_order, p.id.0, p.id)).collect();
+        stack.sort();
+        stack.into_iter().map(|(_, _, id)| id).collect()
+    }
+
+    /// Move previews one layer up or down as a block: each swaps places
+    /// with the unlisted tile directly above or below it, and one already
+    /// held up by another listed tile (or the end of the stack) stays put,
+    /// so listed tiles never trade places with each other.
+    pub fn step_z_order(&mut self, ids: &[PreviewId], up: bool) {
+        let mut stack = self.stacking();
+        // Nearest the destination first, so a tile's way is clear by the
+        // time it moves
+        let mut order: Vec<usize> = (0..stack.len()).collect();
+        if up {
+            order.reverse();
+        }
+        for index in order {
+            if !ids.contains(&stack[index]) {
+                continue;
+            }
+            let target = if up { index + 1 } else { index.wrapping_sub(1) };
+            if target < stack.len() && !ids.contains(&stack[target]) {
+                stack.swap(index, target);
+            }
+        }
+        self.restack(&stack);
+    }
+
+    /// Move a preview to a layer counted from the bottom (0-based),
+    /// shifting the tiles in between by one
+    pub fn move_to_layer(&mut self, id: PreviewId, layer: usize) {
+        let mut stack = self.stacking();
+        let Some(index) = stack.iter().position(|&p| p == id) else { return; };
+        stack.remove(index);
+        stack.insert(layer.min(stack.len()), id);
+        self.restack(&stack);
+    }
+
+    /// Renumber z-orders in stacking order, as send_to_back does, so equal
+    /// z-orders can't stall a move
+    fn restack(&mut self, stack: &[PreviewId]) {
+        for (i, preview_id) in stack.iter().enumerate() {
+            if let Some(p) = self.previews.get_mut(preview_id) {
+                p.z_order = i as u32;
+            }
+        }
+        self.max_z_order = stack.len() as u32;
+    }
+
+    /// A preview's layer counted from the bottom (1-based), and the number
+    /// of layers
+    pub fn depth(&self, id: PreviewId) -> Option<(usize, usize)> {
+        let stack = self.stacking();
+        stack.iter().position(|&p| p == id).map(|i| (i + 1, stack.len()))
+    }
+
     /// Set FPS preset for a preview
     #[allow(dead_code)]
     pub fn set_fps_preset(&mut self, id: PreviewId, preset: FpsPreset) {
@@ -293,3 +539,27 @@ impl Default for PreviewManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::PreviewManager;
+    use eframe::egui::{Pos2, Vec2};
+
+    #[test]
+    fn stepping_moves_the_selection_as_a_block() {
+        let mut manager = PreviewManager::new();
+        let [a, b, c, d] = ["a", "b", "c", "d"].map(|title| manager.add(title.to_string(), Pos2::ZERO, Vec2::splat(10.0)));
+
+        // Adjacent selected tiles pass the one above together
+        manager.step_z_order(&[a, b], true);
+        assert_eq!(manager.stacking(), [c, a, b, d]);
+
+        // At the top, the blocked tile holds back the one below it rather
+        // than trading places with it
+        manager.step_z_order(&[b, d], true);
+        assert_eq!(manager.stacking(), [c, a, b, d]);
+
+        manager.step_z_order(&[c, b], false);
+        assert_eq!(manager.stacking(), [c, b, a, d]);
+    }
+}
@@ -1,5 +1,8 @@
 mod preview;
 mod manager;
+mod atlas;
+mod effects;
 
-pub use preview::{Preview, PreviewId, FpsPreset, PreviewLayout, WindowHandle};
+pub use preview::{apply_layout_fields, Preview, PreviewId, FpsPreset, CaptureBackend, AlphaMode, CaptureOptions, CropPreset, Corner, DisplayMode, FrameOverlays, PreviewLayout, SourceStats, Watermark, WindowHandle};
 pub use manager::{PreviewManager, RemovedPreviewInfo};
+pub use effects::{ChromaKey, Effect, EffectKind, Passthrough};
@@ -1,8 +1,10 @@
-use eframe::egui::{self, Pos2, Vec2, Rect, TextureHandle};
+use eframe::egui::{self, Color32, Pos2, Vec2, Rect, TextureHandle, TextureId};
 use serde::{Serialize, Deserialize};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use parking_lot::RwLock;
+use super::atlas::AtlasSlot;
+use super::effects::{ChromaKey, EffectKind};
 
 /// How long the spawn-in / fade-out animations take.
 const SPAWN_DURATION_SECS: f32 = 0.22;
@@ -46,11 +48,268 @@ impl Default for FpsPreset {
     }
 }
 
+/// How frames are grabbed from the source window
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CaptureBackend {
+    /// Windows Graphics Capture (GPU, cheap, the normal path)
+    #[default]
+    GraphicsCapture,
+    /// GDI `PrintWindow`/BitBlt polling, for windows Graphics Capture
+    /// can't see (elevated apps, some DRM/Chromium setups)
+    PrintWindow,
+}
+
+impl CaptureBackend {
+    pub fn label(self) -> &'static str {
+        match self {
+            CaptureBackend::GraphicsCapture => "Graphics Capture",
+            CaptureBackend::PrintWindow => "PrintWindow (compatibility)",
+        }
+    }
+}
+
+/// Quick crops offered in the preview menu
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CropPreset {
+    Wide,
+    Standard,
+    Square,
+    CenterHalf,
+}
+
+impl CropPreset {
+    pub const ALL: [Self; 4] = [Self::Wide, Self::Standard, Self::Square, Self::CenterHalf];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CropPreset::Wide => "16:9",
+            CropPreset::Standard => "4:3",
+            CropPreset::Square => "1:1",
+            CropPreset::CenterHalf => "Center 50%",
+        }
+    }
+
+    /// The centered crop of a `width`×`height` frame, in pixels: the
+    /// largest region with the preset's aspect, or the middle half of each
+    /// side. (min_x, min_y, max_x, max_y)
+    pub fn pixels(self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let (w, h) = match self {
+            CropPreset::CenterHalf => (width / 2, height / 2),
+            aspect => {
+                let (num, den) = match aspect {
+                    CropPreset::Wide => (16, 9),
+                    CropPreset::Standard => (4, 3),
+                    _ => (1, 1),
+                };
+                if width as u64 * den > height as u64 * num {
+                    ((height as u64 * num / den) as u32, height)
+                } else {
+                    (width, (width as u64 * den / num) as u32)
+                }
+            }
+        };
+        let (x, y) = ((width - w) / 2, (height - h) / 2);
+        (x, y, x + w, y + h)
+    }
+}
+
+/// How a frame whose aspect differs from its tile's is drawn
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    /// Squeeze the frame into the tile
+    #[default]
+    Stretch,
+    /// The whole frame, letterboxed
+    Fit,
+    /// Fill the tile, cutting off what overhangs
+    Fill,
+}
+
+impl DisplayMode {
+    pub const ALL: [Self; 3] = [Self::Stretch, Self::Fit, Self::Fill];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DisplayMode::Stretch => "Stretch",
+            DisplayMode::Fit => "Fit (Letterbox)",
+            DisplayMode::Fill => "Fill (Crop Edges)",
+        }
+    }
+
+    /// Where to draw a frame of `aspect` (width / height, as shown) in
+    /// `tile`, and which part of `uv` to sample. On a quarter-turned frame
+    /// the screen's x runs along the frame's v. Flipped UVs stay flipped.
+    pub fn place(self, tile: Rect, uv: Rect, aspect: f32, quarter_turns: u8) -> (Rect, Rect) {
+        let aspect = aspect.max(0.01);
+        let tile_aspect = tile.width() / tile.height().max(1.0);
+        match self {
+            DisplayMode::Stretch => (tile, uv),
+            DisplayMode::Fit => {
+                let size = if tile_aspect > aspect {
+                    Vec2::new(tile.height() * aspect, tile.height())
+                } else {
+                    Vec2::new(tile.width(), tile.width() / aspect)
+                };
+                (Rect::from_center_size(tile.center(), size), uv)
+            }
+            DisplayMode::Fill => {
+                // Share of the frame's width and height that stays in view
+                let keep = if aspect > tile_aspect {
+                    Vec2::new(tile_aspect / aspect, 1.0)
+                } else {
+                    Vec2::new(1.0, aspect / tile_aspect)
+                };
+                let keep = if quarter_turns % 2 == 1 { keep.yx() } else { keep };
+                let center = uv.center();
+                let half = (uv.max - uv.min) * keep / 2.0;
+                (tile, Rect::from_min_max(center - half, center + half))
+            }
+        }
+    }
+}
+
+/// Markers drawn over a preview's frame, for lining up video or game feeds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrameOverlays {
+    /// Cross through the center
+    pub crosshair: bool,
+    /// Rule-of-thirds grid
+    pub thirds: bool,
+    /// Action- and title-safe boxes of the centered 16:9 area
+    pub safe_area: bool,
+}
+
+/// Corner of a preview something is pinned to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl Corner {
+    pub const ALL: [Self; 4] = [Self::TopLeft, Self::TopRight, Self::BottomLeft, Self::BottomRight];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Corner::TopLeft => "Top Left",
+            Corner::TopRight => "Top Right",
+            Corner::BottomLeft => "Bottom Left",
+            Corner::BottomRight => "Bottom Right",
+        }
+    }
+}
+
+/// Image drawn over a corner of a preview, e.g. a "REC" badge or a logo
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Watermark {
+    /// PNG file, read again when the layout loads
+    pub path: String,
+    pub corner: Corner,
+    /// 0-1
+    pub opacity: f32,
+    /// Width as a share of the tile's width
+    pub scale: f32,
+}
+
+impl Watermark {
+    pub fn new(path: String) -> Self {
+        Self { path, corner: Corner::default(), opacity: 0.8, scale: 0.2 }
+    }
+
+    /// Where to draw an image of `image_size` pixels on `tile`: scaled to
+    /// its share of the width and inset from the corner
+    pub fn placement(&self, tile: Rect, image_size: Vec2) -> Rect {
+        let margin = 8.0;
+        let width = tile.width() * self.scale;
+        let size = Vec2::new(width, width * image_size.y / image_size.x.max(1.0));
+        let inner = tile.shrink(margin);
+        let min = match self.corner {
+            Corner::TopLeft => inner.left_top(),
+            Corner::TopRight => Pos2::new(inner.right() - size.x, inner.top()),
+            Corner::BottomLeft => Pos2::new(inner.left(), inner.bottom() - size.y),
+            Corner::BottomRight => inner.right_bottom() - size,
+        };
+        Rect::from_min_size(min, size)
+    }
+}
+
+/// How a frame's alpha channel is interpreted when uploaded
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlphaMode {
+    /// Color is not multiplied by alpha (most windows)
+    #[default]
+    Straight,
+    /// Color is already multiplied by alpha (some layered/overlay windows,
+    /// which otherwise show dark fringes or wrong translucency)
+    Premultiplied,
+    /// Ignore alpha entirely and draw the frame opaque
+    Opaque,
+}
+
+impl AlphaMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            AlphaMode::Straight => "Straight",
+            AlphaMode::Premultiplied => "Premultiplied",
+            AlphaMode::Opaque => "Ignore (opaque)",
+        }
+    }
+
+    /// Build a texture image from RGBA bytes according to this mode
+    pub fn to_color_image(self, size: [usize; 2], rgba: &[u8]) -> egui::ColorImage {
+        match self {
+            AlphaMode::Straight => egui::ColorImage::from_rgba_unmultiplied(size, rgba),
+            AlphaMode::Premultiplied => egui::ColorImage::from_rgba_premultiplied(size, rgba),
+            AlphaMode::Opaque => egui::ColorImage {
+                size,
+                pixels: rgba
+                    .chunks_exact(4)
+                    .map(|p| Color32::from_rgb(p[0], p[1], p[2]))
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// Per-preview capture settings. Changing the backend or HDR restarts the
+/// session; alpha only affects how frames are uploaded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureOptions {
+    pub backend: CaptureBackend,
+    /// Capture as 16-bit float (scRGB) and tone-map to SDR, so HDR games
+    /// and video don't clip. Only applies to Graphics Capture.
+    pub hdr: bool,
+    pub alpha: AlphaMode,
+    /// Sharpen frames while the tile is drawn smaller than the source, so
+    /// shrunk terminals and code stay readable
+    pub sharpen: bool,
+    /// Include the window's own popups (menus, dropdowns, tooltips) in the
+    /// image. Graphics Capture only; Windows 11 and later.
+    pub include_popups: bool,
+    /// The handle is a display (HMONITOR) rather than a window: capture
+    /// the whole screen. Graphics Capture only.
+    pub display: bool,
+}
+
+impl CaptureOptions {
+    /// Do the two differ in anything the capture session itself uses?
+    pub fn needs_restart(&self, other: &CaptureOptions) -> bool {
+        self.backend != other.backend
+            || self.hdr != other.hdr
+            || self.include_popups != other.include_popups
+            || self.display != other.display
+    }
+}
+
 /// Window handle information
 #[derive(Clone, Debug)]
 pub struct WindowHandle {
     pub hwnd: isize,
-    #[allow(dead_code)]
     pub process_id: u32,
 }
 
@@ -68,9 +327,18 @@ pub struct Preview {
     /// Window being captured
     pub window_handle: Option<WindowHandle>,
 
-    /// Display title (cached from window)
+    /// Display title, kept up to date with the window's
     pub title: String,
 
+    /// The window's title when the preview was attached to it; layouts
+    /// are saved (and windows re-matched) by this one
+    pub original_title: String,
+
+    /// Executable and class of the captured window, saved with layouts so
+    /// the window is found again once its title has changed
+    pub window_exe: Option<String>,
+    pub window_class: Option<String>,
+
     /// Is capture currently active?
     pub capture_active: bool,
 
@@ -92,6 +360,9 @@ pub struct Preview {
     /// FPS preset
     pub fps_preset: FpsPreset,
 
+    /// Capture method and format requested for this preview
+    pub capture: CaptureOptions,
+
     /// Crop region in UV coordinates (0.0-1.0), None = full frame
     /// (min_u, min_v, max_u, max_v) where (0,0) is top-left and (1,1) is bottom-right
     pub crop_uv: Option<(f32, f32, f32, f32)>,
@@ -99,9 +370,35 @@ pub struct Preview {
     /// Original frame dimensions (updated when receiving frames)
     pub frame_size: Option<(u32, u32)>,
 
-    /// Current frame texture
+    /// Quarter turns clockwise the frame is shown at (0-3)
+    pub rotation: u8,
+
+    /// Mirrored left-right / upside down, as seen on the canvas
+    pub flip_h: bool,
+    pub flip_v: bool,
+
+    /// How the frame fills a tile of a different aspect
+    pub display_mode: DisplayMode,
+
+    /// Wall-clock time of the last received frame; persisted so a reloaded
+    /// layout can say how old a source's last picture is
+    pub last_seen: Option<SystemTime>,
+
+    /// Capture rate and latency last measured; persisted with `last_seen`
+    pub last_stats: Option<SourceStats>,
+
+    /// Current frame texture (dedicated; unused while packed in the atlas)
     texture: Option<TextureHandle>,
 
+    /// The frame last uploaded, for reading pixels back (the eyedropper).
+    /// Frames arrive at the tile's drawn size, so this is no bigger than
+    /// the texture.
+    last_frame: Option<FrameData>,
+
+    /// Atlas slot and its content UV rect while this preview is small
+    /// enough to share an atlas page instead of owning a texture
+    pub atlas_slot: Option<(AtlasSlot, Rect)>,
+
     /// Frame data buffer (BGRA)
     frame_buffer: Arc<RwLock<Option<FrameData>>>,
 
@@ -113,6 +410,37 @@ pub struct Preview {
     /// Is this browser tile's audio muted? (Only meaningful for browsers.)
     pub browser_muted: bool,
 
+    /// Show the capture statistics overlay on this preview
+    pub show_stats: bool,
+
+    /// Sticky note pinned to the tile's corner
+    pub note: Option<String>,
+
+    /// FPS group whose budget this preview shares, by name
+    pub fps_group: Option<String>,
+
+    /// Container frame that lays this preview out, by name
+    pub container: Option<String>,
+
+    /// Hidden from the layers panel: not drawn, not clickable, and its
+    /// capture paused like an off-screen tile
+    pub hidden: bool,
+
+    /// Name the user gave the preview, shown instead of the window title
+    pub nickname: Option<String>,
+
+    /// Shader effect the frame is drawn through
+    pub effect: Option<EffectKind>,
+
+    /// Color drawn transparent, if any
+    pub chroma_key: Option<ChromaKey>,
+
+    /// Crosshair, thirds and safe-area markers over the frame
+    pub overlays: FrameOverlays,
+
+    /// Image pinned to a corner
+    pub watermark: Option<Watermark>,
+
     /// When this preview was created (drives the spawn-in animation)
     pub created_at: Instant,
 
@@ -122,12 +450,36 @@ pub struct Preview {
     pub removing: Option<Instant>,
 }
 
+/// How a source's capture was doing when last measured, shown on its
+/// tile while it's offline
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SourceStats {
+    /// Frames received per second
+    pub fps: f32,
+    /// Capture-to-screen latency
+    pub latency_ms: u32,
+}
+
 /// Raw frame data from capture
 #[derive(Clone)]
 pub struct FrameData {
     pub width: u32,
     pub height: u32,
-    pub data: Vec<u8>,
+    /// RGBA pixels; duplicates of a preview share them
+    pub data: Arc<Vec<u8>>,
+}
+
+impl FrameData {
+    /// RGBA of the pixel at a point given in 0..1 frame coordinates
+    pub fn pixel_at(&self, point: Pos2) -> Option<[u8; 4]> {
+        if self.width == 0 || self.height == 0 || !(0.0..=1.0).contains(&point.x) || !(0.0..=1.0).contains(&point.y) {
+            return None;
+        }
+        let x = ((point.x * self.width as f32) as u32).min(self.width - 1);
+        let y = ((point.y * self.height as f32) as u32).min(self.height - 1);
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        self.data.get(i..i + 4).map(|p| [p[0], p[1], p[2], p[3]])
+    }
 }
 
 impl Preview {
@@ -139,6 +491,9 @@ impl Preview {
             position,
             size,
             window_handle: None,
+            original_title: title.clone(),
+            window_exe: None,
+            window_class: None,
             title,
             capture_active: false,
             capture_paused: false,
@@ -147,22 +502,52 @@ impl Preview {
             z_order: 0,
             target_fps: FpsPreset::default().as_u32(),
             fps_preset: FpsPreset::default(),
+            capture: CaptureOptions::default(),
             crop_uv: None,
             frame_size: None,
+            rotation: 0,
+            flip_h: false,
+            flip_v: false,
+            display_mode: DisplayMode::default(),
+            last_seen: None,
+            last_stats: None,
             texture: None,
+            last_frame: None,
+            atlas_slot: None,
             frame_buffer: Arc::new(RwLock::new(None)),
             browser_url: None,
             browser_muted: false,
+            show_stats: false,
+            note: None,
+            fps_group: None,
+            container: None,
+            hidden: false,
+            nickname: None,
+            effect: None,
+            chroma_key: None,
+            overlays: FrameOverlays::default(),
+            watermark: None,
             created_at: Instant::now(),
             removing: None,
         }
     }
 
+    /// The nickname if the user gave one, else the window title
+    pub fn display_name(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(&self.title)
+    }
+
     /// Is this preview an app-owned browser tile?
     pub fn is_browser(&self) -> bool {
         self.browser_url.is_some()
     }
 
+    /// Restored from a layout while its window (or display) wasn't there:
+    /// a placeholder waiting for it to appear
+    pub fn is_offline(&self) -> bool {
+        self.window_handle.is_none() && !self.is_browser()
+    }
+
     /// Create a preview for a specific window
     pub fn for_window(
         id: PreviewId,
@@ -199,14 +584,17 @@ impl Preview {
         self.frame_buffer.clone()
     }
 
-    /// Update frame data from capture
-    pub fn update_frame(&mut self, width: u32, height: u32, data: Vec<u8>) {
+    /// Update frame data from capture. `source_size` is the window's
+    /// captured size; the buffer itself may be downscaled for display.
+    pub fn update_frame(&mut self, source_size: (u32, u32), width: u32, height: u32, data: Arc<Vec<u8>>) {
         // Update source aspect ratio from actual frame dimensions
-        if width > 0 && height > 0 {
-            self.frame_size = Some((width, height));
+        self.last_seen = Some(SystemTime::now());
+        let (source_width, source_height) = source_size;
+        if source_width > 0 && source_height > 0 {
+            self.frame_size = Some(source_size);
             // Only update aspect ratio if we don't have a crop region
             if self.crop_uv.is_none() {
-                self.source_aspect_ratio = width as f32 / height as f32;
+                self.source_aspect_ratio = source_width as f32 / source_height as f32;
             }
         }
 
@@ -214,8 +602,28 @@ impl Preview {
         *buffer = Some(FrameData { width, height, data });
     }
 
+    /// Rotate the frame by quarter turns (negative: counter-clockwise).
+    /// Odd turns swap the tile's width and height around its center.
+    pub fn rotate(&mut self, quarter_turns: i32) {
+        self.rotation = (i32::from(self.rotation) + quarter_turns).rem_euclid(4) as u8;
+        if quarter_turns % 2 != 0 {
+            let center = self.rect().center();
+            self.size = Vec2::new(self.size.y, self.size.x);
+            self.position = center - self.size / 2.0;
+        }
+    }
+
+    /// Width / height of the tile showing the (cropped) frame at its
+    /// rotation
+    pub fn tile_aspect_ratio(&self) -> f32 {
+        if self.rotation % 2 == 1 {
+            1.0 / self.source_aspect_ratio
+        } else {
+            self.source_aspect_ratio
+        }
+    }
+
     /// Get the effective aspect ratio (considering crop region)
-    #[allow(dead_code)]
     pub fn effective_aspect_ratio(&self) -> f32 {
         if let (Some(crop), Some((w, h))) = (self.crop_uv, self.frame_size) {
             let crop_width = (crop.2 - crop.0) * w as f32;
@@ -231,19 +639,33 @@ impl Preview {
     }
 
     /// Get UV coordinates for rendering (either crop region or full frame)
+    /// Flips swap the rect's edges, so min can be past max. The frame is
+    /// rotated after sampling, so on a quarter-turned tile a flip on
+    /// screen is one along the frame's other axis.
     pub fn get_uv_rect(&self) -> Rect {
-        if let Some(crop) = self.crop_uv {
+        let mut uv = if let Some(crop) = self.crop_uv {
             Rect::from_min_max(
                 Pos2::new(crop.0, crop.1),
                 Pos2::new(crop.2, crop.3),
             )
         } else {
             Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0))
+        };
+        let (flip_u, flip_v) = if self.rotation % 2 == 0 {
+            (self.flip_h, self.flip_v)
+        } else {
+            (self.flip_v, self.flip_h)
+        };
+        if flip_u {
+            std::mem::swap(&mut uv.min.x, &mut uv.max.x);
         }
+        if flip_v {
+            std::mem::swap(&mut uv.min.y, &mut uv.max.y);
+        }
+        uv
     }
 
     /// Set crop region from pixel coordinates
-    #[allow(dead_code)]
     pub fn set_crop_pixels(&mut self, min_x: u32, min_y: u32, max_x: u32, max_y: u32) {
         if let Some((w, h)) = self.frame_size {
             if w > 0 && h > 0 {
@@ -263,6 +685,17 @@ impl Preview {
         }
     }
 
+    /// Set or clear the crop region, keeping the aspect ratio in step
+    pub fn set_crop(&mut self, crop_uv: Option<(f32, f32, f32, f32)>) {
+        match crop_uv {
+            Some(crop) => {
+                self.crop_uv = Some(crop);
+                self.source_aspect_ratio = self.effective_aspect_ratio();
+            }
+            None => self.clear_crop(),
+        }
+    }
+
     /// Clear crop region (show full frame)
     pub fn clear_crop(&mut self) {
         self.crop_uv = None;
@@ -275,13 +708,42 @@ impl Preview {
     }
 
     /// Check if there's a new frame to upload
-    #[allow(dead_code)]
     pub fn has_pending_frame(&self) -> bool {
         self.frame_buffer.read().is_some()
     }
 
+    /// Take the pending frame, if any, leaving the buffer empty
+    pub fn take_pending_frame(&mut self) -> Option<FrameData> {
+        self.frame_buffer.write().take()
+    }
+
+    /// Hold on to an uploaded frame; a window that stops redrawing won't
+    /// send another
+    pub fn uploaded(&mut self, frame: FrameData) {
+        self.last_frame = Some(frame);
+    }
+
+    /// The last uploaded frame
+    pub fn last_frame(&self) -> Option<&FrameData> {
+        self.last_frame.as_ref()
+    }
+
+    /// Free the dedicated texture (after moving into the atlas)
+    pub fn drop_texture(&mut self) {
+        self.texture = None;
+    }
+
+    /// The dedicated texture, if the frame was last uploaded to one
+    pub fn texture_id(&self) -> Option<TextureId> {
+        self.texture.as_ref().map(|texture| texture.id())
+    }
+
     /// Get or create texture from frame buffer
-    pub fn get_texture(&mut self, ctx: &egui::Context) -> Option<&TextureHandle> {
+    pub fn get_texture(
+        &mut self,
+        ctx: &egui::Context,
+        options: egui::TextureOptions,
+    ) -> Option<&TextureHandle> {
         // Check if we have a new frame to upload
         let frame_data = {
             let mut buffer = self.frame_buffer.write();
@@ -289,20 +751,21 @@ impl Preview {
         };
 
         if let Some(frame) = frame_data {
-            let image = egui::ColorImage::from_rgba_unmultiplied(
+            let image = self.capture.alpha.to_color_image(
                 [frame.width as usize, frame.height as usize],
                 &frame.data,
             );
 
             if let Some(texture) = self.texture.as_mut() {
-                texture.set(image, egui::TextureOptions::LINEAR);
+                texture.set(image, options);
             } else {
                 self.texture = Some(ctx.load_texture(
                     format!("preview_{}", self.id.0),
                     image,
-                    egui::TextureOptions::LINEAR,
+                    options,
                 ));
             }
+            self.uploaded(frame);
         }
 
         self.texture.as_ref()
@@ -344,15 +807,20 @@ impl Preview {
 }
 
 /// Serializable layout for persistence
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PreviewLayout {
     pub position: (f32, f32),
     pub size: (f32, f32),
     pub window_title: String,
     pub window_exe: Option<String>,
+    /// Class name of the window, matched together with `window_exe`
+    #[serde(default)]
+    pub window_class: Option<String>,
     pub lock_aspect_ratio: bool,
     pub z_order: u32,
     pub fps_preset: FpsPreset,
+    #[serde(default)]
+    pub capture: CaptureOptions,
     /// Crop region in UV coordinates (optional)
     #[serde(default)]
     pub crop_uv: Option<(f32, f32, f32, f32)>,
@@ -363,6 +831,111 @@ pub struct PreviewLayout {
     /// WebView2 mute is per-session, so remember it and reapply on restore.
     #[serde(default)]
     pub browser_muted: bool,
+    /// Last frame time, seconds since the Unix epoch
+    #[serde(default)]
+    pub last_seen: Option<u64>,
+    /// Source size in pixels at the last frame
+    #[serde(default)]
+    pub frame_size: Option<(u32, u32)>,
+    /// Capture rate and latency at the last measurement
+    #[serde(default)]
+    pub last_stats: Option<SourceStats>,
+    /// Quarter turns clockwise the frame is shown at
+    #[serde(default)]
+    pub rotation: u8,
+    /// Mirrored on the canvas
+    #[serde(default)]
+    pub flip_h: bool,
+    #[serde(default)]
+    pub flip_v: bool,
+    /// Stretch, letterbox or fill a tile of another aspect
+    #[serde(default)]
+    pub display_mode: DisplayMode,
+    /// Sticky note pinned to the tile
+    #[serde(default)]
+    pub note: Option<String>,
+    /// FPS group whose budget the preview shares
+    #[serde(default)]
+    pub fps_group: Option<String>,
+    /// Container frame the preview is tiled in
+    #[serde(default)]
+    pub container: Option<String>,
+    /// Hidden from the layers panel
+    #[serde(default)]
+    pub hidden: bool,
+    /// Name the user gave the preview; also matches windows whose title
+    /// has changed since the layout was saved
+    #[serde(default)]
+    pub nickname: Option<String>,
+    /// Shader effect the frame is drawn through
+    #[serde(default)]
+    pub effect: Option<EffectKind>,
+    /// Color drawn transparent
+    #[serde(default)]
+    pub chroma_key: Option<ChromaKey>,
+    /// Markers drawn over the frame
+    #[serde(default)]
+    pub overlays: FrameOverlays,
+    /// Image pinned to a corner
+    #[serde(default)]
+    pub watermark: Option<Watermark>,
+}
+
+impl PreviewLayout {
+    /// A new 320x180 preview's layout for `title` at the origin
+    #[cfg(test)]
+    pub fn for_title(title: &str) -> Self {
+        Self::from(&Preview::new(PreviewId(1), title.to_string(), Pos2::ZERO, Vec2::new(320.0, 180.0)))
+    }
+
+    /// Just what the user edits: the capture history (last frame time,
+    /// size and stats) and the window's executable and class cleared
+    pub fn edits_only(mut self) -> Self {
+        self.last_seen = None;
+        self.frame_size = None;
+        self.last_stats = None;
+        self.window_exe = None;
+        self.window_class = None;
+        self
+    }
+
+    /// Apply the persisted last-seen time, frame size and stats to a
+    /// restored preview
+    pub fn restore_history(&self, preview: &mut Preview) {
+        preview.last_seen = self.last_seen.map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        preview.frame_size = self.frame_size;
+        preview.last_stats = self.last_stats;
+    }
+
+    /// Secondary key for re-matching a window whose title no longer equals
+    /// the saved one: its title contains the nickname, ignoring case
+    pub fn nickname_matches(&self, window_title: &str) -> bool {
+        self.nickname
+            .as_deref()
+            .map(str::trim)
+            .is_some_and(|nickname| !nickname.is_empty() && window_title.to_lowercase().contains(&nickname.to_lowercase()))
+    }
+}
+
+/// Copy the per-tile settings the user edits (orientation, display mode,
+/// note, groups, name, effects, overlays, watermark) from a saved or
+/// removed tile onto a preview. Position, size, crop, frame rate and
+/// capture options are left to the caller, which knows whether a change
+/// there has to reach the capture.
+pub fn apply_layout_fields(preview: &mut Preview, layout: &PreviewLayout) {
+    preview.rotation = layout.rotation % 4;
+    preview.flip_h = layout.flip_h;
+    preview.flip_v = layout.flip_v;
+    preview.display_mode = layout.display_mode;
+    preview.note = layout.note.clone();
+    preview.fps_group = layout.fps_group.clone();
+    preview.container = layout.container.clone();
+    preview.hidden = layout.hidden;
+    preview.nickname = layout.nickname.clone();
+    preview.effect = layout.effect;
+    preview.chroma_key = layout.chroma_key;
+    preview.overlays = layout.overlays;
+    preview.watermark = layout.watermark.clone();
 }
 
 impl From<&Preview> for PreviewLayout {
@@ -370,33 +943,152 @@ impl From<&Preview> for PreviewLayout {
         Self {
             position: (preview.position.x, preview.position.y),
             size: (preview.size.x, preview.size.y),
-            window_title: preview.title.clone(),
-            window_exe: None, // TODO: Get exe name from window handle
+            // Browser tiles restore by URL; their page title is just a name
+            window_title: if preview.is_browser() {
+                preview.title.clone()
+            } else {
+                preview.original_title.clone()
+            },
+            window_exe: preview.window_exe.clone(),
+            window_class: preview.window_class.clone(),
             lock_aspect_ratio: preview.lock_aspect_ratio,
             z_order: preview.z_order,
             fps_preset: preview.fps_preset,
+            capture: preview.capture,
             crop_uv: preview.crop_uv,
             browser_url: preview.browser_url.clone(),
             browser_muted: preview.browser_muted,
+            last_seen: preview
+                .last_seen
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|age| age.as_secs()),
+            frame_size: preview.frame_size,
+            last_stats: preview.last_stats,
+            rotation: preview.rotation,
+            flip_h: preview.flip_h,
+            flip_v: preview.flip_v,
+            display_mode: preview.display_mode,
+            note: preview.note.clone(),
+            fps_group: preview.fps_group.clone(),
+            container: preview.container.clone(),
+            hidden: preview.hidden,
+            nickname: preview.nickname.clone(),
+            effect: preview.effect,
+            chroma_key: preview.chroma_key,
+            overlays: preview.overlays,
+            watermark: preview.watermark.clone(),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Preview, PreviewId};
-    use eframe::egui::{Context, Pos2, Vec2};
+    use super::{AlphaMode, Corner, CropPreset, DisplayMode, FrameData, Preview, PreviewId, PreviewLayout, Watermark};
+    use eframe::egui::{Color32, Context, Pos2, TextureOptions, Vec2};
+    use std::sync::Arc;
 
     #[test]
     fn frame_updates_reuse_the_texture() {
         let context = Context::default();
         let mut preview = Preview::new(PreviewId(1), "test".to_owned(), Pos2::ZERO, Vec2::splat(1.0));
 
-        preview.update_frame(1, 1, vec![255, 0, 0, 255]);
-        let first = preview.get_texture(&context).unwrap().id();
-        preview.update_frame(1, 1, vec![0, 255, 0, 255]);
-        let second = preview.get_texture(&context).unwrap().id();
+        preview.update_frame((1, 1), 1, 1, Arc::new(vec![255, 0, 0, 255]));
+        let first = preview.get_texture(&context, TextureOptions::LINEAR).unwrap().id();
+        preview.update_frame((1, 1), 1, 1, Arc::new(vec![0, 255, 0, 255]));
+        let second = preview.get_texture(&context, TextureOptions::LINEAR).unwrap().id();
 
         assert_eq!(first, second);
     }
+
+    #[test]
+    fn alpha_modes_interpret_the_alpha_channel() {
+        let pixel = [200, 100, 50, 128];
+
+        let opaque = AlphaMode::Opaque.to_color_image([1, 1], &pixel);
+        assert_eq!(opaque.pixels[0], Color32::from_rgb(200, 100, 50));
+
+        let premultiplied = AlphaMode::Premultiplied.to_color_image([1, 1], &pixel);
+        assert_eq!(premultiplied.pixels[0], Color32::from_rgba_premultiplied(200, 100, 50, 128));
+    }
+
+    #[test]
+    fn crop_presets_center_the_largest_region() {
+        assert_eq!(CropPreset::Wide.pixels(1920, 1200), (0, 60, 1920, 1140));
+        assert_eq!(CropPreset::Square.pixels(1920, 1080), (420, 0, 1500, 1080));
+        assert_eq!(CropPreset::Standard.pixels(1000, 1000), (0, 125, 1000, 875));
+        assert_eq!(CropPreset::CenterHalf.pixels(1920, 1080), (480, 270, 1440, 810));
+    }
+
+    #[test]
+    fn watermarks_sit_inside_their_corner() {
+        let tile = Rect::from_min_size(Pos2::ZERO, Vec2::new(400.0, 300.0));
+        let mut watermark = Watermark::new("rec.png".to_owned());
+        watermark.scale = 0.25;
+        let placed = watermark.placement(tile, Vec2::new(200.0, 100.0));
+        assert_eq!(placed, Rect::from_min_max(Pos2::new(292.0, 242.0), Pos2::new(392.0, 292.0)));
+        watermark.corner = Corner::TopLeft;
+        assert_eq!(watermark.placement(tile, Vec2::new(200.0, 100.0)).min, Pos2::new(8.0, 8.0));
+    }
+
+    #[test]
+    fn pixels_are_read_at_frame_coordinates() {
+        let frame = FrameData { width: 2, height: 2, data: Arc::new((0..16).collect()) };
+        assert_eq!(frame.pixel_at(Pos2::new(0.0, 0.0)), Some([0, 1, 2, 3]));
+        assert_eq!(frame.pixel_at(Pos2::new(0.75, 0.25)), Some([4, 5, 6, 7]));
+        assert_eq!(frame.pixel_at(Pos2::new(1.0, 1.0)), Some([12, 13, 14, 15]));
+        assert_eq!(frame.pixel_at(Pos2::new(1.5, 0.0)), None);
+    }
+
+    #[test]
+    fn display_modes_letterbox_or_trim_wide_frames() {
+        let tile = Rect::from_min_max(Pos2::ZERO, Pos2::new(100.0, 100.0));
+        let full = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+        assert_eq!(DisplayMode::Stretch.place(tile, full, 2.0, 0), (tile, full));
+
+        let (rect, uv) = DisplayMode::Fit.place(tile, full, 2.0, 0);
+        assert_eq!((rect.min.y, rect.max.y, uv), (25.0, 75.0, full));
+
+        let (rect, uv) = DisplayMode::Fill.place(tile, full, 2.0, 0);
+        assert_eq!((rect, uv.min.x, uv.max.x, uv.min.y), (tile, 0.25, 0.75, 0.0));
+        // Turned: the overhang is along the frame's height
+        let (_, uv) = DisplayMode::Fill.place(tile, full, 2.0, 1);
+        assert_eq!((uv.min.x, uv.min.y, uv.max.y), (0.0, 0.25, 0.75));
+    }
+
+    #[test]
+    fn flips_follow_the_screen_on_turned_tiles() {
+        let mut preview = Preview::new(PreviewId(1), "camera".to_owned(), Pos2::ZERO, Vec2::splat(1.0));
+        preview.crop_uv = Some((0.1, 0.2, 0.5, 0.6));
+        preview.flip_h = true;
+        let uv = preview.get_uv_rect();
+        assert_eq!((uv.min.x, uv.max.x), (0.5, 0.1));
+        assert_eq!((uv.min.y, uv.max.y), (0.2, 0.6));
+
+        // A quarter turn puts the frame's vertical axis across the screen
+        preview.rotation = 1;
+        let uv = preview.get_uv_rect();
+        assert_eq!((uv.min.x, uv.max.x), (0.1, 0.5));
+        assert_eq!((uv.min.y, uv.max.y), (0.6, 0.2));
+    }
+
+    #[test]
+    fn nickname_matches_titles_containing_it() {
+        let preview = Preview::new(PreviewId(1), "main - Build #41 - Jenkins".to_owned(), Pos2::ZERO, Vec2::splat(1.0));
+        let mut layout = PreviewLayout::from(&preview);
+        assert!(!layout.nickname_matches("main - Build #42 - Jenkins"));
+
+        layout.nickname = Some("jenkins".to_owned());
+        assert!(layout.nickname_matches("main - Build #42 - Jenkins"));
+        assert!(!layout.nickname_matches("Slack"));
+
+        layout.nickname = Some("  ".to_owned());
+        assert!(!layout.nickname_matches("Slack"));
+    }
+
+    #[test]
+    fn layouts_keep_the_title_the_window_was_added_with() {
+        let mut preview = Preview::new(PreviewId(1), "main.rs - Editor".to_owned(), Pos2::ZERO, Vec2::splat(1.0));
+        preview.title = "lib.rs - Editor".to_owned();
+        assert_eq!(PreviewLayout::from(&preview).window_title, "main.rs - Editor");
+    }
 }
@@ -36,6 +36,26 @@ pub fn redact_title(title: &str) -> String {
     }
 }
 
+/// Hide (or show again) a window from screen capture and sharing tools via
+/// `SetWindowDisplayAffinity`. Captured output shows the window as empty;
+/// it stays fully visible on the local screen.
+pub fn set_excluded_from_capture(hwnd: isize, excluded: bool) {
+    #[cfg(windows)]
+    {
+        use windows::Win32::Foundation::HWND;
+        use windows::Win32::UI::WindowsAndMessaging::{
+            SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE, WDA_NONE,
+        };
+
+        let affinity = if excluded { WDA_EXCLUDEFROMCAPTURE } else { WDA_NONE };
+        if let Err(e) = unsafe { SetWindowDisplayAffinity(HWND(hwnd as *mut _), affinity) } {
+            log::warn!("Failed to set window display affinity: {}", e);
+        }
+    }
+    #[cfg(not(windows))]
+    let _ = (hwnd, excluded);
+}
+
 /// Check if a window should be ignored based on its process name or title.
 pub fn is_sensitive_window(exe_name: &str, title: &str) -> bool {
     // Check process blacklist
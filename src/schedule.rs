@@ -0,0 +1,122 @@
+use crate::persistence::ScheduleEntry;
+
+/// Seconds in a day; schedule times wrap around midnight.
+const DAY_SECS: u32 = 24 * 60 * 60;
+
+/// The countdown before a scheduled switch starts this long before it.
+pub const COUNTDOWN_SECS: u32 = 60;
+
+/// Seconds since local midnight
+pub fn local_seconds_of_day() -> u32 {
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::SystemInformation::GetLocalTime;
+
+        let time = unsafe { GetLocalTime() };
+        time.wHour as u32 * 3600 + time.wMinute as u32 * 60 + time.wSecond as u32
+    }
+    #[cfg(not(windows))]
+    {
+        let since_epoch = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        (since_epoch.as_secs() % DAY_SECS as u64) as u32
+    }
+}
+
+/// Seconds from `now` until a schedule entry's time, wrapping past midnight
+fn seconds_until(entry: &ScheduleEntry, now: u32) -> u32 {
+    (entry.second_of_day() + DAY_SECS - now % DAY_SECS) % DAY_SECS
+}
+
+/// Decides when the layout schedule switches layouts. Polled every frame
+/// with the local time of day; an entry fires when that time passes it.
+#[derive(Default)]
+pub struct Scheduler {
+    /// Time of day at the last poll; None until the first one, so starting
+    /// the app never fires an entry that passed while it was closed.
+    last_poll: Option<u32>,
+    /// Time of day of an upcoming switch the user chose to skip
+    skipped: Option<u32>,
+}
+
+impl Scheduler {
+    /// The entry whose time passed since the last poll, if any (the latest
+    /// one when several did). A skipped switch passes without firing.
+    pub fn poll<'a>(&mut self, entries: &'a [ScheduleEntry], now: u32) -> Option<&'a ScheduleEntry> {
+        let last = self.last_poll.replace(now)?;
+        let elapsed = (now + DAY_SECS - last) % DAY_SECS;
+        let due = entries
+            .iter()
+            .filter(|entry| {
+                let since = (now + DAY_SECS - entry.second_of_day()) % DAY_SECS;
+                elapsed > 0 && since < elapsed
+            })
+            .min_by_key(|entry| (now + DAY_SECS - entry.second_of_day()) % DAY_SECS)?;
+
+        if self.skipped == Some(due.second_of_day()) {
+            self.skipped = None;
+            return None;
+        }
+        Some(due)
+    }
+
+    /// The next switch if it's within the countdown and not skipped, with
+    /// the seconds left
+    pub fn upcoming<'a>(&self, entries: &'a [ScheduleEntry], now: u32) -> Option<(&'a ScheduleEntry, u32)> {
+        entries
+            .iter()
+            .map(|entry| (entry, seconds_until(entry, now)))
+            .filter(|&(_, secs)| secs > 0 && secs <= COUNTDOWN_SECS)
+            .min_by_key(|&(_, secs)| secs)
+            .filter(|(entry, _)| self.skipped != Some(entry.second_of_day()))
+    }
+
+    /// Stay on the current layout through the upcoming switch
+    pub fn skip(&mut self, entry: &ScheduleEntry) {
+        self.skipped = Some(entry.second_of_day());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scheduler, DAY_SECS};
+    use crate::persistence::ScheduleEntry;
+
+    fn entry(hour: u16, minute: u16, layout: &str) -> ScheduleEntry {
+        ScheduleEntry { minute: hour * 60 + minute, layout: layout.to_string() }
+    }
+
+    #[test]
+    fn fires_once_when_the_time_passes() {
+        let entries = [entry(9, 0, "Work"), entry(18, 30, "Evening")];
+        let mut scheduler = Scheduler::default();
+        let nine = 9 * 3600;
+
+        assert!(scheduler.poll(&entries, nine - 1).is_none());
+        assert_eq!(scheduler.upcoming(&entries, nine - 1).map(|(e, s)| (e.layout.as_str(), s)), Some(("Work", 1)));
+        assert_eq!(scheduler.poll(&entries, nine).map(|e| e.layout.as_str()), Some("Work"));
+        assert!(scheduler.poll(&entries, nine + 1).is_none());
+
+        // Across midnight
+        let mut scheduler = Scheduler::default();
+        let entries = [entry(0, 0, "Night")];
+        scheduler.poll(&entries, DAY_SECS - 5);
+        assert_eq!(scheduler.poll(&entries, 3).map(|e| e.layout.as_str()), Some("Night"));
+    }
+
+    #[test]
+    fn skipped_switch_passes_without_firing() {
+        let entries = [entry(18, 30, "Evening")];
+        let mut scheduler = Scheduler::default();
+        let due = entries[0].second_of_day();
+
+        scheduler.poll(&entries, due - 30);
+        scheduler.skip(&entries[0]);
+        assert!(scheduler.upcoming(&entries, due - 20).is_none());
+        assert!(scheduler.poll(&entries, due + 1).is_none());
+        // Tomorrow it fires again
+        scheduler.poll(&entries, due - 10);
+        assert!(scheduler.poll(&entries, due).is_some());
+    }
+}
@@ -0,0 +1,141 @@
+use eframe::egui::{self, Color32, RichText};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How long a toast stays up; errors linger so they aren't missed.
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+const ERROR_TOAST_DURATION: Duration = Duration::from_secs(8);
+
+/// Older toasts are dropped once the stack is this tall.
+const MAX_TOASTS: usize = 5;
+
+/// Fade-out at the end of a toast's life, in seconds.
+const FADE_SECS: f32 = 0.4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToastKind {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastKind {
+    fn icon(self) -> &'static str {
+        match self {
+            ToastKind::Info => egui_phosphor::regular::INFO,
+            ToastKind::Success => egui_phosphor::regular::CHECK_CIRCLE,
+            ToastKind::Warning => egui_phosphor::regular::WARNING,
+            ToastKind::Error => egui_phosphor::regular::WARNING_CIRCLE,
+        }
+    }
+
+    fn color(self) -> Color32 {
+        match self {
+            ToastKind::Info => Color32::from_rgb(74, 158, 255),
+            ToastKind::Success => Color32::from_rgb(107, 170, 75),
+            ToastKind::Warning => Color32::from_rgb(255, 150, 100),
+            ToastKind::Error => Color32::from_rgb(255, 90, 90),
+        }
+    }
+
+    fn duration(self) -> Duration {
+        match self {
+            ToastKind::Error => ERROR_TOAST_DURATION,
+            _ => TOAST_DURATION,
+        }
+    }
+}
+
+struct Toast {
+    kind: ToastKind,
+    message: String,
+    shown_at: Instant,
+}
+
+/// Short-lived notifications stacked in the bottom-right corner (newest at
+/// the bottom). Click a toast to dismiss it early.
+#[derive(Default)]
+pub struct Toasts {
+    toasts: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(ToastKind::Info, message.into());
+    }
+
+    pub fn success(&mut self, message: impl Into<String>) {
+        self.push(ToastKind::Success, message.into());
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.push(ToastKind::Warning, message.into());
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) {
+        self.push(ToastKind::Error, message.into());
+    }
+
+    fn push(&mut self, kind: ToastKind, message: String) {
+        // The same event firing repeatedly restarts its toast instead of stacking
+        self.toasts.retain(|t| !(t.kind == kind && t.message == message));
+        self.toasts.push_back(Toast { kind, message, shown_at: Instant::now() });
+        while self.toasts.len() > MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+    }
+
+    /// Draw the stack and expire old toasts. Call once per frame.
+    pub fn show(&mut self, ctx: &egui::Context) {
+        self.toasts.retain(|t| t.shown_at.elapsed() < t.kind.duration());
+        if self.toasts.is_empty() {
+            return;
+        }
+
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-16.0, -16.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (index, toast) in self.toasts.iter().enumerate() {
+                    let remaining = toast.kind.duration().saturating_sub(toast.shown_at.elapsed());
+                    let fade = (remaining.as_secs_f32() / FADE_SECS).clamp(0.0, 1.0);
+
+                    let response = ui
+                        .scope(|ui| {
+                            ui.set_opacity(fade);
+                            egui::Frame::none()
+                                .fill(Color32::from_rgba_unmultiplied(24, 24, 28, 235))
+                                .stroke(egui::Stroke::new(1.0, Color32::from_rgb(40, 40, 46)))
+                                .rounding(8.0)
+                                .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+                                .show(ui, |ui| {
+                                    ui.set_max_width(320.0);
+                                    ui.horizontal(|ui| {
+                                        ui.label(RichText::new(toast.kind.icon()).color(toast.kind.color()));
+                                        ui.label(
+                                            RichText::new(&toast.message)
+                                                .size(12.0)
+                                                .color(Color32::from_rgb(210, 210, 215)),
+                                        );
+                                    });
+                                })
+                                .response
+                        })
+                        .inner
+                        .interact(egui::Sense::click());
+                    if response.clicked() {
+                        dismissed = Some(index);
+                    }
+                    ui.add_space(6.0);
+                }
+            });
+
+        if let Some(index) = dismissed {
+            self.toasts.remove(index);
+        }
+        // Keep repainting so toasts fade and expire while the UI is idle
+        ctx.request_repaint_after(Duration::from_millis(100));
+    }
+}
@@ -13,6 +13,7 @@ use windows::Win32::UI::WindowsAndMessaging::{
 /// Menu item IDs
 const MENU_SHOW: &str = "show";
 const MENU_HIDE: &str = "hide";
+const MENU_PANIC: &str = "panic";
 const MENU_QUIT: &str = "quit";
 
 /// Global storage for the main window HWND (needed for static closure)
@@ -58,6 +59,7 @@ impl TrayManager {
                         }
                     }
                 }
+                MENU_PANIC => crate::panic_button::press(),
                 MENU_QUIT => {
                     #[cfg(debug_assertions)]
                     println!("Quit clicked");
@@ -72,12 +74,19 @@ impl TrayManager {
         // Create menu items
         let show_item = MenuItem::with_id(MENU_SHOW, "Show Pluriview", true, None);
         let hide_item = MenuItem::with_id(MENU_HIDE, "Hide", true, None);
+        let panic_item = MenuItem::with_id(
+            MENU_PANIC,
+            format!("Blank / Restore Previews ({})", crate::panic_button::PANIC_HOTKEY_LABEL),
+            true,
+            None,
+        );
         let quit_item = MenuItem::with_id(MENU_QUIT, "Quit", true, None);
 
         // Build the menu
         let menu = Menu::with_items(&[
             &show_item,
             &hide_item,
+            &panic_item,
             &PredefinedMenuItem::separator(),
             &quit_item,
         ]).ok()?;
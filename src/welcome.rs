@@ -0,0 +1,142 @@
+use eframe::egui::{self, Color32, RichText, TextureHandle, Vec2};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::persistence::{Storage, THUMBNAIL_HEIGHT, THUMBNAIL_WIDTH};
+use crate::util::format_age;
+
+/// How many recent layouts the welcome screen lists.
+const MAX_RECENT: usize = 6;
+
+/// What the user picked on the welcome screen.
+pub enum WelcomeAction {
+    NewCanvas,
+    ReopenLastSession,
+    OpenLayout(String),
+    Dismiss,
+}
+
+/// A saved layout as listed on the welcome screen
+struct RecentLayout {
+    name: String,
+    preview_count: usize,
+    modified_at: Option<SystemTime>,
+    thumbnail: Option<TextureHandle>,
+}
+
+/// Start page shown when there's nothing to restore (or from the File
+/// menu): recent layouts with thumbnails, a new empty canvas, or the last
+/// session.
+pub struct WelcomeScreen {
+    recent: Vec<RecentLayout>,
+    has_last_session: bool,
+}
+
+impl WelcomeScreen {
+    pub fn new(ctx: &egui::Context, storage: &Storage, has_last_session: bool) -> Self {
+        let recent = storage
+            .recent_layouts(MAX_RECENT)
+            .into_iter()
+            .map(|layout| {
+                let thumbnail = storage.load_thumbnail(&layout.name).map(|image| {
+                    let size = [image.width() as usize, image.height() as usize];
+                    ctx.load_texture(
+                        format!("layout_thumbnail_{}", layout.name),
+                        egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()),
+                        egui::TextureOptions::LINEAR,
+                    )
+                });
+                RecentLayout {
+                    preview_count: layout.previews.len(),
+                    modified_at: layout
+                        .modified_at
+                        .parse::<u64>()
+                        .ok()
+                        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
+                    name: layout.name,
+                    thumbnail,
+                }
+            })
+            .collect();
+
+        Self { recent, has_last_session }
+    }
+
+    pub fn ui(&self, ctx: &egui::Context) -> Option<WelcomeAction> {
+        let mut action = None;
+
+        egui::Window::new("Welcome to Pluriview")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .frame(egui::Frame::window(&ctx.style())
+                .fill(Color32::from_rgb(25, 25, 28)))
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button(format!("{} New Empty Canvas", egui_phosphor::regular::PLUS)).clicked() {
+                        action = Some(WelcomeAction::NewCanvas);
+                    }
+                    if self.has_last_session
+                        && ui
+                            .button(format!("{} Reopen Last Session", egui_phosphor::regular::CLOCK_COUNTER_CLOCKWISE))
+                            .clicked()
+                    {
+                        action = Some(WelcomeAction::ReopenLastSession);
+                    }
+                });
+
+                ui.add_space(12.0);
+                ui.label(RichText::new("Recent Layouts").strong());
+                ui.add_space(4.0);
+
+                if self.recent.is_empty() {
+                    ui.label(RichText::new("No saved layouts yet. Use File > Save Layout As... to create one.").weak());
+                }
+
+                let thumb_size = Vec2::new(THUMBNAIL_WIDTH as f32, THUMBNAIL_HEIGHT as f32) * 0.75;
+                egui::Grid::new("welcome_recent_grid")
+                    .num_columns(3)
+                    .spacing([12.0, 12.0])
+                    .show(ui, |ui| {
+                        for (i, layout) in self.recent.iter().enumerate() {
+                            let clicked = ui
+                                .vertical(|ui| {
+                                    let response = match &layout.thumbnail {
+                                        Some(texture) => ui.add(
+                                            egui::ImageButton::new(egui::Image::new((texture.id(), thumb_size)))
+                                                .rounding(6.0),
+                                        ),
+                                        None => ui.add_sized(thumb_size, egui::Button::new("").rounding(6.0)),
+                                    };
+                                    ui.label(&layout.name);
+                                    let age = layout
+                                        .modified_at
+                                        .and_then(|t| t.elapsed().ok())
+                                        .map(format_age)
+                                        .unwrap_or_default();
+                                    ui.label(
+                                        RichText::new(format!("{} previews · {}", layout.preview_count, age))
+                                            .weak()
+                                            .small(),
+                                    );
+                                    response.clicked()
+                                })
+                                .inner;
+                            if clicked {
+                                action = Some(WelcomeAction::OpenLayout(layout.name.clone()));
+                            }
+                            if i % 3 == 2 {
+                                ui.end_row();
+                            }
+                        }
+                    });
+
+                ui.add_space(8.0);
+                ui.vertical_centered(|ui| {
+                    if ui.button("Close").clicked() {
+                        action = Some(WelcomeAction::Dismiss);
+                    }
+                });
+            });
+
+        action
+    }
+}
@@ -0,0 +1,177 @@
+use crate::platform::WindowInfo;
+use crate::preview::PreviewLayout;
+use std::collections::HashSet;
+
+/// Title score of an exact match; a nickname match counts for a bit less
+const EXACT_TITLE: u32 = 100;
+const NICKNAME_TITLE: u32 = 80;
+
+/// The open window a saved tile most likely showed, if any. Windows of the
+/// same executable and class come first (titles change as documents and
+/// tabs do), the closest title picks among them; without those the title
+/// must match exactly or contain the tile's nickname. A same-app window
+/// sharing no title words, or one a preview in `taken` already shows, only
+/// counts when it's the app's only window, and a shown window of another
+/// app only counts for an exact title. Of two equally good windows,
+/// one not shown yet wins, then one on the current virtual desktop, then
+/// the first (`enumerate_windows` order).
+pub fn best_match<'a>(
+    tile: &PreviewLayout,
+    windows: &'a [WindowInfo],
+    taken: &HashSet<isize>,
+) -> Option<&'a WindowInfo> {
+    let same_app: Vec<bool> = windows.iter().map(|window| is_same_app(tile, window)).collect();
+    let only_window = same_app.iter().filter(|same| **same).count() == 1;
+    windows
+        .iter()
+        .zip(same_app)
+        .filter_map(|(window, same_app)| {
+            let shown = taken.contains(&window.hwnd);
+            let title = title_score(tile, window);
+            let score = if same_app && (only_window || (!shown && title > 0)) {
+                1000 + title
+            } else if title == EXACT_TITLE || (!shown && title >= NICKNAME_TITLE) {
+                title
+            } else {
+                return None;
+            };
+            Some(((score, !shown, window.on_current_desktop), window))
+        })
+        // max_by_key keeps the last of equals; reversed, that's the first
+        .rev()
+        .max_by_key(|(key, _)| *key)
+        .map(|(_, window)| window)
+}
+
+/// Same executable (and class, if the tile saved one)
+fn is_same_app(tile: &PreviewLayout, window: &WindowInfo) -> bool {
+    tile.window_exe
+        .as_deref()
+        .is_some_and(|exe| exe.eq_ignore_ascii_case(&window.exe_name))
+        && match tile.window_class.as_deref() {
+            Some(class) => class == window.class_name,
+            None => true,
+        }
+}
+
+/// How close a window's title is to the tile's, 0 to `EXACT_TITLE`
+fn title_score(tile: &PreviewLayout, window: &WindowInfo) -> u32 {
+    if window.title == tile.window_title {
+        EXACT_TITLE
+    } else if tile.nickname_matches(&window.title) {
+        NICKNAME_TITLE
+    } else {
+        title_similarity(&tile.window_title, &window.title)
+    }
+}
+
+/// Words two titles share, 0 (none) to 99, ignoring case and punctuation
+fn title_similarity(a: &str, b: &str) -> u32 {
+    let words = |title: &str| -> HashSet<String> {
+        title
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() || b.is_empty() {
+        return 0;
+    }
+    let shared = a.intersection(&b).count();
+    ((shared * 2 * 99) / (a.len() + b.len())) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_match, title_similarity};
+    use crate::preview::PreviewLayout;
+    use crate::platform::WindowInfo;
+    use std::collections::HashSet;
+
+    fn window(hwnd: isize, exe_name: &str, class_name: &str, title: &str) -> WindowInfo {
+        WindowInfo {
+            hwnd,
+            title: title.to_string(),
+            process_id: 0,
+            exe_name: exe_name.to_string(),
+            class_name: class_name.to_string(),
+            icon: None,
+            z_order: 0,
+            desktop: None,
+            on_current_desktop: true,
+            elevated: false,
+        }
+    }
+
+    fn tile(title: &str, exe: Option<&str>, class: Option<&str>) -> PreviewLayout {
+        let mut tile = PreviewLayout::for_title(title);
+        tile.window_exe = exe.map(str::to_string);
+        tile.window_class = class.map(str::to_string);
+        tile
+    }
+
+    #[test]
+    fn the_same_app_wins_over_a_lookalike_title() {
+        let windows = [
+            window(1, "notepad.exe", "Notepad", "main.rs - Notes"),
+            window(2, "Code.exe", "Chrome_WidgetWin_1", "lib.rs - Visual Studio Code"),
+        ];
+        let saved = tile("main.rs - Visual Studio Code", Some("code.exe"), Some("Chrome_WidgetWin_1"));
+        assert_eq!(best_match(&saved, &windows, &HashSet::new()).map(|w| w.hwnd), Some(2));
+
+        // Without the executable only an exact title will do
+        let saved = tile("main.rs - Visual Studio Code", None, None);
+        assert!(best_match(&saved, &windows, &HashSet::new()).is_none());
+    }
+
+    #[test]
+    fn ties_go_to_windows_not_shown_yet() {
+        let windows = [
+            window(1, "Code.exe", "Chrome_WidgetWin_1", "a.rs - Visual Studio Code"),
+            window(2, "Code.exe", "Chrome_WidgetWin_1", "b.rs - Visual Studio Code"),
+        ];
+        let saved = tile("main.rs - Visual Studio Code", Some("Code.exe"), None);
+        assert_eq!(best_match(&saved, &windows, &HashSet::from([1])).map(|w| w.hwnd), Some(2));
+        assert_eq!(title_similarity("main.rs - Visual Studio Code", "Slack"), 0);
+    }
+
+    #[test]
+    fn unrelated_or_shown_windows_of_the_app_are_left_alone() {
+        let windows = [
+            window(1, "chrome.exe", "Chrome_WidgetWin_1", "Inbox - Mail"),
+            window(2, "chrome.exe", "Chrome_WidgetWin_1", "Build #41 - CI"),
+        ];
+        let saved = tile("Quarterly report", Some("chrome.exe"), None);
+        assert!(best_match(&saved, &windows, &HashSet::new()).is_none());
+        // Shown already by another tile: not taken over for a fuzzy match
+        let saved = tile("Build #40 - CI", Some("chrome.exe"), None);
+        assert!(best_match(&saved, &windows, &HashSet::from([2])).is_none());
+        // The app's only window still matches
+        let saved = tile("Quarterly report", Some("chrome.exe"), None);
+        assert_eq!(best_match(&saved, &windows[..1], &HashSet::new()).map(|w| w.hwnd), Some(1));
+    }
+
+    #[test]
+    fn nicknames_dont_take_over_shown_windows() {
+        let windows = [window(1, "chrome.exe", "Chrome_WidgetWin_1", "main - Build #42 - Jenkins")];
+        let mut saved = tile("main - Build #41 - Jenkins", None, None);
+        saved.nickname = Some("jenkins".to_string());
+        assert_eq!(best_match(&saved, &windows, &HashSet::new()).map(|w| w.hwnd), Some(1));
+        assert!(best_match(&saved, &windows, &HashSet::from([1])).is_none());
+        // An exact title still matches a window that's shown already
+        let saved = tile("main - Build #42 - Jenkins", None, None);
+        assert_eq!(best_match(&saved, &windows, &HashSet::from([1])).map(|w| w.hwnd), Some(1));
+    }
+
+    #[test]
+    fn identical_windows_prefer_the_current_desktop() {
+        let windows = [
+            WindowInfo { desktop: Some(2), on_current_desktop: false, ..window(1, "cmd.exe", "Console", "Terminal") },
+            window(2, "cmd.exe", "Console", "Terminal"),
+            window(3, "cmd.exe", "Console", "Terminal"),
+        ];
+        let saved = tile("Terminal", Some("cmd.exe"), Some("Console"));
+        assert_eq!(best_match(&saved, &windows, &HashSet::new()).map(|w| w.hwnd), Some(2));
+    }
+}
@@ -1,5 +1,5 @@
-mod enumerator;
+mod matching;
 mod picker;
 
-pub use enumerator::{WindowInfo, enumerate_windows};
+pub use matching::best_match;
 pub use picker::{WindowPicker, spawn_preview};
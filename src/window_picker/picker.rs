@@ -1,14 +1,29 @@
 use eframe::egui::{self, Pos2, Vec2, Rounding, Stroke, RichText};
-use super::{WindowInfo, enumerate_windows};
-use crate::preview::PreviewManager;
-use crate::capture::CaptureCoordinator;
-use crate::canvas::CanvasState;
+use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use crate::platform::{desktop_label, DisplayInfo, Native, WindowInfo, WindowSource};
+use crate::preview::{PreviewManager, CaptureOptions};
+use crate::capture::{CaptureCoordinator, snapshot_window};
+use crate::canvas::{grid_layout, CanvasState, ARRANGE_GAP};
+use crate::persistence::{PickerSort, RecentWindow, WindowBlocklist};
+
+/// Longer side of a row's thumbnail, in pixels
+const THUMBNAIL_SIDE: u32 = 128;
+
+/// Size of the thumbnail slot at the start of each row
+const THUMBNAIL_SLOT: Vec2 = Vec2::new(64.0, 36.0);
+
+/// Most windows remembered under Recent
+const MAX_RECENT: usize = 6;
 
 /// Window picker panel state
 pub struct WindowPicker {
     /// Cached list of windows
     windows: Vec<WindowInfo>,
 
+    /// Cached list of displays, shown above the windows
+    displays: Vec<DisplayInfo>,
+
     /// Search filter text
     search_filter: String,
 
@@ -17,22 +32,174 @@ pub struct WindowPicker {
 
     /// Auto-refresh interval
     refresh_interval: std::time::Duration,
+
+    /// Latest snapshot of each listed window, by HWND
+    thumbnails: HashMap<isize, egui::TextureHandle>,
+
+    /// Order of the list; the app saves it in the settings
+    pub sort: PickerSort,
+
+    /// Windows left out of the list; the app saves it in the settings
+    pub blocklist: WindowBlocklist,
+
+    /// Which virtual desktops' windows are listed
+    desktop_filter: DesktopFilter,
+
+    /// Virtual desktops in Task View order, for naming them
+    desktop_order: Vec<u128>,
+
+    /// When each window was last in the foreground, for sorting by
+    /// activity
+    activated: HashMap<isize, std::time::Instant>,
+
+    /// Windows Ctrl+clicked for "Add selected", by HWND
+    selected: HashSet<isize>,
+
+    /// Windows added before, most recent first
+    recent: Vec<RecentWindow>,
+
+    /// `recent` changed since the app last saved it
+    recent_changed: bool,
+
+    /// Uploaded app icons, by executable path
+    icons: HashMap<String, egui::TextureHandle>,
+
+    /// Snapshots still coming in from the batch taken on the last refresh
+    pending_thumbnails: Option<Receiver<(isize, egui::ColorImage)>>,
+
+    /// The panic button is blanking previews; no thumbnails are taken or shown
+    blanked: bool,
 }
 
 impl WindowPicker {
     pub fn new() -> Self {
         Self {
             windows: Vec::new(),
+            displays: Vec::new(),
             search_filter: String::new(),
             last_refresh: std::time::Instant::now() - std::time::Duration::from_secs(10),
             refresh_interval: std::time::Duration::from_secs(2),
+            thumbnails: HashMap::new(),
+            sort: PickerSort::default(),
+            blocklist: WindowBlocklist::default(),
+            desktop_filter: DesktopFilter::All,
+            desktop_order: Vec::new(),
+            activated: HashMap::new(),
+            selected: HashSet::new(),
+            recent: Vec::new(),
+            recent_changed: false,
+            icons: HashMap::new(),
+            pending_thumbnails: None,
+            blanked: false,
         }
     }
 
     /// Refresh the window list
     pub fn refresh(&mut self) {
-        self.windows = enumerate_windows();
+        self.windows = Native::windows();
+        self.windows.retain(|w| !self.blocklist.blocks(&w.exe_name, &w.title, &w.class_name));
+        sort_windows(&mut self.windows, self.sort, &self.activated);
+        self.displays = Native::displays();
+        self.desktop_order = Native::desktop_order();
         self.last_refresh = std::time::Instant::now();
+        self.thumbnails.retain(|hwnd, _| self.windows.iter().any(|w| w.hwnd == *hwnd));
+        self.selected.retain(|hwnd| self.windows.iter().any(|w| w.hwnd == *hwnd));
+        self.activated.retain(|hwnd, _| self.windows.iter().any(|w| w.hwnd == *hwnd));
+        self.snapshot_windows();
+    }
+
+    /// Follow the panic button: drop every thumbnail while previews are
+    /// blanked, and take fresh ones once they're back. Call every frame.
+    pub fn set_blanked(&mut self, blanked: bool) {
+        if self.blanked == blanked {
+            return;
+        }
+        self.blanked = blanked;
+        if blanked {
+            self.thumbnails.clear();
+            self.pending_thumbnails = None;
+        } else {
+            self.snapshot_windows();
+        }
+    }
+
+    /// Note the window in the desktop's foreground; call every frame
+    pub fn note_foreground(&mut self, hwnd: isize) {
+        self.activated.insert(hwnd, std::time::Instant::now());
+    }
+
+    /// Replace the remembered windows (loaded from settings)
+    pub fn set_recent(&mut self, recent: Vec<RecentWindow>) {
+        self.recent = recent;
+        self.recent_changed = false;
+    }
+
+    /// Put `window` at the top of Recent
+    pub fn remember(&mut self, window: &WindowInfo) {
+        let entry = RecentWindow { exe_name: window.exe_name.clone(), title: window.title.clone() };
+        self.recent.retain(|r| *r != entry);
+        self.recent.insert(0, entry);
+        self.recent.truncate(MAX_RECENT);
+        self.recent_changed = true;
+    }
+
+    /// The remembered windows, if they changed since the last call
+    pub fn take_recent_changes(&mut self) -> Option<Vec<RecentWindow>> {
+        std::mem::take(&mut self.recent_changed).then(|| self.recent.clone())
+    }
+
+    /// Snapshot every listed window on a background thread (PrintWindow
+    /// can block on a busy window). Skipped while the last batch is still
+    /// coming in, so a slow window doesn't pile up threads.
+    fn snapshot_windows(&mut self) {
+        if self.pending_thumbnails.is_some() || self.blanked {
+            return;
+        }
+        let hwnds: Vec<isize> = self.windows.iter().map(|w| w.hwnd).collect();
+        let (sender, receiver) = mpsc::channel();
+        let spawned = std::thread::Builder::new()
+            .name("picker-thumbnails".into())
+            .spawn(move || {
+                for hwnd in hwnds {
+                    let Some((width, height, rgba)) = snapshot_window(hwnd, THUMBNAIL_SIDE) else {
+                        continue;
+                    };
+                    let image = egui::ColorImage::from_rgba_unmultiplied([width as usize, height as usize], &rgba);
+                    if sender.send((hwnd, image)).is_err() {
+                        break;
+                    }
+                }
+            });
+        match spawned {
+            Ok(_) => self.pending_thumbnails = Some(receiver),
+            Err(e) => log::warn!("Failed to start window thumbnails: {}", e),
+        }
+    }
+
+    /// Upload the snapshots that have arrived since the last frame
+    fn receive_thumbnails(&mut self, ctx: &egui::Context) {
+        let Some(receiver) = &self.pending_thumbnails else {
+            return;
+        };
+        loop {
+            match receiver.try_recv() {
+                Ok((hwnd, image)) => match self.thumbnails.get_mut(&hwnd) {
+                    Some(texture) => texture.set(image, egui::TextureOptions::LINEAR),
+                    None => {
+                        let texture = ctx.load_texture(format!("picker thumbnail {}", hwnd), image, egui::TextureOptions::LINEAR);
+                        self.thumbnails.insert(hwnd, texture);
+                    }
+                },
+                Err(TryRecvError::Empty) => {
+                    ctx.request_repaint_after(std::time::Duration::from_millis(100));
+                    break;
+                }
+                Err(TryRecvError::Disconnected) => {
+                    self.pending_thumbnails = None;
+                    break;
+                }
+            }
+        }
     }
 
     /// UI for the window picker
@@ -47,6 +214,7 @@ impl WindowPicker {
         if self.last_refresh.elapsed() > self.refresh_interval {
             self.refresh();
         }
+        self.receive_thumbnails(ui.ctx());
 
         // Colors for the modern theme
         let card_bg = egui::Color32::from_rgb(28, 28, 32);
@@ -95,7 +263,7 @@ impl WindowPicker {
         ui.horizontal(|ui| {
             let filter_lower = self.search_filter.to_lowercase();
             let count = self.windows.iter()
-                .filter(|w| Self::window_matches(w, &filter_lower))
+                .filter(|w| self.desktop_filter.allows(w) && Self::window_matches(w, &filter_lower))
                 .count();
 
             ui.label(
@@ -105,6 +273,20 @@ impl WindowPicker {
             );
 
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                // Sort order
+                let previous = self.sort;
+                egui::ComboBox::from_id_salt("picker_sort")
+                    .selected_text(RichText::new(self.sort.label()).size(12.0))
+                    .width(110.0)
+                    .show_ui(ui, |ui| {
+                        for sort in PickerSort::ALL {
+                            ui.selectable_value(&mut self.sort, sort, sort.label());
+                        }
+                    });
+                if self.sort != previous {
+                    sort_windows(&mut self.windows, self.sort, &self.activated);
+                }
+
                 // Refresh button (subtle, icon-based)
                 let refresh_btn = ui.add(
                     egui::Button::new(
@@ -122,6 +304,48 @@ impl WindowPicker {
             });
         });
 
+        // Virtual desktop filter, once windows are spread over several
+        if self.windows.iter().any(|w| !w.on_current_desktop) || self.desktop_filter != DesktopFilter::All {
+            let mut desktops: Vec<u128> = self.desktop_order.clone();
+            for desktop in self.windows.iter().filter_map(|w| w.desktop) {
+                if !desktops.contains(&desktop) {
+                    desktops.push(desktop);
+                }
+            }
+            let label = |filter: DesktopFilter| match filter {
+                DesktopFilter::All => "All Desktops".to_string(),
+                DesktopFilter::Current => "This Desktop".to_string(),
+                DesktopFilter::Desktop(desktop) => desktop_label(desktop, &self.desktop_order),
+            };
+            ui.add_space(4.0);
+            egui::ComboBox::from_id_salt("picker_desktop")
+                .selected_text(RichText::new(label(self.desktop_filter)).size(12.0))
+                .width(ui.available_width())
+                .show_ui(ui, |ui| {
+                    let choices = [DesktopFilter::All, DesktopFilter::Current]
+                        .into_iter()
+                        .chain(desktops.into_iter().map(DesktopFilter::Desktop));
+                    for choice in choices {
+                        ui.selectable_value(&mut self.desktop_filter, choice, label(choice));
+                    }
+                });
+        }
+
+        // Ctrl+clicked windows are added together
+        let mut add_selected = false;
+        if !self.selected.is_empty() {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                let label = format!("{} Add selected ({})", egui_phosphor::regular::PLUS, self.selected.len());
+                if ui.add(egui::Button::new(RichText::new(label).color(egui::Color32::WHITE)).fill(accent_color)).clicked() {
+                    add_selected = true;
+                }
+                if ui.button("Clear").clicked() {
+                    self.selected.clear();
+                }
+            });
+        }
+
         ui.add_space(6.0);
 
         // Precompute the filtered set once (avoids cloning the whole window
@@ -129,17 +353,103 @@ impl WindowPicker {
         let filter_lower = self.search_filter.to_lowercase();
         let filtered: Vec<usize> = self.windows.iter()
             .enumerate()
-            .filter(|(_, w)| Self::window_matches(w, &filter_lower))
+            .filter(|(_, w)| self.desktop_filter.allows(w) && Self::window_matches(w, &filter_lower))
             .map(|(i, _)| i)
             .collect();
+        let recent: Vec<usize> = recent_windows(&self.recent, &self.windows)
+            .into_iter()
+            .filter(|&idx| self.desktop_filter.allows(&self.windows[idx]) && Self::window_matches(&self.windows[idx], &filter_lower))
+            .collect();
+        // Upload icons of executables seen for the first time
+        for &idx in &filtered {
+            let Some(icon) = &self.windows[idx].icon else { continue; };
+            if !self.icons.contains_key(&icon.key) {
+                let image = egui::ColorImage::from_rgba_unmultiplied([icon.width as usize, icon.height as usize], &icon.rgba);
+                let texture = ui.ctx().load_texture(format!("app icon {}", icon.key), image, egui::TextureOptions::LINEAR);
+                self.icons.insert(icon.key.clone(), texture);
+            }
+        }
+        let displays: Vec<&DisplayInfo> = self.displays.iter()
+            .filter(|d| filter_lower.is_empty() || d.name.to_lowercase().contains(&filter_lower))
+            .collect();
 
         // Window list with card-style items
+        let mut added = None;
+        let mut toggled = None;
+        let mut hidden = None;
         egui::ScrollArea::vertical()
             .auto_shrink([false, false])
             .show(ui, |ui| {
                 let available_width = ui.available_width();
 
-                for &idx in &filtered {
+                // Displays first: full-screen sources are added from here too
+                if !displays.is_empty() {
+                    ui.label(RichText::new("Displays").size(11.0).color(text_secondary));
+                    ui.add_space(4.0);
+                    for display in &displays {
+                        let (rect, response) = ui.allocate_exact_size(
+                            Vec2::new(available_width, 44.0),
+                            egui::Sense::click()
+                        );
+                        let bg_color = if response.hovered() { card_hover } else { card_bg };
+                        ui.painter().rect_filled(rect, Rounding::same(6.0), bg_color);
+
+                        let inner_rect = rect.shrink(10.0);
+                        ui.painter().text(
+                            Pos2::new(inner_rect.min.x, inner_rect.center().y),
+                            egui::Align2::LEFT_CENTER,
+                            egui_phosphor::regular::MONITOR,
+                            egui::FontId::proportional(16.0),
+                            text_secondary
+                        );
+                        ui.painter().text(
+                            Pos2::new(inner_rect.min.x + 26.0, inner_rect.min.y),
+                            egui::Align2::LEFT_TOP,
+                            &display.name,
+                            egui::FontId::proportional(14.0),
+                            egui::Color32::WHITE
+                        );
+                        let resolution = format!(
+                            "{} × {}{}",
+                            display.width,
+                            display.height,
+                            if display.primary { " · primary" } else { "" }
+                        );
+                        ui.painter().text(
+                            Pos2::new(inner_rect.min.x + 26.0, inner_rect.max.y),
+                            egui::Align2::LEFT_BOTTOM,
+                            resolution,
+                            egui::FontId::proportional(11.0),
+                            text_secondary
+                        );
+
+                        let btn_center = Pos2::new(inner_rect.max.x - 15.0, inner_rect.center().y);
+                        if add_button(ui, btn_center, response.id.with("add_btn"), accent_color) {
+                            self.add_display_to_canvas(display, preview_manager, capture_coordinator, canvas);
+                        }
+
+                        ui.add_space(4.0);
+                    }
+                    ui.add_space(4.0);
+                }
+
+                // Windows added before, then every window alphabetically
+                for (row, &idx) in recent.iter().chain(&filtered).enumerate() {
+                    let heading = if row == 0 && !recent.is_empty() {
+                        Some("Recent")
+                    } else if row == recent.len() && (!recent.is_empty() || !displays.is_empty()) {
+                        Some("Windows")
+                    } else {
+                        None
+                    };
+                    if let Some(heading) = heading {
+                        if row > 0 {
+                            ui.add_space(4.0);
+                        }
+                        ui.label(RichText::new(heading).size(11.0).color(text_secondary));
+                        ui.add_space(4.0);
+                    }
+
                     let window = &self.windows[idx];
 
                     // Card frame
@@ -150,6 +460,16 @@ impl WindowPicker {
 
                     let is_hovered = response.hovered();
                     let bg_color = if is_hovered { card_hover } else { card_bg };
+                    if response.clicked() && ui.input(|i| i.modifiers.command) {
+                        toggled = Some(window.hwnd);
+                    }
+                    let is_selected = self.selected.contains(&window.hwnd);
+                    response.context_menu(|ui| {
+                        if ui.button(format!("Hide {} Windows", window.exe_name)).clicked() {
+                            hidden = Some(window.exe_name.clone());
+                            ui.close_menu();
+                        }
+                    });
 
                     // Draw card background
                     ui.painter().rect_filled(
@@ -158,8 +478,11 @@ impl WindowPicker {
                         bg_color
                     );
 
-                    // Draw subtle border on hover
-                    if is_hovered {
+                    // Selected cards are outlined in the accent color,
+                    // others get a subtle border on hover
+                    if is_selected {
+                        ui.painter().rect_stroke(rect, Rounding::same(6.0), Stroke::new(1.5, accent_color));
+                    } else if is_hovered {
                         ui.painter().rect_stroke(
                             rect,
                             Rounding::same(6.0),
@@ -169,8 +492,12 @@ impl WindowPicker {
 
                     // Content layout
                     let inner_rect = rect.shrink(10.0);
+                    let thumb_slot = egui::Rect::from_min_size(
+                        Pos2::new(inner_rect.min.x, inner_rect.center().y - THUMBNAIL_SLOT.y / 2.0),
+                        THUMBNAIL_SLOT,
+                    );
                     let text_rect = egui::Rect::from_min_max(
-                        inner_rect.min,
+                        Pos2::new(thumb_slot.max.x + 8.0, inner_rect.min.y),
                         egui::Pos2::new(inner_rect.max.x - 36.0, inner_rect.max.y)
                     );
                     let button_rect = egui::Rect::from_min_max(
@@ -178,8 +505,22 @@ impl WindowPicker {
                         egui::Pos2::new(inner_rect.max.x, inner_rect.max.y - 8.0)
                     );
 
+                    // Live thumbnail, letterboxed into its slot
+                    ui.painter().rect_filled(thumb_slot, Rounding::same(3.0), search_bg);
+                    if let Some(texture) = self.thumbnails.get(&window.hwnd) {
+                        let size = texture.size_vec2();
+                        let scale = (thumb_slot.width() / size.x).min(thumb_slot.height() / size.y);
+                        let thumb_rect = egui::Rect::from_center_size(thumb_slot.center(), size * scale);
+                        ui.painter().image(
+                            texture.id(),
+                            thumb_rect,
+                            egui::Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                            egui::Color32::WHITE,
+                        );
+                    }
+
                     // Title (truncated, char-safe to avoid panics on multibyte titles)
-                    let max_title_chars = ((text_rect.width() - 10.0) / 7.0) as usize;
+                    let max_title_chars = ((text_rect.width() - 32.0) / 7.0) as usize;
                     let title = if window.title.chars().count() > max_title_chars {
                         let kept: String = window.title.chars()
                             .take(max_title_chars.saturating_sub(3))
@@ -189,56 +530,73 @@ impl WindowPicker {
                         window.title.clone()
                     };
 
+                    // App icon beside the title
+                    let icon = window.icon.as_ref().and_then(|icon| self.icons.get(&icon.key));
+                    let title_x = match icon {
+                        Some(texture) => {
+                            ui.painter().image(
+                                texture.id(),
+                                egui::Rect::from_min_size(Pos2::new(text_rect.min.x, text_rect.min.y + 2.0), Vec2::splat(16.0)),
+                                egui::Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+                                egui::Color32::WHITE,
+                            );
+                            text_rect.min.x + 22.0
+                        }
+                        None => text_rect.min.x,
+                    };
+
                     // Draw title
                     ui.painter().text(
-                        egui::Pos2::new(text_rect.min.x, text_rect.min.y + 2.0),
+                        egui::Pos2::new(title_x, text_rect.min.y + 2.0),
                         egui::Align2::LEFT_TOP,
                         &title,
                         egui::FontId::proportional(14.0),
                         egui::Color32::WHITE
                     );
 
-                    // Draw exe name
+                    // Draw exe name, and the nickname of a preview already
+                    // showing this window
+                    let nickname = preview_manager
+                        .all()
+                        .filter(|p| p.window_handle.as_ref().is_some_and(|h| h.hwnd == window.hwnd))
+                        .find_map(|p| p.nickname.as_deref());
+                    let mut subtitle = match nickname {
+                        Some(nickname) => format!("{} · \"{}\"", window.exe_name, nickname),
+                        None => window.exe_name.clone(),
+                    };
+                    if let (false, Some(desktop)) = (window.on_current_desktop, window.desktop) {
+                        subtitle.push_str(" · ");
+                        subtitle.push_str(&desktop_label(desktop, &self.desktop_order));
+                    }
+                    // Shield for windows of elevated processes, which
+                    // can't be captured until Pluriview is elevated too
+                    let subtitle_x = if window.elevated {
+                        ui.painter().text(
+                            egui::Pos2::new(text_rect.min.x, text_rect.min.y + 20.0),
+                            egui::Align2::LEFT_TOP,
+                            egui_phosphor::regular::SHIELD_WARNING,
+                            egui::FontId::proportional(11.0),
+                            egui::Color32::from_rgb(255, 150, 100)
+                        );
+                        text_rect.min.x + 15.0
+                    } else {
+                        text_rect.min.x
+                    };
                     ui.painter().text(
-                        egui::Pos2::new(text_rect.min.x, text_rect.min.y + 20.0),
+                        egui::Pos2::new(subtitle_x, text_rect.min.y + 20.0),
                         egui::Align2::LEFT_TOP,
-                        &window.exe_name,
+                        &subtitle,
                         egui::FontId::proportional(11.0),
                         text_secondary
                     );
 
                     // Add button (+ icon)
-                    let btn_center = button_rect.center();
-                    let btn_radius = 14.0;
-                    let btn_rect = egui::Rect::from_center_size(btn_center, Vec2::splat(btn_radius * 2.0));
-
-                    let btn_response = ui.interact(btn_rect, response.id.with("add_btn"), egui::Sense::click());
-                    let btn_hovered = btn_response.hovered();
-
-                    // Draw + button circle
-                    ui.painter().circle_filled(
-                        btn_center,
-                        btn_radius,
-                        if btn_hovered { accent_color } else { egui::Color32::from_rgb(60, 60, 68) }
-                    );
-
-                    // Draw + icon
-                    let plus_color = if btn_hovered { egui::Color32::WHITE } else { egui::Color32::from_rgb(180, 180, 190) };
-                    ui.painter().text(
-                        btn_center,
-                        egui::Align2::CENTER_CENTER,
-                        egui_phosphor::regular::PLUS,
-                        egui::FontId::proportional(14.0),
-                        plus_color
-                    );
-
-                    // Handle add button click
-                    if btn_response.clicked() {
-                        self.add_window_to_canvas(
-                            window,
-                            preview_manager,
-                            capture_coordinator,
-                            canvas
+                    if add_button(ui, button_rect.center(), response.id.with("add_btn"), accent_color) {
+                        added = Some(idx);
+                    }
+                    if window.elevated {
+                        response.on_hover_text(
+                            "Runs as administrator: Pluriview has to be restarted as administrator to capture it"
                         );
                     }
 
@@ -246,7 +604,7 @@ impl WindowPicker {
                 }
 
                 // Empty state
-                if filtered.is_empty() && !filter_lower.is_empty() {
+                if filtered.is_empty() && displays.is_empty() && !filter_lower.is_empty() {
                     ui.add_space(20.0);
                     ui.vertical_centered(|ui| {
                         ui.label(
@@ -257,6 +615,48 @@ impl WindowPicker {
                     });
                 }
             });
+
+        if let Some(idx) = added {
+            let window = self.windows[idx].clone();
+            self.add_window_to_canvas(&window, preview_manager, capture_coordinator, canvas);
+            self.remember(&window);
+        }
+        if let Some(hwnd) = toggled {
+            if !self.selected.remove(&hwnd) {
+                self.selected.insert(hwnd);
+            }
+        }
+        if let Some(exe_name) = hidden {
+            self.blocklist.exe_names.push(exe_name);
+            self.refresh();
+        }
+        if add_selected {
+            self.add_selected_to_canvas(preview_manager, capture_coordinator, canvas);
+        }
+    }
+
+    /// Add every selected window at once, in list order, arranged into a
+    /// grid centered on the view
+    fn add_selected_to_canvas(
+        &mut self,
+        preview_manager: &mut PreviewManager,
+        capture_coordinator: &mut CaptureCoordinator,
+        canvas: &CanvasState,
+    ) {
+        let windows: Vec<WindowInfo> = self.windows.iter()
+            .filter(|w| self.selected.contains(&w.hwnd))
+            .cloned()
+            .collect();
+        self.selected.clear();
+
+        let size = Vec2::new(320.0, 240.0);
+        let cells = grid_layout(&vec![size.x / size.y; windows.len()], Pos2::ZERO, size.y, ARRANGE_GAP);
+        let Some(bounds) = cells.iter().copied().reduce(|a, b| a.union(b)) else { return; };
+        let offset = canvas.view_center() - bounds.center();
+        for (window, cell) in windows.iter().zip(&cells) {
+            spawn_preview(window, preview_manager, capture_coordinator, cell.min + offset, cell.size());
+            self.remember(window);
+        }
     }
 
     /// Returns true if a window matches the (already lowercased) search filter.
@@ -275,22 +675,122 @@ impl WindowPicker {
         capture_coordinator: &mut CaptureCoordinator,
         canvas: &CanvasState,
     ) {
-        // Calculate position (center of current viewport with offset)
+        let position = Self::next_position(preview_manager, canvas);
+        spawn_preview(window, preview_manager, capture_coordinator, position, Vec2::new(320.0, 240.0));
+    }
+
+    /// Add a whole display to the canvas, sized to its aspect ratio
+    fn add_display_to_canvas(
+        &self,
+        display: &DisplayInfo,
+        preview_manager: &mut PreviewManager,
+        capture_coordinator: &mut CaptureCoordinator,
+        canvas: &CanvasState,
+    ) {
+        let position = Self::next_position(preview_manager, canvas);
+        let aspect = display.height.max(1) as f32 / display.width.max(1) as f32;
+        let size = Vec2::new(320.0, 320.0 * aspect);
+
+        let id = preview_manager.add_for_window(display.handle, 0, display.name.clone(), position, size);
+        let options = CaptureOptions { display: true, ..Default::default() };
+        if let Some(preview) = preview_manager.get_mut(id) {
+            preview.capture_active = true;
+            preview.capture = options;
+        }
+        capture_coordinator.start_capture(id, display.handle, display.name.clone(), 30, options);
+    }
+
+    /// Where the next added preview goes: near the top left of the
+    /// viewport, stepping along so several adds don't stack exactly
+    fn next_position(preview_manager: &PreviewManager, canvas: &CanvasState) -> Pos2 {
         let preview_count = preview_manager.count();
         let offset = Vec2::new(
             (preview_count % 3) as f32 * 50.0,
             (preview_count / 3) as f32 * 50.0,
         );
 
-        let position = Pos2::new(
+        Pos2::new(
             -canvas.pan.x + 50.0 + offset.x,
             -canvas.pan.y + 50.0 + offset.y,
-        );
+        )
+    }
+}
 
-        spawn_preview(window, preview_manager, capture_coordinator, position, Vec2::new(320.0, 240.0));
+/// Which virtual desktops the picker lists windows from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DesktopFilter {
+    All,
+    /// The desktop being shown
+    Current,
+    Desktop(u128),
+}
+
+impl DesktopFilter {
+    /// Windows on every desktop pass any filter
+    fn allows(self, window: &WindowInfo) -> bool {
+        match self {
+            DesktopFilter::All => true,
+            DesktopFilter::Current => window.on_current_desktop,
+            DesktopFilter::Desktop(desktop) => !matches!(window.desktop, Some(d) if d != desktop),
+        }
     }
 }
 
+/// Order `windows` by `sort`. Windows never seen in the foreground sort
+/// after the rest, front to back, when ordering by activity.
+fn sort_windows(windows: &mut [WindowInfo], sort: PickerSort, activated: &HashMap<isize, std::time::Instant>) {
+    match sort {
+        PickerSort::Title => windows.sort_by_cached_key(|w| w.display_name().to_lowercase()),
+        PickerSort::Process => windows.sort_by_cached_key(|w| (w.exe_name.to_lowercase(), w.title.to_lowercase())),
+        PickerSort::RecentlyActive => windows.sort_by_key(|w| (std::cmp::Reverse(activated.get(&w.hwnd).copied()), w.z_order)),
+        PickerSort::ZOrder => windows.sort_by_key(|w| w.z_order),
+    }
+}
+
+/// Indices of the open windows matching `recent`, in its order: the
+/// window with the same executable and title, else another window of that
+/// executable which isn't listed already or an exact match for a later entry
+fn recent_windows(recent: &[RecentWindow], windows: &[WindowInfo]) -> Vec<usize> {
+    let mut found: Vec<usize> = Vec::new();
+    for entry in recent {
+        let same_exe = |i: &usize| windows[*i].exe_name.eq_ignore_ascii_case(&entry.exe_name);
+        let exact = (0..windows.len())
+            .find(|i| same_exe(i) && windows[*i].title == entry.title && !found.contains(i));
+        let retitled = || {
+            (0..windows.len())
+                .find(|i| same_exe(i) && !found.contains(i) && !recent.iter().any(|r| r.title == windows[*i].title))
+        };
+        if let Some(idx) = exact.or_else(retitled) {
+            found.push(idx);
+        }
+    }
+    found
+}
+
+/// Round "+" button centered on `center`; true when clicked
+fn add_button(ui: &egui::Ui, center: Pos2, id: egui::Id, accent_color: egui::Color32) -> bool {
+    let radius = 14.0;
+    let rect = egui::Rect::from_center_size(center, Vec2::splat(radius * 2.0));
+    let response = ui.interact(rect, id, egui::Sense::click());
+    let hovered = response.hovered();
+
+    ui.painter().circle_filled(
+        center,
+        radius,
+        if hovered { accent_color } else { egui::Color32::from_rgb(60, 60, 68) }
+    );
+    let plus_color = if hovered { egui::Color32::WHITE } else { egui::Color32::from_rgb(180, 180, 190) };
+    ui.painter().text(
+        center,
+        egui::Align2::CENTER_CENTER,
+        egui_phosphor::regular::PLUS,
+        egui::FontId::proportional(14.0),
+        plus_color
+    );
+
+    response.clicked()
+}
+
 /// Create a preview for `window` at `position`/`size` and start capturing it.
 /// Shared by the sidebar picker's "+" button and the canvas right-click
 /// quick-add popup so both add windows the same way.
@@ -311,9 +811,11 @@ pub fn spawn_preview(
 
     if let Some(preview) = preview_manager.get_mut(id) {
         preview.capture_active = true;
+        preview.window_exe = Some(window.exe_name.clone());
+        preview.window_class = Some(window.class_name.clone());
     }
 
-    capture_coordinator.start_capture(id, window.hwnd, window.title.clone(), 30);
+    capture_coordinator.start_capture(id, window.hwnd, window.title.clone(), 30, CaptureOptions::default());
 }
 
 impl Default for WindowPicker {
@@ -321,3 +823,61 @@ impl Default for WindowPicker {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{recent_windows, sort_windows};
+    use crate::persistence::{PickerSort, RecentWindow};
+    use crate::platform::WindowInfo;
+    use std::collections::HashMap;
+    use std::time::{Duration, Instant};
+
+    fn window(exe_name: &str, title: &str) -> WindowInfo {
+        WindowInfo {
+            hwnd: 0,
+            title: title.to_string(),
+            process_id: 0,
+            exe_name: exe_name.to_string(),
+            class_name: String::new(),
+            icon: None,
+            z_order: 0,
+            desktop: None,
+            on_current_desktop: true,
+            elevated: false,
+        }
+    }
+
+    fn recent(exe_name: &str, title: &str) -> RecentWindow {
+        RecentWindow { exe_name: exe_name.to_string(), title: title.to_string() }
+    }
+
+    #[test]
+    fn recent_windows_match_by_title_then_executable() {
+        let windows = [
+            window("chrome.exe", "Inbox"),
+            window("code.exe", "main.rs"),
+            window("chrome.exe", "Docs"),
+        ];
+        // The second Chrome entry's tab has changed title since
+        let history = [recent("code.exe", "main.rs"), recent("Chrome.exe", "Calendar"), recent("chrome.exe", "Docs")];
+        assert_eq!(recent_windows(&history, &windows), vec![1, 0, 2]);
+        // Nothing open for a forgotten executable
+        assert!(recent_windows(&[recent("vlc.exe", "film")], &windows).is_empty());
+    }
+
+    #[test]
+    fn activity_sorts_the_latest_foreground_first() {
+        let mut windows: Vec<WindowInfo> = ["a", "b", "c"]
+            .iter()
+            .enumerate()
+            .map(|(i, title)| WindowInfo { hwnd: i as isize, z_order: i, ..window("x.exe", title) })
+            .collect();
+        let now = Instant::now();
+        let activated = HashMap::from([(2, now), (1, now - Duration::from_secs(5))]);
+        sort_windows(&mut windows, PickerSort::RecentlyActive, &activated);
+        let titles: Vec<&str> = windows.iter().map(|w| w.title.as_str()).collect();
+        assert_eq!(titles, ["c", "b", "a"]);
+        sort_windows(&mut windows, PickerSort::ZOrder, &activated);
+        assert_eq!(windows[0].title, "a");
+    }
+}